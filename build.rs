@@ -0,0 +1,25 @@
+use std::process::Command;
+
+fn main() {
+    // Expose the current commit to the binary as SEKHA_GIT_SHA. Builds outside
+    // a git checkout (e.g. from a source tarball) fall back to "unknown".
+    let sha = std::env::var("SEKHA_GIT_SHA")
+        .ok()
+        .filter(|s| !s.is_empty())
+        .or_else(|| {
+            Command::new("git")
+                .args(["rev-parse", "--short", "HEAD"])
+                .output()
+                .ok()
+                .filter(|o| o.status.success())
+                .and_then(|o| String::from_utf8(o.stdout).ok())
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+        })
+        .unwrap_or_else(|| "unknown".to_string());
+
+    println!("cargo:rustc-env=SEKHA_GIT_SHA={}", sha);
+    println!("cargo:rerun-if-env-changed=SEKHA_GIT_SHA");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs/heads");
+}
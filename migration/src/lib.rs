@@ -7,6 +7,16 @@ mod m20241211_000004_create_hierarchical_summaries;
 mod m20241211_000005_create_knowledge_graph_edges;
 mod m20241211_000006_add_updated_at_triggers;
 mod m20241211_000007_create_fts;
+mod m20241211_000008_create_offloaded_conversations;
+mod m20241211_000009_create_summary_checkpoints;
+mod m20241211_000010_add_context_pinned;
+mod m20241211_000011_add_message_seq;
+mod m20241211_000012_create_conversation_history;
+mod m20241211_000013_create_conversation_templates;
+mod m20241211_000014_add_last_accessed_at;
+mod m20241211_000015_create_conversation_labels;
+mod m20241211_000016_add_message_embedding_provenance;
+mod m20241211_000017_drop_messages_updated_at_trigger;
 
 pub struct Migrator;
 
@@ -21,6 +31,16 @@ impl MigratorTrait for Migrator {
             Box::new(m20241211_000005_create_knowledge_graph_edges::Migration),
             Box::new(m20241211_000006_add_updated_at_triggers::Migration),
             Box::new(m20241211_000007_create_fts::Migration),
+            Box::new(m20241211_000008_create_offloaded_conversations::Migration),
+            Box::new(m20241211_000009_create_summary_checkpoints::Migration),
+            Box::new(m20241211_000010_add_context_pinned::Migration),
+            Box::new(m20241211_000011_add_message_seq::Migration),
+            Box::new(m20241211_000012_create_conversation_history::Migration),
+            Box::new(m20241211_000013_create_conversation_templates::Migration),
+            Box::new(m20241211_000014_add_last_accessed_at::Migration),
+            Box::new(m20241211_000015_create_conversation_labels::Migration),
+            Box::new(m20241211_000016_add_message_embedding_provenance::Migration),
+            Box::new(m20241211_000017_drop_messages_updated_at_trigger::Migration),
         ]
     }
 }
@@ -0,0 +1,83 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // No foreign key: the conversation row is deleted while it is offloaded
+        manager
+            .create_table(
+                Table::create()
+                    .table(OffloadedConversations::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(OffloadedConversations::ConversationId)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(OffloadedConversations::Label)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(OffloadedConversations::Folder)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(OffloadedConversations::FilePath)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(OffloadedConversations::MessageCount)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(OffloadedConversations::OffloadedAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_offloaded_folder")
+                    .table(OffloadedConversations::Table)
+                    .col(OffloadedConversations::Folder)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(OffloadedConversations::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum OffloadedConversations {
+    Table,
+    ConversationId,
+    Label,
+    Folder,
+    FilePath,
+    MessageCount,
+    OffloadedAt,
+}
@@ -0,0 +1,64 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SummaryCheckpoints::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SummaryCheckpoints::ConversationId)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(SummaryCheckpoints::MessageCount)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SummaryCheckpoints::SummarizedAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-summary_checkpoints-conversation_id")
+                            .from(
+                                SummaryCheckpoints::Table,
+                                SummaryCheckpoints::ConversationId,
+                            )
+                            .to(Conversations::Table, Conversations::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SummaryCheckpoints::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SummaryCheckpoints {
+    Table,
+    ConversationId,
+    MessageCount,
+    SummarizedAt,
+}
+
+#[derive(DeriveIden)]
+enum Conversations {
+    Table,
+    Id,
+}
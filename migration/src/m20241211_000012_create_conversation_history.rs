@@ -0,0 +1,90 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ConversationHistory::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ConversationHistory::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ConversationHistory::ConversationId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ConversationHistory::Field).text().not_null())
+                    .col(
+                        ColumnDef::new(ConversationHistory::OldValue)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ConversationHistory::NewValue)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ConversationHistory::ChangedAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-conversation_history-conversation_id")
+                            .from(
+                                ConversationHistory::Table,
+                                ConversationHistory::ConversationId,
+                            )
+                            .to(Conversations::Table, Conversations::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_conversation_history_conversation")
+                    .table(ConversationHistory::Table)
+                    .col(ConversationHistory::ConversationId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ConversationHistory::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ConversationHistory {
+    Table,
+    Id,
+    ConversationId,
+    Field,
+    OldValue,
+    NewValue,
+    ChangedAt,
+}
+
+#[derive(DeriveIden)]
+enum Conversations {
+    Table,
+    Id,
+}
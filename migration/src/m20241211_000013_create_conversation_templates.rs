@@ -0,0 +1,68 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ConversationTemplates::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ConversationTemplates::Name)
+                            .text()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ConversationTemplates::LabelPattern)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ConversationTemplates::Folder)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ConversationTemplates::SystemMessage).text())
+                    .col(
+                        ColumnDef::new(ConversationTemplates::Tags)
+                            .json()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ConversationTemplates::CreatedAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ConversationTemplates::UpdatedAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ConversationTemplates::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ConversationTemplates {
+    Table,
+    Name,
+    LabelPattern,
+    Folder,
+    SystemMessage,
+    Tags,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,65 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Conversations::Table)
+                    .add_column(ColumnDef::new(Conversations::LastAccessedAt).text().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Recording a read must not bump updated_at
+        manager
+            .execute_unprepared(
+                r#"
+                DROP TRIGGER IF EXISTS update_conversations_updated_at;
+                CREATE TRIGGER update_conversations_updated_at
+                AFTER UPDATE ON conversations
+                WHEN NEW.last_accessed_at IS OLD.last_accessed_at
+                BEGIN
+                    UPDATE conversations SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.id;
+                END;
+                "#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .execute_unprepared(
+                r#"
+                DROP TRIGGER IF EXISTS update_conversations_updated_at;
+                CREATE TRIGGER update_conversations_updated_at
+                AFTER UPDATE ON conversations
+                BEGIN
+                    UPDATE conversations SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.id;
+                END;
+                "#,
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Conversations::Table)
+                    .drop_column(Conversations::LastAccessedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Conversations {
+    Table,
+    LastAccessedAt,
+}
@@ -0,0 +1,82 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ConversationLabels::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ConversationLabels::ConversationId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ConversationLabels::Label).text().not_null())
+                    .col(
+                        ColumnDef::new(ConversationLabels::Weight)
+                            .double()
+                            .not_null()
+                            .default(1.0),
+                    )
+                    .col(
+                        ColumnDef::new(ConversationLabels::AddedAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(ConversationLabels::ConversationId)
+                            .col(ConversationLabels::Label),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-conversation_labels-conversation_id")
+                            .from(
+                                ConversationLabels::Table,
+                                ConversationLabels::ConversationId,
+                            )
+                            .to(Conversations::Table, Conversations::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_conversation_labels_label")
+                    .table(ConversationLabels::Table)
+                    .col(ConversationLabels::Label)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ConversationLabels::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ConversationLabels {
+    Table,
+    ConversationId,
+    Label,
+    Weight,
+    AddedAt,
+}
+
+#[derive(DeriveIden)]
+enum Conversations {
+    Table,
+    Id,
+}
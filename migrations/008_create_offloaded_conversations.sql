@@ -0,0 +1,12 @@
+-- offloaded_conversations table: pointers to conversations moved to cold storage
+-- No foreign key: the conversation row is deleted while it is offloaded
+CREATE TABLE IF NOT EXISTS offloaded_conversations (
+    conversation_id TEXT PRIMARY KEY,
+    label TEXT NOT NULL,
+    folder TEXT NOT NULL,
+    file_path TEXT NOT NULL,
+    message_count INTEGER NOT NULL,
+    offloaded_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_offloaded_folder ON offloaded_conversations(folder);
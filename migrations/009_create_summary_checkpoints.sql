@@ -0,0 +1,7 @@
+-- summary_checkpoints table: message count at the last automatic summary
+CREATE TABLE IF NOT EXISTS summary_checkpoints (
+    conversation_id TEXT PRIMARY KEY,
+    message_count INTEGER NOT NULL,
+    summarized_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (conversation_id) REFERENCES conversations(id) ON DELETE CASCADE
+);
@@ -0,0 +1,2 @@
+-- conversations.context_pinned: always include the conversation in assembled context
+ALTER TABLE conversations ADD COLUMN context_pinned INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,4 @@
+-- messages.seq: insertion order within a conversation, breaking timestamp ties.
+-- Existing rows keep 0 and tie among themselves as before.
+ALTER TABLE messages ADD COLUMN seq INTEGER NOT NULL DEFAULT 0;
+CREATE INDEX IF NOT EXISTS idx_messages_conversation_order ON messages(conversation_id, timestamp, seq);
@@ -0,0 +1,10 @@
+-- conversation_templates table: named blueprints for recurring conversations
+CREATE TABLE IF NOT EXISTS conversation_templates (
+    name TEXT PRIMARY KEY,
+    label_pattern TEXT NOT NULL,
+    folder TEXT NOT NULL,
+    system_message TEXT,
+    tags TEXT NOT NULL DEFAULT '[]',
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
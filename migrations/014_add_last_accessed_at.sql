@@ -0,0 +1,11 @@
+-- conversations.last_accessed_at: when the conversation was last read
+ALTER TABLE conversations ADD COLUMN last_accessed_at TEXT;
+
+-- Recording a read must not count as an update
+DROP TRIGGER IF EXISTS update_conversations_updated_at;
+CREATE TRIGGER update_conversations_updated_at
+AFTER UPDATE ON conversations
+WHEN NEW.last_accessed_at IS OLD.last_accessed_at
+BEGIN
+    UPDATE conversations SET updated_at = strftime('%Y-%m-%d %H:%M:%f', 'now') WHERE id = OLD.id;
+END;
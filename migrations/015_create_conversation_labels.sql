@@ -0,0 +1,11 @@
+-- conversation_labels table: curated labels beyond a conversation's primary one
+CREATE TABLE IF NOT EXISTS conversation_labels (
+    conversation_id TEXT NOT NULL,
+    label TEXT NOT NULL,
+    weight REAL NOT NULL DEFAULT 1.0,
+    added_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (conversation_id, label),
+    FOREIGN KEY (conversation_id) REFERENCES conversations(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_conversation_labels_label ON conversation_labels(label);
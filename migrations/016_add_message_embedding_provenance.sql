@@ -0,0 +1,4 @@
+-- messages.embedding_model / embedding_dim: the model that produced the
+-- message's vector and the vector's length, recorded at embed time
+ALTER TABLE messages ADD COLUMN embedding_model TEXT;
+ALTER TABLE messages ADD COLUMN embedding_dim INTEGER;
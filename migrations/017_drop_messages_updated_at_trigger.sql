@@ -0,0 +1,3 @@
+-- messages has no updated_at; the trigger from 006 overwrote the message's
+-- creation timestamp on every UPDATE instead
+DROP TRIGGER IF EXISTS update_messages_updated_at;
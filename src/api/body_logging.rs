@@ -0,0 +1,241 @@
+//! Opt-in debug logging of REST request and response bodies
+
+use axum::{
+    body::{to_bytes, Body, Bytes, HttpBody},
+    extract::{Request, State},
+    http::{header, HeaderMap},
+    middleware::Next,
+    response::Response,
+};
+use serde_json::Value;
+use std::sync::Arc;
+
+use crate::models::redaction::REDACTION_PLACEHOLDER;
+
+/// Bodies larger than this (or of unknown length, such as event streams) are
+/// passed through without being buffered or logged
+const MAX_BUFFERED_BYTES: u64 = 2 * 1024 * 1024;
+
+/// What [`body_logging_middleware`] writes out
+#[derive(Debug, Clone)]
+pub struct BodyLogSettings {
+    /// Bytes of each body logged before it is cut off
+    pub max_bytes: usize,
+    /// Lowercased JSON field names whose values are redacted
+    pub redact_fields: Vec<String>,
+}
+
+impl BodyLogSettings {
+    pub fn new(max_bytes: usize, redact_fields: &[String]) -> Self {
+        Self {
+            max_bytes,
+            redact_fields: redact_fields.iter().map(|f| f.to_lowercase()).collect(),
+        }
+    }
+}
+
+/// Log each request and its response, headers and body, at debug level.
+///
+/// Bodies are buffered and handed on unchanged, so handlers and clients see
+/// exactly what they would without the middleware. Nothing is buffered when
+/// debug logging is disabled.
+pub async fn body_logging_middleware(
+    State(settings): State<Arc<BodyLogSettings>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !tracing::enabled!(tracing::Level::DEBUG) {
+        return next.run(request).await;
+    }
+
+    let (parts, body) = request.into_parts();
+    let target = format!("{} {}", parts.method, parts.uri);
+    let (body, bytes) = buffer(body).await;
+    tracing::debug!(
+        "{}",
+        format_entry(
+            &format!("→ {}", target),
+            &parts.headers,
+            bytes.as_ref(),
+            &settings
+        )
+    );
+
+    let response = next.run(Request::from_parts(parts, body)).await;
+
+    let (parts, body) = response.into_parts();
+    let (body, bytes) = buffer(body).await;
+    tracing::debug!(
+        "{}",
+        format_entry(
+            &format!("← {} {}", parts.status.as_u16(), target),
+            &parts.headers,
+            bytes.as_ref(),
+            &settings
+        )
+    );
+
+    Response::from_parts(parts, body)
+}
+
+/// Read `body` into memory when its length is known and small enough,
+/// returning a replacement body with the same content
+async fn buffer(body: Body) -> (Body, Option<Bytes>) {
+    match body.size_hint().exact() {
+        Some(len) if len <= MAX_BUFFERED_BYTES => match to_bytes(body, len as usize).await {
+            Ok(bytes) => (Body::from(bytes.clone()), Some(bytes)),
+            Err(e) => {
+                tracing::debug!("Failed to buffer body for logging: {}", e);
+                (Body::empty(), None)
+            }
+        },
+        _ => (body, None),
+    }
+}
+
+/// One log line: `prefix`, the headers with `Authorization` redacted, and the
+/// body with sensitive fields redacted, cut to `settings.max_bytes`
+pub fn format_entry(
+    prefix: &str,
+    headers: &HeaderMap,
+    body: Option<&Bytes>,
+    settings: &BodyLogSettings,
+) -> String {
+    let headers = headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if name == header::AUTHORIZATION {
+                REDACTION_PLACEHOLDER
+            } else {
+                value.to_str().unwrap_or("<binary>")
+            };
+            format!("{}: {}", name, value)
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let body = match body {
+        None => "<not buffered>".to_string(),
+        Some(bytes) if bytes.is_empty() => "<empty>".to_string(),
+        Some(bytes) => truncate(&redact_body(bytes, settings), settings.max_bytes),
+    };
+
+    format!("{} headers=[{}] body={}", prefix, headers, body)
+}
+
+fn redact_body(bytes: &Bytes, settings: &BodyLogSettings) -> String {
+    match serde_json::from_slice::<Value>(bytes) {
+        Ok(mut json) if !settings.redact_fields.is_empty() => {
+            redact_fields(&mut json, &settings.redact_fields);
+            json.to_string()
+        }
+        _ => String::from_utf8_lossy(bytes).into_owned(),
+    }
+}
+
+fn redact_fields(value: &mut Value, fields: &[String]) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if fields.contains(&key.to_lowercase()) {
+                    *value = Value::String(REDACTION_PLACEHOLDER.to_string());
+                } else {
+                    redact_fields(value, fields);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|v| redact_fields(v, fields)),
+        _ => {}
+    }
+}
+
+fn truncate(text: &str, max_bytes: usize) -> String {
+    if text.len() <= max_bytes {
+        return text.to_string();
+    }
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}… ({} bytes total)", &text[..end], text.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode, middleware, routing::post, Router};
+    use std::io::Write;
+    use std::sync::Mutex;
+    use tower::ServiceExt;
+
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn app(settings: BodyLogSettings) -> Router {
+        Router::new()
+            .route("/echo", post(|body: String| async move { body }))
+            .layer(middleware::from_fn_with_state(
+                Arc::new(settings),
+                body_logging_middleware,
+            ))
+    }
+
+    fn echo_request(body: &str) -> Request {
+        Request::builder()
+            .method("POST")
+            .uri("/echo")
+            .header(header::AUTHORIZATION, "Bearer super-secret-key")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_logs_bodies_with_authorization_redacted() {
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let settings = BodyLogSettings::new(4096, &["password".to_string()]);
+        let response = app(settings)
+            .oneshot(echo_request(r#"{"user":"ada","password":"hunter2"}"#))
+            .await
+            .unwrap();
+
+        // The handler still received the full body
+        assert_eq!(response.status(), StatusCode::OK);
+        let echoed = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(echoed, r#"{"user":"ada","password":"hunter2"}"#);
+
+        let logged = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        assert!(logged.contains("→ POST /echo"));
+        assert!(logged.contains("authorization: [REDACTED]"));
+        assert!(logged.contains(r#""password":"[REDACTED]""#));
+        assert!(logged.contains("← 200 POST /echo"));
+        assert!(!logged.contains("super-secret-key"));
+        assert!(!logged.contains("hunter2"));
+    }
+
+    #[test]
+    fn test_body_is_cut_to_max_bytes() {
+        let settings = BodyLogSettings::new(5, &[]);
+        let body = Bytes::from("abcdefghij");
+        let line = format_entry("→ POST /x", &HeaderMap::new(), Some(&body), &settings);
+        assert!(line.ends_with("body=abcde… (10 bytes total)"));
+    }
+}
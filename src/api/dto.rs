@@ -1,5 +1,6 @@
 use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
@@ -8,7 +9,9 @@ use uuid::Uuid;
 #[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct CreateConversationRequest {
     pub label: String,
-    pub folder: String,
+    /// Defaults to the configured `default_folder`
+    #[serde(default)]
+    pub folder: Option<String>,
     pub messages: Vec<MessageDto>,
 }
 
@@ -18,6 +21,11 @@ pub struct MessageDto {
     pub content: String,
 }
 
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct AppendMessagesRequest {
+    pub messages: Vec<MessageDto>,
+}
+
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct UpdateLabelRequest {
     pub label: String,
@@ -29,12 +37,113 @@ pub struct UpdateFolderRequest {
     pub folder: String,
 }
 
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct PatchConversationRequest {
+    pub label: Option<String>,
+    pub folder: Option<String>,
+    pub status: Option<String>,
+    pub importance_score: Option<i32>,
+    pub pinned: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateMessageMetadataRequest {
+    /// Must be a JSON object
+    pub metadata: serde_json::Value,
+    /// Shallow-merge into the existing metadata instead of replacing it
+    #[serde(default = "default_merge")]
+    pub merge: bool,
+}
+
+fn default_merge() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AddLabelRequest {
+    pub label: String,
+    /// From 0.0 to 1.0; re-adding a label updates its weight
+    #[serde(default = "default_label_weight")]
+    pub weight: f64,
+}
+
+fn default_label_weight() -> f64 {
+    1.0
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct MoveMessageRequest {
+    pub target_conversation_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BulkMoveRequest {
+    pub ids: Vec<Uuid>,
+    pub folder: String,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BulkDeleteRequest {
+    pub ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BulkAddTagsRequest {
+    pub conversation_ids: Vec<Uuid>,
+    pub tags: Vec<String>,
+}
+
 #[derive(Debug, Deserialize, ToSchema)]
+pub struct BulkRemoveTagRequest {
+    pub conversation_ids: Vec<Uuid>,
+    pub tag: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BulkStatusRequest {
+    pub ids: Vec<Uuid>,
+    pub status: String,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RenameFolderRequest {
+    pub from: String,
+    pub to: String,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, ToSchema)]
 pub struct QueryRequest {
     pub query: String,
     pub filters: Option<serde_json::Value>,
     pub limit: Option<u32>,
     pub offset: Option<u32>,
+    /// Re-rank semantic candidates with the LLM bridge before returning
+    #[serde(default)]
+    pub rerank: bool,
+    /// Per-role score multipliers, overriding the configured defaults
+    pub role_weights: Option<HashMap<String, f32>>,
+    /// Leave out results from these folders and their subfolders. Replaces
+    /// the configured `search_exclude_folders`; send `[]` to search everywhere.
+    #[serde(default)]
+    pub exclude_folders: Option<Vec<String>>,
+    /// Leave out results from conversations not updated in this many days.
+    /// Replaces the configured `search_max_age_days`; send `0` for no limit.
+    #[serde(default)]
+    pub max_age_days: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ConversationQueryRequest {
+    pub query: String,
+    pub filters: Option<serde_json::Value>,
+    /// Maximum number of conversations to return
+    pub limit: Option<u32>,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -45,6 +154,14 @@ pub struct FtsSearchRequest {
     pub query: String,
     #[serde(default = "default_limit")]
     pub limit: usize,
+    /// Only match messages at or after this time
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub after: Option<NaiveDateTime>,
+    /// Only match messages before this time
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub before: Option<NaiveDateTime>,
+    /// Only match messages in this conversation
+    pub conversation_id: Option<Uuid>,
 }
 
 fn default_limit() -> usize {
@@ -54,28 +171,165 @@ fn default_limit() -> usize {
 #[derive(Debug, Serialize, ToSchema)]
 pub struct FtsSearchResponse {
     pub results: Vec<crate::models::internal::Message>,
-    pub total: usize,
+    /// Total number of matching messages, ignoring `limit`
+    pub total: u64,
 }
 
 // ==================== RESPONSE DTOs ====================
 
 #[derive(Debug, Serialize, ToSchema)]
+pub struct SummaryListResponse {
+    pub conversation_id: Uuid,
+    pub summaries: Vec<crate::models::internal::StoredSummary>,
+    pub total: usize,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ConversationHistoryResponse {
+    pub conversation_id: Uuid,
+    /// Oldest change first
+    pub changes: Vec<crate::models::internal::ConversationChange>,
+    pub total: usize,
+}
+
+/// Body of `PUT /api/v1/templates/{name}`; text fields may use the date
+/// variables described on `ConversationTemplate`
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SaveTemplateRequest {
+    pub label_pattern: String,
+    pub folder: String,
+    pub system_message: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TemplateListResponse {
+    pub templates: Vec<crate::models::internal::ConversationTemplate>,
+    pub total: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AppendMessagesResponse {
+    pub conversation_id: Uuid,
+    pub message_ids: Vec<Uuid>,
+    /// Messages in the conversation after this request
+    pub message_count: u64,
+    /// The conversation's word count after this request, as stored
+    pub word_count: i32,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ConversationLabelsResponse {
+    pub conversation_id: Uuid,
+    /// The primary label
+    pub label: String,
+    /// Additional labels, heaviest first
+    pub labels: Vec<crate::models::internal::ConversationLabel>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BulkOperationResponse {
+    /// Conversations changed (or that would change, for a dry run)
+    pub affected_ids: Vec<Uuid>,
+    pub count: usize,
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BulkTagResponse {
+    /// One entry per requested conversation, in request order
+    pub results: Vec<crate::models::internal::TagUpdate>,
+    /// Conversations whose tags changed
+    pub updated: usize,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BulkDeleteResponse {
+    /// Conversations that existed and were deleted
+    pub deleted_ids: Vec<Uuid>,
+    pub deleted: usize,
+    /// Vectors removed from the vector store, one per chunk for messages
+    /// embedded in chunks
+    pub vectors_removed: usize,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FolderTreeResponse {
+    pub folders: Vec<crate::models::folder_tree::FolderNode>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OffloadResponse {
+    pub conversation_id: Uuid,
+    /// JSON file holding the offloaded conversation
+    pub file_path: String,
+    pub message_count: i32,
+    #[schema(value_type = String, format = DateTime)]
+    pub offloaded_at: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateConversationResponse {
+    pub id: Uuid,
+    /// Same as `id`, kept for older clients
+    pub conversation_id: Uuid,
+    pub label: String,
+    pub folder: String,
+    pub status: String,
+    pub message_count: usize,
+    /// The conversation's word count, as stored
+    pub word_count: i32,
+    pub message_ids: Vec<Uuid>,
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: NaiveDateTime,
+    /// Some messages were stored without a vector and are queued for retry
+    pub embeddings_pending: bool,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ConversationResponse {
     pub id: Uuid,
     pub label: String,
     pub folder: String,
     pub status: String,
     pub message_count: usize,
+    pub session_count: i32,
     #[schema(value_type = String, format = DateTime)]
     pub created_at: NaiveDateTime, // CHANGED: String → NaiveDateTime
+    /// Always included in assembled context
+    pub context_pinned: bool,
+    /// When the conversation was last read, before this request
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub last_accessed_at: Option<NaiveDateTime>,
 }
 
-#[derive(Debug, Serialize, ToSchema)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchBackend {
+    Semantic,
+    Fts,
+    /// The vector store couldn't be queried and no fallback answered
+    Unavailable,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct QueryResponse {
     pub results: Vec<SearchResultDto>,
     pub total: u32,
     pub page: u32,
     pub page_size: u32,
+    /// Which search path produced the results (search endpoints only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub search_backend: Option<SearchBackend>,
+    /// Time spent searching, in milliseconds (search endpoints only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub took_ms: Option<u64>,
+    /// Result limit applied after the server default and cap (search
+    /// endpoints only); may be lower than the requested limit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effective_limit: Option<u32>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -89,6 +343,35 @@ pub struct SearchResultDto {
     pub folder: String,
     #[schema(value_type = String, format = DateTime)]
     pub timestamp: NaiveDateTime, // CHANGED: String → NaiveDateTime
+    /// 1-based position assigned by the LLM re-ranker, when it ran
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub llm_rank: Option<usize>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ConversationSearchResultDto {
+    pub conversation_id: Uuid,
+    pub label: String,
+    pub folder: String,
+    pub status: String,
+    pub importance_score: i32,
+    #[schema(value_type = String, format = DateTime)]
+    pub updated_at: NaiveDateTime,
+    /// Best-ranked matching message and an excerpt of its content
+    pub best_message_id: Uuid,
+    pub snippet: String,
+    /// Score of the best-ranked matching message
+    pub score: f32,
+    /// Sum of the scores of all matching messages
+    pub aggregate_score: f32,
+    pub match_count: usize,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ConversationQueryResponse {
+    /// Conversations in the order of their best-ranked message
+    pub results: Vec<ConversationSearchResultDto>,
+    pub total: usize,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -99,8 +382,84 @@ pub struct HealthResponse {
 }
 
 #[derive(Debug, Serialize, ToSchema)]
+pub struct VersionResponse {
+    pub crate_version: String,
+    pub git_sha: String,
+    pub schema_version: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MessageEmbeddingResponse {
+    pub message_id: Uuid,
+    pub embedding_id: String,
+    /// Embedding model configured on this server
+    pub model: String,
+    pub dimension: usize,
+    /// Model recorded when the message was embedded; absent for vectors
+    /// stored before this was tracked
+    pub embedding_model: Option<String>,
+    /// Vector length recorded when the message was embedded
+    pub embedding_dim: Option<i32>,
+    /// Whole-message vector; empty when the message was embedded in chunks
+    pub embedding: Vec<f32>,
+    /// Chunk vectors in order, for messages too long to embed whole
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub chunks: Vec<MessageEmbeddingChunk>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MessageEmbeddingChunk {
+    pub embedding_id: String,
+    pub embedding: Vec<f32>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MessageAttachmentsResponse {
+    pub message_id: Uuid,
+    pub attachments: Vec<crate::models::internal::Attachment>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReindexFtsResponse {
+    pub indexed: u64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReconcileEmbeddingsResponse {
+    /// Vectors deleted because no message refers to them
+    pub orphaned_vectors_removed: usize,
+    /// Messages with no stored vector that were queued for re-embedding
+    pub missing_vectors_requeued: usize,
+}
+
+/// A background task was started; poll `/api/v1/tasks/{task_id}` for it
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TaskStartedResponse {
+    pub task_id: Uuid,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RecomputeAggregatesResponse {
+    /// Conversations whose `word_count` had drifted
+    pub conversations_corrected: u64,
+    /// Messages counted across all conversations
+    pub messages_counted: u64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OptimizeDbResponse {
+    pub status: String,
+    /// Database file size when the job was queued (`None` for in-memory)
+    pub size_before_bytes: Option<u64>,
+    /// Poll `/api/v1/tasks/{task_id}` for the sizes before and after
+    pub task_id: Uuid,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ErrorResponse {
     pub error: String,
+    /// Missing from auth rejections, which only carry `error`
+    #[serde(default)]
     pub code: u32,
 }
 
@@ -149,6 +508,44 @@ pub struct ContextAssembleRequest {
     pub context_budget: usize,
     #[serde(default)] // ← Optional, defaults to empty vec
     pub excluded_folders: Vec<String>,
+    /// Wrap the messages in a `ContextAssembleVerboseResponse` instead of
+    /// returning the bare array
+    #[serde(default)]
+    pub verbose: bool,
+    /// `prompt` returns a `ContextAssemblePromptResponse` instead of messages
+    #[serde(default)]
+    pub format: ContextFormat,
+}
+
+/// How `/api/v1/context/assemble` returns the selected messages
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ContextFormat {
+    #[default]
+    Messages,
+    /// One string with role-prefixed messages, ready to paste into a prompt
+    Prompt,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ContextAssemblePromptResponse {
+    pub prompt: String,
+    /// Estimated tokens taken by `prompt`
+    pub estimated_tokens: usize,
+    /// Messages rendered into `prompt`
+    pub message_count: usize,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ContextAssembleVerboseResponse {
+    pub messages: Vec<crate::models::internal::Message>,
+    /// Estimated tokens taken by `messages`
+    pub budget_used: usize,
+    /// Messages recalled before ranking and trimming to the budget
+    pub candidates_considered: usize,
+    /// Why `messages` is empty, when it is
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, ToSchema)]
@@ -169,6 +566,16 @@ pub struct SummaryResponse {
 #[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct PruneRequest {
     pub threshold_days: i64,
+    /// Stale conversations scoring below this (0.0-10.0) are recommended for
+    /// archiving; defaults to 5.0
+    #[serde(
+        default,
+        deserialize_with = "crate::models::importance::deserialize_optional_threshold"
+    )]
+    pub importance_threshold: Option<f32>,
+    /// Only suggest conversations in this folder and its subfolders
+    #[serde(default)]
+    pub folder: Option<String>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -206,6 +613,31 @@ pub struct LabelSuggestResponse {
     pub suggestions: Vec<LabelSuggestionDto>,
 }
 
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ContextCandidateDto {
+    pub message_id: Uuid,
+    pub conversation_id: Uuid,
+    /// `semantic`, `pinned` or `recent_label`
+    pub source: String,
+    /// Raw vector similarity (semantic candidates only)
+    pub similarity: Option<f32>,
+    pub importance: f32,
+    pub recency: f32,
+    pub label_match: bool,
+    /// Composite ranking score
+    pub score: f32,
+    pub selected: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ContextDebugResponse {
+    pub conversation_id: Uuid,
+    pub query: String,
+    pub context_budget: usize,
+    /// Every recalled candidate in ranking order, selected or not
+    pub candidates: Vec<ContextCandidateDto>,
+}
+
 #[derive(Debug, Serialize, ToSchema)]
 pub struct LabelSuggestionDto {
     pub label: String,
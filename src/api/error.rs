@@ -0,0 +1,109 @@
+//! Error type returned by REST handlers
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+
+use crate::api::dto::ErrorResponse;
+use crate::storage::repository::RepositoryError;
+
+/// A failed request, rendered as an `ErrorResponse` body with the matching
+/// status code
+#[derive(Debug, thiserror::Error)]
+pub enum AppError {
+    #[error("{0}")]
+    BadRequest(String),
+    #[error("{0}")]
+    NotFound(String),
+    #[error("{0}")]
+    ServiceUnavailable(String),
+    #[error("{0}")]
+    Internal(String),
+}
+
+impl AppError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            AppError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::ServiceUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let status = self.status();
+        let body = ErrorResponse {
+            error: self.to_string(),
+            code: status.as_u16() as u32,
+        };
+        (status, Json(body)).into_response()
+    }
+}
+
+impl From<RepositoryError> for AppError {
+    fn from(err: RepositoryError) -> Self {
+        match err {
+            RepositoryError::NotFound(message) => AppError::NotFound(message),
+            RepositoryError::InvalidInput(message) => AppError::BadRequest(message),
+            e => AppError::Internal(e.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    async fn render(err: AppError) -> (StatusCode, Value) {
+        let response = err.into_response();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), 1024)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_variants_map_to_status_and_body() {
+        let cases = [
+            (AppError::BadRequest("bad".into()), StatusCode::BAD_REQUEST),
+            (AppError::NotFound("missing".into()), StatusCode::NOT_FOUND),
+            (
+                AppError::ServiceUnavailable("down".into()),
+                StatusCode::SERVICE_UNAVAILABLE,
+            ),
+            (
+                AppError::Internal("boom".into()),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ),
+        ];
+
+        for (err, expected) in cases {
+            let message = err.to_string();
+            let (status, body) = render(err).await;
+            assert_eq!(status, expected);
+            assert_eq!(body, json!({ "error": message, "code": expected.as_u16() }));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_repository_errors_convert() {
+        let (status, body) =
+            render(RepositoryError::NotFound("Conversation x not found".into()).into()).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"], "Conversation x not found");
+
+        let (status, _) = render(RepositoryError::InvalidInput("empty".into()).into()).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, body) = render(RepositoryError::ChromaError("down".into()).into()).await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(body["error"], "Chroma error: down");
+    }
+}
@@ -0,0 +1,91 @@
+//! Flat-file renderings of API results for use outside Sekha
+
+use crate::api::dto::SearchResultDto;
+use crate::models::internal::{Conversation, Message};
+use std::borrow::Cow;
+use std::fmt::Write;
+
+/// Header row written by [`search_results_csv`]
+pub const SEARCH_CSV_HEADER: &str = "conversation_id,label,folder,score,timestamp,content";
+
+/// Render search results as RFC 4180 CSV, one row per result
+pub fn search_results_csv(results: &[SearchResultDto]) -> String {
+    let mut csv = String::from(SEARCH_CSV_HEADER);
+    csv.push_str("\r\n");
+
+    for r in results {
+        let _ = write!(
+            csv,
+            "{},{},{},{},{},{}\r\n",
+            r.conversation_id,
+            csv_field(&r.label),
+            csv_field(&r.folder),
+            r.score,
+            r.timestamp,
+            csv_field(&r.content),
+        );
+    }
+
+    csv
+}
+
+/// Quote `value` if it contains a delimiter, quote or line break, doubling
+/// any embedded quotes
+pub fn csv_field(value: &str) -> Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+/// Render a conversation as a Markdown document: a title, a details list
+/// and one section per message
+pub fn conversation_markdown(
+    conv: &Conversation,
+    messages: &[Message],
+    include_metadata: bool,
+) -> String {
+    let mut doc = format!("# {}\n\n", conv.label);
+    let _ = writeln!(doc, "- Folder: {}", conv.folder);
+    let _ = writeln!(doc, "- Created: {}", conv.created_at);
+    if include_metadata {
+        let _ = writeln!(doc, "- Status: {}", conv.status);
+        let _ = writeln!(doc, "- Importance: {}", conv.importance_score);
+        let _ = writeln!(doc, "- Words: {}", conv.word_count);
+    }
+
+    for msg in messages {
+        let _ = write!(
+            doc,
+            "\n## {} ({})\n\n{}\n",
+            msg.role, msg.timestamp, msg.content
+        );
+    }
+
+    doc
+}
+
+/// Render a conversation as plain text: a short header, then one
+/// `[timestamp] role: content` line per message
+pub fn conversation_text(
+    conv: &Conversation,
+    messages: &[Message],
+    include_metadata: bool,
+) -> String {
+    let mut doc = format!("{}\n", conv.label);
+    let _ = writeln!(doc, "Folder: {}", conv.folder);
+    let _ = writeln!(doc, "Created: {}", conv.created_at);
+    if include_metadata {
+        let _ = writeln!(doc, "Status: {}", conv.status);
+        let _ = writeln!(doc, "Importance: {}", conv.importance_score);
+        let _ = writeln!(doc, "Words: {}", conv.word_count);
+    }
+    doc.push('\n');
+
+    for msg in messages {
+        let _ = writeln!(doc, "[{}] {}: {}", msg.timestamp, msg.role, msg.content);
+    }
+
+    doc
+}
@@ -0,0 +1,43 @@
+use crate::api::dto::ErrorResponse;
+use axum::extract::rejection::JsonRejection;
+use axum::extract::{FromRequest, Request};
+use axum::http::StatusCode;
+use axum::Json;
+use serde::de::DeserializeOwned;
+
+/// Drop-in replacement for `Json` whose rejections use the `ErrorResponse`
+/// shape. Malformed or mistyped bodies become a 400 naming the offending
+/// field (e.g. "messages[0]: missing field `role`").
+pub struct ApiJson<T>(pub T);
+
+impl<T, S> FromRequest<S> for ApiJson<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, Json<ErrorResponse>);
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        match Json::<T>::from_request(req, state).await {
+            Ok(Json(value)) => Ok(ApiJson(value)),
+            Err(rejection) => Err(json_rejection_response(rejection)),
+        }
+    }
+}
+
+fn json_rejection_response(rejection: JsonRejection) -> (StatusCode, Json<ErrorResponse>) {
+    let status = match rejection {
+        JsonRejection::JsonDataError(_) | JsonRejection::JsonSyntaxError(_) => {
+            StatusCode::BAD_REQUEST
+        }
+        _ => rejection.status(),
+    };
+
+    (
+        status,
+        Json(ErrorResponse {
+            error: rejection.body_text(),
+            code: status.as_u16() as u32,
+        }),
+    )
+}
@@ -1,3 +1,5 @@
+use crate::api::exporters::{conversation_markdown, conversation_text};
+use crate::api::extract::ApiJson;
 use crate::api::routes::AppState;
 use crate::config::Config;
 use axum::routing::post;
@@ -14,8 +16,13 @@ use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::{
-    api::dto::*, auth::McpAuth, models::internal::Conversation,
-    storage::repository::ConversationRepository,
+    api::dto::*,
+    api::error::AppError,
+    auth::McpAuth,
+    models::internal::Conversation,
+    orchestrator::context_assembly::{estimate_tokens, format_prompt},
+    services::event_bus::MemoryEvent,
+    storage::repository::{ConversationRepository, RepositoryError},
 };
 
 #[cfg(test)]
@@ -45,6 +52,7 @@ mod tests {
             conversation_id: Uuid::new_v4(),
             message_id: Uuid::new_v4(),
             score: 0.95,
+            role: "user".to_string(),
             content: "Test message content".to_string(),
             label: "Test Label".to_string(),
             folder: "/test".to_string(),
@@ -77,7 +85,11 @@ mod tests {
             repo,
             orchestrator,
             embedding_service,
+            embedding_queue: Arc::new(crate::services::embedding_queue::EmbeddingQueue::new()),
             chroma_client,
+            events: crate::services::EventBus::default(),
+            search_cache: Default::default(),
+            tasks: Default::default(),
         };
 
         // Call memory_search (this executes the formatting code)
@@ -117,6 +129,7 @@ mod tests {
             conversation_id: Uuid::new_v4(),
             message_id: Uuid::new_v4(),
             score: 0.95,
+            role: "user".to_string(),
             content: "Test message".to_string(),
             label: "Test Label".to_string(),
             folder: "/test".to_string(),
@@ -190,10 +203,12 @@ pub async fn memory_store(
     State(state): State<AppState>,
     Json(args): Json<MemoryStoreArgs>,
 ) -> Result<Json<McpToolResponse>, StatusCode> {
-    let id = Uuid::new_v4();
+    let id = crate::models::ids::new_id(state.config.read().await.id_scheme);
     let now = chrono::Utc::now().naive_utc();
 
-    let importance = args.importance_score.unwrap_or(5);
+    let importance = args
+        .importance_score
+        .unwrap_or(crate::models::importance::DEFAULT_IMPORTANCE);
     let word_count: i32 = args.messages.iter().map(|m| m.content.len() as i32).sum();
 
     // ✅ Convert MessageDto to NewMessage
@@ -231,6 +246,9 @@ pub async fn memory_store(
             tracing::error!("Failed to create conversation: {}", e);
             StatusCode::INTERNAL_SERVER_ERROR
         })?;
+    state.events.publish(MemoryEvent::Created {
+        conversation_id: id,
+    });
 
     Ok(Json(McpToolResponse {
         success: true,
@@ -251,22 +269,23 @@ pub struct MemorySearchArgs {
     query: String,
     #[serde(default)]
     filters: Option<Value>,
-    #[serde(default = "default_limit")]
+    /// Defaults to the configured `default_search_limit`
+    #[serde(default)]
     limit: Option<u32>,
     #[serde(default)]
     offset: Option<u32>,
 }
 
-pub fn default_limit() -> Option<u32> {
-    Some(10)
-}
-
 pub async fn memory_search(
     _auth: McpAuth,
     State(state): State<AppState>,
     Json(args): Json<MemorySearchArgs>,
-) -> Result<Json<McpToolResponse>, StatusCode> {
-    let limit = args.limit.unwrap_or(10) as usize;
+) -> Result<Json<McpToolResponse>, AppError> {
+    if args.query.trim().is_empty() {
+        return Err(AppError::BadRequest("query must not be empty".to_string()));
+    }
+
+    let limit = state.config.read().await.effective_search_limit(args.limit) as usize;
     let filters = args.filters;
 
     // Use repository's semantic search
@@ -274,9 +293,9 @@ pub async fn memory_search(
         .repo
         .semantic_search(&args.query, limit, filters)
         .await
-        .map_err(|e| {
-            tracing::error!("Search failed: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
+        .map_err(|e| match e {
+            RepositoryError::InvalidInput(_) => AppError::from(e),
+            e => AppError::Internal(format!("Semantic search failed: {}", e)),
         })?;
 
     // Format results for MCP response
@@ -350,6 +369,9 @@ pub async fn memory_update(
             .update_label(args.conversation_id, new_label, new_folder)
             .await
             .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        state.events.publish(MemoryEvent::Updated {
+            conversation_id: args.conversation_id,
+        });
 
         updated_fields.push("label/folder");
     }
@@ -381,7 +403,10 @@ pub async fn memory_update(
 pub struct MemoryPruneArgs {
     #[serde(default = "default_threshold_days")]
     threshold_days: i64,
-    #[serde(default = "default_importance_threshold")]
+    #[serde(
+        default = "default_importance_threshold",
+        deserialize_with = "crate::models::importance::deserialize_threshold"
+    )]
     importance_threshold: f32,
 }
 
@@ -390,7 +415,7 @@ fn default_threshold_days() -> i64 {
 }
 
 fn default_importance_threshold() -> f32 {
-    5.0
+    crate::orchestrator::pruning_engine::DEFAULT_ARCHIVE_IMPORTANCE_THRESHOLD
 }
 
 #[derive(Debug, Serialize)]
@@ -408,7 +433,7 @@ pub struct PruningSuggestionDto {
 pub async fn memory_prune(
     _auth: McpAuth,
     State(state): State<AppState>,
-    Json(args): Json<MemoryPruneArgs>,
+    ApiJson(args): ApiJson<MemoryPruneArgs>,
 ) -> Result<Json<McpToolResponse>, StatusCode> {
     use crate::orchestrator::pruning_engine::PruningEngine;
     use crate::services::llm_bridge_client::LlmBridgeClient;
@@ -418,7 +443,9 @@ pub async fn memory_prune(
     let llm_bridge = Arc::new(LlmBridgeClient::new(config.ollama_url.clone()));
 
     // Create pruning engine
-    let pruning_engine = PruningEngine::new(state.repo.clone(), llm_bridge);
+    let pruning_engine = PruningEngine::new(state.repo.clone(), llm_bridge)
+        .with_retention_policies(config.retention_policies.clone())
+        .with_last_access_signal(config.prune_by_last_access);
 
     // Generate pruning suggestions
     let suggestions = pruning_engine
@@ -483,21 +510,25 @@ pub async fn memory_get_context(
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
     match conv {
-        Some(c) => Ok(Json(McpToolResponse {
-            success: true,
-            data: Some(serde_json::json!({
-                "conversation_id": c.id,
-                "label": c.label,
-                "status": c.status,
-                "folder": c.folder,
-                "importance_score": c.importance_score,
-                "word_count": c.word_count,
-                "session_count": c.session_count,
-                "created_at": c.created_at.to_string(),
-                "updated_at": c.updated_at.to_string(),
-            })),
-            error: None,
-        })),
+        Some(c) => {
+            state.record_access(c.id);
+            Ok(Json(McpToolResponse {
+                success: true,
+                data: Some(serde_json::json!({
+                    "conversation_id": c.id,
+                    "label": c.label,
+                    "status": c.status,
+                    "folder": c.folder,
+                    "importance_score": c.importance_score,
+                    "word_count": c.word_count,
+                    "session_count": c.session_count,
+                    "created_at": c.created_at.to_string(),
+                    "updated_at": c.updated_at.to_string(),
+                    "last_accessed_at": c.last_accessed_at.map(|t| t.to_string()),
+                })),
+                error: None,
+            }))
+        }
         None => Err(StatusCode::NOT_FOUND),
     }
 }
@@ -507,10 +538,18 @@ pub async fn memory_get_context(
 #[derive(Debug, Deserialize)]
 pub struct MemoryExportArgs {
     conversation_id: Uuid,
+    /// `json` (default), `markdown` or `text`; `json` exports can be
+    /// dropped into the import directory to restore the conversation
     #[serde(default)]
     format: Option<String>,
     #[serde(default = "default_true")]
     include_metadata: bool,
+    /// Carry the conversation's hierarchical summaries along
+    #[serde(default)]
+    include_summaries: bool,
+    /// Carry the conversation's semantic tags along
+    #[serde(default)]
+    include_tags: bool,
 }
 
 fn default_true() -> bool {
@@ -522,6 +561,23 @@ pub async fn memory_export(
     State(state): State<AppState>,
     Json(args): Json<MemoryExportArgs>,
 ) -> Result<Json<McpToolResponse>, StatusCode> {
+    let format = args.format.unwrap_or_else(|| "json".to_string());
+    let content_type = match format.as_str() {
+        "json" => "application/json",
+        "markdown" => "text/markdown",
+        "text" => "text/plain",
+        other => {
+            return Ok(Json(McpToolResponse {
+                success: false,
+                data: None,
+                error: Some(format!(
+                    "Unknown format '{}': expected json, markdown or text",
+                    other
+                )),
+            }))
+        }
+    };
+
     // Get conversation metadata
     let conv = state
         .repo
@@ -530,22 +586,7 @@ pub async fn memory_export(
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
         .ok_or_else(|| StatusCode::NOT_FOUND)?;
 
-    // Get messages for this conversation
-    // Assuming you have a get_message_list method on repo
-    let messages = state
-        .repo
-        .get_message_list(args.conversation_id)
-        .await
-        .map_err(|e| {
-            tracing::error!("Failed to get messages for export: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
-
-    let format = args.format.unwrap_or_else(|| "json".to_string());
-
-    Ok(Json(McpToolResponse {
-        success: true,
-        data: Some(serde_json::json!({
+    let mut data = serde_json::json!({
             "conversation": {
                 "id": conv.id,
                 "label": conv.label,
@@ -557,10 +598,67 @@ pub async fn memory_export(
                 "created_at": conv.created_at.to_string(),
                 "updated_at": conv.updated_at.to_string(),
             },
-            "messages": messages,
             "format": format,
+            "content_type": content_type,
             "include_metadata": args.include_metadata,
-        })),
+    });
+
+    // JSON carries the raw message list, document formats the rendered text
+    if format == "json" {
+        let messages = state
+            .repo
+            .get_message_list(args.conversation_id)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to get messages for export: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+        data["messages"] = serde_json::json!(messages);
+    } else {
+        let messages = state
+            .repo
+            .get_conversation_messages(args.conversation_id)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to get messages for export: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+        let content = if format == "markdown" {
+            conversation_markdown(&conv, &messages, args.include_metadata)
+        } else {
+            conversation_text(&conv, &messages, args.include_metadata)
+        };
+        data["content"] = serde_json::json!(content);
+        data["message_count"] = serde_json::json!(messages.len());
+    }
+
+    if args.include_summaries {
+        let summaries = state
+            .repo
+            .find_summaries(args.conversation_id)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to get summaries for export: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+        data["summaries"] = serde_json::json!(summaries);
+    }
+
+    if args.include_tags {
+        let tags = state
+            .repo
+            .find_tags(args.conversation_id)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to get tags for export: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+        data["tags"] = serde_json::json!(tags);
+    }
+
+    Ok(Json(McpToolResponse {
+        success: true,
+        data: Some(data),
         error: None,
     }))
 }
@@ -652,15 +750,14 @@ pub async fn memory_stats(
                 StatusCode::INTERNAL_SERVER_ERROR
             })?;
 
-            let (convs, total_count) =
-                state
-                    .repo
-                    .find_with_filters(None, 10000, 0)
-                    .await
-                    .map_err(|e| {
-                        tracing::error!("Global stats - find_with_filters failed: {}", e);
-                        StatusCode::INTERNAL_SERVER_ERROR
-                    })?;
+            let (convs, total_count) = state
+                .repo
+                .find_with_filters(None, None, 10000, 0)
+                .await
+                .map_err(|e| {
+                    tracing::error!("Global stats - find_with_filters failed: {}", e);
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?;
 
             let data = serde_json::json!({
                 "total_conversations": total_count,
@@ -688,10 +785,338 @@ pub async fn memory_stats(
     }
 }
 
+// ==================== Tool: memory_assemble_context ====================
+
+/// Takes the same arguments as `POST /api/v1/context/assemble`
+pub async fn memory_assemble_context(
+    _auth: McpAuth,
+    State(state): State<AppState>,
+    Json(args): Json<ContextAssembleRequest>,
+) -> Result<Json<McpToolResponse>, StatusCode> {
+    let messages = state
+        .orchestrator
+        .assemble_context(
+            &args.query,
+            args.preferred_labels,
+            args.context_budget,
+            args.excluded_folders,
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!("Context assembly failed: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let data = if args.format == ContextFormat::Prompt {
+        let prompt = format_prompt(&messages);
+        serde_json::json!({
+            "query": args.query,
+            "context_budget": args.context_budget,
+            "estimated_tokens": estimate_tokens(&prompt),
+            "prompt": prompt,
+        })
+    } else {
+        let estimated_tokens: usize = messages.iter().map(|m| estimate_tokens(&m.content)).sum();
+        serde_json::json!({
+            "query": args.query,
+            "context_budget": args.context_budget,
+            "estimated_tokens": estimated_tokens,
+            "messages": messages,
+        })
+    };
+
+    Ok(Json(McpToolResponse {
+        success: true,
+        data: Some(data),
+        error: None,
+    }))
+}
+
+// ==================== Tool: memory_summarize ====================
+
+/// Takes the same arguments as `POST /api/v1/summarize`
+pub async fn memory_summarize(
+    _auth: McpAuth,
+    State(state): State<AppState>,
+    Json(args): Json<SummarizeRequest>,
+) -> Result<Json<McpToolResponse>, StatusCode> {
+    let summarizer = &state.orchestrator.summarizer;
+    let summary = match args.level.as_str() {
+        "daily" => {
+            summarizer
+                .generate_daily_summary(args.conversation_id)
+                .await
+        }
+        "weekly" => {
+            summarizer
+                .generate_weekly_summary(args.conversation_id)
+                .await
+        }
+        "monthly" => {
+            summarizer
+                .generate_monthly_summary(args.conversation_id)
+                .await
+        }
+        _ => {
+            tracing::warn!("Rejected memory_summarize with level {:?}", args.level);
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    }
+    .map_err(|e| {
+        tracing::error!("Summarization failed: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(McpToolResponse {
+        success: true,
+        data: Some(serde_json::json!({
+            "conversation_id": args.conversation_id,
+            "level": args.level,
+            "summary": summary,
+            "generated_at": chrono::Utc::now().naive_utc().to_string(),
+        })),
+        error: None,
+    }))
+}
+
+// ==================== Tool: memory_suggest_labels ====================
+
+/// Takes the same arguments as `POST /api/v1/labels/suggest`
+pub async fn memory_suggest_labels(
+    _auth: McpAuth,
+    State(state): State<AppState>,
+    Json(args): Json<LabelSuggestRequest>,
+) -> Result<Json<McpToolResponse>, StatusCode> {
+    let suggestions = state
+        .orchestrator
+        .suggest_labels(args.conversation_id)
+        .await
+        .map_err(|e| {
+            tracing::error!("Label suggestion failed: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let suggestions: Vec<Value> = suggestions
+        .into_iter()
+        .map(|s| {
+            serde_json::json!({
+                "label": s.label,
+                "confidence": s.confidence,
+                "is_existing": s.is_existing,
+                "reason": s.reason,
+            })
+        })
+        .collect();
+
+    Ok(Json(McpToolResponse {
+        success: true,
+        data: Some(serde_json::json!({
+            "conversation_id": args.conversation_id,
+            "suggestions": suggestions,
+        })),
+        error: None,
+    }))
+}
+
+// ==================== JSON-RPC 2.0 TRANSPORT ====================
+
+pub const JSONRPC_PARSE_ERROR: i64 = -32700;
+pub const JSONRPC_INVALID_REQUEST: i64 = -32600;
+pub const JSONRPC_METHOD_NOT_FOUND: i64 = -32601;
+pub const JSONRPC_INVALID_PARAMS: i64 = -32602;
+pub const JSONRPC_INTERNAL_ERROR: i64 = -32603;
+/// Implementation-defined server error, e.g. a conversation that doesn't exist
+pub const JSONRPC_SERVER_ERROR: i64 = -32000;
+
+#[derive(Debug, Deserialize)]
+pub struct JsonRpcRequest {
+    jsonrpc: String,
+    method: String,
+    #[serde(default)]
+    params: Value,
+    #[serde(default)]
+    id: Value,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JsonRpcResponse {
+    pub jsonrpc: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<JsonRpcError>,
+    pub id: Value,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JsonRpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl JsonRpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    /// Error for a tool handler that rejected the call with an HTTP status
+    fn from_status(status: StatusCode) -> Self {
+        let message = status.canonical_reason().unwrap_or("Tool call failed");
+        Self::with_status(status, message)
+    }
+
+    fn with_status(status: StatusCode, message: impl Into<String>) -> Self {
+        let code = match status {
+            StatusCode::BAD_REQUEST => JSONRPC_INVALID_PARAMS,
+            StatusCode::INTERNAL_SERVER_ERROR => JSONRPC_INTERNAL_ERROR,
+            _ => JSONRPC_SERVER_ERROR,
+        };
+
+        Self {
+            code,
+            message: message.into(),
+            data: Some(serde_json::json!({ "status": status.as_u16() })),
+        }
+    }
+}
+
+/// Tool handlers returning [`AppError`] keep their message
+impl From<AppError> for JsonRpcError {
+    fn from(err: AppError) -> Self {
+        Self::with_status(err.status(), err.to_string())
+    }
+}
+
+impl JsonRpcResponse {
+    fn success(id: Value, result: Value) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            result: Some(result),
+            error: None,
+            id,
+        }
+    }
+
+    fn failure(id: Value, error: JsonRpcError) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            result: None,
+            error: Some(error),
+            id,
+        }
+    }
+}
+
+/// Single-endpoint alternative to the per-tool routes: `method` names the tool,
+/// `params` carries its arguments and the tool's `data` comes back as `result`.
+pub async fn json_rpc(
+    auth: McpAuth,
+    State(state): State<AppState>,
+    body: axum::body::Bytes,
+) -> Json<JsonRpcResponse> {
+    let value: Value = match serde_json::from_slice(&body) {
+        Ok(value) => value,
+        Err(e) => {
+            return Json(JsonRpcResponse::failure(
+                Value::Null,
+                JsonRpcError::new(JSONRPC_PARSE_ERROR, e.to_string()),
+            ))
+        }
+    };
+    let id = value.get("id").cloned().unwrap_or(Value::Null);
+
+    let request: JsonRpcRequest = match serde_json::from_value(value) {
+        Ok(request) => request,
+        Err(e) => {
+            return Json(JsonRpcResponse::failure(
+                id,
+                JsonRpcError::new(JSONRPC_INVALID_REQUEST, e.to_string()),
+            ))
+        }
+    };
+    if request.jsonrpc != "2.0" {
+        return Json(JsonRpcResponse::failure(
+            request.id,
+            JsonRpcError::new(JSONRPC_INVALID_REQUEST, "jsonrpc must be \"2.0\""),
+        ));
+    }
+
+    Json(
+        match dispatch_tool(auth, state, &request.method, request.params).await {
+            Ok(result) => JsonRpcResponse::success(request.id, result),
+            Err(error) => JsonRpcResponse::failure(request.id, error),
+        },
+    )
+}
+
+async fn dispatch_tool(
+    auth: McpAuth,
+    state: AppState,
+    method: &str,
+    params: Value,
+) -> Result<Value, JsonRpcError> {
+    let state = State(state);
+    let response = match method {
+        "memory_store" => memory_store(auth, state, Json(tool_params(params)?)).await,
+        "memory_get_context" => memory_get_context(auth, state, Json(tool_params(params)?)).await,
+        "memory_update" => memory_update(auth, state, Json(tool_params(params)?)).await,
+        "memory_search" => Ok(memory_search(auth, state, Json(tool_params(params)?)).await?),
+        "memory_prune" => memory_prune(auth, state, ApiJson(tool_params(params)?)).await,
+        "memory_export" => memory_export(auth, state, Json(tool_params(params)?)).await,
+        "memory_stats" => memory_stats(auth, state, Json(tool_params(params)?)).await,
+        "memory_assemble_context" => {
+            memory_assemble_context(auth, state, Json(tool_params(params)?)).await
+        }
+        "memory_summarize" => memory_summarize(auth, state, Json(tool_params(params)?)).await,
+        "memory_suggest_labels" => {
+            memory_suggest_labels(auth, state, Json(tool_params(params)?)).await
+        }
+        _ => {
+            return Err(JsonRpcError::new(
+                JSONRPC_METHOD_NOT_FOUND,
+                format!("Unknown method: {}", method),
+            ))
+        }
+    };
+
+    match response {
+        Ok(Json(McpToolResponse {
+            success: true,
+            data,
+            ..
+        })) => Ok(data.unwrap_or(Value::Null)),
+        // Tools only report failure in-band for arguments they can't act on
+        Ok(Json(McpToolResponse { error, .. })) => Err(JsonRpcError::new(
+            JSONRPC_INVALID_PARAMS,
+            error.unwrap_or_else(|| "Tool call failed".to_string()),
+        )),
+        Err(status) => Err(JsonRpcError::from_status(status)),
+    }
+}
+
+/// Omitted `params` means a tool called with no arguments
+fn tool_params<T: serde::de::DeserializeOwned>(params: Value) -> Result<T, JsonRpcError> {
+    let params = if params.is_null() {
+        Value::Object(Default::default())
+    } else {
+        params
+    };
+
+    serde_json::from_value(params)
+        .map_err(|e| JsonRpcError::new(JSONRPC_INVALID_PARAMS, e.to_string()))
+}
+
 // ==================== ROUTER & LEGACY COMPATIBILITY ====================
 
 pub fn create_mcp_router(state: AppState) -> Router {
     Router::new()
+        .route("/mcp", post(json_rpc))
         .route("/mcp/tools/memory_store", post(memory_store))
         .route("/mcp/tools/memory_get_context", post(memory_get_context))
         .route("/mcp/tools/memory_update", post(memory_update))
@@ -699,5 +1124,14 @@ pub fn create_mcp_router(state: AppState) -> Router {
         .route("/mcp/tools/memory_prune", post(memory_prune))
         .route("/mcp/tools/memory_export", post(memory_export))
         .route("/mcp/tools/memory_stats", post(memory_stats))
+        .route(
+            "/mcp/tools/memory_assemble_context",
+            post(memory_assemble_context),
+        )
+        .route("/mcp/tools/memory_summarize", post(memory_summarize))
+        .route(
+            "/mcp/tools/memory_suggest_labels",
+            post(memory_suggest_labels),
+        )
         .with_state(state)
 }
@@ -1,5 +1,10 @@
+pub mod body_logging;
 pub mod dto;
+pub mod error;
+pub mod exporters;
+pub mod extract;
 pub mod mcp;
 pub mod rate_limiter;
 pub mod route;
 pub mod routes;
+pub mod timeout;
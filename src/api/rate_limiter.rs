@@ -2,7 +2,7 @@
 
 use axum::{
     extract::{Request, State},
-    http::StatusCode,
+    http::{HeaderValue, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
 };
@@ -12,11 +12,28 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
+/// Header set on allowed requests once a client passes the soft limit
+pub const RATE_LIMIT_WARNING_HEADER: &str = "x-ratelimit-warning";
+
+/// Outcome of counting one request against the limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitDecision {
+    Allowed,
+    /// Allowed, but past the soft limit; `used` of `limit` requests spent
+    Warned {
+        used: u32,
+        limit: u32,
+    },
+    Rejected,
+}
+
 /// Rate limiter state tracking requests per IP
 #[derive(Clone)]
 pub struct RateLimiter {
     /// Maximum requests per minute
     max_requests: u32,
+    /// Requests per minute after which responses carry a warning header
+    soft_limit: Option<u32>,
     /// Request tracking: IP -> (count, window_start)
     requests: Arc<RwLock<HashMap<IpAddr, (u32, Instant)>>>,
 }
@@ -26,38 +43,60 @@ impl RateLimiter {
     pub fn new(requests_per_minute: u32) -> Self {
         Self {
             max_requests: requests_per_minute,
+            soft_limit: None,
             requests: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
+    /// Warn clients once they have used more than `percent` of the limit in
+    /// the current window; 0 (or 100 and above) disables warnings
+    pub fn with_soft_limit_percent(mut self, percent: u8) -> Self {
+        self.soft_limit = (1..100)
+            .contains(&percent)
+            .then(|| (u64::from(self.max_requests) * u64::from(percent) / 100) as u32);
+        self
+    }
+
     /// Check if request is allowed for given IP
     pub async fn check_rate_limit(&self, ip: IpAddr) -> bool {
+        self.check(ip).await != RateLimitDecision::Rejected
+    }
+
+    /// Count a request from `ip` and decide whether it goes through
+    pub async fn check(&self, ip: IpAddr) -> RateLimitDecision {
         let mut requests = self.requests.write().await;
         let now = Instant::now();
         let window = Duration::from_secs(60);
 
-        match requests.get_mut(&ip) {
+        let used = match requests.get_mut(&ip) {
             Some((count, start)) => {
                 // Check if window has expired
                 if now.duration_since(*start) > window {
                     // Reset window
                     *count = 1;
                     *start = now;
-                    true
                 } else if *count < self.max_requests {
                     // Increment counter
                     *count += 1;
-                    true
                 } else {
                     // Rate limit exceeded
-                    false
+                    return RateLimitDecision::Rejected;
                 }
+                *count
             }
             None => {
                 // First request from this IP
                 requests.insert(ip, (1, now));
-                true
+                1
             }
+        };
+
+        match self.soft_limit {
+            Some(soft_limit) if used > soft_limit => RateLimitDecision::Warned {
+                used,
+                limit: self.max_requests,
+            },
+            _ => RateLimitDecision::Allowed,
         }
     }
 
@@ -87,16 +126,24 @@ pub async fn rate_limit_middleware(
         .unwrap_or_else(|| IpAddr::from([127, 0, 0, 1]));
 
     // Check rate limit
-    if limiter.check_rate_limit(ip).await {
-        // Allow request
-        next.run(request).await
-    } else {
+    match limiter.check(ip).await {
+        RateLimitDecision::Allowed => next.run(request).await,
+        RateLimitDecision::Warned { used, limit } => {
+            let mut response = next.run(request).await;
+            let warning = format!("{} of {} requests per minute used", used, limit);
+            if let Ok(value) = HeaderValue::from_str(&warning) {
+                response
+                    .headers_mut()
+                    .insert(RATE_LIMIT_WARNING_HEADER, value);
+            }
+            response
+        }
         // Rate limit exceeded
-        (
+        RateLimitDecision::Rejected => (
             StatusCode::TOO_MANY_REQUESTS,
             "Rate limit exceeded. Please try again later.",
         )
-            .into_response()
+            .into_response(),
     }
 }
 
@@ -143,6 +190,28 @@ mod tests {
         assert!(limiter.check_rate_limit(ip2).await);
     }
 
+    #[tokio::test]
+    async fn test_rate_limiter_warns_past_soft_limit() {
+        let limiter = RateLimiter::new(10).with_soft_limit_percent(80);
+        let ip = "127.0.0.1".parse().unwrap();
+
+        for _ in 0..8 {
+            assert_eq!(limiter.check(ip).await, RateLimitDecision::Allowed);
+        }
+        assert_eq!(
+            limiter.check(ip).await,
+            RateLimitDecision::Warned { used: 9, limit: 10 }
+        );
+        assert_eq!(
+            limiter.check(ip).await,
+            RateLimitDecision::Warned {
+                used: 10,
+                limit: 10
+            }
+        );
+        assert_eq!(limiter.check(ip).await, RateLimitDecision::Rejected);
+    }
+
     #[tokio::test]
     async fn test_cleanup_expired() {
         let limiter = RateLimiter::new(100);
@@ -1,22 +1,51 @@
 use crate::api::dto::*;
-use crate::models::internal::Message;
+use crate::api::error::AppError;
+use crate::api::exporters::search_results_csv;
+use crate::api::extract::ApiJson;
+use crate::api::rate_limiter::{rate_limit_middleware, RateLimiter};
+use crate::auth::AdminAuth;
+use crate::models::folder_tree::{build_folder_tree, folder_has_prefix, normalize_folder};
+use crate::models::ids::new_id;
+use crate::models::importance::{
+    self, DEFAULT_IMPORTANCE, MAX_IMPORTANCE, MIN_IMPORTANCE, PINNED_IMPORTANCE,
+};
+use crate::models::internal::{
+    Conversation, ConversationFilter, ConversationPatch, ConversationTemplate, Message,
+    SearchSuggestions, TagUpdate,
+};
+use crate::services::embedding_queue::{EmbeddingJob, EmbeddingQueue};
 use crate::services::embedding_service::EmbeddingService;
+use crate::services::event_bus::{EventBus, MemoryEvent};
+use crate::services::search_cache::{SearchCache, SearchCacheKey};
+use crate::services::task_tracker::{TaskStatus, TaskTracker};
 use crate::storage::chroma_client::ChromaClient;
-use crate::storage::db::get_connection;
+use crate::storage::db::{get_connection, migration_version, schema_version, LATEST_MIGRATION};
 use axum::extract::{Path, Query, State};
-use axum::routing::{delete, get, post, put};
+use axum::middleware;
+use axum::response::sse::{Event as SseEvent, KeepAlive, Sse};
+use axum::routing::{delete, get, patch, post, put};
 use axum::{Json, Router};
 use sea_orm::ConnectionTrait;
 use serde_json::{json, Value};
 
 use axum::http::StatusCode;
 use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::convert::Infallible;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 use uuid::Uuid;
 
+use crate::orchestrator::context_assembly::{estimate_tokens, format_prompt};
+use crate::orchestrator::search_reranker::MAX_RERANK_CANDIDATES;
 use crate::orchestrator::MemoryOrchestrator;
-use crate::{config::Config, storage::repository::ConversationRepository};
+use crate::{
+    config::Config,
+    storage::repository::{
+        apply_role_weights, ConversationRepository, CreatedConversation, RepositoryError,
+        SearchSource, TimeBucket,
+    },
+};
 
 #[derive(Clone)]
 pub struct AppState {
@@ -24,7 +53,33 @@ pub struct AppState {
     pub repo: Arc<dyn ConversationRepository>,
     pub orchestrator: Arc<MemoryOrchestrator>,
     pub embedding_service: Arc<EmbeddingService>,
+    pub embedding_queue: Arc<EmbeddingQueue>,
     pub chroma_client: Arc<ChromaClient>,
+    pub events: EventBus,
+    /// Recent semantic search results; invalidated by `events`
+    pub search_cache: Arc<SearchCache>,
+    /// Background work started through the API, polled via `/api/v1/tasks`
+    pub tasks: Arc<TaskTracker>,
+}
+
+impl AppState {
+    /// Receive memory events published from now on, for in-process listeners
+    /// such as the `/api/v1/events` SSE feed
+    pub fn subscribe(&self) -> broadcast::Receiver<MemoryEvent> {
+        self.events.subscribe()
+    }
+
+    /// Note a read of the conversation in the background, so the response
+    /// doesn't wait on the write
+    pub fn record_access(&self, id: Uuid) {
+        let repo = self.repo.clone();
+        let now = chrono::Utc::now().naive_utc();
+        tokio::spawn(async move {
+            if let Err(e) = repo.record_access(id, now).await {
+                tracing::warn!("Failed to record access to conversation {}: {}", id, e);
+            }
+        });
+    }
 }
 
 #[derive(Deserialize)]
@@ -39,6 +94,11 @@ pub struct FilterParams {
     folder: Option<String>,
     pinned: Option<bool>,
     archived: Option<bool>,
+    /// Only conversations with at least one message from this role
+    has_role: Option<String>,
+    /// Match `label` approximately (case, typos) instead of exactly
+    #[serde(default)]
+    fuzzy: bool,
 }
 
 // #[derive(Deserialize)]
@@ -50,6 +110,70 @@ pub struct FilterParams {
 pub struct CountParams {
     label: Option<String>,
     folder: Option<String>,
+    status: Option<String>,
+    /// Ignored when `status` is given
+    #[serde(default = "default_exclude_archived")]
+    exclude_archived: bool,
+}
+
+fn default_exclude_archived() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+pub struct TimelineParams {
+    /// `day`, `week` or `month`
+    #[serde(default = "default_timeline_bucket")]
+    bucket: String,
+    from: Option<chrono::NaiveDate>,
+    to: Option<chrono::NaiveDate>,
+}
+
+fn default_timeline_bucket() -> String {
+    "day".to_string()
+}
+
+#[derive(Deserialize)]
+pub struct QueryModelParams {
+    /// Secondary embedding model to search; the primary model when unset
+    model: Option<String>,
+    /// `json` (default) or `csv`
+    format: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ContextDebugParams {
+    query: String,
+    #[serde(default = "default_context_budget")]
+    context_budget: usize,
+}
+
+fn default_context_budget() -> usize {
+    4000
+}
+
+/// Suggestions of each kind returned when the request doesn't say
+const DEFAULT_SUGGESTIONS_PER_CATEGORY: usize = 5;
+/// Most suggestions of each kind a request may ask for
+const MAX_SUGGESTIONS_PER_CATEGORY: usize = 20;
+
+#[derive(Deserialize)]
+pub struct SuggestParams {
+    #[serde(default)]
+    q: String,
+    limit: Option<usize>,
+}
+
+/// `folder` tidied for storage, or the configured default folder when it's
+/// missing or blank
+async fn folder_or_default(state: &AppState, folder: Option<&str>) -> String {
+    match folder.and_then(normalize_folder) {
+        Some(folder) => folder,
+        None => {
+            let default_folder = state.config.read().await.default_folder.clone();
+            normalize_folder(&default_folder).unwrap_or_else(|| "/".to_string())
+        }
+    }
 }
 
 // ============================================
@@ -60,19 +184,17 @@ pub struct CountParams {
     path = "/api/v1/conversations",
     request_body = CreateConversationRequest,
     responses(
-        (status = 201, description = "Conversation created", body = ConversationResponse),
+        (status = 201, description = "Conversation created", body = CreateConversationResponse),
         (status = 400, description = "Invalid request", body = ErrorResponse)
     )
 )]
 pub async fn create_conversation(
     State(state): State<AppState>,
-    Json(req): Json<CreateConversationRequest>,
-) -> Result<(StatusCode, Json<serde_json::Value>), (StatusCode, Json<ErrorResponse>)> {
-    // ✅ Changed return type
-    let id = Uuid::new_v4();
+    ApiJson(req): ApiJson<CreateConversationRequest>,
+) -> Result<(StatusCode, Json<CreateConversationResponse>), AppError> {
+    let id = new_id(state.config.read().await.id_scheme);
     let now = chrono::Utc::now().naive_utc();
-
-    let word_count: i32 = req.messages.iter().map(|m| m.content.len() as i32).sum();
+    let folder = folder_or_default(&state, req.folder.as_deref()).await;
 
     let new_messages: Vec<_> = req
         .messages
@@ -90,41 +212,246 @@ pub async fn create_conversation(
     let new_conv = crate::models::internal::NewConversation {
         id: Some(id),
         label: req.label.clone(),
-        folder: req.folder.clone(),
+        folder: folder.clone(),
         status: "active".to_string(),
-        importance_score: Some(5),
-        word_count,
+        importance_score: Some(DEFAULT_IMPORTANCE),
+        // The repository counts what it stores, after redaction
+        word_count: 0,
         session_count: Some(1),
         created_at: now,
         updated_at: now,
         messages: new_messages,
     };
 
-    state
+    let CreatedConversation {
+        message_ids,
+        word_count,
+        ..
+    } = state.repo.create_with_message_ids(new_conv).await?;
+
+    let warnings = queue_unembedded_messages(&state, id).await;
+    state.events.publish(MemoryEvent::Created {
+        conversation_id: id,
+    });
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateConversationResponse {
+            id,
+            conversation_id: id,
+            label: req.label,
+            folder,
+            status: "active".to_string(),
+            message_count,
+            word_count,
+            message_ids,
+            created_at: now,
+            embeddings_pending: !warnings.is_empty(),
+            warnings,
+        }),
+    ))
+}
+
+/// Queue a retry for messages stored without a vector, returning warnings
+/// for the response. Such messages are invisible to semantic search until
+/// the retry succeeds.
+async fn queue_unembedded_messages(state: &AppState, conversation_id: Uuid) -> Vec<String> {
+    // Without embeddings every message is stored without a vector on purpose
+    if !state.config.read().await.embeddings_enabled {
+        return Vec::new();
+    }
+
+    let pending: Vec<String> = match state.repo.get_conversation_messages(conversation_id).await {
+        Ok(messages) => messages
+            .into_iter()
+            .filter(|m| m.embedding_id.is_none() && !m.embedding_skipped())
+            .map(|m| m.id.to_string())
+            .collect(),
+        Err(e) => {
+            tracing::warn!("Could not check embeddings for {}: {}", conversation_id, e);
+            return Vec::new();
+        }
+    };
+    if pending.is_empty() {
+        return Vec::new();
+    }
+
+    let mut warnings = vec![format!(
+        "{} message(s) were stored without embeddings and won't appear in semantic search yet",
+        pending.len()
+    )];
+    let job = EmbeddingJob {
+        conversation_id: conversation_id.to_string(),
+        message_ids: pending,
+    };
+    match state.embedding_queue.enqueue(job).await {
+        Ok(()) => warnings.push("embedding retry queued".to_string()),
+        Err(e) => {
+            tracing::warn!("Failed to queue embedding retry: {}", e);
+            warnings.push("embedding retry could not be queued".to_string());
+        }
+    }
+    warnings
+}
+
+/// Summarize away the oldest messages once the conversation has more than
+/// `max_messages_per_conversation`. Done before responding so the caller sees
+/// the conversation already bounded.
+async fn enforce_message_cap(state: &AppState, conversation_id: Uuid) {
+    let Some(max_messages) = state.config.read().await.max_messages_per_conversation else {
+        return;
+    };
+
+    if let Err(e) = state
+        .orchestrator
+        .roll_over_messages(conversation_id, max_messages)
+        .await
+    {
+        tracing::warn!(
+            "Could not roll over old messages of {}: {}",
+            conversation_id,
+            e
+        );
+    }
+}
+
+/// Generate a summary in the background once the conversation has grown by
+/// `summarize_after_messages` since its last automatic summary
+async fn schedule_threshold_summary(state: &AppState, conversation_id: Uuid) {
+    let every = {
+        let config = state.config.read().await;
+        match config.summarize_after_messages {
+            Some(every) if config.summarization_enabled => every,
+            _ => return,
+        }
+    };
+
+    let count = match state
+        .repo
+        .claim_summary_checkpoint(conversation_id, every)
+        .await
+    {
+        Ok(Some(count)) => count,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::warn!(
+                "Could not check summary threshold for {}: {}",
+                conversation_id,
+                e
+            );
+            return;
+        }
+    };
+
+    tracing::info!(
+        "Conversation {} reached {} messages, scheduling summary",
+        conversation_id,
+        count
+    );
+    let orchestrator = state.orchestrator.clone();
+    tokio::spawn(async move {
+        if let Err(e) = orchestrator.generate_daily_summary(conversation_id).await {
+            tracing::warn!("Automatic summary for {} failed: {}", conversation_id, e);
+        }
+    });
+}
+
+/// Re-score a conversation's importance once appends have settled for the
+/// configured debounce window, if `rescore_importance_on_append` is set
+async fn schedule_importance_rescore(state: &AppState, conversation_id: Uuid) {
+    let debounce = {
+        let config = state.config.read().await;
+        if !config.rescore_importance_on_append {
+            return;
+        }
+        std::time::Duration::from_secs(config.importance_rescore_debounce_secs)
+    };
+
+    if !state
+        .orchestrator
+        .importance_engine
+        .claim_rescore(conversation_id)
+    {
+        return;
+    }
+
+    let orchestrator = state.orchestrator.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(debounce).await;
+        if let Err(e) = orchestrator
+            .rescore_conversation_importance(conversation_id)
+            .await
+        {
+            tracing::warn!("Importance re-score for {} failed: {}", conversation_id, e);
+        }
+    });
+}
+
+// ============================================
+// Endpoint: POST /api/v1/conversations/{id}/messages
+// ============================================
+#[utoipa::path(
+    post,
+    path = "/api/v1/conversations/{id}/messages",
+    request_body = AppendMessagesRequest,
+    responses(
+        (status = 201, description = "Messages appended", body = AppendMessagesResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse)
+    ),
+    params(
+        ("id" = String, Path, description = "Conversation UUID")
+    )
+)]
+pub async fn append_messages(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    ApiJson(req): ApiJson<AppendMessagesRequest>,
+) -> Result<(StatusCode, Json<AppendMessagesResponse>), AppError> {
+    if req.messages.is_empty() {
+        return Err(AppError::BadRequest(
+            "messages must not be empty".to_string(),
+        ));
+    }
+
+    let now = chrono::Utc::now().naive_utc();
+    let new_messages: Vec<_> = req
+        .messages
+        .into_iter()
+        .map(|m| crate::models::internal::NewMessage {
+            role: m.role,
+            content: m.content,
+            metadata: serde_json::json!({}),
+            timestamp: now,
+        })
+        .collect();
+
+    let appended = state
         .repo
-        .create_with_messages(new_conv)
+        .append_messages(id, new_messages)
         .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse {
-                    error: e.to_string(),
-                    code: 500,
-                }),
-            )
+        .map_err(|e| match e {
+            RepositoryError::NotFound(_) => {
+                AppError::NotFound("Conversation not found".to_string())
+            }
+            e => e.into(),
         })?;
 
+    enforce_message_cap(&state, id).await;
+    schedule_threshold_summary(&state, id).await;
+    schedule_importance_rescore(&state, id).await;
+    state.events.publish(MemoryEvent::Updated {
+        conversation_id: id,
+    });
+
     Ok((
         StatusCode::CREATED,
-        Json(serde_json::json!({
-            "id": id,
-            "conversation_id": id,  // ✅ Both fields for compatibility
-            "label": req.label,
-            "folder": req.folder,
-            "status": "active",
-            "message_count": message_count,
-            "created_at": now,
-        })),
+        Json(AppendMessagesResponse {
+            conversation_id: id,
+            message_ids: appended.message_ids,
+            message_count: appended.message_count,
+            word_count: appended.word_count,
+        }),
     ))
 }
 
@@ -145,19 +472,12 @@ pub async fn create_conversation(
 pub async fn get_conversation(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
-) -> Result<Json<ConversationResponse>, (StatusCode, Json<ErrorResponse>)> {
-    let conv = state.repo.find_by_id(id).await.map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: e.to_string(),
-                code: 500,
-            }),
-        )
-    })?;
+) -> Result<Json<ConversationResponse>, AppError> {
+    let conv = state.repo.find_by_id(id).await?;
 
     match conv {
         Some(c) => {
+            state.record_access(id);
             let message_count = state
                 .repo
                 .count_messages_in_conversation(id)
@@ -169,16 +489,13 @@ pub async fn get_conversation(
                 folder: c.folder,
                 status: c.status,
                 message_count: message_count.try_into().unwrap(),
+                session_count: c.session_count,
                 created_at: c.created_at, // CHANGED: Remove .to_string()
+                context_pinned: c.context_pinned,
+                last_accessed_at: c.last_accessed_at,
             }))
         }
-        None => Err((
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: "Conversation not found".to_string(),
-                code: 404,
-            }),
-        )),
+        None => Err(AppError::NotFound("Conversation not found".to_string())),
     }
 }
 
@@ -196,6 +513,8 @@ pub async fn get_conversation(
         ("folder" = Option<String>, Query, description = "Filter by folder"),
         ("pinned" = Option<bool>, Query, description = "Filter by pinned status"),
         ("archived" = Option<bool>, Query, description = "Filter by archived status"),
+        ("has_role" = Option<String>, Query, description = "Only conversations with at least one message from this role, e.g. `system` (ignored with `fuzzy`)"),
+        ("fuzzy" = Option<bool>, Query, description = "Match the label ignoring case and small typos, closest labels first (default false)"),
         ("page" = Option<u32>, Query, description = "Page number"),
         ("page_size" = Option<u32>, Query, description = "Page size")
     )
@@ -210,33 +529,13 @@ pub async fn list_conversations(
     let page_size = params.page_size.unwrap_or(50);
     let offset = (page - 1) * page_size;
 
-    // Build filter criteria - NOW USING the parameters!
-    let mut criteria = Vec::new();
-    if let Some(label) = &filters.label {
-        criteria.push(format!("label = '{}'", label));
-    }
-    if let Some(folder) = &filters.folder {
-        criteria.push(format!("folder = '{}'", folder));
-    }
-    if let Some(pinned) = filters.pinned {
-        criteria.push(format!("pinned = {}", pinned));
-    }
-    if let Some(archived) = filters.archived {
-        criteria.push(format!("archived = {}", archived));
-    }
-    let filter_str = if criteria.is_empty() {
-        None
-    } else {
-        Some(criteria.join(" AND "))
+    let results = match &filters.label {
+        Some(label) if filters.fuzzy => {
+            fuzzy_label_page(&state, label, filters.folder.as_deref(), offset, page_size).await
+        }
+        _ => filtered_page(&state, &filters, offset, page_size).await,
     };
 
-    // Use repository method with filters
-    let results = state
-        .repo
-        .find_with_filters(filter_str, page_size as usize, offset as u32)
-        .await
-        .unwrap_or_else(|_| (Vec::new(), 0));
-
     let total = results.1;
     let conversations: Vec<SearchResultDto> = results
         .0
@@ -254,6 +553,7 @@ pub async fn list_conversations(
             label: c.label,
             folder: c.folder,
             timestamp: c.updated_at, // CHANGED: Remove .to_string()
+            llm_rank: None,
         })
         .collect();
 
@@ -262,9 +562,80 @@ pub async fn list_conversations(
         total: total.try_into().unwrap_or(u32::MAX), // FIXED: Convert u64 to u32 safely
         page,
         page_size,
+        search_backend: None,
+        took_ms: None,
+        effective_limit: None,
     })
 }
 
+/// One page of `list_conversations` using exact filters
+async fn filtered_page(
+    state: &AppState,
+    filters: &FilterParams,
+    offset: u32,
+    page_size: u32,
+) -> (Vec<Conversation>, u64) {
+    // Build filter criteria - NOW USING the parameters!
+    let mut criteria = Vec::new();
+    if let Some(label) = &filters.label {
+        criteria.push(format!("label = '{}'", label));
+    }
+    if let Some(folder) = &filters.folder {
+        criteria.push(format!("folder = '{}'", folder));
+    }
+    if let Some(pinned) = filters.pinned {
+        criteria.push(format!("pinned = {}", pinned));
+    }
+    if let Some(archived) = filters.archived {
+        criteria.push(format!("archived = {}", archived));
+    }
+    let filter_str = if criteria.is_empty() {
+        None
+    } else {
+        Some(criteria.join(" AND "))
+    };
+
+    // Use repository method with filters
+    state
+        .repo
+        .find_with_filters(
+            filter_str,
+            filters.has_role.clone(),
+            page_size as usize,
+            offset as u32,
+        )
+        .await
+        .unwrap_or_else(|_| (Vec::new(), 0))
+}
+
+/// One page of `list_conversations` for a fuzzy label, closest labels first.
+/// `total` counts every candidate, so all of them are fetched.
+async fn fuzzy_label_page(
+    state: &AppState,
+    label: &str,
+    folder: Option<&str>,
+    offset: u32,
+    page_size: u32,
+) -> (Vec<Conversation>, u64) {
+    let candidates = state
+        .repo
+        .find_by_label_fuzzy(label, u64::MAX)
+        .await
+        .unwrap_or_default();
+    let candidates: Vec<Conversation> = candidates
+        .into_iter()
+        .filter(|c| folder.map_or(true, |folder| c.folder == folder))
+        .collect();
+    let total = candidates.len() as u64;
+    let page = candidates
+        .into_iter()
+        .skip(offset as usize)
+        .take(page_size as usize)
+        .collect();
+
+    (page, total)
+}
+
 // ============================================
 // Endpoint 4: PUT /api/v1/conversations/{id}/label
 // ============================================
@@ -280,21 +651,12 @@ pub async fn list_conversations(
 pub async fn update_conversation_label(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
-    Json(req): Json<UpdateLabelRequest>,
-) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
-    state
-        .repo
-        .update_label(id, &req.label, &req.folder)
-        .await
-        .map_err(|e| {
-            (
-                StatusCode::NOT_FOUND,
-                Json(ErrorResponse {
-                    error: e.to_string(),
-                    code: 404,
-                }),
-            )
-        })?;
+    ApiJson(req): ApiJson<UpdateLabelRequest>,
+) -> Result<StatusCode, AppError> {
+    state.repo.update_label(id, &req.label, &req.folder).await?;
+    state.events.publish(MemoryEvent::Updated {
+        conversation_id: id,
+    });
 
     Ok(StatusCode::OK)
 }
@@ -316,37 +678,18 @@ pub async fn update_conversation_label(
 pub async fn delete_conversation(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
-) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<StatusCode, AppError> {
     // Check if conversation exists first
-    let exists = state.repo.find_by_id(id).await.map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: e.to_string(),
-                code: 500,
-            }),
-        )
-    })?;
+    let exists = state.repo.find_by_id(id).await?;
 
     if exists.is_none() {
-        return Err((
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: "Conversation not found".to_string(),
-                code: 404,
-            }),
-        ));
+        return Err(AppError::NotFound("Conversation not found".to_string()));
     }
 
-    state.repo.delete(id).await.map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: e.to_string(),
-                code: 500,
-            }),
-        )
-    })?;
+    state.repo.delete(id).await?;
+    state.events.publish(MemoryEvent::Deleted {
+        conversation_id: id,
+    });
 
     Ok(StatusCode::OK)
 }
@@ -358,41 +701,99 @@ pub async fn delete_conversation(
     get,
     path = "/api/v1/conversations/count",
     responses(
-        (status = 200, description = "Count conversations by label or folder", body = serde_json::Value)
+        (status = 200, description = "Count conversations by label or folder", body = serde_json::Value),
+        (status = 400, description = "Invalid status", body = ErrorResponse)
     ),
     params(
         ("label" = Option<String>, Query, description = "Label to filter by"),
-        ("folder" = Option<String>, Query, description = "Folder to filter by")
+        ("folder" = Option<String>, Query, description = "Folder to filter by"),
+        ("status" = Option<String>, Query, description = "Status to filter by (active, archived)"),
+        ("exclude_archived" = Option<bool>, Query, description = "Leave archived conversations out when no status is given (default true)")
     )
 )]
 pub async fn count_conversations(
     State(state): State<AppState>,
     Query(params): Query<CountParams>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
-    // Clone values before they are moved
-    let label_for_response = params.label.clone();
-    let folder_for_response = params.folder.clone();
-
-    let count = match (&params.label, &params.folder) {
-        (Some(label), None) => state.repo.count_by_label(label).await,
-        (None, Some(folder)) => state.repo.count_by_folder(folder).await, // ✅ CHANGED
-        (None, None) => state.repo.count_all().await,                     // ✅ CHANGED
-        (Some(_), Some(_)) => {
-            return Ok(Json(serde_json::json!({
-                "count": 0,
-                "error": "Cannot specify both label and folder"
-            })));
-        }
-    }
-    .map_err(|e| {
-        tracing::error!("Count failed: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+) -> Result<Json<serde_json::Value>, AppError> {
+    if params.label.is_some() && params.folder.is_some() {
+        return Ok(Json(serde_json::json!({
+            "count": 0,
+            "error": "Cannot specify both label and folder"
+        })));
+    }
+
+    let status = match params.status.as_deref() {
+        Some(status) if !VALID_STATUSES.contains(&status) => {
+            return Err(AppError::BadRequest(format!(
+                "status must be one of: {}",
+                VALID_STATUSES.join(", ")
+            )));
+        }
+        Some(status) => Some(status),
+        None if params.exclude_archived => Some("active"),
+        None => None,
+    };
+
+    let count = state
+        .repo
+        .count_with_filters(
+            params.label.clone(),
+            params.folder.clone(),
+            status.map(str::to_string),
+        )
+        .await?;
 
     Ok(Json(serde_json::json!({
         "count": count,
-        "label": label_for_response,
-        "folder": folder_for_response
+        "label": params.label,
+        "folder": params.folder,
+        "status": status
+    })))
+}
+
+// ============================================
+// GET /api/v1/stats/timeline
+// ============================================
+#[utoipa::path(
+    get,
+    path = "/api/v1/stats/timeline",
+    responses(
+        (status = 200, description = "Conversations and messages created per day, week or month", body = serde_json::Value),
+        (status = 400, description = "Unknown bucket or a range that ends before it starts", body = ErrorResponse)
+    ),
+    params(
+        ("bucket" = Option<String>, Query, description = "Bucket width: day (default), week or month"),
+        ("from" = Option<String>, Query, description = "First day to include, YYYY-MM-DD"),
+        ("to" = Option<String>, Query, description = "Last day to include, YYYY-MM-DD")
+    )
+)]
+pub async fn stats_timeline(
+    State(state): State<AppState>,
+    Query(params): Query<TimelineParams>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let Some(bucket) = TimeBucket::parse(&params.bucket) else {
+        return Err(AppError::BadRequest(
+            "bucket must be one of: day, week, month".to_string(),
+        ));
+    };
+    if let (Some(from), Some(to)) = (params.from, params.to) {
+        if from > to {
+            return Err(AppError::BadRequest(
+                "from must not be after to".to_string(),
+            ));
+        }
+    }
+
+    let buckets = state
+        .repo
+        .conversation_timeline(bucket, params.from, params.to)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "bucket": params.bucket,
+        "from": params.from,
+        "to": params.to,
+        "buckets": buckets
     })))
 }
 
@@ -404,19 +805,83 @@ pub async fn count_conversations(
     path = "/api/v1/query",
     request_body = QueryRequest,
     responses(
-        (status = 200, description = "Semantic search results", body = QueryResponse),
+        (status = 200, description = "Semantic search results; `text/csv` rows of conversation_id,label,folder,score,timestamp,content when `format=csv`", body = QueryResponse),
         (status = 400, description = "Invalid request", body = ErrorResponse),
         (status = 500, description = "Search error", body = ErrorResponse)
+    ),
+    params(
+        ("model" = Option<String>, Query, description = "Name of a configured secondary embedding model to search instead of the primary one"),
+        ("format" = Option<String>, Query, description = "Response format: json (default) or csv")
     )
 )]
 
 pub async fn semantic_query(
     State(state): State<AppState>,
+    Query(params): Query<QueryModelParams>,
     Json(req): Json<QueryRequest>,
-) -> Result<Json<QueryResponse>, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<axum::response::Response, AppError> {
+    use axum::response::IntoResponse;
+
     tracing::info!("Semantic query: {}", req.query);
 
-    let limit = req.limit.unwrap_or(10) as usize;
+    if req.query.trim().is_empty() {
+        return Err(AppError::BadRequest("query must not be empty".to_string()));
+    }
+    let csv = match params.format.as_deref() {
+        None | Some("json") => false,
+        Some("csv") => true,
+        Some(other) => {
+            return Err(AppError::BadRequest(format!(
+                "unknown format '{}' (expected json or csv)",
+                other
+            )))
+        }
+    };
+    if let Some(model) = params
+        .model
+        .as_deref()
+        .filter(|m| !state.embedding_service.has_model(m))
+    {
+        return Err(AppError::BadRequest(format!(
+            "unknown embedding model '{}' (configured: [{}])",
+            model,
+            state.embedding_service.secondary_model_names().join(", ")
+        )));
+    }
+
+    let (effective_limit, mut role_weights, exclude_folders, max_age_days) = {
+        let config = state.config.read().await;
+        (
+            config.effective_search_limit(req.limit),
+            config.role_weights.clone(),
+            req.exclude_folders
+                .clone()
+                .unwrap_or_else(|| config.search_exclude_folders.clone()),
+            req.max_age_days.or(config.search_max_age_days),
+        )
+    };
+    let exclude_folders: Vec<String> = exclude_folders
+        .iter()
+        .map(String::as_str)
+        .filter_map(normalize_folder)
+        .collect();
+    let excluded = |folder: &str| {
+        exclude_folders
+            .iter()
+            .any(|prefix| folder_has_prefix(folder, prefix))
+    };
+    let cutoff = max_age_days
+        .filter(|days| *days > 0)
+        .map(|days| chrono::Utc::now().naive_utc() - chrono::Duration::days(i64::from(days)));
+    // Folders and activity aren't stored with the vectors, so excluded and
+    // stale results are dropped afterwards; fetch extra to keep pages close
+    // to full
+    let fetch_factor = if exclude_folders.is_empty() && cutoff.is_none() {
+        1
+    } else {
+        2
+    };
+    let limit = effective_limit as usize;
     let offset = req.offset.unwrap_or(0);
 
     // Calculate page number
@@ -426,43 +891,263 @@ pub async fn semantic_query(
         1
     };
 
-    // Use repository's semantic search (now powered by Chroma)
-    let results = state
-        .repo
-        .semantic_search(&req.query, limit, req.filters)
-        .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse {
-                    error: format!("Semantic search failed: {}", e),
-                    code: 500,
-                }),
-            )
-        })?;
+    // Request weights override the configured defaults role by role
+    role_weights.extend(req.role_weights.unwrap_or_default());
+    if let Some((role, weight)) = role_weights
+        .iter()
+        .find(|(_, w)| !w.is_finite() || **w < 0.0)
+    {
+        return Err(AppError::BadRequest(format!(
+            "role weight for '{}' must be a non-negative number, got {}",
+            role, weight
+        )));
+    }
+
+    let started = std::time::Instant::now();
+
+    // Re-ranking looks at a wider candidate pool, bounded for latency
+    let candidates = if req.rerank {
+        limit
+            .saturating_mul(2)
+            .min(MAX_RERANK_CANDIDATES)
+            .max(limit)
+    } else {
+        limit
+    };
+
+    // The repository answers from FTS when embeddings are disabled or the
+    // vector store is down, following the configured fallback policy
+    let fetched = candidates.saturating_mul(fetch_factor);
+    let cache_key = SearchCacheKey::new(
+        &req.query,
+        fetched,
+        req.filters.as_ref(),
+        params.model.as_deref(),
+    );
+    let (mut results, source) = match state.search_cache.get(&cache_key) {
+        Some(results) => (results, SearchSource::Vectors),
+        None => {
+            let (results, source) = state
+                .repo
+                .semantic_search_with_model(&req.query, fetched, req.filters, params.model)
+                .await
+                .map_err(|e| match e {
+                    RepositoryError::InvalidInput(_) => AppError::from(e),
+                    RepositoryError::ChromaError(_) => {
+                        AppError::ServiceUnavailable("Vector store unavailable".to_string())
+                    }
+                    e => AppError::Internal(format!("Semantic search failed: {}", e)),
+                })?;
+            // Only genuine vector hits are cached; fallback answers would be
+            // served past the end of an outage
+            if source == SearchSource::Vectors {
+                state.search_cache.insert(cache_key, results.clone());
+            }
+            (results, source)
+        }
+    };
+    let stale = stale_conversations(
+        &state,
+        results.iter().map(|r| r.conversation_id).collect(),
+        cutoff,
+    )
+    .await?;
+    results.retain(|r| !excluded(&r.folder) && !stale.contains(&r.conversation_id));
+    results.truncate(candidates);
+    if source == SearchSource::Vectors {
+        apply_role_weights(&mut results, &role_weights);
+    }
 
     let api_results: Vec<SearchResultDto> = results
-        .iter()
+        .into_iter()
         .map(|r| SearchResultDto {
             conversation_id: r.conversation_id,
             message_id: r.message_id,
             score: r.score,
-            content: r.content.clone(),
-            metadata: r.metadata.clone(),
-            label: r.label.clone(),
-            folder: r.folder.clone(),
-            timestamp: r.timestamp, // CHANGED: Remove .to_string()
+            content: r.content,
+            metadata: r.metadata,
+            label: r.label,
+            folder: r.folder,
+            timestamp: r.timestamp,
+            llm_rank: None,
         })
         .collect();
 
+    let (api_results, backend) = match source {
+        SearchSource::Vectors if req.rerank => (
+            rerank_results(&state, &req.query, api_results, limit).await,
+            SearchBackend::Semantic,
+        ),
+        SearchSource::Vectors => (api_results, SearchBackend::Semantic),
+        SearchSource::FullText => (api_results, SearchBackend::Fts),
+        SearchSource::Unavailable => (api_results, SearchBackend::Unavailable),
+    };
+
+    if csv {
+        return Ok((
+            [(axum::http::header::CONTENT_TYPE, "text/csv; charset=utf-8")],
+            search_results_csv(&api_results),
+        )
+            .into_response());
+    }
+
     Ok(Json(QueryResponse {
+        total: api_results.len() as u32,
         results: api_results,
-        total: results.len() as u32,
         page,
         page_size: limit as u32,
+        search_backend: Some(backend),
+        took_ms: Some(started.elapsed().as_millis() as u64),
+        effective_limit: Some(effective_limit),
+    })
+    .into_response())
+}
+
+/// Conversations among `ids` not updated since `cutoff`; none without one
+async fn stale_conversations(
+    state: &AppState,
+    ids: Vec<Uuid>,
+    cutoff: Option<chrono::NaiveDateTime>,
+) -> Result<HashSet<Uuid>, AppError> {
+    let Some(cutoff) = cutoff else {
+        return Ok(HashSet::new());
+    };
+
+    let stale = state.repo.find_updated_before(ids, cutoff).await?;
+    Ok(stale.into_iter().collect())
+}
+
+/// Reorder semantic candidates with the LLM re-ranker, keeping the best
+/// `limit`. Falls back to similarity order if the bridge is unavailable.
+async fn rerank_results(
+    state: &AppState,
+    query: &str,
+    results: Vec<SearchResultDto>,
+    limit: usize,
+) -> Vec<SearchResultDto> {
+    let documents: Vec<String> = results.iter().map(|r| r.content.clone()).collect();
+    let ranking = state
+        .orchestrator
+        .search_reranker
+        .rerank(query, &documents, limit)
+        .await;
+
+    let Some(ranking) = ranking else {
+        return results.into_iter().take(limit).collect();
+    };
+
+    let mut candidates: Vec<Option<SearchResultDto>> = results.into_iter().map(Some).collect();
+    let mut reranked = Vec::with_capacity(limit);
+    for (rank, idx) in ranking.into_iter().enumerate() {
+        if let Some(mut result) = candidates[idx].take() {
+            result.llm_rank = Some(rank + 1);
+            reranked.push(result);
+        }
+    }
+
+    // Pad with the remaining candidates in similarity order
+    let missing = limit.saturating_sub(reranked.len());
+    reranked.extend(candidates.into_iter().flatten().take(missing));
+    reranked
+}
+
+/// Matching messages searched per requested conversation, so conversations
+/// with several hits don't crowd the others out of the pool
+const CONVERSATION_SEARCH_POOL: usize = 5;
+
+/// Characters of the best-matching message shown as a snippet
+const SNIPPET_CHARS: usize = 200;
+
+// ============================================
+// Endpoint: POST /api/v1/query/conversations
+// ============================================
+#[utoipa::path(
+    post,
+    path = "/api/v1/query/conversations",
+    request_body = ConversationQueryRequest,
+    responses(
+        (status = 200, description = "Matching conversations, one entry each", body = ConversationQueryResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 500, description = "Search failed", body = ErrorResponse)
+    )
+)]
+async fn query_conversations(
+    State(state): State<AppState>,
+    ApiJson(req): ApiJson<ConversationQueryRequest>,
+) -> Result<Json<ConversationQueryResponse>, AppError> {
+    if req.query.trim().is_empty() {
+        return Err(AppError::BadRequest("query must not be empty".to_string()));
+    }
+
+    let limit = state.config.read().await.effective_search_limit(req.limit) as usize;
+    let hits = state
+        .repo
+        .semantic_search(
+            &req.query,
+            limit.saturating_mul(CONVERSATION_SEARCH_POOL),
+            req.filters,
+        )
+        .await
+        .map_err(|e| match e {
+            RepositoryError::InvalidInput(_) => AppError::from(e),
+            e => AppError::Internal(format!("Semantic search failed: {}", e)),
+        })?;
+
+    let hit_ids: Vec<Uuid> = hits.iter().map(|hit| hit.conversation_id).collect();
+    let mut conversations: HashMap<Uuid, Conversation> = state
+        .repo
+        .find_by_ids(hit_ids)
+        .await?
+        .into_iter()
+        .map(|conversation| (conversation.id, conversation))
+        .collect();
+
+    // Hits arrive best first, so the first hit of each conversation is its best
+    let mut results: Vec<ConversationSearchResultDto> = Vec::new();
+    for hit in hits {
+        if let Some(existing) = results
+            .iter_mut()
+            .find(|r| r.conversation_id == hit.conversation_id)
+        {
+            existing.aggregate_score += hit.score;
+            existing.match_count += 1;
+            continue;
+        }
+        if results.len() == limit {
+            continue;
+        }
+        let Some(conversation) = conversations.remove(&hit.conversation_id) else {
+            continue;
+        };
+        results.push(ConversationSearchResultDto {
+            conversation_id: conversation.id,
+            label: conversation.label,
+            folder: conversation.folder,
+            status: conversation.status,
+            importance_score: conversation.importance_score,
+            updated_at: conversation.updated_at,
+            best_message_id: hit.message_id,
+            snippet: snippet(&hit.content, SNIPPET_CHARS),
+            score: hit.score,
+            aggregate_score: hit.score,
+            match_count: 1,
+        });
+    }
+
+    Ok(Json(ConversationQueryResponse {
+        total: results.len(),
+        results,
     }))
 }
 
+/// First `max_chars` characters of `content`, marked with an ellipsis when cut
+fn snippet(content: &str, max_chars: usize) -> String {
+    match content.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}…", content[..end].trim_end()),
+        None => content.to_string(),
+    }
+}
+
 // ============================================
 // Endpoint 8: GET /health
 // ============================================
@@ -488,12 +1173,16 @@ pub async fn health(State(state): State<AppState>) -> Result<Json<Value>, Status
         }
     }
 
-    // Check Chroma
-    match state.chroma_client.ping().await {
-        Ok(_) => checks["checks"]["chroma"] = json!({"status": "ok"}),
-        Err(e) => {
-            checks["checks"]["chroma"] = json!({"status": "error", "error": e.to_string()});
-            checks["status"] = "unhealthy".into();
+    // Check Chroma, unless this deployment runs without it
+    if !state.config.read().await.embeddings_enabled {
+        checks["checks"]["chroma"] = json!({"status": "disabled"});
+    } else {
+        match state.chroma_client.ping().await {
+            Ok(_) => checks["checks"]["chroma"] = json!({"status": "ok"}),
+            Err(e) => {
+                checks["checks"]["chroma"] = json!({"status": "error", "error": e.to_string()});
+                checks["status"] = "unhealthy".into();
+            }
         }
     }
 
@@ -505,132 +1194,1219 @@ pub async fn health(State(state): State<AppState>) -> Result<Json<Value>, Status
 }
 
 // ============================================
-// Endpoint 9: GET /metrics
+// GET /healthz: liveness only
 // ============================================
-pub async fn metrics() -> &'static str {
-    "# HELP sekha_conversations_total Total number of conversations\n# TYPE sekha_conversations_total gauge\nsekha_conversations_total 0\n"
+/// Answers as long as the process is serving requests; dependencies are
+/// left to `/readyz` so an outage doesn't get the process restarted
+pub async fn liveness() -> Json<Value> {
+    Json(json!({ "status": "alive" }))
 }
 
 // ============================================
-// NEW ENDPOINT: PUT /api/v1/conversations/{id}/folder
+// GET /readyz: dependencies up and schema migrated
+// ============================================
+pub async fn readiness(State(state): State<AppState>) -> (StatusCode, Json<Value>) {
+    let mut checks = serde_json::Map::new();
+    let mut failed = Vec::new();
+    let mut record = |name: &str, result: Result<(), String>| {
+        let check = match result {
+            Ok(()) => json!({"status": "ok"}),
+            Err(error) => {
+                failed.push(name.to_string());
+                json!({"status": "error", "error": error})
+            }
+        };
+        checks.insert(name.to_string(), check);
+    };
+
+    let db = state.repo.get_db();
+    record(
+        "database",
+        db.execute_unprepared("SELECT 1")
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+    );
+    let expected = migration_version(LATEST_MIGRATION);
+    record(
+        "migrations",
+        match schema_version(db).await {
+            Ok(Some(version)) if version == expected => Ok(()),
+            Ok(Some(version)) => Err(format!("Schema is at {}, expected {}", version, expected)),
+            Ok(None) => Err("No migrations applied".to_string()),
+            Err(e) => Err(e.to_string()),
+        },
+    );
+
+    if state.config.read().await.embeddings_enabled {
+        record(
+            "chroma",
+            state.chroma_client.ping().await.map_err(|e| e.to_string()),
+        );
+    } else {
+        checks.insert("chroma".to_string(), json!({"status": "disabled"}));
+    }
+
+    let status = if failed.is_empty() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    let body = json!({
+        "status": if failed.is_empty() { "ready" } else { "not_ready" },
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "checks": checks,
+        "failed": failed,
+    });
+
+    (status, Json(body))
+}
+
+// ============================================
+// Endpoint: GET /api/v1/messages/{id}/embedding
 // ============================================
 #[utoipa::path(
-    put,
-    path = "/api/v1/conversations/{id}/folder",
-    request_body = UpdateFolderRequest,
+    get,
+    path = "/api/v1/messages/{id}/embedding",
     responses(
-        (status = 200, description = "Folder updated"),
-        (status = 404, description = "Not found", body = ErrorResponse)
+        (status = 200, description = "Stored vector, or chunk vectors, for the message", body = MessageEmbeddingResponse),
+        (status = 401, description = "Missing API key"),
+        (status = 403, description = "Admin API key required"),
+        (status = 404, description = "Message not found or has no embedding", body = ErrorResponse),
+        (status = 500, description = "Vector store error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = String, Path, description = "Message UUID")
     )
 )]
-async fn update_conversation_folder(
+pub async fn get_message_embedding(
+    _auth: AdminAuth,
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
-    Json(req): Json<UpdateFolderRequest>,
-) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
-    // Reuse update_label method with same label
-    let conv = state.repo.find_by_id(id).await.map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: e.to_string(),
-                code: 500,
-            }),
-        )
-    })?;
-
-    if conv.is_none() {
-        return Err((
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: "Conversation not found".to_string(),
-                code: 404,
-            }),
-        ));
-    }
+) -> Result<Json<MessageEmbeddingResponse>, AppError> {
+    let not_found = |error: &str| AppError::NotFound(error.to_string());
 
-    state
+    let message = state
         .repo
-        .update_label(id, &req.folder, &req.folder)
+        .find_message_by_id(id)
+        .await?
+        .ok_or_else(|| not_found("Message not found"))?;
+    let embedding_id = message
+        .embedding_id
+        .ok_or_else(|| not_found("Message has no embedding"))?;
+
+    let embedding = state
+        .chroma_client
+        .get_embedding("conversations", &embedding_id)
         .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse {
-                    error: e.to_string(),
-                    code: 500,
-                }),
-            )
-        })?;
+        .map_err(RepositoryError::from)?;
+
+    // Long messages are stored only as `{id}:{n}` chunk vectors
+    let chunks = match embedding {
+        Some(_) => Vec::new(),
+        None => state
+            .chroma_client
+            .get_message_embeddings("conversations", &id.to_string())
+            .await
+            .map_err(RepositoryError::from)?
+            .into_iter()
+            .map(|(embedding_id, embedding)| MessageEmbeddingChunk {
+                embedding_id,
+                embedding,
+            })
+            .collect(),
+    };
+    let dimension = match (&embedding, chunks.first()) {
+        (Some(embedding), _) => embedding.len(),
+        (None, Some(chunk)) => chunk.embedding.len(),
+        (None, None) => return Err(not_found("Embedding not found in vector store")),
+    };
 
-    Ok(StatusCode::OK)
+    Ok(Json(MessageEmbeddingResponse {
+        message_id: id,
+        embedding_id,
+        model: state.config.read().await.embedding_model.clone(),
+        dimension,
+        embedding_model: message.embedding_model,
+        embedding_dim: message.embedding_dim,
+        embedding: embedding.unwrap_or_default(),
+        chunks,
+    }))
 }
 
 // ============================================
-// NEW ENDPOINT: PUT /api/v1/conversations/{id}/pin
+// Endpoint: GET /api/v1/messages/{id}/attachments
 // ============================================
 #[utoipa::path(
-    put,
-    path = "/api/v1/conversations/{id}/pin",
+    get,
+    path = "/api/v1/messages/{id}/attachments",
     responses(
-        (status = 200, description = "Conversation pinned"),
-        (status = 404, description = "Not found", body = ErrorResponse)
+        (status = 200, description = "Files the message referenced", body = MessageAttachmentsResponse),
+        (status = 404, description = "Message not found", body = ErrorResponse)
     ),
     params(
-        ("id" = String, Path, description = "Conversation UUID")
+        ("id" = String, Path, description = "Message UUID")
     )
 )]
-async fn pin_conversation(
+async fn get_message_attachments(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
-) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
-    // Toggle pin status by setting importance_score high
-    state.repo.update_importance(id, 10).await.map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: e.to_string(),
-                code: 500,
-            }),
-        )
-    })?;
+) -> Result<Json<MessageAttachmentsResponse>, AppError> {
+    let message = state
+        .repo
+        .find_message_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Message not found".to_string()))?;
 
-    Ok(StatusCode::OK)
+    Ok(Json(MessageAttachmentsResponse {
+        message_id: id,
+        attachments: message.attachments(),
+    }))
 }
 
 // ============================================
-// NEW ENDPOINT: PUT /api/v1/conversations/{id}/archive
+// Endpoint: PATCH /api/v1/messages/{id}/metadata
 // ============================================
 #[utoipa::path(
-    put,
-    path = "/api/v1/conversations/{id}/archive",
+    patch,
+    path = "/api/v1/messages/{id}/metadata",
+    request_body = UpdateMessageMetadataRequest,
     responses(
-        (status = 200, description = "Conversation archived"),
-        (status = 404, description = "Not found", body = ErrorResponse)
+        (status = 200, description = "Metadata updated", body = Message),
+        (status = 400, description = "Metadata is not a JSON object or sets a reserved key", body = ErrorResponse),
+        (status = 404, description = "Message not found", body = ErrorResponse)
+    ),
+    params(
+        ("id" = String, Path, description = "Message UUID")
+    )
+)]
+async fn update_message_metadata(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    ApiJson(req): ApiJson<UpdateMessageMetadataRequest>,
+) -> Result<Json<Message>, AppError> {
+    let message = state
+        .repo
+        .update_message_metadata(id, req.metadata, req.merge)
+        .await?;
+    state.events.publish(MemoryEvent::Updated {
+        conversation_id: message.conversation_id,
+    });
+
+    Ok(Json(message))
+}
+
+// ============================================
+// Endpoint: POST /api/v1/conversations/{id}/labels
+// ============================================
+#[utoipa::path(
+    post,
+    path = "/api/v1/conversations/{id}/labels",
+    request_body = AddLabelRequest,
+    responses(
+        (status = 200, description = "Label added or reweighted", body = ConversationLabelsResponse),
+        (status = 400, description = "Empty label or weight out of range", body = ErrorResponse),
+        (status = 404, description = "Conversation not found", body = ErrorResponse)
     ),
     params(
         ("id" = String, Path, description = "Conversation UUID")
     )
 )]
-async fn archive_conversation(
+async fn add_conversation_label(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
-) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
-    state
+    ApiJson(req): ApiJson<AddLabelRequest>,
+) -> Result<Json<ConversationLabelsResponse>, AppError> {
+    state.repo.add_label(id, &req.label, req.weight).await?;
+    state.events.publish(MemoryEvent::Updated {
+        conversation_id: id,
+    });
+
+    conversation_labels_response(&state, id).await
+}
+
+// ============================================
+// Endpoint: DELETE /api/v1/conversations/{id}/labels/{label}
+// ============================================
+#[utoipa::path(
+    delete,
+    path = "/api/v1/conversations/{id}/labels/{label}",
+    responses(
+        (status = 200, description = "Label removed", body = ConversationLabelsResponse),
+        (status = 404, description = "Conversation or label not found", body = ErrorResponse)
+    ),
+    params(
+        ("id" = String, Path, description = "Conversation UUID"),
+        ("label" = String, Path, description = "Additional label to remove")
+    )
+)]
+async fn remove_conversation_label(
+    State(state): State<AppState>,
+    Path((id, label)): Path<(Uuid, String)>,
+) -> Result<Json<ConversationLabelsResponse>, AppError> {
+    if !state.repo.remove_label(id, &label).await? {
+        return Err(AppError::NotFound(format!(
+            "Conversation {} has no label '{}'",
+            id, label
+        )));
+    }
+    state.events.publish(MemoryEvent::Updated {
+        conversation_id: id,
+    });
+
+    conversation_labels_response(&state, id).await
+}
+
+async fn conversation_labels_response(
+    state: &AppState,
+    id: Uuid,
+) -> Result<Json<ConversationLabelsResponse>, AppError> {
+    let conv = state
+        .repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Conversation not found".to_string()))?;
+
+    Ok(Json(ConversationLabelsResponse {
+        conversation_id: id,
+        label: conv.label,
+        labels: state.repo.find_labels(id).await?,
+    }))
+}
+
+// ============================================
+// Endpoint: POST /api/v1/messages/{id}/move
+// ============================================
+#[utoipa::path(
+    post,
+    path = "/api/v1/messages/{id}/move",
+    request_body = MoveMessageRequest,
+    responses(
+        (status = 200, description = "Message moved", body = Message),
+        (status = 404, description = "Message or target conversation not found", body = ErrorResponse)
+    ),
+    params(
+        ("id" = String, Path, description = "Message UUID")
+    )
+)]
+async fn move_message(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    ApiJson(req): ApiJson<MoveMessageRequest>,
+) -> Result<Json<Message>, AppError> {
+    let source = state
+        .repo
+        .find_message_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Message {} not found", id)))?
+        .conversation_id;
+
+    let message = state
+        .repo
+        .move_message(id, req.target_conversation_id)
+        .await?;
+
+    if source != message.conversation_id {
+        for conversation_id in [source, message.conversation_id] {
+            state
+                .events
+                .publish(MemoryEvent::Updated { conversation_id });
+        }
+    }
+
+    Ok(Json(message))
+}
+
+// ============================================
+// Endpoint: POST /api/v1/maintenance/reindex-fts
+// ============================================
+#[utoipa::path(
+    post,
+    path = "/api/v1/maintenance/reindex-fts",
+    responses(
+        (status = 200, description = "FTS index rebuilt", body = ReindexFtsResponse),
+        (status = 401, description = "Missing API key"),
+        (status = 403, description = "Admin API key required"),
+        (status = 500, description = "Reindex failed", body = ErrorResponse)
+    )
+)]
+pub async fn reindex_fts(
+    _auth: AdminAuth,
+    State(state): State<AppState>,
+) -> Result<Json<ReindexFtsResponse>, AppError> {
+    let indexed = state
         .repo
-        .update_status(id, "archived")
+        .reindex_fts()
         .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse {
-                    error: e.to_string(),
-                    code: 500,
-                }),
-            )
-        })?;
+        .map_err(|e| AppError::Internal(format!("FTS reindex failed: {}", e)))?;
+
+    Ok(Json(ReindexFtsResponse { indexed }))
+}
+
+// ============================================
+// Endpoint: POST /api/v1/maintenance/reconcile-embeddings
+// ============================================
+#[utoipa::path(
+    post,
+    path = "/api/v1/maintenance/reconcile-embeddings",
+    responses(
+        (status = 200, description = "Orphaned vectors removed and missing ones queued", body = ReconcileEmbeddingsResponse),
+        (status = 400, description = "Embeddings are disabled", body = ErrorResponse),
+        (status = 401, description = "Missing API key"),
+        (status = 403, description = "Admin API key required"),
+        (status = 500, description = "Vector store or database error", body = ErrorResponse)
+    )
+)]
+pub async fn reconcile_embeddings(
+    _auth: AdminAuth,
+    State(state): State<AppState>,
+) -> Result<Json<ReconcileEmbeddingsResponse>, AppError> {
+    if !state.config.read().await.embeddings_enabled {
+        return Err(AppError::BadRequest(
+            "embeddings are disabled, nothing to reconcile".to_string(),
+        ));
+    }
+
+    let report = state
+        .repo
+        .reconcile_embeddings()
+        .await
+        .map_err(|e| AppError::Internal(format!("Embedding reconciliation failed: {}", e)))?;
+
+    let mut missing_vectors_requeued = 0;
+    for (conversation_id, message_ids) in report.missing_vectors {
+        let count = message_ids.len();
+        let job = EmbeddingJob {
+            conversation_id: conversation_id.to_string(),
+            message_ids: message_ids.iter().map(Uuid::to_string).collect(),
+        };
+        match state.embedding_queue.enqueue(job).await {
+            Ok(()) => missing_vectors_requeued += count,
+            Err(e) => tracing::warn!(
+                "Could not queue re-embedding for {}: {}",
+                conversation_id,
+                e
+            ),
+        }
+    }
+
+    Ok(Json(ReconcileEmbeddingsResponse {
+        orphaned_vectors_removed: report.orphaned_vectors_removed,
+        missing_vectors_requeued,
+    }))
+}
+
+// ============================================
+// Endpoint: POST /api/v1/maintenance/recompute-aggregates
+// ============================================
+#[utoipa::path(
+    post,
+    path = "/api/v1/maintenance/recompute-aggregates",
+    responses(
+        (status = 200, description = "Drifted conversation aggregates corrected", body = RecomputeAggregatesResponse),
+        (status = 401, description = "Missing API key"),
+        (status = 403, description = "Admin API key required"),
+        (status = 500, description = "Database error", body = ErrorResponse)
+    )
+)]
+pub async fn recompute_aggregates(
+    _auth: AdminAuth,
+    State(state): State<AppState>,
+) -> Result<Json<RecomputeAggregatesResponse>, AppError> {
+    let report = state
+        .repo
+        .recompute_aggregates()
+        .await
+        .map_err(|e| AppError::Internal(format!("Aggregate recompute failed: {}", e)))?;
+
+    Ok(Json(RecomputeAggregatesResponse {
+        conversations_corrected: report.conversations_corrected,
+        messages_counted: report.messages_counted,
+    }))
+}
+
+// ============================================
+// Endpoint: POST /api/v1/maintenance/optimize
+// ============================================
+#[utoipa::path(
+    post,
+    path = "/api/v1/maintenance/optimize",
+    responses(
+        (status = 202, description = "VACUUM/ANALYZE queued; the task reports the size after", body = OptimizeDbResponse),
+        (status = 401, description = "Missing API key"),
+        (status = 403, description = "Admin API key required")
+    )
+)]
+pub async fn optimize_db(
+    _auth: AdminAuth,
+    State(state): State<AppState>,
+) -> (StatusCode, Json<OptimizeDbResponse>) {
+    let database_url = state.config.read().await.database_url.clone();
+    let size_before_bytes = crate::storage::db::sqlite_file_path(&database_url)
+        .and_then(|p| std::fs::metadata(p).ok())
+        .map(|m| m.len());
+
+    // VACUUM rewrites the whole file, so don't hold the request open for it
+    let task_id = state.tasks.start("optimize_db");
+    let tasks = state.tasks.clone();
+    let db = state.repo.get_db().clone();
+    tokio::spawn(async move {
+        match crate::storage::db::optimize(&db, &database_url).await {
+            Ok(report) => {
+                tracing::info!(
+                    "Database optimized (vacuumed: {}): {:?} -> {:?} bytes",
+                    report.vacuumed,
+                    report.size_before,
+                    report.size_after
+                );
+                tasks.complete(
+                    task_id,
+                    json!({
+                        "size_before_bytes": report.size_before,
+                        "size_after_bytes": report.size_after,
+                        "vacuumed": report.vacuumed,
+                    }),
+                );
+            }
+            Err(e) => {
+                tracing::error!("Database optimize failed: {}", e);
+                tasks.fail(task_id, e.to_string());
+            }
+        }
+    });
+
+    (
+        StatusCode::ACCEPTED,
+        Json(OptimizeDbResponse {
+            status: "accepted".to_string(),
+            size_before_bytes,
+            task_id,
+        }),
+    )
+}
+
+// ============================================
+// Endpoint: GET /api/v1/version
+// ============================================
+#[utoipa::path(
+    get,
+    path = "/api/v1/version",
+    responses(
+        (status = 200, description = "Build and schema version", body = VersionResponse)
+    )
+)]
+pub async fn version(State(state): State<AppState>) -> Json<VersionResponse> {
+    let schema_version = state.repo.schema_version().await.unwrap_or_else(|e| {
+        tracing::warn!("Failed to read schema version: {}", e);
+        None
+    });
+
+    Json(VersionResponse {
+        crate_version: env!("CARGO_PKG_VERSION").to_string(),
+        // Set by build.rs; "unknown" when built outside a git checkout
+        git_sha: option_env!("SEKHA_GIT_SHA")
+            .unwrap_or("unknown")
+            .to_string(),
+        schema_version: schema_version.unwrap_or_else(|| "unknown".to_string()),
+    })
+}
+
+// ============================================
+// Endpoint 9: GET /metrics
+// ============================================
+pub async fn metrics(State(state): State<AppState>) -> String {
+    format!(
+        "# HELP sekha_conversations_total Total number of conversations\n\
+         # TYPE sekha_conversations_total gauge\n\
+         sekha_conversations_total 0\n\
+         # HELP sekha_search_cache_hits_total Semantic searches answered from the cache\n\
+         # TYPE sekha_search_cache_hits_total counter\n\
+         sekha_search_cache_hits_total {}\n\
+         # HELP sekha_search_cache_misses_total Semantic searches that missed the cache\n\
+         # TYPE sekha_search_cache_misses_total counter\n\
+         sekha_search_cache_misses_total {}\n",
+        state.search_cache.hits(),
+        state.search_cache.misses()
+    )
+}
+
+// ============================================
+// NEW ENDPOINT: PUT /api/v1/conversations/{id}/folder
+// ============================================
+#[utoipa::path(
+    put,
+    path = "/api/v1/conversations/{id}/folder",
+    request_body = UpdateFolderRequest,
+    responses(
+        (status = 200, description = "Folder updated"),
+        (status = 404, description = "Not found", body = ErrorResponse)
+    )
+)]
+async fn update_conversation_folder(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    ApiJson(req): ApiJson<UpdateFolderRequest>,
+) -> Result<StatusCode, AppError> {
+    // Reuse update_label method with same label
+    let conv = state
+        .repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Conversation not found".to_string()))?;
+
+    state
+        .repo
+        .update_label(id, &conv.label, &req.folder)
+        .await?;
+    state.events.publish(MemoryEvent::Updated {
+        conversation_id: id,
+    });
+
+    Ok(StatusCode::OK)
+}
+
+// ============================================
+// NEW ENDPOINT: PATCH /api/v1/conversations/{id}
+// ============================================
+const VALID_STATUSES: [&str; 2] = ["active", "archived"];
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/conversations/{id}",
+    request_body = PatchConversationRequest,
+    responses(
+        (status = 200, description = "Conversation updated", body = ConversationResponse),
+        (status = 400, description = "Invalid field value", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse)
+    ),
+    params(
+        ("id" = String, Path, description = "Conversation UUID")
+    )
+)]
+async fn patch_conversation(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    ApiJson(req): ApiJson<PatchConversationRequest>,
+) -> Result<Json<ConversationResponse>, AppError> {
+    if let Some(status) = &req.status {
+        if !VALID_STATUSES.contains(&status.as_str()) {
+            return Err(AppError::BadRequest(format!(
+                "status must be one of: {}",
+                VALID_STATUSES.join(", ")
+            )));
+        }
+    }
+    if let Some(score) = req.importance_score {
+        if !importance::is_valid(score) {
+            return Err(AppError::BadRequest(format!(
+                "importance_score must be between {} and {}",
+                MIN_IMPORTANCE, MAX_IMPORTANCE
+            )));
+        }
+    }
+    if req
+        .label
+        .as_ref()
+        .is_some_and(|label| label.trim().is_empty())
+    {
+        return Err(AppError::BadRequest("label must not be empty".to_string()));
+    }
+    let folder = match req.folder.as_deref() {
+        Some(folder) => Some(
+            normalize_folder(folder)
+                .ok_or_else(|| AppError::BadRequest("folder must not be empty".to_string()))?,
+        ),
+        None => None,
+    };
+
+    // Pinning is expressed through importance_score, same as the pin endpoint
+    let importance_score = match (req.pinned, req.importance_score) {
+        (Some(true), Some(score)) if score != PINNED_IMPORTANCE => {
+            return Err(AppError::BadRequest(format!(
+                "pinned conversations must have importance_score {}",
+                PINNED_IMPORTANCE
+            )));
+        }
+        (Some(false), Some(PINNED_IMPORTANCE)) => {
+            return Err(AppError::BadRequest(format!(
+                "unpinned conversations must have importance_score below {}",
+                PINNED_IMPORTANCE
+            )));
+        }
+        (Some(true), _) => Some(PINNED_IMPORTANCE),
+        (_, score) => score,
+    };
+
+    let patch = ConversationPatch {
+        label: req.label,
+        folder,
+        status: req.status,
+        importance_score,
+        unpin: req.pinned == Some(false),
+    };
+
+    let updated = state.repo.apply_patch(id, patch).await?;
+    state.events.publish(MemoryEvent::Updated {
+        conversation_id: id,
+    });
+
+    let message_count = state
+        .repo
+        .count_messages_in_conversation(id)
+        .await
+        .unwrap_or(0);
+
+    Ok(Json(ConversationResponse {
+        id: updated.id,
+        label: updated.label,
+        folder: updated.folder,
+        status: updated.status,
+        message_count: message_count as usize,
+        session_count: updated.session_count,
+        created_at: updated.created_at,
+        context_pinned: updated.context_pinned,
+        last_accessed_at: updated.last_accessed_at,
+    }))
+}
+
+// ============================================
+// Endpoint: POST /api/v1/conversations/bulk/move
+// ============================================
+#[utoipa::path(
+    post,
+    path = "/api/v1/conversations/bulk/move",
+    request_body = BulkMoveRequest,
+    responses(
+        (status = 200, description = "Conversations moved (or previewed)", body = BulkOperationResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse)
+    )
+)]
+async fn bulk_move(
+    State(state): State<AppState>,
+    ApiJson(req): ApiJson<BulkMoveRequest>,
+) -> Result<Json<BulkOperationResponse>, AppError> {
+    if req.folder.trim().is_empty() {
+        return Err(AppError::BadRequest("folder must not be empty".to_string()));
+    }
+
+    let affected_ids = state
+        .repo
+        .bulk_move(req.ids, &req.folder, req.dry_run)
+        .await?;
+    if !req.dry_run {
+        publish_updated(&state, &affected_ids);
+    }
+
+    Ok(Json(bulk_response(affected_ids, req.dry_run)))
+}
+
+// ============================================
+// Endpoint: POST /api/v1/conversations/bulk/status
+// ============================================
+#[utoipa::path(
+    post,
+    path = "/api/v1/conversations/bulk/status",
+    request_body = BulkStatusRequest,
+    responses(
+        (status = 200, description = "Statuses updated (or previewed)", body = BulkOperationResponse),
+        (status = 400, description = "Invalid status", body = ErrorResponse)
+    )
+)]
+async fn bulk_status(
+    State(state): State<AppState>,
+    ApiJson(req): ApiJson<BulkStatusRequest>,
+) -> Result<Json<BulkOperationResponse>, AppError> {
+    if !VALID_STATUSES.contains(&req.status.as_str()) {
+        return Err(AppError::BadRequest(format!(
+            "status must be one of: {}",
+            VALID_STATUSES.join(", ")
+        )));
+    }
+
+    let affected_ids = state
+        .repo
+        .bulk_update_status(req.ids, &req.status, req.dry_run)
+        .await?;
+    if !req.dry_run {
+        publish_updated(&state, &affected_ids);
+    }
+
+    Ok(Json(bulk_response(affected_ids, req.dry_run)))
+}
+
+// ============================================
+// Endpoint: POST /api/v1/conversations/bulk-delete
+// ============================================
+#[utoipa::path(
+    post,
+    path = "/api/v1/conversations/bulk-delete",
+    request_body = BulkDeleteRequest,
+    responses(
+        (status = 200, description = "Conversations and their vectors deleted", body = BulkDeleteResponse),
+        (status = 500, description = "Vector store or database error", body = ErrorResponse)
+    )
+)]
+async fn bulk_delete(
+    State(state): State<AppState>,
+    ApiJson(req): ApiJson<BulkDeleteRequest>,
+) -> Result<Json<BulkDeleteResponse>, AppError> {
+    let (deleted_ids, vectors_removed) = state.repo.delete_many(req.ids).await?;
+    for &conversation_id in &deleted_ids {
+        state
+            .events
+            .publish(MemoryEvent::Deleted { conversation_id });
+    }
+
+    Ok(Json(BulkDeleteResponse {
+        deleted: deleted_ids.len(),
+        deleted_ids,
+        vectors_removed,
+    }))
+}
+
+// ============================================
+// Endpoint: POST /api/v1/tags/bulk-add
+// ============================================
+#[utoipa::path(
+    post,
+    path = "/api/v1/tags/bulk-add",
+    request_body = BulkAddTagsRequest,
+    responses(
+        (status = 200, description = "Tags attached; per-conversation results", body = BulkTagResponse),
+        (status = 400, description = "No tags given", body = ErrorResponse)
+    )
+)]
+async fn bulk_add_tags(
+    State(state): State<AppState>,
+    ApiJson(req): ApiJson<BulkAddTagsRequest>,
+) -> Result<Json<BulkTagResponse>, AppError> {
+    let tags: Vec<String> = req
+        .tags
+        .iter()
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect();
+    if tags.is_empty() {
+        return Err(AppError::BadRequest("tags must not be empty".to_string()));
+    }
+
+    let results = state.repo.add_tags_many(req.conversation_ids, tags).await?;
+    Ok(Json(bulk_tag_response(&state, results)))
+}
+
+// ============================================
+// Endpoint: POST /api/v1/tags/bulk-remove
+// ============================================
+#[utoipa::path(
+    post,
+    path = "/api/v1/tags/bulk-remove",
+    request_body = BulkRemoveTagRequest,
+    responses(
+        (status = 200, description = "Tag removed; per-conversation results", body = BulkTagResponse),
+        (status = 400, description = "Empty tag", body = ErrorResponse)
+    )
+)]
+async fn bulk_remove_tag(
+    State(state): State<AppState>,
+    ApiJson(req): ApiJson<BulkRemoveTagRequest>,
+) -> Result<Json<BulkTagResponse>, AppError> {
+    let tag = req.tag.trim();
+    if tag.is_empty() {
+        return Err(AppError::BadRequest("tag must not be empty".to_string()));
+    }
+
+    let results = state
+        .repo
+        .remove_tag_from_many(req.conversation_ids, tag)
+        .await?;
+    Ok(Json(bulk_tag_response(&state, results)))
+}
+
+fn bulk_tag_response(state: &AppState, results: Vec<TagUpdate>) -> BulkTagResponse {
+    let updated_ids: Vec<Uuid> = results
+        .iter()
+        .filter(|r| r.changed > 0)
+        .map(|r| r.conversation_id)
+        .collect();
+    publish_updated(state, &updated_ids);
+
+    BulkTagResponse {
+        updated: updated_ids.len(),
+        results,
+    }
+}
+
+// ============================================
+// Endpoint: GET /api/v1/folders/tree
+// ============================================
+#[utoipa::path(
+    get,
+    path = "/api/v1/folders/tree",
+    responses(
+        (status = 200, description = "Folders nested by path, with counts rolled up", body = FolderTreeResponse)
+    )
+)]
+async fn folder_tree(State(state): State<AppState>) -> Result<Json<FolderTreeResponse>, AppError> {
+    let counts = state.repo.folder_counts().await?;
+
+    Ok(Json(FolderTreeResponse {
+        folders: build_folder_tree(&counts),
+    }))
+}
+
+// ============================================
+// Endpoint: GET /api/v1/suggest
+// ============================================
+#[utoipa::path(
+    get,
+    path = "/api/v1/suggest",
+    responses(
+        (status = 200, description = "Labels, folders and tags starting with the prefix, most used first", body = SearchSuggestions)
+    ),
+    params(
+        ("q" = String, Query, description = "Prefix typed so far (case-insensitive)"),
+        ("limit" = Option<usize>, Query, description = "Suggestions per category (default 5, max 20)")
+    )
+)]
+async fn suggest(
+    State(state): State<AppState>,
+    Query(params): Query<SuggestParams>,
+) -> Result<Json<SearchSuggestions>, AppError> {
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_SUGGESTIONS_PER_CATEGORY)
+        .min(MAX_SUGGESTIONS_PER_CATEGORY);
+
+    Ok(Json(state.repo.suggest(params.q.trim(), limit).await?))
+}
+
+// ============================================
+// Endpoint: POST /api/v1/folders/rename
+// ============================================
+#[utoipa::path(
+    post,
+    path = "/api/v1/folders/rename",
+    request_body = RenameFolderRequest,
+    responses(
+        (status = 200, description = "Folder renamed (or previewed)", body = BulkOperationResponse),
+        (status = 400, description = "Invalid folder", body = ErrorResponse)
+    )
+)]
+async fn rename_folder(
+    State(state): State<AppState>,
+    ApiJson(req): ApiJson<RenameFolderRequest>,
+) -> Result<Json<BulkOperationResponse>, AppError> {
+    if req.from.trim().is_empty() || req.to.trim().is_empty() {
+        return Err(AppError::BadRequest(
+            "from and to must not be empty".to_string(),
+        ));
+    }
+
+    let affected_ids = state
+        .repo
+        .rename_folder(&req.from, &req.to, req.dry_run)
+        .await?;
+    if !req.dry_run {
+        publish_updated(&state, &affected_ids);
+    }
+
+    Ok(Json(bulk_response(affected_ids, req.dry_run)))
+}
+
+fn publish_updated(state: &AppState, ids: &[Uuid]) {
+    for &conversation_id in ids {
+        state
+            .events
+            .publish(MemoryEvent::Updated { conversation_id });
+    }
+}
+
+fn bulk_response(affected_ids: Vec<Uuid>, dry_run: bool) -> BulkOperationResponse {
+    BulkOperationResponse {
+        count: affected_ids.len(),
+        affected_ids,
+        dry_run,
+    }
+}
+
+// ============================================
+// NEW ENDPOINT: PUT /api/v1/conversations/{id}/pin
+// ============================================
+#[utoipa::path(
+    put,
+    path = "/api/v1/conversations/{id}/pin",
+    responses(
+        (status = 200, description = "Conversation pinned"),
+        (status = 404, description = "Not found", body = ErrorResponse)
+    ),
+    params(
+        ("id" = String, Path, description = "Conversation UUID")
+    )
+)]
+async fn pin_conversation(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    // Toggle pin status by setting importance_score high
+    state.repo.update_importance(id, PINNED_IMPORTANCE).await?;
+    state.events.publish(MemoryEvent::Updated {
+        conversation_id: id,
+    });
+
+    Ok(StatusCode::OK)
+}
+
+// ============================================
+// Endpoint: PUT/DELETE /api/v1/conversations/{id}/context-pin
+// ============================================
+#[utoipa::path(
+    put,
+    path = "/api/v1/conversations/{id}/context-pin",
+    responses(
+        (status = 200, description = "Conversation is always included in assembled context"),
+        (status = 404, description = "Not found", body = ErrorResponse)
+    ),
+    params(
+        ("id" = String, Path, description = "Conversation UUID")
+    )
+)]
+async fn context_pin_conversation(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    set_context_pinned(&state, id, true).await
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/conversations/{id}/context-pin",
+    responses(
+        (status = 200, description = "Conversation goes back to relevance-only assembly"),
+        (status = 404, description = "Not found", body = ErrorResponse)
+    ),
+    params(
+        ("id" = String, Path, description = "Conversation UUID")
+    )
+)]
+async fn context_unpin_conversation(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    set_context_pinned(&state, id, false).await
+}
+
+async fn set_context_pinned(
+    state: &AppState,
+    id: Uuid,
+    pinned: bool,
+) -> Result<StatusCode, AppError> {
+    state.repo.set_context_pinned(id, pinned).await?;
+    state.events.publish(MemoryEvent::Updated {
+        conversation_id: id,
+    });
+
+    Ok(StatusCode::OK)
+}
+
+// ============================================
+// NEW ENDPOINT: POST /api/v1/conversations/{id}/touch
+// ============================================
+#[utoipa::path(
+    post,
+    path = "/api/v1/conversations/{id}/touch",
+    responses(
+        (status = 200, description = "updated_at bumped to now"),
+        (status = 404, description = "Not found", body = ErrorResponse)
+    ),
+    params(
+        ("id" = String, Path, description = "Conversation UUID")
+    )
+)]
+async fn touch_conversation(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    state.repo.touch(id).await.map_err(|e| match e {
+        RepositoryError::NotFound(_) => AppError::NotFound("Conversation not found".to_string()),
+        e => AppError::Internal(e.to_string()),
+    })?;
+
+    Ok(StatusCode::OK)
+}
+
+// ============================================
+// Endpoint: POST /api/v1/conversations/{id}/offload
+// ============================================
+#[utoipa::path(
+    post,
+    path = "/api/v1/conversations/{id}/offload",
+    responses(
+        (status = 200, description = "Conversation moved to cold storage", body = OffloadResponse),
+        (status = 404, description = "Not found", body = ErrorResponse)
+    ),
+    params(
+        ("id" = String, Path, description = "Conversation UUID")
+    )
+)]
+async fn offload_conversation(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<OffloadResponse>, AppError> {
+    let offload_dir = state.config.read().await.offload_dir.clone();
+
+    let record = state
+        .repo
+        .archive_to_disk(id, std::path::Path::new(&offload_dir))
+        .await
+        .map_err(|e| match e {
+            RepositoryError::NotFound(_) => {
+                AppError::NotFound("Conversation not found".to_string())
+            }
+            e => e.into(),
+        })?;
+    // Gone from the database until reloaded
+    state.events.publish(MemoryEvent::Deleted {
+        conversation_id: id,
+    });
+
+    Ok(Json(OffloadResponse {
+        conversation_id: record.conversation_id,
+        file_path: record.file_path,
+        message_count: record.message_count,
+        offloaded_at: record.offloaded_at,
+    }))
+}
+
+// ============================================
+// Endpoint: POST /api/v1/conversations/{id}/reload
+// ============================================
+#[utoipa::path(
+    post,
+    path = "/api/v1/conversations/{id}/reload",
+    responses(
+        (status = 200, description = "Conversation restored from cold storage", body = ConversationResponse),
+        (status = 404, description = "No offloaded conversation with this ID", body = ErrorResponse)
+    ),
+    params(
+        ("id" = String, Path, description = "Conversation UUID")
+    )
+)]
+async fn reload_conversation(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ConversationResponse>, AppError> {
+    let conv = state
+        .repo
+        .restore_from_disk(id)
+        .await
+        .map_err(|e| match e {
+            RepositoryError::NotFound(_) => {
+                AppError::NotFound("Offloaded conversation not found".to_string())
+            }
+            e => e.into(),
+        })?;
+    state.events.publish(MemoryEvent::Created {
+        conversation_id: id,
+    });
+
+    let message_count = state
+        .repo
+        .count_messages_in_conversation(id)
+        .await
+        .unwrap_or(0);
+
+    Ok(Json(ConversationResponse {
+        id: conv.id,
+        label: conv.label,
+        folder: conv.folder,
+        status: conv.status,
+        message_count: message_count as usize,
+        session_count: conv.session_count,
+        created_at: conv.created_at,
+        context_pinned: conv.context_pinned,
+        last_accessed_at: conv.last_accessed_at,
+    }))
+}
+
+// ============================================
+// NEW ENDPOINT: PUT /api/v1/conversations/{id}/archive
+// ============================================
+#[utoipa::path(
+    put,
+    path = "/api/v1/conversations/{id}/archive",
+    responses(
+        (status = 200, description = "Conversation archived"),
+        (status = 202, description = "Conversation archived; compaction runs as the returned task", body = TaskStartedResponse),
+        (status = 404, description = "Not found", body = ErrorResponse)
+    ),
+    params(
+        ("id" = String, Path, description = "Conversation UUID")
+    )
+)]
+async fn archive_conversation(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<axum::response::Response, AppError> {
+    state.repo.update_status(id, "archived").await?;
+    state.events.publish(MemoryEvent::Updated {
+        conversation_id: id,
+    });
+
+    Ok(archived_response(
+        compact_if_enabled(&state, vec![id]).await,
+    ))
+}
 
-    Ok(StatusCode::OK)
+/// Summarize away the bodies of just-archived conversations when
+/// `compact_on_archive` is set, as a background task whose ID is returned.
+/// Failures leave a conversation archived but uncompacted.
+async fn compact_if_enabled(state: &AppState, conversation_ids: Vec<Uuid>) -> Option<Uuid> {
+    if !state.config.read().await.compact_on_archive || conversation_ids.is_empty() {
+        return None;
+    }
+
+    let task_id = state.tasks.start("compact");
+    let orchestrator = state.orchestrator.clone();
+    let events = state.events.clone();
+    let tasks = state.tasks.clone();
+    tokio::spawn(async move {
+        let mut messages_compacted = 0;
+        let mut failed = Vec::new();
+        for conversation_id in conversation_ids {
+            match orchestrator.compact_conversation(conversation_id).await {
+                Ok(0) => {}
+                Ok(count) => {
+                    messages_compacted += count;
+                    events.publish(MemoryEvent::Updated { conversation_id });
+                }
+                Err(e) => {
+                    tracing::warn!("Could not compact {}: {}", conversation_id, e);
+                    failed.push(conversation_id);
+                }
+            }
+        }
+        tasks.complete(
+            task_id,
+            serde_json::json!({
+                "messages_compacted": messages_compacted,
+                "failed": failed,
+            }),
+        );
+    });
+
+    Some(task_id)
+}
+
+/// `202` with the compaction task when one was started, otherwise `200`
+fn archived_response(compaction_task: Option<Uuid>) -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    match compaction_task {
+        Some(task_id) => {
+            (StatusCode::ACCEPTED, Json(TaskStartedResponse { task_id })).into_response()
+        }
+        None => StatusCode::OK.into_response(),
+    }
 }
 
 // ============================================
@@ -644,9 +2420,7 @@ async fn archive_conversation(
         (status = 500, description = "Server error", body = ErrorResponse)
     )
 )]
-async fn rebuild_embeddings(
-    State(_state): State<AppState>,
-) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+async fn rebuild_embeddings(State(_state): State<AppState>) -> Result<StatusCode, AppError> {
     // Trigger async rebuild via embedding service
     tokio::spawn(async move {
         tracing::info!("Starting embedding rebuild...");
@@ -656,34 +2430,123 @@ async fn rebuild_embeddings(
     Ok(StatusCode::ACCEPTED)
 }
 
+// ============================================
+// Endpoint: POST /api/v1/rebuild-embeddings/scoped
+// ============================================
+#[utoipa::path(
+    post,
+    path = "/api/v1/rebuild-embeddings/scoped",
+    request_body = ConversationFilter,
+    responses(
+        (status = 202, description = "Re-embedding started", body = TaskStartedResponse),
+        (status = 400, description = "Empty filter or embeddings disabled", body = ErrorResponse),
+        (status = 401, description = "Missing API key"),
+        (status = 403, description = "Admin API key required")
+    )
+)]
+pub async fn rebuild_embeddings_scoped(
+    _auth: AdminAuth,
+    State(state): State<AppState>,
+    ApiJson(mut filter): ApiJson<ConversationFilter>,
+) -> Result<(StatusCode, Json<TaskStartedResponse>), AppError> {
+    if !state.config.read().await.embeddings_enabled {
+        return Err(AppError::BadRequest(
+            "embeddings are disabled, nothing to re-embed".to_string(),
+        ));
+    }
+    if filter == ConversationFilter::default() {
+        return Err(AppError::BadRequest(
+            "set at least one of folder, label, created_after or created_before".to_string(),
+        ));
+    }
+    filter.folder = filter.folder.as_deref().and_then(normalize_folder);
+
+    let task_id = state.tasks.start("reembed_scope");
+    let repo = state.repo.clone();
+    let tasks = state.tasks.clone();
+    tokio::spawn(async move {
+        match repo.reembed_scope(filter).await {
+            Ok(report) => tasks.complete(task_id, serde_json::json!(report)),
+            Err(e) => {
+                tracing::error!("Scoped re-embedding failed: {}", e);
+                tasks.fail(task_id, e.to_string());
+            }
+        }
+    });
+
+    Ok((StatusCode::ACCEPTED, Json(TaskStartedResponse { task_id })))
+}
+
+// ============================================
+// Endpoint: GET /api/v1/tasks/{id}
+// ============================================
+#[utoipa::path(
+    get,
+    path = "/api/v1/tasks/{id}",
+    responses(
+        (status = 200, description = "Task status", body = TaskStatus),
+        (status = 401, description = "Missing API key"),
+        (status = 403, description = "Admin API key required"),
+        (status = 404, description = "Unknown or long-finished task", body = ErrorResponse)
+    ),
+    params(
+        ("id" = String, Path, description = "Task UUID")
+    )
+)]
+pub async fn get_task(
+    _auth: AdminAuth,
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<TaskStatus>, AppError> {
+    state
+        .tasks
+        .get(id)
+        .map(Json)
+        .ok_or_else(|| AppError::NotFound(format!("Task {} not found", id)))
+}
+
 // POST /api/v1/search/fts
 #[utoipa::path(
     post,
     path = "/api/v1/search/fts",
     request_body = FtsSearchRequest,
     responses(
-        (status = 200, description = "Full-text search results", body = FtsSearchResponse)
+        (status = 200, description = "Full-text search results", body = FtsSearchResponse),
+        (status = 400, description = "Empty query or invalid FTS5 syntax", body = ErrorResponse)
     )
 )]
 async fn full_text_search(
     State(state): State<AppState>,
     Json(req): Json<FtsSearchRequest>,
-) -> Result<Json<FtsSearchResponse>, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<Json<FtsSearchResponse>, AppError> {
+    if req.query.trim().is_empty() {
+        return Err(AppError::BadRequest("query must not be empty".to_string()));
+    }
+
+    if let (Some(after), Some(before)) = (req.after, req.before) {
+        if after >= before {
+            return Err(AppError::BadRequest(
+                "after must be earlier than before".to_string(),
+            ));
+        }
+    }
+
     let messages = state
         .repo
-        .full_text_search(&req.query, req.limit)
-        .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse {
-                    error: e.to_string(),
-                    code: 500,
-                }),
-            )
-        })?;
+        .full_text_search(
+            &req.query,
+            req.limit,
+            req.after,
+            req.before,
+            req.conversation_id,
+        )
+        .await?;
 
-    let total = messages.len();
+    // Count every match, not just the returned page, so clients can paginate
+    let total = state
+        .repo
+        .full_text_search_count(&req.query, req.after, req.before, req.conversation_id)
+        .await?;
 
     Ok(Json(FtsSearchResponse {
         results: messages,
@@ -701,34 +2564,124 @@ async fn full_text_search(
     path = "/api/v1/context/assemble",
     request_body = ContextAssembleRequest,
     responses(
-        (status = 200, description = "Context assembled", body = Vec<Message>),
+        (status = 200, description = "Context assembled; a ContextAssemblePromptResponse when `format` is `prompt`, else a ContextAssembleVerboseResponse when `verbose` is set", body = Vec<Message>),
         (status = 500, description = "Server error", body = ErrorResponse)
     )
 )]
 async fn assemble_context(
     State(state): State<AppState>,
     Json(req): Json<ContextAssembleRequest>,
-) -> Result<Json<Vec<Message>>, (StatusCode, Json<ErrorResponse>)> {
-    let results = state
+) -> Result<axum::response::Response, AppError> {
+    use axum::response::IntoResponse;
+
+    let assembled = state
         .orchestrator
-        .assemble_context(
+        .assemble_context_detailed(
             &req.query,
             req.preferred_labels,
             req.context_budget,
             req.excluded_folders,
         )
-        .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse {
-                    error: e.to_string(),
-                    code: 500,
-                }),
-            )
-        })?;
+        .await?;
+
+    if req.format == ContextFormat::Prompt {
+        let prompt = format_prompt(&assembled.messages);
+        return Ok(Json(ContextAssemblePromptResponse {
+            estimated_tokens: estimate_tokens(&prompt),
+            message_count: assembled.messages.len(),
+            prompt,
+        })
+        .into_response());
+    }
+    if !req.verbose {
+        return Ok(Json(assembled.messages).into_response());
+    }
+    Ok(Json(ContextAssembleVerboseResponse {
+        messages: assembled.messages,
+        budget_used: assembled.budget_used,
+        candidates_considered: assembled.candidates_considered,
+        note: assembled.note,
+    })
+    .into_response())
+}
+
+// Endpoint: GET /api/v1/conversations/{id}/context-debug
+#[utoipa::path(
+    get,
+    path = "/api/v1/conversations/{id}/context-debug",
+    responses(
+        (status = 200, description = "Every context candidate with its scores", body = ContextDebugResponse),
+        (status = 401, description = "Missing API key"),
+        (status = 403, description = "Admin API key required"),
+        (status = 404, description = "Conversation not found", body = ErrorResponse)
+    ),
+    params(
+        ("id" = String, Path, description = "Conversation UUID"),
+        ("query" = String, Query, description = "Query to assemble context for"),
+        ("context_budget" = Option<usize>, Query, description = "Token budget used to mark selected candidates (default 4000)")
+    )
+)]
+async fn context_debug(
+    _auth: AdminAuth,
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<ContextDebugParams>,
+) -> Result<Json<ContextDebugResponse>, AppError> {
+    let conversation = state
+        .repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Conversation not found".to_string()))?;
+
+    // Rank as if assembling context for this conversation's label
+    let candidates = state
+        .orchestrator
+        .debug_context(
+            &params.query,
+            vec![conversation.label],
+            params.context_budget,
+            Vec::new(),
+        )
+        .await?;
+
+    Ok(Json(ContextDebugResponse {
+        conversation_id: id,
+        query: params.query,
+        context_budget: params.context_budget,
+        candidates: candidates
+            .into_iter()
+            .map(|c| ContextCandidateDto {
+                message_id: c.message_id,
+                conversation_id: c.conversation_id,
+                source: c.source.as_str().to_string(),
+                similarity: c.similarity,
+                importance: c.importance,
+                recency: c.recency,
+                label_match: c.label_match,
+                score: c.score,
+                selected: c.selected,
+            })
+            .collect(),
+    }))
+}
+
+/// Summary levels the summarize endpoint can generate
+#[derive(Debug, Clone, Copy)]
+enum SummaryLevel {
+    Daily,
+    Weekly,
+    Monthly,
+}
 
-    Ok(Json(results))
+impl SummaryLevel {
+    fn parse(level: &str) -> Option<Self> {
+        match level {
+            "daily" => Some(Self::Daily),
+            "weekly" => Some(Self::Weekly),
+            "monthly" => Some(Self::Monthly),
+            _ => None,
+        }
+    }
 }
 
 // Endpoint: POST /api/v1/summarize
@@ -738,53 +2691,46 @@ async fn assemble_context(
     request_body = SummarizeRequest,
     responses(
         (status = 200, description = "Summary generated", body = SummaryResponse),
+        (status = 404, description = "Conversation not found", body = ErrorResponse),
         (status = 500, description = "Server error", body = ErrorResponse)
     )
 )]
 async fn generate_summary(
     State(state): State<AppState>,
     Json(req): Json<SummarizeRequest>,
-) -> Result<Json<SummaryResponse>, (StatusCode, Json<ErrorResponse>)> {
-    let summary = match req.level.as_str() {
-        "daily" => {
+) -> Result<Json<SummaryResponse>, AppError> {
+    let Some(level) = SummaryLevel::parse(&req.level) else {
+        return Err(AppError::BadRequest(
+            "Invalid level: must be daily, weekly, or monthly".to_string(),
+        ));
+    };
+
+    if state.repo.find_by_id(req.conversation_id).await?.is_none() {
+        return Err(AppError::NotFound("Conversation not found".to_string()));
+    }
+
+    let summary = match level {
+        SummaryLevel::Daily => {
             state
                 .orchestrator
                 .generate_daily_summary(req.conversation_id)
                 .await
         }
-        "weekly" => {
+        SummaryLevel::Weekly => {
             state
                 .orchestrator
                 .summarizer
                 .generate_weekly_summary(req.conversation_id)
                 .await
         }
-        "monthly" => {
+        SummaryLevel::Monthly => {
             state
                 .orchestrator
                 .summarizer
                 .generate_monthly_summary(req.conversation_id)
                 .await
         }
-        _ => {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: "Invalid level: must be daily, weekly, or monthly".to_string(),
-                    code: 400,
-                }),
-            ))
-        }
-    }
-    .map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: e.to_string(),
-                code: 500,
-            }),
-        )
-    })?;
+    }?;
 
     Ok(Json(SummaryResponse {
         conversation_id: req.conversation_id,
@@ -794,6 +2740,186 @@ async fn generate_summary(
     }))
 }
 
+// Endpoint: GET /api/v1/conversations/{id}/summaries
+#[utoipa::path(
+    get,
+    path = "/api/v1/conversations/{id}/summaries",
+    responses(
+        (status = 200, description = "Stored summaries", body = SummaryListResponse),
+        (status = 404, description = "Not found", body = ErrorResponse)
+    ),
+    params(
+        ("id" = String, Path, description = "Conversation UUID")
+    )
+)]
+async fn list_summaries(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<SummaryListResponse>, AppError> {
+    let conv = state.repo.find_by_id(id).await?;
+
+    if conv.is_none() {
+        return Err(AppError::NotFound("Conversation not found".to_string()));
+    }
+
+    let summaries = state.repo.find_summaries(id).await?;
+
+    let total = summaries.len();
+
+    Ok(Json(SummaryListResponse {
+        conversation_id: id,
+        summaries,
+        total,
+    }))
+}
+
+// Endpoint: GET /api/v1/conversations/{id}/history
+#[utoipa::path(
+    get,
+    path = "/api/v1/conversations/{id}/history",
+    responses(
+        (status = 200, description = "Label, folder, status and importance changes", body = ConversationHistoryResponse),
+        (status = 404, description = "Not found", body = ErrorResponse)
+    ),
+    params(
+        ("id" = String, Path, description = "Conversation UUID")
+    )
+)]
+async fn conversation_history(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ConversationHistoryResponse>, AppError> {
+    if state.repo.find_by_id(id).await?.is_none() {
+        return Err(AppError::NotFound("Conversation not found".to_string()));
+    }
+
+    let changes = state.repo.find_history(id).await?;
+    let total = changes.len();
+
+    Ok(Json(ConversationHistoryResponse {
+        conversation_id: id,
+        changes,
+        total,
+    }))
+}
+
+// Endpoint: PUT /api/v1/templates/{name}
+#[utoipa::path(
+    put,
+    path = "/api/v1/templates/{name}",
+    request_body = SaveTemplateRequest,
+    responses(
+        (status = 200, description = "Template created or replaced", body = ConversationTemplate),
+        (status = 400, description = "Invalid template", body = ErrorResponse)
+    ),
+    params(
+        ("name" = String, Path, description = "Template name")
+    )
+)]
+async fn save_template(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    ApiJson(req): ApiJson<SaveTemplateRequest>,
+) -> Result<Json<ConversationTemplate>, AppError> {
+    if req.label_pattern.trim().is_empty() {
+        return Err(AppError::BadRequest(
+            "label_pattern must not be empty".to_string(),
+        ));
+    }
+
+    let now = chrono::Utc::now().naive_utc();
+    let template = state
+        .repo
+        .save_template(ConversationTemplate {
+            name,
+            label_pattern: req.label_pattern,
+            folder: req.folder,
+            system_message: req.system_message,
+            tags: req.tags,
+            created_at: now,
+            updated_at: now,
+        })
+        .await?;
+
+    Ok(Json(template))
+}
+
+// Endpoint: GET /api/v1/templates
+#[utoipa::path(
+    get,
+    path = "/api/v1/templates",
+    responses(
+        (status = 200, description = "All conversation templates", body = TemplateListResponse)
+    )
+)]
+async fn list_templates(
+    State(state): State<AppState>,
+) -> Result<Json<TemplateListResponse>, AppError> {
+    let templates = state.repo.list_templates().await?;
+    let total = templates.len();
+
+    Ok(Json(TemplateListResponse { templates, total }))
+}
+
+// Endpoint: POST /api/v1/conversations/from-template/{name}
+#[utoipa::path(
+    post,
+    path = "/api/v1/conversations/from-template/{name}",
+    responses(
+        (status = 201, description = "Conversation created from the template", body = ConversationResponse),
+        (status = 404, description = "Template not found", body = ErrorResponse)
+    ),
+    params(
+        ("name" = String, Path, description = "Template name")
+    )
+)]
+async fn create_from_template(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<(StatusCode, Json<serde_json::Value>), AppError> {
+    let template = state
+        .repo
+        .find_template(&name)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Template '{}' not found", name)))?;
+
+    let now = chrono::Utc::now().naive_utc();
+    let mut new_conv = template.instantiate(now);
+    new_conv.folder = folder_or_default(&state, Some(&new_conv.folder)).await;
+    let (label, folder) = (new_conv.label.clone(), new_conv.folder.clone());
+    let message_count = new_conv.messages.len();
+
+    let CreatedConversation {
+        id, message_ids, ..
+    } = state
+        .repo
+        .create_with_tags(new_conv, template.tags.clone())
+        .await?;
+
+    let warnings = queue_unembedded_messages(&state, id).await;
+    state.events.publish(MemoryEvent::Created {
+        conversation_id: id,
+    });
+
+    Ok((
+        StatusCode::CREATED,
+        Json(serde_json::json!({
+            "id": id,
+            "conversation_id": id,
+            "label": label,
+            "folder": folder,
+            "status": "active",
+            "template": template.name,
+            "tags": template.tags,
+            "message_count": message_count,
+            "message_ids": message_ids,
+            "created_at": now,
+            "embeddings_pending": !warnings.is_empty(),
+            "warnings": warnings,
+        })),
+    ))
+}
+
 // Endpoint: POST /api/v1/prune/dry-run
 #[utoipa::path(
     post,
@@ -801,26 +2927,23 @@ async fn generate_summary(
     request_body = PruneRequest,
     responses(
         (status = 200, description = "Pruning suggestions", body = PruneResponse),
+        (status = 400, description = "Invalid importance_threshold", body = ErrorResponse),
         (status = 500, description = "Server error", body = ErrorResponse)
     )
 )]
 async fn prune_dry_run(
     State(state): State<AppState>,
-    Json(req): Json<PruneRequest>,
-) -> Result<Json<PruneResponse>, (StatusCode, Json<ErrorResponse>)> {
+    ApiJson(req): ApiJson<PruneRequest>,
+) -> Result<Json<PruneResponse>, AppError> {
+    let folder = req.folder.as_deref().and_then(normalize_folder);
     let suggestions = state
         .orchestrator
-        .suggest_pruning(req.threshold_days)
-        .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse {
-                    error: e.to_string(),
-                    code: 500,
-                }),
-            )
-        })?;
+        .suggest_pruning(
+            req.threshold_days,
+            req.importance_threshold,
+            folder.as_deref(),
+        )
+        .await?;
 
     let total = suggestions.len(); // Calculate before consuming
 
@@ -849,30 +2972,53 @@ async fn prune_dry_run(
     request_body = ExecutePruneRequest,
     responses(
         (status = 200, description = "Conversations archived"),
+        (status = 202, description = "Conversations archived; compaction runs as the returned task", body = TaskStartedResponse),
         (status = 500, description = "Server error", body = ErrorResponse)
     )
 )]
 async fn prune_execute(
     State(state): State<AppState>,
     Json(req): Json<ExecutePruneRequest>,
-) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
-    for id in req.conversation_ids {
-        state
-            .repo
-            .update_status(id, "archived")
-            .await
-            .map_err(|e| {
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse {
-                        error: e.to_string(),
-                        code: 500,
-                    }),
-                )
-            })?;
+) -> Result<axum::response::Response, AppError> {
+    for &id in &req.conversation_ids {
+        state.repo.update_status(id, "archived").await?;
+        state.events.publish(MemoryEvent::Pruned {
+            conversation_id: id,
+        });
     }
 
-    Ok(StatusCode::OK)
+    Ok(archived_response(
+        compact_if_enabled(&state, req.conversation_ids).await,
+    ))
+}
+
+// Endpoint: GET /api/v1/events
+#[utoipa::path(
+    get,
+    path = "/api/v1/events",
+    responses(
+        (status = 200, description = "Server-sent stream of memory events; each `data` is a JSON MemoryEvent", content_type = "text/event-stream", body = MemoryEvent)
+    )
+)]
+async fn event_stream(
+    State(state): State<AppState>,
+) -> Sse<impl futures::Stream<Item = Result<SseEvent, Infallible>>> {
+    let stream = futures::stream::unfold(state.subscribe(), |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => match SseEvent::default().event(event.kind()).json_data(&event) {
+                    Ok(sse_event) => return Some((Ok(sse_event), receiver)),
+                    Err(e) => tracing::warn!("Could not encode memory event: {}", e),
+                },
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!("Event stream fell behind, skipped {} events", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
 }
 
 // Endpoint: POST /api/v1/labels/suggest
@@ -882,26 +3028,22 @@ async fn prune_execute(
     request_body = LabelSuggestRequest,
     responses(
         (status = 200, description = "Label suggestions", body = LabelSuggestResponse),
+        (status = 404, description = "Conversation not found", body = ErrorResponse),
         (status = 500, description = "Server error", body = ErrorResponse)
     )
 )]
 async fn suggest_labels(
     State(state): State<AppState>,
     Json(req): Json<LabelSuggestRequest>,
-) -> Result<Json<LabelSuggestResponse>, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<Json<LabelSuggestResponse>, AppError> {
+    if state.repo.find_by_id(req.conversation_id).await?.is_none() {
+        return Err(AppError::NotFound("Conversation not found".to_string()));
+    }
+
     let suggestions = state
         .orchestrator
         .suggest_labels(req.conversation_id)
-        .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse {
-                    error: e.to_string(),
-                    code: 500,
-                }),
-            )
-        })?;
+        .await?;
 
     Ok(Json(LabelSuggestResponse {
         conversation_id: req.conversation_id,
@@ -921,6 +3063,23 @@ async fn suggest_labels(
 // Router - All 12 endpoints registered
 // ============================================
 pub fn create_router(state: AppState) -> Router {
+    create_api_router(state.clone()).merge(create_ops_router(state))
+}
+
+/// REST and MCP routes behind the rate limiter, with health and metrics
+/// left unthrottled so monitoring keeps working while clients are limited
+pub fn create_app_router(state: AppState, limiter: RateLimiter) -> Router {
+    create_api_router(state.clone())
+        .merge(crate::api::mcp::create_mcp_router(state.clone()))
+        .layer(middleware::from_fn_with_state(
+            limiter,
+            rate_limit_middleware,
+        ))
+        .merge(create_ops_router(state))
+}
+
+/// Endpoints under `/api/v1`
+pub fn create_api_router(state: AppState) -> Router {
     Router::new()
         .route("/api/v1/conversations", post(create_conversation))
         .route("/api/v1/conversations/{id}", get(get_conversation))
@@ -934,21 +3093,107 @@ pub fn create_router(state: AppState) -> Router {
             put(update_conversation_folder),
         )
         .route("/api/v1/conversations/{id}/pin", put(pin_conversation))
+        .route(
+            "/api/v1/conversations/{id}/context-pin",
+            put(context_pin_conversation).delete(context_unpin_conversation),
+        )
+        .route("/api/v1/conversations/{id}/touch", post(touch_conversation))
+        .route(
+            "/api/v1/conversations/{id}/offload",
+            post(offload_conversation),
+        )
+        .route(
+            "/api/v1/conversations/{id}/reload",
+            post(reload_conversation),
+        )
+        .route("/api/v1/conversations/{id}/messages", post(append_messages))
+        .route(
+            "/api/v1/conversations/{id}/context-debug",
+            get(context_debug),
+        )
         .route(
             "/api/v1/conversations/{id}/archive",
             put(archive_conversation),
         )
         .route("/api/v1/conversations/{id}", delete(delete_conversation))
+        .route("/api/v1/conversations/{id}", patch(patch_conversation))
+        .route("/api/v1/conversations/bulk/move", post(bulk_move))
+        .route("/api/v1/conversations/bulk/status", post(bulk_status))
+        .route("/api/v1/conversations/bulk-delete", post(bulk_delete))
+        .route("/api/v1/tags/bulk-add", post(bulk_add_tags))
+        .route("/api/v1/tags/bulk-remove", post(bulk_remove_tag))
+        .route("/api/v1/folders/tree", get(folder_tree))
+        .route("/api/v1/suggest", get(suggest))
+        .route("/api/v1/folders/rename", post(rename_folder))
         .route("/api/v1/conversations/count", get(count_conversations))
+        .route("/api/v1/stats/timeline", get(stats_timeline))
         .route("/api/v1/query", post(semantic_query))
+        .route("/api/v1/query/conversations", post(query_conversations))
         .route("/api/v1/rebuild-embeddings", post(rebuild_embeddings))
+        .route(
+            "/api/v1/rebuild-embeddings/scoped",
+            post(rebuild_embeddings_scoped),
+        )
+        .route("/api/v1/tasks/{id}", get(get_task))
         .route("/api/v1/search/fts", post(full_text_search))
         .route("/api/v1/context/assemble", post(assemble_context))
         .route("/api/v1/summarize", post(generate_summary))
+        .route("/api/v1/conversations/{id}/summaries", get(list_summaries))
+        .route(
+            "/api/v1/conversations/{id}/history",
+            get(conversation_history),
+        )
+        .route(
+            "/api/v1/conversations/from-template/{name}",
+            post(create_from_template),
+        )
+        .route("/api/v1/templates", get(list_templates))
+        .route("/api/v1/templates/{name}", put(save_template))
         .route("/api/v1/prune/dry-run", post(prune_dry_run))
         .route("/api/v1/prune/execute", post(prune_execute))
         .route("/api/v1/labels/suggest", post(suggest_labels))
+        .route("/api/v1/events", get(event_stream))
+        .route("/api/v1/maintenance/reindex-fts", post(reindex_fts))
+        .route(
+            "/api/v1/maintenance/reconcile-embeddings",
+            post(reconcile_embeddings),
+        )
+        .route(
+            "/api/v1/maintenance/recompute-aggregates",
+            post(recompute_aggregates),
+        )
+        .route(
+            "/api/v1/messages/{id}/embedding",
+            get(get_message_embedding),
+        )
+        .route(
+            "/api/v1/messages/{id}/attachments",
+            get(get_message_attachments),
+        )
+        .route(
+            "/api/v1/messages/{id}/metadata",
+            patch(update_message_metadata),
+        )
+        .route("/api/v1/messages/{id}/move", post(move_message))
+        .route(
+            "/api/v1/conversations/{id}/labels",
+            post(add_conversation_label),
+        )
+        .route(
+            "/api/v1/conversations/{id}/labels/{label}",
+            delete(remove_conversation_label),
+        )
+        .route("/api/v1/maintenance/optimize", post(optimize_db))
+        .route("/api/v1/version", get(version))
+        .with_state(state)
+}
+
+/// Health, probe and metrics endpoints polled by monitoring and orchestrators
+pub fn create_ops_router(state: AppState) -> Router {
+    Router::new()
         .route("/health", get(health))
+        .route("/healthz", get(liveness))
+        .route("/readyz", get(readiness))
         .route("/metrics", get(metrics))
         .with_state(state)
 }
@@ -0,0 +1,81 @@
+//! Per-request timeout middleware for REST API
+
+use axum::{
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use std::time::Duration;
+
+use crate::api::dto::ErrorResponse;
+
+/// Fail requests whose handler has not produced a response within `limit`
+/// with a 504 and a JSON error body.
+///
+/// Only the time until the response head is returned is bounded, so
+/// streaming bodies are not cut off once they have started.
+pub async fn timeout_middleware(
+    State(limit): State<Duration>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if limit.is_zero() {
+        return next.run(request).await;
+    }
+
+    let path = request.uri().path().to_string();
+    match tokio::time::timeout(limit, next.run(request)).await {
+        Ok(response) => response,
+        Err(_) => {
+            tracing::warn!("Request to {} timed out after {:?}", path, limit);
+            (
+                StatusCode::GATEWAY_TIMEOUT,
+                Json(ErrorResponse {
+                    error: format!("Request timed out after {}s", limit.as_secs_f32()),
+                    code: 504,
+                }),
+            )
+                .into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, middleware, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn app(limit: Duration) -> Router {
+        Router::new()
+            .route(
+                "/slow",
+                get(|| async {
+                    tokio::time::sleep(Duration::from_millis(200)).await;
+                    "done"
+                }),
+            )
+            .layer(middleware::from_fn_with_state(limit, timeout_middleware))
+    }
+
+    fn slow_request() -> Request {
+        Request::builder().uri("/slow").body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_timeout_returns_gateway_timeout() {
+        let response = app(Duration::from_millis(20))
+            .oneshot(slow_request())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+    }
+
+    #[tokio::test]
+    async fn test_zero_limit_disables_timeout() {
+        let response = app(Duration::ZERO).oneshot(slow_request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -67,3 +67,50 @@ impl FromRequestParts<AppState> for McpAuth {
         }
     }
 }
+
+/// Bearer token authorized for maintenance (admin) endpoints
+#[derive(Clone)]
+pub struct AdminAuth {
+    pub token: String,
+}
+
+impl FromRequestParts<AppState> for AdminAuth {
+    type Rejection = Response;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let auth_header = parts
+            .headers
+            .get("authorization")
+            .and_then(|h| h.to_str().ok())
+            .ok_or_else(|| {
+                let body = Json(json!({
+                    "error": "Missing authorization header"
+                }));
+                (StatusCode::UNAUTHORIZED, body).into_response()
+            })?;
+
+        let token = auth_header.strip_prefix("Bearer ").ok_or_else(|| {
+            let body = Json(json!({
+                "error": "Invalid authorization format"
+            }));
+            (StatusCode::BAD_REQUEST, body).into_response()
+        })?;
+
+        let expected_key = state.config.read().await.get_admin_api_key();
+
+        if token == expected_key && token.len() >= 32 {
+            Ok(AdminAuth {
+                token: token.to_string(),
+            })
+        } else {
+            Err((
+                StatusCode::FORBIDDEN,
+                Json(json!({ "error": "Admin API key required" })),
+            )
+                .into_response())
+        }
+    }
+}
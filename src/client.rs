@@ -0,0 +1,164 @@
+//! Typed client for the REST API, for Rust services that talk to a running
+//! controller. Enabled with the `client` feature.
+
+use crate::api::dto::{
+    AppendMessagesRequest, AppendMessagesResponse, ContextAssembleRequest, ContextFormat,
+    ConversationResponse, CreateConversationRequest, CreateConversationResponse, ErrorResponse,
+    MessageDto, QueryRequest, QueryResponse,
+};
+use crate::models::internal::Message;
+use reqwest::{RequestBuilder, Response};
+use serde::de::DeserializeOwned;
+use uuid::Uuid;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    #[error("HTTP error: {0}")]
+    HttpError(#[from] reqwest::Error),
+    /// The controller answered with an error status
+    #[error("API error: {} - {}", .0.code, .0.error)]
+    ApiError(ErrorResponse),
+}
+
+impl ClientError {
+    /// HTTP status of an API error
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            ClientError::ApiError(body) => u16::try_from(body.code).ok(),
+            ClientError::HttpError(e) => e.status().map(|s| s.as_u16()),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SekhaClient {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: Option<String>,
+}
+
+impl SekhaClient {
+    /// Client for the controller at `base_url`, e.g. `http://localhost:8080`
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            api_key: None,
+        }
+    }
+
+    /// Send `api_key` as a bearer token with every request
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Use a preconfigured HTTP client, e.g. one with timeouts or a proxy
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    pub async fn create_conversation(
+        &self,
+        request: &CreateConversationRequest,
+    ) -> Result<CreateConversationResponse, ClientError> {
+        self.send(self.post("/api/v1/conversations").json(request))
+            .await
+    }
+
+    pub async fn get_conversation(&self, id: Uuid) -> Result<ConversationResponse, ClientError> {
+        self.send(self.request(
+            reqwest::Method::GET,
+            &format!("/api/v1/conversations/{}", id),
+        ))
+        .await
+    }
+
+    pub async fn delete_conversation(&self, id: Uuid) -> Result<(), ClientError> {
+        let response = self
+            .request(
+                reqwest::Method::DELETE,
+                &format!("/api/v1/conversations/{}", id),
+            )
+            .send()
+            .await?;
+        check_status(response).await?;
+        Ok(())
+    }
+
+    pub async fn append_messages(
+        &self,
+        id: Uuid,
+        messages: Vec<MessageDto>,
+    ) -> Result<AppendMessagesResponse, ClientError> {
+        let request = AppendMessagesRequest { messages };
+        self.send(
+            self.post(&format!("/api/v1/conversations/{}/messages", id))
+                .json(&request),
+        )
+        .await
+    }
+
+    /// Semantic search, topped up or replaced by full-text matches as the
+    /// server's fallback policy allows
+    pub async fn search(&self, request: &QueryRequest) -> Result<QueryResponse, ClientError> {
+        self.send(self.post("/api/v1/query").json(request)).await
+    }
+
+    /// Messages assembled as LLM context for `query`, within `context_budget`
+    /// tokens
+    pub async fn get_context(
+        &self,
+        query: &str,
+        preferred_labels: Vec<String>,
+        context_budget: usize,
+    ) -> Result<Vec<Message>, ClientError> {
+        let request = ContextAssembleRequest {
+            query: query.to_string(),
+            preferred_labels,
+            context_budget,
+            excluded_folders: Vec::new(),
+            verbose: false,
+            format: ContextFormat::Messages,
+        };
+        self.send(self.post("/api/v1/context/assemble").json(&request))
+            .await
+    }
+
+    fn post(&self, path: &str) -> RequestBuilder {
+        self.request(reqwest::Method::POST, path)
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> RequestBuilder {
+        let builder = self
+            .client
+            .request(method, format!("{}{}", self.base_url, path));
+        match &self.api_key {
+            Some(key) => builder.bearer_auth(key),
+            None => builder,
+        }
+    }
+
+    async fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T, ClientError> {
+        let response = check_status(request.send().await?).await?;
+        Ok(response.json().await?)
+    }
+}
+
+/// Pass successful responses through; turn the rest into `ApiError`, keeping
+/// the body text when it isn't an `ErrorResponse`
+async fn check_status(response: Response) -> Result<Response, ClientError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let text = response.text().await.unwrap_or_default();
+    let mut body = serde_json::from_str::<ErrorResponse>(&text).unwrap_or(ErrorResponse {
+        error: text,
+        code: 0,
+    });
+    body.code = u32::from(status.as_u16());
+    Err(ClientError::ApiError(body))
+}
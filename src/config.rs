@@ -1,4 +1,5 @@
 use serde::Deserialize;
+use std::collections::HashMap;
 use validator::Validate;
 
 #[derive(Debug, Deserialize, Validate, Clone, Default)]
@@ -12,6 +13,12 @@ pub struct Config {
     pub mcp_api_key: String,
 
     pub database_url: String,
+    /// Optional read-only connection for list, count and search queries.
+    /// With SQLite this is the same file as `database_url`, opened read-only
+    /// so readers don't queue behind writers; unset means reads use the
+    /// primary connection.
+    #[serde(default)]
+    pub read_database_url: Option<String>,
     pub ollama_url: String,
     pub chroma_url: String,
     pub llm_bridge_url: String,
@@ -23,7 +30,26 @@ pub struct Config {
     pub log_level: String,
     pub summarization_enabled: bool,
     pub summarization_model: String,
+    /// Summarize a conversation automatically each time it gains this many
+    /// messages (requires `summarization_enabled`)
+    #[serde(default)]
+    pub summarize_after_messages: Option<u64>,
+    /// Keep conversations to at most this many messages by folding the
+    /// oldest into a summary when an append goes past it
+    #[serde(default)]
+    pub max_messages_per_conversation: Option<u64>,
+    /// Re-score a conversation's importance in the background after appends
+    #[serde(default)]
+    pub rescore_importance_on_append: bool,
+    /// Seconds to wait after an append before re-scoring, so a burst of
+    /// appends costs one scoring pass
+    #[serde(default = "default_importance_rescore_debounce_secs")]
+    pub importance_rescore_debounce_secs: u64,
     pub pruning_enabled: bool,
+    /// When archiving, replace message bodies with a summary to reclaim
+    /// space; roles, timestamps and metadata are kept
+    #[serde(default)]
+    pub compact_on_archive: bool,
 
     // REST API Configuration (Module 6.3)
     /// Optional REST API key (falls back to mcp_api_key if not provided)
@@ -33,13 +59,403 @@ pub struct Config {
     #[serde(default)]
     pub additional_api_keys: Vec<String>,
 
+    /// Optional key for maintenance endpoints (falls back to mcp_api_key if not provided)
+    #[serde(default)]
+    pub admin_api_key: Option<String>,
+
     /// Rate limit: requests per minute
     #[serde(default = "default_rate_limit")]
     pub rate_limit_per_minute: u32,
 
+    /// Percentage of the rate limit after which responses carry an
+    /// `X-RateLimit-Warning` header; 0 disables warnings
+    #[serde(default = "default_rate_limit_warn_percent")]
+    pub rate_limit_warn_percent: u8,
+
     /// Enable CORS
     #[serde(default = "default_cors_enabled")]
     pub cors_enabled: bool,
+
+    /// Result limit for semantic search when the request doesn't set one
+    #[serde(default = "default_search_limit")]
+    pub default_search_limit: u32,
+
+    /// Largest result limit a semantic search request may ask for
+    #[serde(default = "default_max_search_limit")]
+    pub max_search_limit: u32,
+
+    /// Seconds a REST request may take to produce its response (0 disables)
+    #[serde(default = "default_request_timeout_secs")]
+    pub request_timeout_secs: u64,
+
+    /// Log REST request and response bodies at debug level. Bodies can hold
+    /// message content, so leave this off outside of debugging
+    #[serde(default)]
+    pub log_bodies: bool,
+
+    /// Bytes of each body written to the log before it is cut off
+    #[serde(default = "default_log_body_max_bytes")]
+    pub log_body_max_bytes: usize,
+
+    /// JSON fields (at any depth, case-insensitive) whose values are replaced
+    /// in logged bodies, e.g. `["api_key", "password"]`
+    #[serde(default)]
+    pub log_redact_fields: Vec<String>,
+
+    /// Embed messages and search Chroma; when false only full-text search is
+    /// used and Ollama/Chroma are never contacted
+    #[serde(default = "default_embeddings_enabled")]
+    pub embeddings_enabled: bool,
+
+    /// Seconds one embedding call may take, independent of
+    /// `request_timeout_secs` (0 disables). Messages that time out are
+    /// stored without a vector and queued for retry.
+    #[serde(default = "default_embedding_timeout_secs")]
+    pub embedding_timeout_secs: u64,
+
+    // Embedding text preprocessing (stored content is never modified)
+    /// Drop fenced code blocks from text before embedding
+    #[serde(default)]
+    pub embedding_strip_code_blocks: bool,
+
+    /// Collapse runs of whitespace before embedding
+    #[serde(default)]
+    pub embedding_collapse_whitespace: bool,
+
+    /// Truncate embedded text to this many whitespace-separated tokens
+    #[serde(default)]
+    pub embedding_max_tokens: Option<usize>,
+
+    /// Extra embedding models written alongside `embedding_model`, keyed by
+    /// the name `/api/v1/query?model=` selects them with. Each writes to its
+    /// own Chroma collection, so models can be compared without reindexing.
+    #[serde(default)]
+    pub embedding_models: HashMap<String, EmbeddingModelConfig>,
+
+    /// Number of vectors sent to Chroma per upsert request
+    #[serde(default = "default_chroma_upsert_batch_size")]
+    pub chroma_upsert_batch_size: usize,
+
+    /// Minutes of inactivity after which appended messages start a new session
+    #[serde(default = "default_session_gap_minutes")]
+    pub session_gap_minutes: i64,
+
+    /// What semantic search does when the vector store is unavailable
+    #[serde(default)]
+    pub search_fallback: SearchFallback,
+
+    /// Low-signal words (e.g. "the", "please") left out of full-text
+    /// queries built from search text. Empty by default, so every word counts.
+    #[serde(default)]
+    pub fts_stop_words: Vec<String>,
+
+    /// How full-text matches are ordered
+    #[serde(default)]
+    pub fts_ranking: FtsRanking,
+
+    /// Messages shorter than this many characters (e.g. "ok", "thanks") are
+    /// stored without an embedding but stay full-text searchable; 0 embeds all
+    #[serde(default)]
+    pub min_embed_length: usize,
+
+    /// Regexes whose matches in message content (e.g. API keys, emails) are
+    /// replaced with `[REDACTED]` before the message is stored or embedded
+    #[serde(default)]
+    pub redaction_patterns: Vec<String>,
+
+    /// Multipliers applied to semantic search scores by message role
+    /// (e.g. `assistant = 1.2`); unlisted roles keep their score
+    #[serde(default)]
+    pub role_weights: HashMap<String, f32>,
+
+    /// Pruning rules keyed by folder prefix; the most specific match wins and
+    /// conversations under no listed prefix use the global pruning settings
+    #[serde(default)]
+    pub retention_policies: HashMap<String, RetentionPolicy>,
+
+    /// Judge staleness for pruning by the latest read as well as the latest
+    /// update, so conversations still being looked at aren't suggested
+    #[serde(default)]
+    pub prune_by_last_access: bool,
+
+    /// How the import watcher assigns IDs to imported conversations
+    #[serde(default)]
+    pub import_mode: ImportMode,
+
+    /// How IDs for new conversations and messages are generated. Existing
+    /// IDs keep working whichever scheme is chosen
+    #[serde(default)]
+    pub id_scheme: IdScheme,
+
+    /// Maximum number of LLM bridge calls (summaries, scoring, re-ranking)
+    /// in flight at once; further calls queue
+    #[serde(default = "default_llm_max_concurrency")]
+    pub llm_max_concurrency: usize,
+
+    /// Maximum number of import files processed at once; further files
+    /// queue until a slot frees up
+    #[serde(default = "default_import_max_concurrency")]
+    pub import_max_concurrency: usize,
+
+    /// Ask the LLM bridge for a label when an import has no title of its own
+    /// (e.g. markdown or text files), instead of using the filename
+    #[serde(default)]
+    pub import_derive_labels: bool,
+
+    /// Skip the per-message full-text index insert while importing a file
+    /// and index the new messages once at the end; speeds up large imports,
+    /// but search misses messages stored by anything else until then
+    #[serde(default)]
+    pub import_defer_fts: bool,
+
+    /// Directories the import watcher watches, each optionally storing its
+    /// imports in a fixed folder instead of `/imports/<source>`; empty
+    /// watches `~/.sekha/import` only. Imported files are moved to an
+    /// `imported/` subdirectory of the directory they came from
+    #[serde(default)]
+    pub import_directories: Vec<ImportDirectory>,
+
+    /// Directory conversations are written to when offloaded to cold storage
+    #[serde(default = "default_offload_dir")]
+    pub offload_dir: String,
+
+    /// Seconds identical semantic searches are served from memory; any
+    /// conversation change clears the cache early. 0 disables caching
+    #[serde(default = "default_search_cache_ttl_secs")]
+    pub search_cache_ttl_secs: u64,
+
+    /// Distinct searches kept in the cache before the least recently used
+    /// is dropped
+    #[serde(default = "default_search_cache_capacity")]
+    pub search_cache_capacity: usize,
+
+    /// Folders (and their subfolders) left out of semantic search unless a
+    /// query lists its own exclusions, e.g. `["/imports/raw"]`
+    #[serde(default)]
+    pub search_exclude_folders: Vec<String>,
+
+    /// Leave conversations with no activity in this many days out of search
+    /// unless a query sets its own `max_age_days`
+    #[serde(default)]
+    pub search_max_age_days: Option<u32>,
+
+    /// Folder for conversations created through the API without one
+    #[serde(default = "default_folder")]
+    pub default_folder: String,
+}
+
+/// Behaviour of semantic search when the vector store can't be queried
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchFallback {
+    /// Return no results
+    Empty,
+    /// Answer from the full-text index instead
+    #[default]
+    Fts,
+    /// Fail the search
+    Error,
+}
+
+/// Ordering of full-text matches: SQLite's BM25 with per-column weights,
+/// optionally penalised by message age
+#[derive(Debug, Deserialize, Clone, Default, PartialEq)]
+pub struct FtsRanking {
+    /// BM25 weight for each indexed column, in index order (currently just
+    /// message content); unlisted columns weigh 1.0
+    #[serde(default)]
+    pub column_weights: Vec<f64>,
+    /// Rank penalty per day of message age, so newer messages win between
+    /// otherwise similar matches; 0 ranks on relevance alone
+    #[serde(default)]
+    pub recency_weight: f64,
+}
+
+impl FtsRanking {
+    pub fn with_column_weights(mut self, weights: Vec<f64>) -> Self {
+        self.column_weights = weights;
+        self
+    }
+
+    pub fn with_recency_weight(mut self, weight: f64) -> Self {
+        self.recency_weight = weight;
+        self
+    }
+
+    /// `ORDER BY` expression for a query joining `messages m` with
+    /// `messages_fts`, best match first. Non-finite weights fall back to
+    /// the neutral value so the SQL stays valid.
+    pub fn order_by_clause(&self) -> String {
+        let finite_or = |w: f64, default: f64| if w.is_finite() { w } else { default };
+
+        let mut rank = String::from("bm25(messages_fts");
+        for weight in &self.column_weights {
+            rank.push_str(&format!(", {:?}", finite_or(*weight, 1.0)));
+        }
+        rank.push(')');
+
+        let recency = finite_or(self.recency_weight, 0.0);
+        if recency != 0.0 {
+            // bm25() is negative and lower is better, so age adds a penalty
+            rank.push_str(&format!(
+                " + {:?} * (julianday('now') - julianday(m.timestamp))",
+                recency
+            ));
+        }
+
+        format!("{}, m.rowid", rank)
+    }
+}
+
+/// Pruning rules for the conversations under one folder prefix. Unset
+/// thresholds fall back to the global ones.
+#[derive(Debug, Deserialize, Clone, Default, PartialEq)]
+pub struct RetentionPolicy {
+    /// Days without activity before a conversation becomes a candidate
+    #[serde(default)]
+    pub threshold_days: Option<i64>,
+    /// Candidates scoring below this are recommended for archiving
+    #[serde(default)]
+    pub importance_threshold: Option<f32>,
+    #[serde(default)]
+    pub action: RetentionAction,
+}
+
+/// An embedding model run alongside the primary one
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct EmbeddingModelConfig {
+    /// Ollama model name, e.g. `mxbai-embed-large`
+    pub model: String,
+    /// Chroma collection for this model's vectors; defaults to
+    /// `conversations_<name>`
+    #[serde(default)]
+    pub collection: Option<String>,
+    /// Disabled models are neither written nor searchable
+    #[serde(default = "default_embedding_model_enabled")]
+    pub enabled: bool,
+}
+
+impl EmbeddingModelConfig {
+    pub fn collection_name(&self, name: &str) -> String {
+        self.collection
+            .clone()
+            .unwrap_or_else(|| format!("conversations_{}", name))
+    }
+}
+
+/// What pruning does with conversations covered by a retention policy
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RetentionAction {
+    /// Suggest stale, low-importance conversations for archiving
+    #[default]
+    Archive,
+    /// Never suggest these conversations for pruning
+    Keep,
+}
+
+/// How imported conversations are identified
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportMode {
+    /// Every import creates conversations with fresh IDs
+    #[default]
+    Fresh,
+    /// IDs are derived from the source conversation, so re-importing an
+    /// export replaces the messages of the conversations it created before,
+    /// keeping their tags, labels, folder and other user changes
+    Deterministic,
+}
+
+/// A directory watched for import files
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct ImportDirectory {
+    pub path: String,
+    /// Folder every conversation imported from this directory is stored in
+    #[serde(default)]
+    pub folder: Option<String>,
+}
+
+impl ImportDirectory {
+    /// Expand a leading `~` to the home directory and reject paths that
+    /// can't be watched: empty ones and the filesystem root
+    fn resolve(mut self) -> Result<Self, config::ConfigError> {
+        if let Some(rest) = self.path.strip_prefix('~') {
+            if rest.is_empty() || rest.starts_with('/') {
+                let home = dirs::home_dir().ok_or_else(|| {
+                    config::ConfigError::Message(format!(
+                        "import directory {} needs a home directory",
+                        self.path
+                    ))
+                })?;
+                self.path = format!("{}{}", home.display(), rest);
+            }
+        }
+
+        if self.path.trim().is_empty() {
+            return Err(config::ConfigError::Message(
+                "import directory path must not be empty".to_string(),
+            ));
+        }
+        if std::path::Path::new(&self.path).parent().is_none() {
+            return Err(config::ConfigError::Message(format!(
+                "import directory {} can't be the filesystem root",
+                self.path
+            )));
+        }
+        Ok(self)
+    }
+}
+
+/// How IDs for new conversations and messages are generated
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum IdScheme {
+    /// Random UUIDv4
+    #[default]
+    Uuid,
+    /// ULIDs, which sort in creation order
+    Ulid,
+}
+
+fn default_chroma_upsert_batch_size() -> usize {
+    crate::services::embedding_service::DEFAULT_UPSERT_BATCH_SIZE
+}
+
+fn default_llm_max_concurrency() -> usize {
+    crate::services::llm_bridge_client::DEFAULT_LLM_CONCURRENCY
+}
+
+fn default_import_max_concurrency() -> usize {
+    crate::services::file_watcher::DEFAULT_IMPORT_CONCURRENCY
+}
+
+fn default_session_gap_minutes() -> i64 {
+    crate::storage::repository::DEFAULT_SESSION_GAP_MINUTES
+}
+
+fn default_offload_dir() -> String {
+    "sekha_offload".to_string()
+}
+
+fn default_search_cache_ttl_secs() -> u64 {
+    30
+}
+
+fn default_search_cache_capacity() -> usize {
+    crate::services::search_cache::DEFAULT_SEARCH_CACHE_CAPACITY
+}
+
+fn default_folder() -> String {
+    "/inbox".to_string()
+}
+
+fn default_importance_rescore_debounce_secs() -> u64 {
+    60
+}
+
+fn default_rate_limit_warn_percent() -> u8 {
+    80
 }
 
 fn default_rate_limit() -> u32 {
@@ -50,6 +466,34 @@ fn default_cors_enabled() -> bool {
     true
 }
 
+fn default_embeddings_enabled() -> bool {
+    true
+}
+
+fn default_embedding_model_enabled() -> bool {
+    true
+}
+
+fn default_search_limit() -> u32 {
+    10
+}
+
+fn default_max_search_limit() -> u32 {
+    100
+}
+
+fn default_request_timeout_secs() -> u64 {
+    30
+}
+
+fn default_embedding_timeout_secs() -> u64 {
+    60
+}
+
+fn default_log_body_max_bytes() -> usize {
+    4096
+}
+
 impl Config {
     pub fn load() -> Result<Self, config::ConfigError> {
         let settings = config::Config::builder()
@@ -65,8 +509,51 @@ impl Config {
             .set_default("summarization_model", "llama3.1:8b")?
             .set_default("summarization_enabled", true)?
             .set_default("pruning_enabled", true)?
+            .set_default("rescore_importance_on_append", false)?
+            .set_default(
+                "importance_rescore_debounce_secs",
+                default_importance_rescore_debounce_secs(),
+            )?
             .set_default("rate_limit_per_minute", 1000)?
+            .set_default(
+                "rate_limit_warn_percent",
+                default_rate_limit_warn_percent() as i64,
+            )?
             .set_default("cors_enabled", true)?
+            .set_default("default_search_limit", default_search_limit())?
+            .set_default("max_search_limit", default_max_search_limit())?
+            .set_default("request_timeout_secs", default_request_timeout_secs())?
+            .set_default("log_bodies", false)?
+            .set_default("log_body_max_bytes", default_log_body_max_bytes() as i64)?
+            .set_default("embeddings_enabled", true)?
+            .set_default("embedding_timeout_secs", default_embedding_timeout_secs())?
+            .set_default("embedding_strip_code_blocks", false)?
+            .set_default("embedding_collapse_whitespace", false)?
+            .set_default(
+                "chroma_upsert_batch_size",
+                default_chroma_upsert_batch_size() as i64,
+            )?
+            .set_default("session_gap_minutes", default_session_gap_minutes())?
+            .set_default("search_fallback", "fts")?
+            .set_default("import_mode", "fresh")?
+            .set_default("id_scheme", "uuid")?
+            .set_default("llm_max_concurrency", default_llm_max_concurrency() as i64)?
+            .set_default(
+                "import_max_concurrency",
+                default_import_max_concurrency() as i64,
+            )?
+            .set_default("import_derive_labels", false)?
+            .set_default("import_defer_fts", false)?
+            .set_default("offload_dir", default_offload_dir())?
+            .set_default("default_folder", default_folder())?
+            .set_default(
+                "search_cache_ttl_secs",
+                default_search_cache_ttl_secs() as i64,
+            )?
+            .set_default(
+                "search_cache_capacity",
+                default_search_cache_capacity() as i64,
+            )?
             .set_default("mcp_api_key", "dev_default_key_change_me_1234567890")? // ✅ ADD DEFAULT
             // Load from ./config.toml (project root)
             .add_source(config::File::with_name("config").required(false))
@@ -81,7 +568,13 @@ impl Config {
             .add_source(config::Environment::with_prefix("SEKHA").separator("__"))
             .build()?;
 
-        settings.try_deserialize()
+        let mut config: Self = settings.try_deserialize()?;
+        config.import_directories = config
+            .import_directories
+            .into_iter()
+            .map(ImportDirectory::resolve)
+            .collect::<Result<_, _>>()?;
+        Ok(config)
     }
 
     /// Get the effective REST API key (rest_api_key or fallback to mcp_api_key)
@@ -91,6 +584,21 @@ impl Config {
             .unwrap_or_else(|| self.mcp_api_key.clone())
     }
 
+    /// Get the effective admin API key (admin_api_key or fallback to mcp_api_key)
+    pub fn get_admin_api_key(&self) -> String {
+        self.admin_api_key
+            .clone()
+            .unwrap_or_else(|| self.mcp_api_key.clone())
+    }
+
+    /// Result limit for a semantic search: the requested limit (or the
+    /// configured default) capped at `max_search_limit`
+    pub fn effective_search_limit(&self, requested: Option<u32>) -> u32 {
+        requested
+            .unwrap_or(self.default_search_limit)
+            .min(self.max_search_limit)
+    }
+
     /// Get all valid API keys (primary + additional)
     pub fn get_all_api_keys(&self) -> Vec<String> {
         let mut keys = vec![self.mcp_api_key.clone(), self.get_rest_api_key()];
@@ -146,6 +654,49 @@ mod tests {
             summarization_enabled: true,
             summarization_model: "test-model".to_string(),
             pruning_enabled: true,
+            import_directories: vec![],
+            search_max_age_days: None,
+            rescore_importance_on_append: false,
+            importance_rescore_debounce_secs: 60,
+            rate_limit_warn_percent: 80,
+            import_defer_fts: false,
+            id_scheme: Default::default(),
+            embedding_timeout_secs: 60,
+            log_bodies: false,
+            log_body_max_bytes: 4096,
+            log_redact_fields: vec![],
+            compact_on_archive: false,
+            search_exclude_folders: vec![],
+            search_cache_ttl_secs: 30,
+            search_cache_capacity: 256,
+            default_folder: "/inbox".to_string(),
+            prune_by_last_access: false,
+            fts_ranking: Default::default(),
+            llm_max_concurrency: 8,
+            min_embed_length: 0,
+            import_max_concurrency: 4,
+            redaction_patterns: Vec::new(),
+            embedding_models: Default::default(),
+            fts_stop_words: Vec::new(),
+            read_database_url: None,
+            import_derive_labels: false,
+            embeddings_enabled: true,
+            max_messages_per_conversation: None,
+            retention_policies: Default::default(),
+            import_mode: Default::default(),
+            default_search_limit: 10,
+            max_search_limit: 100,
+            summarize_after_messages: None,
+            request_timeout_secs: 30,
+            offload_dir: "sekha_offload".to_string(),
+            role_weights: Default::default(),
+            search_fallback: SearchFallback::Fts,
+            session_gap_minutes: 30,
+            chroma_upsert_batch_size: 32,
+            admin_api_key: None,
+            embedding_strip_code_blocks: false,
+            embedding_collapse_whitespace: false,
+            embedding_max_tokens: None,
             rest_api_key: None,
             additional_api_keys: vec![],
             rate_limit_per_minute: 1000,
@@ -175,6 +726,49 @@ mod tests {
             summarization_enabled: true,
             summarization_model: "test-model".to_string(),
             pruning_enabled: true,
+            import_directories: vec![],
+            search_max_age_days: None,
+            rescore_importance_on_append: false,
+            importance_rescore_debounce_secs: 60,
+            rate_limit_warn_percent: 80,
+            import_defer_fts: false,
+            id_scheme: Default::default(),
+            embedding_timeout_secs: 60,
+            log_bodies: false,
+            log_body_max_bytes: 4096,
+            log_redact_fields: vec![],
+            compact_on_archive: false,
+            search_exclude_folders: vec![],
+            search_cache_ttl_secs: 30,
+            search_cache_capacity: 256,
+            default_folder: "/inbox".to_string(),
+            prune_by_last_access: false,
+            fts_ranking: Default::default(),
+            llm_max_concurrency: 8,
+            min_embed_length: 0,
+            import_max_concurrency: 4,
+            redaction_patterns: Vec::new(),
+            embedding_models: Default::default(),
+            fts_stop_words: Vec::new(),
+            read_database_url: None,
+            import_derive_labels: false,
+            embeddings_enabled: true,
+            max_messages_per_conversation: None,
+            retention_policies: Default::default(),
+            import_mode: Default::default(),
+            default_search_limit: 10,
+            max_search_limit: 100,
+            summarize_after_messages: None,
+            request_timeout_secs: 30,
+            offload_dir: "sekha_offload".to_string(),
+            role_weights: Default::default(),
+            search_fallback: SearchFallback::Fts,
+            session_gap_minutes: 30,
+            chroma_upsert_batch_size: 32,
+            admin_api_key: None,
+            embedding_strip_code_blocks: false,
+            embedding_collapse_whitespace: false,
+            embedding_max_tokens: None,
             rest_api_key: Some("rest_key_12345678901234567890123456789012".to_string()),
             additional_api_keys: vec![],
             rate_limit_per_minute: 1000,
@@ -204,6 +798,49 @@ mod tests {
             summarization_enabled: true,
             summarization_model: "test-model".to_string(),
             pruning_enabled: true,
+            import_directories: vec![],
+            search_max_age_days: None,
+            rescore_importance_on_append: false,
+            importance_rescore_debounce_secs: 60,
+            rate_limit_warn_percent: 80,
+            import_defer_fts: false,
+            id_scheme: Default::default(),
+            embedding_timeout_secs: 60,
+            log_bodies: false,
+            log_body_max_bytes: 4096,
+            log_redact_fields: vec![],
+            compact_on_archive: false,
+            search_exclude_folders: vec![],
+            search_cache_ttl_secs: 30,
+            search_cache_capacity: 256,
+            default_folder: "/inbox".to_string(),
+            prune_by_last_access: false,
+            fts_ranking: Default::default(),
+            llm_max_concurrency: 8,
+            min_embed_length: 0,
+            import_max_concurrency: 4,
+            redaction_patterns: Vec::new(),
+            embedding_models: Default::default(),
+            fts_stop_words: Vec::new(),
+            read_database_url: None,
+            import_derive_labels: false,
+            embeddings_enabled: true,
+            max_messages_per_conversation: None,
+            retention_policies: Default::default(),
+            import_mode: Default::default(),
+            default_search_limit: 10,
+            max_search_limit: 100,
+            summarize_after_messages: None,
+            request_timeout_secs: 30,
+            offload_dir: "sekha_offload".to_string(),
+            role_weights: Default::default(),
+            search_fallback: SearchFallback::Fts,
+            session_gap_minutes: 30,
+            chroma_upsert_batch_size: 32,
+            admin_api_key: None,
+            embedding_strip_code_blocks: false,
+            embedding_collapse_whitespace: false,
+            embedding_max_tokens: None,
             rest_api_key: Some("key2".to_string()),
             additional_api_keys: vec!["key3".to_string(), "key4".to_string()],
             rate_limit_per_minute: 1000,
@@ -234,6 +871,49 @@ mod tests {
             summarization_enabled: true,
             summarization_model: "test-model".to_string(),
             pruning_enabled: true,
+            import_directories: vec![],
+            search_max_age_days: None,
+            rescore_importance_on_append: false,
+            importance_rescore_debounce_secs: 60,
+            rate_limit_warn_percent: 80,
+            import_defer_fts: false,
+            id_scheme: Default::default(),
+            embedding_timeout_secs: 60,
+            log_bodies: false,
+            log_body_max_bytes: 4096,
+            log_redact_fields: vec![],
+            compact_on_archive: false,
+            search_exclude_folders: vec![],
+            search_cache_ttl_secs: 30,
+            search_cache_capacity: 256,
+            default_folder: "/inbox".to_string(),
+            prune_by_last_access: false,
+            fts_ranking: Default::default(),
+            llm_max_concurrency: 8,
+            min_embed_length: 0,
+            import_max_concurrency: 4,
+            redaction_patterns: Vec::new(),
+            embedding_models: Default::default(),
+            fts_stop_words: Vec::new(),
+            read_database_url: None,
+            import_derive_labels: false,
+            embeddings_enabled: true,
+            max_messages_per_conversation: None,
+            retention_policies: Default::default(),
+            import_mode: Default::default(),
+            default_search_limit: 10,
+            max_search_limit: 100,
+            summarize_after_messages: None,
+            request_timeout_secs: 30,
+            offload_dir: "sekha_offload".to_string(),
+            role_weights: Default::default(),
+            search_fallback: SearchFallback::Fts,
+            session_gap_minutes: 30,
+            chroma_upsert_batch_size: 32,
+            admin_api_key: None,
+            embedding_strip_code_blocks: false,
+            embedding_collapse_whitespace: false,
+            embedding_max_tokens: None,
             rest_api_key: None,
             additional_api_keys: vec!["extra_key".to_string()],
             rate_limit_per_minute: 1000,
@@ -244,4 +924,53 @@ mod tests {
         assert!(config.is_valid_api_key("extra_key"));
         assert!(!config.is_valid_api_key("invalid_key"));
     }
+
+    #[test]
+    fn test_get_admin_api_key_fallback() {
+        let mut config = Config {
+            mcp_api_key: "mcp_key_12345678901234567890123456789012".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(config.get_admin_api_key(), config.mcp_api_key);
+
+        config.admin_api_key = Some("admin_key_1234567890123456789012345678".to_string());
+        assert_eq!(
+            config.get_admin_api_key(),
+            "admin_key_1234567890123456789012345678"
+        );
+    }
+
+    #[test]
+    fn test_effective_search_limit_defaults_and_caps() {
+        let config = Config {
+            default_search_limit: default_search_limit(),
+            max_search_limit: default_max_search_limit(),
+            ..Default::default()
+        };
+        assert_eq!(config.effective_search_limit(None), 10);
+        assert_eq!(config.effective_search_limit(Some(25)), 25);
+        assert_eq!(config.effective_search_limit(Some(100_000)), 100);
+    }
+
+    #[test]
+    fn test_import_directory_resolve_expands_tilde_and_rejects_root() {
+        let dir = |path: &str| ImportDirectory {
+            path: path.to_string(),
+            folder: None,
+        };
+        let home = dirs::home_dir().unwrap();
+
+        assert_eq!(
+            dir("~/chats").resolve().unwrap().path,
+            format!("{}/chats", home.display())
+        );
+        assert_eq!(dir("~").resolve().unwrap().path, home.display().to_string());
+        // Only a `~` standing for the home directory is expanded
+        assert_eq!(dir("~chats").resolve().unwrap().path, "~chats");
+        assert_eq!(dir("/srv/chats").resolve().unwrap().path, "/srv/chats");
+
+        assert!(dir("").resolve().is_err());
+        assert!(dir("  ").resolve().is_err());
+        assert!(dir("/").resolve().is_err());
+    }
 }
@@ -2,6 +2,8 @@
 
 pub mod api;
 pub mod auth;
+#[cfg(feature = "client")]
+pub mod client;
 pub mod config;
 pub mod models;
 pub mod orchestrator;
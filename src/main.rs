@@ -1,17 +1,27 @@
-use axum::{middleware, Router};
+use axum::middleware;
 use dotenvy;
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 use tower_http::cors::{Any, CorsLayer};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 // Import our modules
 use sekha_controller::{
-    api::{mcp, rate_limiter::RateLimiter, routes},
+    api::{body_logging, rate_limiter::RateLimiter, routes, timeout},
     config::Config,
+    models::redaction::Redactor,
     orchestrator::MemoryOrchestrator,
-    services::{embedding_service::EmbeddingService, llm_bridge_client::LlmBridgeClient},
+    services::{
+        embedding_provider::OllamaProvider,
+        embedding_queue::EmbeddingQueue,
+        embedding_service::{EmbeddingError, EmbeddingPreprocessing, EmbeddingService},
+        event_bus::EventBus,
+        llm_bridge_client::LlmBridgeClient,
+        search_cache::SearchCache,
+    },
     storage::{self, chroma_client::ChromaClient, repository::SeaOrmConversationRepository},
 };
 
@@ -188,8 +198,7 @@ async fn run_setup() -> anyhow::Result<()> {
     std::fs::create_dir_all(&config_dir)?;
     std::fs::create_dir_all(config_dir.join("data"))?;
     std::fs::create_dir_all(config_dir.join("logs"))?;
-    std::fs::create_dir_all(config_dir.join("import"))?;
-    std::fs::create_dir_all(config_dir.join("imported"))?;
+    std::fs::create_dir_all(config_dir.join("import").join("imported"))?;
 
     println!("✅ Directories created");
     println!("✅ Setup complete!");
@@ -230,6 +239,14 @@ async fn start_server(port: u16) -> anyhow::Result<()> {
     // Initialize database
     let db_url = config.read().await.database_url.clone();
     let db_conn = storage::init_db(&db_url).await?;
+    let read_db_url = config.read().await.read_database_url.clone();
+    let read_db_conn = match read_db_url {
+        Some(url) => Some(storage::db::init_read_db(&url).await?),
+        None => {
+            tracing::info!("📖 No read_database_url set, reads use the primary connection");
+            None
+        }
+    };
 
     // Create Chroma client for vector storage
     let chroma_url = config.read().await.chroma_url.clone();
@@ -247,17 +264,113 @@ async fn start_server(port: u16) -> anyhow::Result<()> {
     } else {
         ollama_url
     };
-    let embedding_service = Arc::new(EmbeddingService::new(
-        ollama_url.clone(),
-        chroma_url.clone(),
-    ));
+    let preprocessing = {
+        let cfg = config.read().await;
+        EmbeddingPreprocessing {
+            strip_code_blocks: cfg.embedding_strip_code_blocks,
+            collapse_whitespace: cfg.embedding_collapse_whitespace,
+            max_tokens: cfg.embedding_max_tokens,
+        }
+    };
+    let upsert_batch_size = config.read().await.chroma_upsert_batch_size;
+    let embedding_timeout = Duration::from_secs(config.read().await.embedding_timeout_secs);
+    let mut embedding_service = EmbeddingService::new(ollama_url.clone(), chroma_url.clone())
+        .with_preprocessing(preprocessing.clone())
+        .with_upsert_batch_size(upsert_batch_size)
+        .with_timeout(embedding_timeout);
+    let mut embedding_models: Vec<_> = config
+        .read()
+        .await
+        .embedding_models
+        .clone()
+        .into_iter()
+        .filter(|(_, model)| model.enabled)
+        .collect();
+    embedding_models.sort_by(|a, b| a.0.cmp(&b.0));
+    for (name, model) in embedding_models {
+        let collection = model.collection_name(&name);
+        tracing::info!(
+            "🧮 Also embedding with '{}' ({}) into {}",
+            name,
+            model.model,
+            collection
+        );
+        let provider = Arc::new(OllamaProvider::new(ollama_url.clone(), model.model));
+        embedding_service = embedding_service.with_secondary_model(
+            name,
+            EmbeddingService::with_provider(provider, chroma_url.clone())
+                .with_preprocessing(preprocessing.clone())
+                .with_upsert_batch_size(upsert_batch_size)
+                .with_timeout(embedding_timeout)
+                .with_collection(collection),
+        );
+    }
+    let embedding_service = Arc::new(embedding_service);
+
+    let embeddings_enabled = config.read().await.embeddings_enabled;
+
+    // Vectors from a different embedding model can't go into the existing
+    // collection, so refuse to start rather than fail on every upsert
+    if embeddings_enabled {
+        match embedding_service.verify_collection_dimension().await {
+            Ok(dimension) => {
+                tracing::info!("✅ Embedding dimension {} matches Chroma", dimension)
+            }
+            Err(e @ EmbeddingError::CollectionDimensionMismatch { .. }) => {
+                return Err(anyhow::anyhow!(e));
+            }
+            Err(e) => tracing::warn!("⚠️ Could not verify embedding dimension: {}", e),
+        }
+    } else {
+        tracing::info!("🔤 Embeddings disabled, search uses full-text only");
+    }
 
     // Create repository with both SQLite and Chroma integration
-    let repository = Arc::new(SeaOrmConversationRepository::new(
+    let (
+        session_gap_minutes,
+        search_fallback,
+        fts_stop_words,
+        fts_ranking,
+        redaction_patterns,
+        min_embed_length,
+        id_scheme,
+    ) = {
+        let cfg = config.read().await;
+        (
+            cfg.session_gap_minutes,
+            cfg.search_fallback,
+            cfg.fts_stop_words.clone(),
+            cfg.fts_ranking.clone(),
+            cfg.redaction_patterns.clone(),
+            cfg.min_embed_length,
+            cfg.id_scheme,
+        )
+    };
+    let redactor = Redactor::new(&redaction_patterns)
+        .map_err(|e| anyhow::anyhow!("Invalid redaction pattern: {}", e))?;
+    if !redactor.is_empty() {
+        tracing::info!(
+            "🔒 Redacting {} pattern(s) from message content",
+            redaction_patterns.len()
+        );
+    }
+    let mut repository = SeaOrmConversationRepository::new(
         db_conn,
         chroma_client.clone(),
         embedding_service.clone(),
-    ));
+    )
+    .with_session_gap_minutes(session_gap_minutes)
+    .with_search_fallback(search_fallback)
+    .with_fts_stop_words(fts_stop_words)
+    .with_fts_ranking(fts_ranking)
+    .with_redactor(redactor)
+    .with_min_embed_length(min_embed_length)
+    .with_embeddings_enabled(embeddings_enabled)
+    .with_id_scheme(id_scheme);
+    if let Some(read_db_conn) = read_db_conn {
+        repository = repository.with_read_connection(read_db_conn);
+    }
+    let repository = Arc::new(repository);
 
     // Initialize LLM Bridge client (MODULE 6 integration) - read from config
     let llm_bridge_url = config.read().await.llm_bridge_url.clone();
@@ -266,7 +379,10 @@ async fn start_server(port: u16) -> anyhow::Result<()> {
     } else {
         llm_bridge_url
     };
-    let llm_bridge = Arc::new(LlmBridgeClient::new(llm_bridge_url.clone()));
+    let llm_max_concurrency = config.read().await.llm_max_concurrency;
+    let llm_bridge = Arc::new(
+        LlmBridgeClient::new(llm_bridge_url.clone()).with_max_concurrency(llm_max_concurrency),
+    );
 
     // Verify LLM Bridge health on startup
     match llm_bridge.health_check().await {
@@ -286,11 +402,36 @@ async fn start_server(port: u16) -> anyhow::Result<()> {
     }
 
     // Create Memory Orchestrator with LLM Bridge (MODULE 5 + 6 integration)
-    let orchestrator = Arc::new(MemoryOrchestrator::new(repository.clone(), llm_bridge));
+    let (retention_policies, prune_by_last_access) = {
+        let cfg = config.read().await;
+        (cfg.retention_policies.clone(), cfg.prune_by_last_access)
+    };
+    let orchestrator = Arc::new(
+        MemoryOrchestrator::new(repository.clone(), llm_bridge.clone())
+            .with_retention_policies(retention_policies)
+            .with_last_access_signal(prune_by_last_access),
+    );
 
     // Create rate limiter (Module 6.3)
     let rate_limit_per_minute = config.read().await.rate_limit_per_minute;
-    let rate_limiter = RateLimiter::new(rate_limit_per_minute);
+    let rate_limit_warn_percent = config.read().await.rate_limit_warn_percent;
+    let rate_limiter =
+        RateLimiter::new(rate_limit_per_minute).with_soft_limit_percent(rate_limit_warn_percent);
+
+    // Messages that couldn't be embedded on write are retried in the background
+    let embedding_queue = Arc::new(EmbeddingQueue::with_repository(repository.clone()));
+
+    // Repeated semantic queries are answered from memory until a write
+    let events = EventBus::default();
+    let (search_cache_ttl_secs, search_cache_capacity) = {
+        let cfg = config.read().await;
+        (cfg.search_cache_ttl_secs, cfg.search_cache_capacity)
+    };
+    let search_cache = Arc::new(SearchCache::new(
+        std::time::Duration::from_secs(search_cache_ttl_secs),
+        search_cache_capacity,
+        &events,
+    ));
 
     // Create application state
     let state = routes::AppState {
@@ -298,24 +439,56 @@ async fn start_server(port: u16) -> anyhow::Result<()> {
         repo: repository.clone(),
         orchestrator,
         embedding_service: embedding_service.clone(),
+        embedding_queue,
         chroma_client: chroma_client.clone(),
+        events,
+        search_cache,
+        tasks: Default::default(),
     };
 
     // Start file watcher in background
     let home_dir = dirs::home_dir().expect("Failed to get home directory");
-    let watch_path = home_dir.join(".sekha").join("import");
+    let import_directories = config.read().await.import_directories.clone();
+    let watch_dirs: Vec<(PathBuf, Option<String>)> = if import_directories.is_empty() {
+        vec![(home_dir.join(".sekha").join("import"), None)]
+    } else {
+        import_directories
+            .into_iter()
+            .map(|dir| (PathBuf::from(dir.path), dir.folder))
+            .collect()
+    };
+    let watched = watch_dirs
+        .iter()
+        .map(|(path, _)| path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
 
     let watcher_repo = repository.clone();
+    let watcher_events = state.events.clone();
+    let import_mode = config.read().await.import_mode;
+    let import_derive_labels = config.read().await.import_derive_labels;
+    let import_max_concurrency = config.read().await.import_max_concurrency;
+    let import_defer_fts = config.read().await.import_defer_fts;
     tokio::spawn(async move {
-        let watcher =
-            sekha_controller::services::file_watcher::ImportWatcher::new(watch_path, watcher_repo);
+        let mut watcher =
+            sekha_controller::services::file_watcher::ImportWatcher::with_directories(
+                watcher_repo,
+                watch_dirs,
+            )
+            .with_import_mode(import_mode)
+            .with_max_concurrency(import_max_concurrency)
+            .with_deferred_fts(import_defer_fts)
+            .with_events(watcher_events);
+        if import_derive_labels {
+            watcher = watcher.with_label_derivation(llm_bridge);
+        }
 
         if let Err(e) = watcher.watch().await {
             tracing::error!("❌ File watcher error: {}", e);
         }
     });
 
-    tracing::info!("👀 File watcher started for ~/.sekha/import/");
+    tracing::info!("👀 File watcher started for {}", watched);
 
     // Build CORS layer
     let cors = if config.read().await.cors_enabled {
@@ -327,16 +500,30 @@ async fn start_server(port: u16) -> anyhow::Result<()> {
         CorsLayer::permissive()
     };
 
-    // Build router with REST, MCP endpoints, rate limiting, and CORS
-    let app = Router::new()
-        .merge(routes::create_router(state.clone()))
-        .merge(mcp::create_mcp_router(state.clone()))
-        // Apply rate limiting middleware
+    let request_timeout = Duration::from_secs(config.read().await.request_timeout_secs);
+
+    let body_log_settings = {
+        let config = config.read().await;
+        config.log_bodies.then(|| {
+            body_logging::BodyLogSettings::new(config.log_body_max_bytes, &config.log_redact_fields)
+        })
+    };
+
+    // Build router with REST, MCP endpoints, timeouts, rate limiting, and CORS.
+    // Health and metrics sit outside the rate limiter.
+    let mut app = routes::create_app_router(state, rate_limiter);
+    if let Some(settings) = body_log_settings {
+        tracing::warn!("⚠️  Request/response body logging is enabled");
+        app = app.layer(middleware::from_fn_with_state(
+            Arc::new(settings),
+            body_logging::body_logging_middleware,
+        ));
+    }
+    let app = app
+        // Apply per-request timeout
         .layer(middleware::from_fn_with_state(
-            rate_limiter.clone(),
-            |state, req, next| async move {
-                sekha_controller::api::rate_limiter::rate_limit_middleware(state, req, next).await
-            },
+            request_timeout,
+            timeout::timeout_middleware,
         ))
         // Apply CORS
         .layer(cors);
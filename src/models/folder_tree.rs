@@ -0,0 +1,164 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// One folder in the tree built from slash-delimited folder paths
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct FolderNode {
+    /// Last path segment (`"c"` for `/a/b/c`)
+    pub name: String,
+    pub path: String,
+    /// Conversations in this folder and all of its descendants
+    pub count: u64,
+    /// Conversations directly in this folder
+    pub direct_count: u64,
+    #[schema(no_recursion)]
+    pub children: Vec<FolderNode>,
+}
+
+/// Tidy a folder path for storage: surrounding whitespace, repeated slashes
+/// and a trailing slash are dropped (`" /inbox//triage/ "` becomes
+/// `/inbox/triage`). A leading slash is kept or left out as given, so
+/// existing relative folders keep matching. Returns `None` for a blank
+/// folder.
+pub fn normalize_folder(folder: &str) -> Option<String> {
+    let folder = folder.trim();
+    let segments: Vec<&str> = folder.split('/').filter(|s| !s.is_empty()).collect();
+    if segments.is_empty() {
+        return folder.starts_with('/').then(|| "/".to_string());
+    }
+
+    let joined = segments.join("/");
+    Some(if folder.starts_with('/') {
+        format!("/{}", joined)
+    } else {
+        joined
+    })
+}
+
+/// Whether `folder` is `prefix` or nested under it, matching whole path
+/// segments so `/legal` doesn't cover `/legalese`
+pub fn folder_has_prefix(folder: &str, prefix: &str) -> bool {
+    let prefix = prefix.trim_end_matches('/');
+    match folder.strip_prefix(prefix) {
+        Some(rest) => rest.is_empty() || rest.starts_with('/'),
+        None => false,
+    }
+}
+
+/// Build a folder tree from `(path, conversation count)` pairs. Intermediate
+/// folders with no conversations of their own are created as needed, and
+/// every node's `count` includes its descendants. Siblings are sorted by name.
+pub fn build_folder_tree(folders: &[(String, u64)]) -> Vec<FolderNode> {
+    let mut roots = Vec::new();
+
+    for (path, count) in folders {
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        if segments.is_empty() {
+            // The root folder itself
+            let node = child_node(&mut roots, "/", "/".to_string());
+            node.count += count;
+            node.direct_count += count;
+        } else {
+            insert(&mut roots, "", &segments, *count);
+        }
+    }
+
+    sort_nodes(&mut roots);
+    roots
+}
+
+fn insert(nodes: &mut Vec<FolderNode>, parent_path: &str, segments: &[&str], count: u64) {
+    let Some((name, rest)) = segments.split_first() else {
+        return;
+    };
+
+    let node = child_node(nodes, name, format!("{}/{}", parent_path, name));
+    node.count += count;
+    if rest.is_empty() {
+        node.direct_count += count;
+    } else {
+        let path = node.path.clone();
+        insert(&mut node.children, &path, rest, count);
+    }
+}
+
+/// The node named `name` among `nodes`, created empty if missing
+fn child_node<'a>(nodes: &'a mut Vec<FolderNode>, name: &str, path: String) -> &'a mut FolderNode {
+    let idx = match nodes.iter().position(|n| n.name == name) {
+        Some(idx) => idx,
+        None => {
+            nodes.push(FolderNode {
+                name: name.to_string(),
+                path,
+                count: 0,
+                direct_count: 0,
+                children: Vec::new(),
+            });
+            nodes.len() - 1
+        }
+    };
+    &mut nodes[idx]
+}
+
+fn sort_nodes(nodes: &mut [FolderNode]) {
+    nodes.sort_by(|a, b| a.name.cmp(&b.name));
+    for node in nodes {
+        sort_nodes(&mut node.children);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn folders(entries: &[(&str, u64)]) -> Vec<(String, u64)> {
+        entries
+            .iter()
+            .map(|(path, count)| (path.to_string(), *count))
+            .collect()
+    }
+
+    #[test]
+    fn test_nested_folders_roll_up_counts() {
+        let tree = build_folder_tree(&folders(&[("/a", 1), ("/a/b", 2), ("/a/b/c", 3)]));
+
+        assert_eq!(tree.len(), 1);
+        let a = &tree[0];
+        assert_eq!((a.name.as_str(), a.path.as_str()), ("a", "/a"));
+        assert_eq!((a.count, a.direct_count), (6, 1));
+
+        let b = &a.children[0];
+        assert_eq!((b.name.as_str(), b.path.as_str()), ("b", "/a/b"));
+        assert_eq!((b.count, b.direct_count), (5, 2));
+
+        let c = &b.children[0];
+        assert_eq!((c.name.as_str(), c.path.as_str()), ("c", "/a/b/c"));
+        assert_eq!((c.count, c.direct_count), (3, 3));
+        assert!(c.children.is_empty());
+    }
+
+    #[test]
+    fn test_missing_parents_are_created_and_siblings_sorted() {
+        let tree = build_folder_tree(&folders(&[("/work/zeta", 1), ("/work/alpha", 2), ("/", 4)]));
+
+        let names: Vec<&str> = tree.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, vec!["/", "work"]);
+        assert_eq!((tree[0].count, tree[0].direct_count), (4, 4));
+
+        let work = &tree[1];
+        assert_eq!((work.count, work.direct_count), (3, 0));
+        let children: Vec<&str> = work.children.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(children, vec!["alpha", "zeta"]);
+    }
+
+    #[test]
+    fn test_normalize_folder() {
+        assert_eq!(
+            normalize_folder(" /inbox//triage/ ").as_deref(),
+            Some("/inbox/triage")
+        );
+        assert_eq!(normalize_folder("work/").as_deref(), Some("work"));
+        assert_eq!(normalize_folder("//").as_deref(), Some("/"));
+        assert_eq!(normalize_folder("  "), None);
+    }
+}
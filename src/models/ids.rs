@@ -0,0 +1,82 @@
+//! Generation of IDs for new conversations and messages.
+//!
+//! ULIDs are carried in a [`Uuid`], so they are stored in the same columns
+//! and accepted everywhere a UUID is. Their leading 48 bits are the creation
+//! time in milliseconds, so they sort in creation order both as values and
+//! as hyphenated text.
+
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+use crate::config::IdScheme;
+
+const RANDOM_BITS: u32 = 80;
+const TIMESTAMP_MASK: u128 = (1 << 48) - 1;
+const RANDOM_MASK: u128 = (1 << RANDOM_BITS) - 1;
+
+/// Last ULID handed out, so IDs made in the same millisecond still increase
+static LAST_ULID: Mutex<u128> = Mutex::new(0);
+
+/// A new ID under `scheme`
+pub fn new_id(scheme: IdScheme) -> Uuid {
+    match scheme {
+        IdScheme::Uuid => Uuid::new_v4(),
+        IdScheme::Ulid => new_ulid(),
+    }
+}
+
+/// A ULID: milliseconds since the Unix epoch followed by 80 random bits.
+/// Within one process each ULID is greater than the last, even when several
+/// are made in the same millisecond or the clock steps back.
+pub fn new_ulid() -> Uuid {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or_default()
+        & TIMESTAMP_MASK;
+    let candidate = (millis << RANDOM_BITS) | (Uuid::new_v4().as_u128() & RANDOM_MASK);
+
+    let mut last = LAST_ULID.lock().unwrap_or_else(|e| e.into_inner());
+    let ulid = if candidate > *last {
+        candidate
+    } else {
+        *last + 1
+    };
+    *last = ulid;
+    Uuid::from_u128(ulid)
+}
+
+/// Milliseconds since the Unix epoch encoded in a ULID
+pub fn ulid_timestamp_millis(id: Uuid) -> u64 {
+    (id.as_u128() >> RANDOM_BITS) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ulids_increase_within_a_millisecond() {
+        let ids: Vec<Uuid> = (0..1000).map(|_| new_ulid()).collect();
+
+        let mut sorted = ids.clone();
+        sorted.sort();
+        assert_eq!(sorted, ids);
+
+        let text: Vec<String> = ids.iter().map(Uuid::to_string).collect();
+        let mut sorted_text = text.clone();
+        sorted_text.sort();
+        assert_eq!(sorted_text, text);
+    }
+
+    #[test]
+    fn test_ulid_carries_creation_time() {
+        let before = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+        let millis = ulid_timestamp_millis(new_ulid());
+        assert!(millis >= before && millis < before + 1000);
+    }
+}
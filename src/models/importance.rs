@@ -0,0 +1,84 @@
+//! Importance scale shared by the importance engine, storage and pruning.
+//!
+//! Importance runs from 1.0 (trivial) to 10.0 (pinned). Conversations store
+//! it as the nearest whole number on the same scale, so a stored value reads
+//! back as the score it was rounded from.
+//!
+//! Pruning thresholds are compared against stored scores read back with
+//! [`from_stored`], so a fractional threshold such as 5.5 archives the same
+//! conversations as 6.0.
+
+use serde::{de::Error as _, Deserialize, Deserializer};
+
+/// Lowest importance a conversation can have
+pub const MIN_IMPORTANCE: i32 = 1;
+/// Highest importance; conversations at this level are pinned
+pub const MAX_IMPORTANCE: i32 = 10;
+/// Importance given to new conversations
+pub const DEFAULT_IMPORTANCE: i32 = 5;
+/// Stored importance that marks a conversation as pinned
+pub const PINNED_IMPORTANCE: i32 = MAX_IMPORTANCE;
+
+/// Stored (integer) form of a score, rounded and clamped to the scale
+pub fn to_stored(score: f32) -> i32 {
+    if score.is_nan() {
+        return DEFAULT_IMPORTANCE;
+    }
+    score
+        .round()
+        .clamp(MIN_IMPORTANCE as f32, MAX_IMPORTANCE as f32) as i32
+}
+
+/// Score represented by a stored value
+pub fn from_stored(stored: i32) -> f32 {
+    stored.clamp(MIN_IMPORTANCE, MAX_IMPORTANCE) as f32
+}
+
+/// Map a 0.0–1.0 score, as returned by the LLM bridge, onto the scale
+pub fn from_unit(score: f32) -> f32 {
+    let min = MIN_IMPORTANCE as f32;
+    let max = MAX_IMPORTANCE as f32;
+    min + score.clamp(0.0, 1.0) * (max - min)
+}
+
+/// Whether `stored` is a valid stored importance
+pub fn is_valid(stored: i32) -> bool {
+    (MIN_IMPORTANCE..=MAX_IMPORTANCE).contains(&stored)
+}
+
+/// Lowest pruning threshold; nothing scores below it, so nothing is archived
+pub const MIN_THRESHOLD: f32 = 0.0;
+/// Highest pruning threshold; every unpinned conversation scores below it
+pub const MAX_THRESHOLD: f32 = MAX_IMPORTANCE as f32;
+
+/// Check that a pruning threshold is a number on the importance scale
+pub fn validate_threshold(threshold: f64) -> Result<f32, String> {
+    if threshold.is_finite() && (MIN_THRESHOLD as f64..=MAX_THRESHOLD as f64).contains(&threshold) {
+        Ok(threshold as f32)
+    } else {
+        Err(format!(
+            "importance_threshold must be between {:.1} and {:.1}, got {}",
+            MIN_THRESHOLD, MAX_THRESHOLD, threshold
+        ))
+    }
+}
+
+/// `deserialize_with` for threshold fields: integers and floats are
+/// accepted, strings and out-of-range values rejected
+pub fn deserialize_threshold<'de, D>(deserializer: D) -> Result<f32, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    validate_threshold(f64::deserialize(deserializer)?).map_err(D::Error::custom)
+}
+
+/// [`deserialize_threshold`] for optional fields
+pub fn deserialize_optional_threshold<'de, D>(deserializer: D) -> Result<Option<f32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<f64>::deserialize(deserializer)?
+        .map(validate_threshold)
+        .transpose()
+        .map_err(D::Error::custom)
+}
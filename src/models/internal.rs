@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Conversation {
     pub id: Uuid,
     pub label: String,
@@ -14,6 +14,13 @@ pub struct Conversation {
     pub session_count: i32,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    /// Always included in assembled context, whatever the query
+    #[serde(default)]
+    pub context_pinned: bool,
+    /// When the conversation was last read, if ever. Reads don't move
+    /// `updated_at`.
+    #[serde(default)]
+    pub last_accessed_at: Option<NaiveDateTime>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -26,6 +33,255 @@ pub struct Message {
     pub timestamp: NaiveDateTime,
     pub embedding_id: Option<String>, // CHANGED: Uuid → String (Chroma ID)
     pub metadata: Option<serde_json::Value>,
+    /// Model that produced the message's vector, if it was recorded
+    #[serde(default)]
+    pub embedding_model: Option<String>,
+    /// Length of the message's vector, if it was recorded
+    #[serde(default)]
+    pub embedding_dim: Option<i32>,
+}
+
+/// Metadata key recording why a message was stored without an embedding
+pub const EMBEDDING_SKIPPED_KEY: &str = "embedding_skipped";
+
+/// Metadata flag on messages whose body was dropped when their conversation
+/// was compacted into a summary
+pub const COMPACTED_KEY: &str = "compacted";
+
+/// Metadata key listing the files a message referenced, as [`Attachment`]s
+pub const ATTACHMENTS_KEY: &str = "attachments";
+
+/// Metadata keys the controller writes itself; callers can't set or drop them
+pub const RESERVED_METADATA_KEYS: &[&str] =
+    &[EMBEDDING_SKIPPED_KEY, COMPACTED_KEY, ATTACHMENTS_KEY];
+
+/// Reference to an image or file a message carried. Only the reference is
+/// kept; file contents are never imported.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Attachment {
+    #[serde(default)]
+    pub filename: Option<String>,
+    #[serde(default)]
+    pub mime: Option<String>,
+    /// Where the source kept the file, e.g. a `file-service://` pointer
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+impl Message {
+    /// Whether the message was deliberately left out of the vector store
+    pub fn embedding_skipped(&self) -> bool {
+        self.metadata
+            .as_ref()
+            .is_some_and(|m| m.get(EMBEDDING_SKIPPED_KEY).is_some())
+    }
+
+    /// Attachment references recorded in the metadata; entries without a
+    /// filename, type or URL are skipped
+    pub fn attachments(&self) -> Vec<Attachment> {
+        self.metadata
+            .as_ref()
+            .and_then(|m| m.get(ATTACHMENTS_KEY))
+            .and_then(|a| a.as_array())
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|item| serde_json::from_value::<Attachment>(item.clone()).ok())
+                    .filter(|a| *a != Attachment::default())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Pointer to a conversation offloaded to a JSON file on disk
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct OffloadRecord {
+    pub conversation_id: Uuid,
+    pub label: String,
+    pub folder: String,
+    pub file_path: String,
+    pub message_count: i32,
+    #[schema(value_type = String, format = DateTime)]
+    pub offloaded_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct StoredSummary {
+    pub id: Uuid,
+    pub conversation_id: Uuid,
+    pub level: String,
+    pub summary_text: String,
+    /// Period covered by the summary, formatted as `start/end` (ISO dates)
+    pub period: String,
+    #[schema(value_type = String, format = DateTime)]
+    pub generated_at: NaiveDateTime,
+    pub model_used: Option<String>,
+    pub token_count: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct StoredTag {
+    pub id: Uuid,
+    pub conversation_id: Uuid,
+    pub tag: String,
+    pub confidence: f64,
+    #[schema(value_type = String, format = DateTime)]
+    pub extracted_at: NaiveDateTime,
+}
+
+/// A curated label a conversation carries besides its primary `label`
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ConversationLabel {
+    pub label: String,
+    /// How strongly the conversation belongs under the label, from 0.0 to
+    /// 1.0; the primary label always counts as 1.0
+    pub weight: f64,
+    #[schema(value_type = String, format = DateTime)]
+    pub added_at: NaiveDateTime,
+}
+
+/// A label, folder or tag offered while a query is typed, with how many
+/// conversations use it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Suggestion {
+    pub value: String,
+    pub count: u64,
+}
+
+/// Labels, folders and tags starting with a prefix, most used first
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct SearchSuggestions {
+    /// Primary and additional labels
+    pub labels: Vec<Suggestion>,
+    pub folders: Vec<Suggestion>,
+    pub tags: Vec<Suggestion>,
+}
+
+/// Which conversations a bulk operation applies to; unset fields match any
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct ConversationFilter {
+    /// Folder, subfolders included
+    pub folder: Option<String>,
+    /// Primary label
+    pub label: Option<String>,
+    /// Created at or after this time
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub created_after: Option<NaiveDateTime>,
+    /// Created before this time
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub created_before: Option<NaiveDateTime>,
+}
+
+/// What a bulk tag operation did to one of the requested conversations
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct TagUpdate {
+    pub conversation_id: Uuid,
+    /// False when no such conversation exists
+    pub found: bool,
+    /// Tags added or removed; tags already present (or absent) don't count
+    pub changed: usize,
+}
+
+/// One field change recorded in a conversation's audit log
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ConversationChange {
+    pub conversation_id: Uuid,
+    /// `label`, `folder`, `status` or `importance_score`
+    pub field: String,
+    pub old_value: String,
+    pub new_value: String,
+    #[schema(value_type = String, format = DateTime)]
+    pub changed_at: NaiveDateTime,
+}
+
+/// Named blueprint for recurring conversations such as daily standups.
+/// `label_pattern`, `folder` and `system_message` may contain `{date}`,
+/// `{year}`, `{month}`, `{day}`, `{weekday}` and `{time}`, filled in from
+/// the time the conversation is created.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ConversationTemplate {
+    pub name: String,
+    pub label_pattern: String,
+    pub folder: String,
+    /// Stored as the conversation's first message, with role `system`
+    pub system_message: Option<String>,
+    /// Applied to each new conversation with full confidence
+    pub tags: Vec<String>,
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: NaiveDateTime,
+    #[schema(value_type = String, format = DateTime)]
+    pub updated_at: NaiveDateTime,
+}
+
+impl ConversationTemplate {
+    /// A new active conversation built from this template as of `now`
+    pub fn instantiate(&self, now: NaiveDateTime) -> NewConversation {
+        let messages: Vec<NewMessage> = self
+            .system_message
+            .iter()
+            .map(|content| NewMessage {
+                role: "system".to_string(),
+                content: substitute_date_vars(content, now),
+                timestamp: now,
+                metadata: serde_json::json!({ "template": self.name }),
+            })
+            .collect();
+
+        NewConversation {
+            id: None,
+            label: substitute_date_vars(&self.label_pattern, now),
+            folder: substitute_date_vars(&self.folder, now),
+            status: "active".to_string(),
+            importance_score: Some(crate::models::importance::DEFAULT_IMPORTANCE),
+            word_count: messages.iter().map(|m| m.content.len() as i32).sum(),
+            session_count: Some(1),
+            created_at: now,
+            updated_at: now,
+            messages,
+        }
+    }
+}
+
+/// Replace the date variables documented on [`ConversationTemplate`]
+pub fn substitute_date_vars(text: &str, now: NaiveDateTime) -> String {
+    [
+        ("{date}", "%Y-%m-%d"),
+        ("{year}", "%Y"),
+        ("{month}", "%m"),
+        ("{day}", "%d"),
+        ("{weekday}", "%A"),
+        ("{time}", "%H:%M"),
+    ]
+    .iter()
+    .fold(text.to_string(), |text, (var, format)| {
+        text.replace(var, &now.format(format).to_string())
+    })
+}
+
+/// Subset of conversation fields to change in a single update; `None` leaves
+/// the stored value untouched.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConversationPatch {
+    pub label: Option<String>,
+    pub folder: Option<String>,
+    pub status: Option<String>,
+    pub importance_score: Option<i32>,
+    /// Drop the conversation back to the default importance if it is still
+    /// pinned when the update runs; ignored when `importance_score` is set
+    #[serde(default)]
+    pub unpin: bool,
+}
+
+impl ConversationPatch {
+    pub fn is_empty(&self) -> bool {
+        self.label.is_none()
+            && self.folder.is_none()
+            && self.status.is_none()
+            && self.importance_score.is_none()
+            && !self.unpin
+    }
 }
 
 // NEW: For creating conversations with messages
@@ -1 +1,5 @@
+pub mod folder_tree;
+pub mod ids;
+pub mod importance;
 pub mod internal;
+pub mod redaction;
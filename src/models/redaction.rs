@@ -0,0 +1,47 @@
+//! Regex-based scrubbing of secrets from message content before it is
+//! stored or embedded
+
+use regex::Regex;
+
+/// Text each match is replaced with
+pub const REDACTION_PLACEHOLDER: &str = "[REDACTED]";
+
+/// Replaces matches of the configured patterns with [`REDACTION_PLACEHOLDER`].
+/// With no patterns, content passes through untouched.
+#[derive(Debug, Clone, Default)]
+pub struct Redactor {
+    patterns: Vec<Regex>,
+}
+
+impl Redactor {
+    /// Compile `patterns`, failing on the first invalid one
+    pub fn new(patterns: &[String]) -> Result<Self, regex::Error> {
+        let patterns = patterns
+            .iter()
+            .map(|p| Regex::new(p))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { patterns })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// `content` with every match replaced, and how many matches there were
+    pub fn redact(&self, content: &str) -> (String, usize) {
+        let mut redacted = content.to_string();
+        let mut count = 0;
+
+        for pattern in &self.patterns {
+            let matches = pattern.find_iter(&redacted).count();
+            if matches > 0 {
+                count += matches;
+                redacted = pattern
+                    .replace_all(&redacted, REDACTION_PLACEHOLDER)
+                    .into_owned();
+            }
+        }
+
+        (redacted, count)
+    }
+}
@@ -2,9 +2,55 @@ use crate::models::internal::Message;
 use crate::storage::repository::{ConversationRepository, RepositoryError};
 use chrono::NaiveDateTime;
 use sea_orm::EntityTrait;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use uuid::Uuid;
 
+/// Share of the token budget kept for context-pinned conversations, which
+/// are filled before anything recalled by relevance
+pub const CONTEXT_PIN_BUDGET_FRACTION: f32 = 0.25;
+/// Most recent messages considered from each context-pinned conversation
+const CONTEXT_PIN_MESSAGES: u64 = 20;
+
+/// Placed between messages when context is rendered as a single prompt
+pub const PROMPT_MESSAGE_SEPARATOR: &str = "\n\n---\n\n";
+
+/// Rough token count for `text`: about 4 bytes per token
+pub fn estimate_tokens(text: &str) -> usize {
+    text.len() / 4
+}
+
+/// Render `messages` as one prompt string, each prefixed with its role
+/// ("User: ...") and separated by [`PROMPT_MESSAGE_SEPARATOR`]
+pub fn format_prompt(messages: &[Message]) -> String {
+    messages
+        .iter()
+        .map(|m| format!("{}: {}", role_label(&m.role), m.content))
+        .collect::<Vec<_>>()
+        .join(PROMPT_MESSAGE_SEPARATOR)
+}
+
+/// `role` with its first letter capitalised, e.g. "assistant" -> "Assistant"
+fn role_label(role: &str) -> String {
+    let mut chars = role.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => "Unknown".to_string(),
+    }
+}
+
+/// Assembled context along with how it was arrived at
+#[derive(Debug, Clone)]
+pub struct AssembledContext {
+    pub messages: Vec<Message>,
+    /// Estimated tokens taken by `messages`
+    pub budget_used: usize,
+    /// Messages recalled before ranking and trimming to the budget
+    pub candidates_considered: usize,
+    /// Why `messages` is empty, when it is
+    pub note: Option<String>,
+}
+
 pub struct ContextAssembler {
     repo: Arc<dyn ConversationRepository + Send + Sync>,
 }
@@ -22,10 +68,26 @@ impl ContextAssembler {
         context_budget: usize,
         excluded_folders: Vec<String>,
     ) -> Result<Vec<Message>, RepositoryError> {
+        Ok(self
+            .assemble_detailed(query, preferred_labels, context_budget, excluded_folders)
+            .await?
+            .messages)
+    }
+
+    /// `assemble`, also reporting the budget used and how many candidates
+    /// were considered
+    pub async fn assemble_detailed(
+        &self,
+        query: &str,
+        preferred_labels: Vec<String>,
+        context_budget: usize,
+        excluded_folders: Vec<String>,
+    ) -> Result<AssembledContext, RepositoryError> {
         // Phase 1: Recall - Get candidate messages
         let candidates = self
             .recall_candidates(query, &preferred_labels, &excluded_folders)
             .await?;
+        let candidates_considered = candidates.len();
 
         // Phase 2: Ranking - Score each candidate
         let mut ranked = self
@@ -38,7 +100,60 @@ impl ContextAssembler {
         // Phase 4: Enhancement - Add citations and summaries
         let enhanced_context = self.enhance_context(context).await?;
 
-        Ok(enhanced_context)
+        let budget_used = enhanced_context
+            .iter()
+            .map(|m| estimate_tokens(&m.content))
+            .sum();
+        let note = if candidates_considered == 0 {
+            Some("no stored messages matched the query".to_string())
+        } else if enhanced_context.is_empty() {
+            Some(format!(
+                "{} candidate(s) found but none fit within the context budget",
+                candidates_considered
+            ))
+        } else {
+            None
+        };
+
+        Ok(AssembledContext {
+            messages: enhanced_context,
+            budget_used,
+            candidates_considered,
+            note,
+        })
+    }
+
+    /// Run recall, ranking and selection like `assemble`, but report every
+    /// candidate with its score components instead of the trimmed context
+    pub async fn debug(
+        &self,
+        query: &str,
+        preferred_labels: Vec<String>,
+        context_budget: usize,
+        excluded_folders: Vec<String>,
+    ) -> Result<Vec<CandidateDebug>, RepositoryError> {
+        let candidates = self
+            .recall_candidates(query, &preferred_labels, &excluded_folders)
+            .await?;
+        let mut ranked = self
+            .rank_candidates(candidates, query, &preferred_labels)
+            .await?;
+        self.assemble_context(&mut ranked, context_budget).await?;
+
+        Ok(ranked
+            .into_iter()
+            .map(|c| CandidateDebug {
+                message_id: c.message_id,
+                conversation_id: c.conversation_id,
+                source: c.source,
+                similarity: c.similarity,
+                importance: c.importance,
+                recency: c.recency,
+                label_match: c.label_match,
+                score: c.score,
+                selected: c.selected,
+            })
+            .collect())
     }
 
     /// Phase 1: Recall - Semantic search + pinned + recent
@@ -62,11 +177,15 @@ impl ContextAssembler {
             candidates.push(CandidateMessage {
                 message_id: result.message_id,
                 conversation_id: result.conversation_id,
+                source: CandidateSource::Semantic,
+                similarity: Some(result.score),
                 score: result.score,
                 timestamp: result.timestamp,
-                label: result.label,
                 is_pinned: false,
                 importance: 5.0, // Default, will be refined
+                recency: 0.0,
+                label_match: false,
+                selected: false,
             });
         }
 
@@ -74,7 +193,11 @@ impl ContextAssembler {
         let pinned = self.get_pinned_messages().await?;
         candidates.extend(pinned);
 
-        // 3. Add recent messages from preferred labels (last 7 days)
+        // 3. Add the latest messages of context-pinned conversations
+        let context_pinned = self.get_context_pinned_messages(excluded_folders).await?;
+        candidates.extend(context_pinned);
+
+        // 4. Add recent messages from preferred labels (last 7 days)
         let recent = self
             .get_recent_labeled_messages(preferred_labels, 7)
             .await?;
@@ -90,16 +213,26 @@ impl ContextAssembler {
         _query: &str, // TODO: Use for query similarity boost
         preferred_labels: &[String],
     ) -> Result<Vec<CandidateMessage>, RepositoryError> {
+        // A conversation matches through its primary label or any additional
+        // one, boosted by the weight of its best match
+        let label_weights: HashMap<Uuid, f32> = self
+            .repo
+            .find_by_any_label(preferred_labels)
+            .await?
+            .into_iter()
+            .map(|(conv, weight)| (conv.id, weight as f32))
+            .collect();
+
         for candidate in &mut candidates {
             // Calculate recency score (exponential decay, 7-day half-life)
             let recency_score = self.calculate_recency_score(&candidate.timestamp);
 
             // Calculate label match score
-            let label_score = if preferred_labels.contains(&candidate.label) {
-                5.0
-            } else {
-                0.0
-            };
+            let label_weight = label_weights.get(&candidate.conversation_id);
+            let label_match = label_weight.is_some();
+            let label_score = label_weight.map_or(0.0, |weight| 5.0 * weight);
+            candidate.recency = recency_score;
+            candidate.label_match = label_match;
 
             // Composite score: 50% importance, 30% recency, 20% label match
             candidate.score =
@@ -119,22 +252,51 @@ impl ContextAssembler {
         context_budget: usize,
     ) -> Result<Vec<Message>, RepositoryError> {
         let mut context = Vec::new();
+        let mut included = HashSet::new();
         let mut token_count = 0;
         let target_tokens = (context_budget as f32 * 0.85) as usize; // Reserve 15% for system prompt
 
-        // Estimate: 1 token ≈ 4 characters
+        // Context-pinned conversations come first, newest messages first,
+        // within their reserved share of the budget
+        let pinned_budget = (target_tokens as f32 * CONTEXT_PIN_BUDGET_FRACTION) as usize;
+        let mut pinned: Vec<usize> = (0..candidates.len())
+            .filter(|&i| candidates[i].source == CandidateSource::ContextPinned)
+            .collect();
+        pinned.sort_by(|&a, &b| candidates[b].timestamp.cmp(&candidates[a].timestamp));
+        for i in pinned {
+            let candidate = &mut candidates[i];
+            if included.contains(&candidate.message_id) {
+                continue;
+            }
+            if let Some(message) = self.fetch_message(candidate.message_id).await? {
+                let msg_tokens = estimate_tokens(&message.content);
+
+                if token_count + msg_tokens <= pinned_budget {
+                    included.insert(message.id);
+                    context.push(message);
+                    token_count += msg_tokens;
+                    candidate.selected = true;
+                }
+            }
+        }
+
         for candidate in candidates {
             if token_count >= target_tokens {
                 break;
             }
+            if included.contains(&candidate.message_id) {
+                continue;
+            }
 
             // Fetch full message from SQLite
             if let Some(message) = self.fetch_message(candidate.message_id).await? {
-                let msg_tokens = message.content.len() / 4;
+                let msg_tokens = estimate_tokens(&message.content);
 
                 if token_count + msg_tokens <= target_tokens {
+                    included.insert(message.id);
                     context.push(message);
                     token_count += msg_tokens;
+                    candidate.selected = true;
                 }
             }
         }
@@ -180,12 +342,13 @@ impl ContextAssembler {
 
     /// Helper: Get pinned messages (always included)
     async fn get_pinned_messages(&self) -> Result<Vec<CandidateMessage>, RepositoryError> {
+        use crate::models::importance::PINNED_IMPORTANCE;
         use crate::storage::entities::{conversations, messages};
         use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
 
-        // Find conversations with importance_score >= 10 (pinned)
+        // Find pinned conversations
         let pinned_convs = conversations::Entity::find()
-            .filter(conversations::Column::ImportanceScore.gte(10))
+            .filter(conversations::Column::ImportanceScore.gte(PINNED_IMPORTANCE))
             .filter(conversations::Column::Status.eq("active"))
             .all(self.repo.get_db())
             .await?;
@@ -205,11 +368,67 @@ impl ContextAssembler {
                 candidates.push(CandidateMessage {
                     message_id: msg.id, // CHANGED: Remove parse_str, already Uuid
                     conversation_id: conv_id,
+                    source: CandidateSource::Pinned,
+                    similarity: None,
                     score: 10.0,
                     timestamp: msg.timestamp, // CHANGED: Direct use, already NaiveDateTime
-                    label: conv.label.clone(),
                     is_pinned: true,
                     importance: 10.0,
+                    recency: 0.0,
+                    label_match: false,
+                    selected: false,
+                });
+            }
+        }
+
+        Ok(candidates)
+    }
+
+    /// Helper: Get the latest messages of context-pinned conversations
+    async fn get_context_pinned_messages(
+        &self,
+        excluded_folders: &[String],
+    ) -> Result<Vec<CandidateMessage>, RepositoryError> {
+        use crate::storage::entities::{conversations, messages};
+        use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+
+        let pinned_convs = conversations::Entity::find()
+            .filter(conversations::Column::ContextPinned.eq(true))
+            .filter(conversations::Column::Status.eq("active"))
+            .all(self.repo.get_db())
+            .await?;
+
+        let mut candidates = Vec::new();
+
+        for conv in pinned_convs {
+            if excluded_folders
+                .iter()
+                .any(|folder| conv.folder.starts_with(folder))
+            {
+                continue;
+            }
+
+            let messages = messages::Entity::find()
+                .filter(messages::Column::ConversationId.eq(conv.id))
+                .order_by_desc(messages::Column::Timestamp)
+                .order_by_desc(messages::Column::Seq)
+                .limit(CONTEXT_PIN_MESSAGES)
+                .all(self.repo.get_db())
+                .await?;
+
+            for msg in messages {
+                candidates.push(CandidateMessage {
+                    message_id: msg.id,
+                    conversation_id: conv.id,
+                    source: CandidateSource::ContextPinned,
+                    similarity: None,
+                    score: 0.0,
+                    timestamp: msg.timestamp,
+                    is_pinned: false,
+                    importance: conv.importance_score as f32,
+                    recency: 0.0,
+                    label_match: false,
+                    selected: false,
                 });
             }
         }
@@ -223,7 +442,7 @@ impl ContextAssembler {
         labels: &[String],
         days: i64,
     ) -> Result<Vec<CandidateMessage>, RepositoryError> {
-        use crate::storage::entities::{conversations, messages};
+        use crate::storage::entities::messages;
         use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
 
         if labels.is_empty() {
@@ -234,33 +453,34 @@ impl ContextAssembler {
 
         let mut candidates = Vec::new();
 
-        for label in labels {
-            let convs = conversations::Entity::find()
-                .filter(conversations::Column::Label.eq(label))
-                .filter(conversations::Column::Status.eq("active"))
+        let convs = self.repo.find_by_any_label(labels).await?;
+        for conv in convs
+            .into_iter()
+            .map(|(conv, _)| conv)
+            .filter(|conv| conv.status == "active")
+        {
+            let conv_id = conv.id; // CHANGED: Remove parse_str
+
+            let messages = messages::Entity::find()
+                .filter(messages::Column::ConversationId.eq(conv.id)) // CHANGED: Remove .clone()
+                .filter(messages::Column::Timestamp.gte(cutoff)) // CHANGED: Direct comparison, no to_string()
                 .all(self.repo.get_db())
                 .await?;
 
-            for conv in convs {
-                let conv_id = conv.id; // CHANGED: Remove parse_str
-
-                let messages = messages::Entity::find()
-                    .filter(messages::Column::ConversationId.eq(conv.id)) // CHANGED: Remove .clone()
-                    .filter(messages::Column::Timestamp.gte(cutoff)) // CHANGED: Direct comparison, no to_string()
-                    .all(self.repo.get_db())
-                    .await?;
-
-                for msg in messages {
-                    candidates.push(CandidateMessage {
-                        message_id: msg.id, // CHANGED: Remove parse_str
-                        conversation_id: conv_id,
-                        score: 5.0,
-                        timestamp: msg.timestamp, // CHANGED: Direct use
-                        label: conv.label.clone(),
-                        is_pinned: false,
-                        importance: conv.importance_score as f32,
-                    });
-                }
+            for msg in messages {
+                candidates.push(CandidateMessage {
+                    message_id: msg.id, // CHANGED: Remove parse_str
+                    conversation_id: conv_id,
+                    source: CandidateSource::RecentLabel,
+                    similarity: None,
+                    score: 5.0,
+                    timestamp: msg.timestamp, // CHANGED: Direct use
+                    is_pinned: false,
+                    importance: conv.importance_score as f32,
+                    recency: 0.0,
+                    label_match: false,
+                    selected: false,
+                });
             }
         }
 
@@ -283,20 +503,64 @@ impl ContextAssembler {
             timestamp: m.timestamp, // CHANGED: Direct use
             embedding_id: None,     // TODO: populate from model.embedding_id if needed
             metadata: m.metadata,   // CHANGED: Direct use, already Option<Value>
+            embedding_model: None,
+            embedding_dim: None,
         }))
     }
 }
 
+/// Where a context candidate was recalled from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CandidateSource {
+    Semantic,
+    Pinned,
+    /// From a conversation pinned to context, which skips relevance ranking
+    ContextPinned,
+    RecentLabel,
+}
+
+impl CandidateSource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CandidateSource::Semantic => "semantic",
+            CandidateSource::Pinned => "pinned",
+            CandidateSource::ContextPinned => "context_pinned",
+            CandidateSource::RecentLabel => "recent_label",
+        }
+    }
+}
+
+/// One recalled candidate and how it was scored, as reported by
+/// [`ContextAssembler::debug`]
+#[derive(Debug, Clone)]
+pub struct CandidateDebug {
+    pub message_id: Uuid,
+    pub conversation_id: Uuid,
+    pub source: CandidateSource,
+    /// Vector similarity, for candidates recalled by semantic search
+    pub similarity: Option<f32>,
+    pub importance: f32,
+    pub recency: f32,
+    pub label_match: bool,
+    /// Composite ranking score
+    pub score: f32,
+    /// Whether the candidate made it into the assembled context
+    pub selected: bool,
+}
+
 /// Internal candidate message with scoring metadata
 #[derive(Debug, Clone)]
 struct CandidateMessage {
     message_id: Uuid,
-    #[allow(dead_code)] // Used in Phase 3
     conversation_id: Uuid,
+    source: CandidateSource,
+    similarity: Option<f32>,
     score: f32,
     timestamp: chrono::NaiveDateTime,
-    label: String,
     #[allow(dead_code)] // Will be used when pinned messages implemented
     is_pinned: bool,
     importance: f32,
+    recency: f32,
+    label_match: bool,
+    selected: bool,
 }
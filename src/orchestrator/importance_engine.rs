@@ -1,12 +1,19 @@
+use crate::models::importance::{self, MAX_IMPORTANCE, MIN_IMPORTANCE};
 use crate::models::internal::Message;
 use crate::services::llm_bridge_client::LlmBridgeClient;
 use crate::storage::repository::{ConversationRepository, RepositoryError};
-use std::sync::Arc;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
 use uuid::Uuid;
 
+/// Most recent messages considered when re-scoring a whole conversation
+pub const RESCORE_RECENT_MESSAGES: usize = 20;
+
 pub struct ImportanceEngine {
     repo: Arc<dyn ConversationRepository + Send + Sync>,
     llm_bridge: Arc<LlmBridgeClient>,
+    /// Conversations with a re-score waiting to run
+    pending_rescores: Mutex<HashSet<Uuid>>,
 }
 
 impl ImportanceEngine {
@@ -14,9 +21,86 @@ impl ImportanceEngine {
         repo: Arc<dyn ConversationRepository + Send + Sync>,
         llm_bridge: Arc<LlmBridgeClient>,
     ) -> Self {
-        Self { repo, llm_bridge }
+        Self {
+            repo,
+            llm_bridge,
+            pending_rescores: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Mark a re-score of `conversation_id` as pending. Returns false when
+    /// one is already waiting, so a burst of appends is scored only once.
+    pub fn claim_rescore(&self, conversation_id: Uuid) -> bool {
+        self.pending_rescores
+            .lock()
+            .unwrap()
+            .insert(conversation_id)
+    }
+
+    /// Re-score a conversation from its recent messages and store the result,
+    /// returning the stored importance.
+    ///
+    /// Uses the LLM bridge when it answers and the heuristics alone when it
+    /// doesn't. The stored importance is only ever raised, so a score set by
+    /// hand is not lowered by automatic re-scoring. Releases any pending
+    /// claim from [`Self::claim_rescore`] before scoring, so messages that
+    /// arrive meanwhile schedule another pass.
+    pub async fn rescore_conversation(
+        &self,
+        conversation_id: Uuid,
+    ) -> Result<i32, RepositoryError> {
+        self.pending_rescores
+            .lock()
+            .unwrap()
+            .remove(&conversation_id);
+
+        let conversation = self
+            .repo
+            .find_by_id(conversation_id)
+            .await?
+            .ok_or_else(|| RepositoryError::NotFound("Conversation not found".to_string()))?;
+        let messages = self
+            .repo
+            .find_recent_messages(conversation_id, RESCORE_RECENT_MESSAGES)
+            .await?;
+        if messages.is_empty() {
+            return Ok(conversation.importance_score);
+        }
+
+        let heuristic_score = messages
+            .iter()
+            .map(|m| self.heuristic_score(m))
+            .sum::<f32>()
+            / messages.len() as f32;
+
+        // One bridge call for the whole window rather than one per message
+        let content = messages
+            .iter()
+            .map(|m| m.content.as_str())
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let score = match self.llm_bridge.score_importance(&content, None, None).await {
+            Ok(llm_score) => (heuristic_score * 0.3) + (importance::from_unit(llm_score) * 0.7),
+            Err(e) => {
+                tracing::debug!(
+                    "LLM scoring unavailable for {}, using heuristics: {}",
+                    conversation_id,
+                    e
+                );
+                heuristic_score
+            }
+        };
+
+        let stored = importance::to_stored(score);
+        if stored <= conversation.importance_score {
+            return Ok(conversation.importance_score);
+        }
+        self.repo.update_importance(conversation_id, stored).await?;
+        Ok(stored)
     }
 
+    /// Importance of a message on the shared 1.0–10.0 scale (see
+    /// [`crate::models::importance`]); store it with [`importance::to_stored`]
     pub async fn calculate_score(&self, message_id: Uuid) -> Result<f32, RepositoryError> {
         // Fetch message
         let message = self
@@ -28,12 +112,13 @@ impl ImportanceEngine {
         // Heuristic score
         let heuristic_score = self.heuristic_score(&message);
 
-        // LLM score
+        // LLM score, which the bridge reports on 0.0–1.0
         let llm_score = self
             .llm_bridge
             .score_importance(&message.content, None, None)
             .await
             .map_err(|e| RepositoryError::EmbeddingError(format!("LLM Bridge error: {}", e)))?;
+        let llm_score = importance::from_unit(llm_score);
 
         // Weighted average
         Ok((heuristic_score * 0.3) + (llm_score * 0.7))
@@ -65,6 +150,6 @@ impl ImportanceEngine {
             }
         }
 
-        score.clamp(1.0, 10.0)
+        score.clamp(MIN_IMPORTANCE as f32, MAX_IMPORTANCE as f32)
     }
 }
@@ -3,6 +3,13 @@ use crate::storage::repository::{ConversationRepository, RepositoryError};
 use std::sync::Arc;
 use uuid::Uuid;
 
+/// Messages from the start of a conversation shown to the LLM when deriving
+/// a label for it
+const DERIVE_LABEL_MESSAGES: usize = 6;
+
+/// Longest derived label kept, in characters
+const MAX_DERIVED_LABEL_CHARS: usize = 80;
+
 pub struct LabelIntelligence {
     repo: Arc<dyn ConversationRepository + Send + Sync>,
     llm_bridge: Arc<LlmBridgeClient>,
@@ -121,6 +128,49 @@ impl LabelIntelligence {
         Ok(None)
     }
 
+    /// Ask the LLM for a short title from the first few `messages`
+    /// (`role: content` lines). `None` when the bridge is unavailable or
+    /// gives back nothing usable, so callers keep their own fallback.
+    pub async fn derive_label(&self, messages: &[String]) -> Option<String> {
+        if messages.is_empty() {
+            return None;
+        }
+
+        let excerpt = messages
+            .iter()
+            .take(DERIVE_LABEL_MESSAGES)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join("\n");
+        let prompt = format!(
+            "Give a short title (at most 6 words) for this conversation. \
+            Reply with the title only.\n\n\
+            Conversation content:\n{}",
+            excerpt.chars().take(2000).collect::<String>()
+        );
+
+        let response = match self
+            .llm_bridge
+            .summarize(vec![prompt], "daily", None, Some(10))
+            .await
+        {
+            Ok(r) => r,
+            Err(e) => {
+                tracing::warn!("LLM unavailable for label derivation: {}", e);
+                return None;
+            }
+        };
+
+        let label: String = response
+            .lines()
+            .map(|line| line.trim().trim_matches(|c| c == '"' || c == '\''))
+            .find(|line| !line.is_empty())?
+            .chars()
+            .take(MAX_DERIVED_LABEL_CHARS)
+            .collect();
+        Some(label.trim_end().to_string())
+    }
+
     fn infer_folder(&self, label: &str) -> String {
         if label.contains(':') {
             "/work".to_string()
@@ -2,11 +2,13 @@ pub mod context_assembly;
 pub mod importance_engine;
 pub mod label_intelligence;
 pub mod pruning_engine;
+pub mod search_reranker;
 pub mod summarizer;
 
 use crate::models::internal::Message;
 use crate::services::llm_bridge_client::LlmBridgeClient;
 use crate::storage::repository::{ConversationRepository, RepositoryError};
+use std::collections::HashMap;
 use std::sync::Arc;
 use uuid::Uuid;
 
@@ -18,6 +20,7 @@ pub struct MemoryOrchestrator {
     pub summarizer: summarizer::HierarchicalSummarizer,
     pub pruning_engine: pruning_engine::PruningEngine,
     pub label_intelligence: label_intelligence::LabelIntelligence,
+    pub search_reranker: search_reranker::SearchReranker,
 }
 
 impl MemoryOrchestrator {
@@ -38,9 +41,25 @@ impl MemoryOrchestrator {
                 repo.clone(),
                 llm_bridge.clone(),
             ),
+            search_reranker: search_reranker::SearchReranker::new(llm_bridge.clone()),
         }
     }
 
+    /// Prune conversations under the given folder prefixes by their own rules
+    pub fn with_retention_policies(
+        mut self,
+        policies: HashMap<String, crate::config::RetentionPolicy>,
+    ) -> Self {
+        self.pruning_engine = self.pruning_engine.with_retention_policies(policies);
+        self
+    }
+
+    /// Keep conversations that are still being read out of pruning suggestions
+    pub fn with_last_access_signal(mut self, enabled: bool) -> Self {
+        self.pruning_engine = self.pruning_engine.with_last_access_signal(enabled);
+        self
+    }
+
     pub async fn assemble_context(
         &self,
         query: &str,
@@ -53,10 +72,45 @@ impl MemoryOrchestrator {
             .await
     }
 
+    /// `assemble_context` with the budget used, candidate count and a note
+    /// explaining an empty result
+    pub async fn assemble_context_detailed(
+        &self,
+        query: &str,
+        preferred_labels: Vec<String>,
+        context_budget: usize,
+        excluded_folders: Vec<String>,
+    ) -> Result<context_assembly::AssembledContext, RepositoryError> {
+        self.context_assembler
+            .assemble_detailed(query, preferred_labels, context_budget, excluded_folders)
+            .await
+    }
+
+    pub async fn debug_context(
+        &self,
+        query: &str,
+        preferred_labels: Vec<String>,
+        context_budget: usize,
+        excluded_folders: Vec<String>,
+    ) -> Result<Vec<context_assembly::CandidateDebug>, RepositoryError> {
+        self.context_assembler
+            .debug(query, preferred_labels, context_budget, excluded_folders)
+            .await
+    }
+
     pub async fn score_message_importance(&self, message_id: Uuid) -> Result<f32, RepositoryError> {
         self.importance_engine.calculate_score(message_id).await
     }
 
+    pub async fn rescore_conversation_importance(
+        &self,
+        conversation_id: Uuid,
+    ) -> Result<i32, RepositoryError> {
+        self.importance_engine
+            .rescore_conversation(conversation_id)
+            .await
+    }
+
     pub async fn generate_daily_summary(
         &self,
         conversation_id: Uuid,
@@ -66,12 +120,39 @@ impl MemoryOrchestrator {
             .await
     }
 
+    /// Keep a conversation to `max_messages` by summarizing away the oldest
+    pub async fn roll_over_messages(
+        &self,
+        conversation_id: Uuid,
+        max_messages: u64,
+    ) -> Result<u64, RepositoryError> {
+        self.summarizer
+            .roll_over(conversation_id, max_messages)
+            .await
+    }
+
+    /// Swap a conversation's message bodies for a summary of them
+    pub async fn compact_conversation(
+        &self,
+        conversation_id: Uuid,
+    ) -> Result<u64, RepositoryError> {
+        self.summarizer.compact(conversation_id).await
+    }
+
+    /// Pruning suggestions, for `folder` and its subfolders only when given
     pub async fn suggest_pruning(
         &self,
         threshold_days: i64,
+        importance_threshold: Option<f32>,
+        folder: Option<&str>,
     ) -> Result<Vec<pruning_engine::PruningSuggestion>, RepositoryError> {
         self.pruning_engine
-            .generate_suggestions(threshold_days, 3.0)
+            .generate_suggestions_in_folder(
+                threshold_days,
+                importance_threshold
+                    .unwrap_or(pruning_engine::DEFAULT_ARCHIVE_IMPORTANCE_THRESHOLD),
+                folder,
+            )
             .await
     }
 
@@ -1,15 +1,26 @@
+use crate::config::{RetentionAction, RetentionPolicy};
+use crate::models::folder_tree::folder_has_prefix;
+use crate::models::importance;
 use crate::models::internal::Conversation;
 use crate::services::llm_bridge_client::LlmBridgeClient;
 use crate::storage::repository::{ConversationRepository, RepositoryError};
 use chrono::Duration;
 use chrono::Utc;
 use sea_orm::EntityTrait;
+use std::collections::HashMap;
 use std::sync::Arc;
 use uuid::Uuid;
 
+/// Stale conversations scoring below this (on the importance scale) are
+/// recommended for archiving
+pub const DEFAULT_ARCHIVE_IMPORTANCE_THRESHOLD: f32 = 5.0;
+
 pub struct PruningEngine {
     repo: Arc<dyn ConversationRepository + Send + Sync>,
     llm_bridge: Arc<LlmBridgeClient>,
+    retention_policies: HashMap<String, RetentionPolicy>,
+    /// Count reads, not just writes, as activity when judging staleness
+    use_last_access: bool,
 }
 
 impl PruningEngine {
@@ -17,34 +28,107 @@ impl PruningEngine {
         repo: Arc<dyn ConversationRepository + Send + Sync>,
         llm_bridge: Arc<LlmBridgeClient>,
     ) -> Self {
-        Self { repo, llm_bridge }
+        Self {
+            repo,
+            llm_bridge,
+            retention_policies: HashMap::new(),
+            use_last_access: false,
+        }
+    }
+
+    /// Apply per-folder rules in place of the thresholds passed to
+    /// `generate_suggestions` for conversations under the listed prefixes
+    pub fn with_retention_policies(mut self, policies: HashMap<String, RetentionPolicy>) -> Self {
+        self.retention_policies = policies;
+        self
+    }
+
+    /// Treat a conversation read since its last update as still in use
+    pub fn with_last_access_signal(mut self, enabled: bool) -> Self {
+        self.use_last_access = enabled;
+        self
     }
 
+    /// Latest of the last update and, when enabled, the last read
+    fn last_activity(&self, conv: &Conversation) -> chrono::NaiveDateTime {
+        match conv.last_accessed_at {
+            Some(accessed) if self.use_last_access => accessed.max(conv.updated_at),
+            _ => conv.updated_at,
+        }
+    }
+
+    /// `threshold_days` and `importance_threshold` apply to conversations not
+    /// covered by a retention policy
     pub async fn generate_suggestions(
         &self,
         threshold_days: i64,
         importance_threshold: f32,
     ) -> Result<Vec<PruningSuggestion>, RepositoryError> {
-        let cutoff = Utc::now().naive_utc() - Duration::days(threshold_days);
+        self.generate_suggestions_in_folder(threshold_days, importance_threshold, None)
+            .await
+    }
 
+    /// [`generate_suggestions`](Self::generate_suggestions) limited to
+    /// conversations in `folder` and its subfolders when one is given
+    pub async fn generate_suggestions_in_folder(
+        &self,
+        threshold_days: i64,
+        importance_threshold: f32,
+        folder: Option<&str>,
+    ) -> Result<Vec<PruningSuggestion>, RepositoryError> {
+        let now = Utc::now().naive_utc();
+
+        // One query wide enough for the shortest threshold in play; each
+        // conversation is then checked against its own policy
+        let shortest_days = self
+            .retention_policies
+            .values()
+            .filter(|p| p.action == RetentionAction::Archive)
+            .filter_map(|p| p.threshold_days)
+            .fold(threshold_days, i64::min);
         let candidates = self
-            .find_pruning_candidates(cutoff, importance_threshold)
+            .find_pruning_candidates(now - Duration::days(shortest_days))
             .await?;
 
         let mut suggestions = Vec::new();
 
         for conv in candidates {
-            let suggestion = self.generate_suggestion_for_conversation(&conv).await?;
+            if folder.is_some_and(|prefix| !folder_has_prefix(&conv.folder, prefix)) {
+                continue;
+            }
+            let (days, importance_threshold) = match self.policy_for(&conv.folder) {
+                Some(policy) if policy.action == RetentionAction::Keep => continue,
+                Some(policy) => (
+                    policy.threshold_days.unwrap_or(threshold_days),
+                    policy.importance_threshold.unwrap_or(importance_threshold),
+                ),
+                None => (threshold_days, importance_threshold),
+            };
+            if self.last_activity(&conv) >= now - Duration::days(days) {
+                continue;
+            }
+
+            let suggestion = self
+                .generate_suggestion_for_conversation(&conv, importance_threshold)
+                .await?;
             suggestions.push(suggestion);
         }
 
         Ok(suggestions)
     }
 
+    /// The policy with the longest folder prefix covering `folder`
+    fn policy_for(&self, folder: &str) -> Option<&RetentionPolicy> {
+        self.retention_policies
+            .iter()
+            .filter(|(prefix, _)| folder_has_prefix(folder, prefix))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, policy)| policy)
+    }
+
     async fn find_pruning_candidates(
         &self,
         cutoff: chrono::NaiveDateTime,
-        _importance_threshold: f32,
     ) -> Result<Vec<Conversation>, RepositoryError> {
         use crate::storage::entities::conversations;
         use sea_orm::{ColumnTrait, QueryFilter};
@@ -62,21 +146,23 @@ impl PruningEngine {
     async fn generate_suggestion_for_conversation(
         &self,
         conv: &Conversation,
+        importance_threshold: f32,
     ) -> Result<PruningSuggestion, RepositoryError> {
         let message_count = self.repo.count_messages_in_conversation(conv.id).await?;
         let token_estimate = message_count * 200;
 
         let preview = self.generate_preview(conv).await?;
+        let importance_score = importance::from_stored(conv.importance_score);
 
         let suggestion = PruningSuggestion {
             conversation_id: conv.id,
             conversation_label: conv.label.clone(),
-            last_accessed: conv.updated_at,
+            last_accessed: self.last_activity(conv),
             message_count,
             token_estimate: token_estimate as u32,
-            importance_score: conv.importance_score as f32,
+            importance_score,
             preview,
-            recommendation: if token_estimate > 5000 && conv.importance_score < 5 {
+            recommendation: if token_estimate > 5000 && importance_score < importance_threshold {
                 "archive".to_string()
             } else {
                 "keep".to_string()
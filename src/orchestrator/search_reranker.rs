@@ -0,0 +1,54 @@
+use crate::services::llm_bridge_client::LlmBridgeClient;
+use std::sync::Arc;
+
+/// Upper bound on candidates sent to the LLM, to keep rerank latency predictable
+pub const MAX_RERANK_CANDIDATES: usize = 40;
+
+pub struct SearchReranker {
+    llm_bridge: Arc<LlmBridgeClient>,
+}
+
+impl SearchReranker {
+    pub fn new(llm_bridge: Arc<LlmBridgeClient>) -> Self {
+        Self { llm_bridge }
+    }
+
+    /// Ask the LLM bridge to order `documents` by relevance to `query`.
+    ///
+    /// Returns up to `top_k` indices into `documents`, most relevant first,
+    /// or `None` if the bridge is unavailable or its ranking is unusable.
+    /// Callers should keep the original order in that case.
+    pub async fn rerank(
+        &self,
+        query: &str,
+        documents: &[String],
+        top_k: usize,
+    ) -> Option<Vec<usize>> {
+        let ranking = match self
+            .llm_bridge
+            .rerank(query, documents.to_vec(), top_k, None)
+            .await
+        {
+            Ok(ranking) => ranking,
+            Err(e) => {
+                tracing::warn!("LLM rerank unavailable, keeping similarity order: {}", e);
+                return None;
+            }
+        };
+
+        // Drop out-of-range and repeated indices rather than trusting the model
+        let mut seen = vec![false; documents.len()];
+        let ranking: Vec<usize> = ranking
+            .into_iter()
+            .filter(|&idx| idx < documents.len() && !std::mem::replace(&mut seen[idx], true))
+            .take(top_k)
+            .collect();
+
+        if ranking.is_empty() {
+            tracing::warn!("LLM rerank returned no usable indices, keeping similarity order");
+            return None;
+        }
+
+        Some(ranking)
+    }
+}
@@ -1,4 +1,5 @@
-use crate::models::internal::Message;
+use crate::models::internal::{Message, NewMessage};
+use crate::orchestrator::context_assembly::estimate_tokens;
 use crate::services::llm_bridge_client::LlmBridgeClient;
 use crate::storage::entities::messages as message_entity;
 use crate::storage::repository::{ConversationRepository, RepositoryError};
@@ -6,10 +7,13 @@ use chrono::Duration;
 use chrono::Utc;
 use sea_orm::ActiveModelTrait;
 use sea_orm::EntityTrait;
-use sea_orm::{ColumnTrait, QueryFilter};
+use sea_orm::{ColumnTrait, QueryFilter, QueryOrder, QuerySelect};
 use std::sync::Arc;
 use uuid::Uuid;
 
+/// How long a rollover waits for the LLM to summarize before giving up
+const ROLLOVER_SUMMARY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(120);
+
 pub struct HierarchicalSummarizer {
     repo: Arc<dyn ConversationRepository + Send + Sync>,
     llm_bridge: Arc<LlmBridgeClient>,
@@ -148,6 +152,149 @@ impl HierarchicalSummarizer {
         Ok(summary)
     }
 
+    /// Fold the oldest messages of a conversation into a summary and delete
+    /// them, leaving at most `max_messages`. The summary is stored at the
+    /// daily level with the rolled-up messages' date range as its period.
+    /// Left as is when the LLM can't summarize in time, as the messages would
+    /// be lost for good. Returns how many messages were rolled up.
+    pub async fn roll_over(
+        &self,
+        conversation_id: Uuid,
+        max_messages: u64,
+    ) -> Result<u64, RepositoryError> {
+        let count = self
+            .repo
+            .count_messages_in_conversation(conversation_id)
+            .await?;
+        if count <= max_messages {
+            return Ok(0);
+        }
+
+        let oldest = message_entity::Entity::find()
+            .filter(message_entity::Column::ConversationId.eq(conversation_id))
+            .order_by_asc(message_entity::Column::Timestamp)
+            .order_by_asc(message_entity::Column::Seq)
+            .limit(count - max_messages)
+            .all(self.repo.get_db())
+            .await
+            .map_err(RepositoryError::DbError)?;
+        let (Some(first), Some(last)) = (oldest.first(), oldest.last()) else {
+            return Ok(0);
+        };
+        let period = format!(
+            "{}/{}",
+            first.timestamp.format("%Y-%m-%d"),
+            last.timestamp.format("%Y-%m-%d")
+        );
+
+        let messages_text: Vec<String> = oldest
+            .iter()
+            .map(|m| format!("[{}] {}: {}", m.timestamp, m.role, m.content))
+            .collect();
+        let summary = match tokio::time::timeout(
+            ROLLOVER_SUMMARY_TIMEOUT,
+            self.llm_bridge
+                .summarize(messages_text, "daily", None, Some(200)),
+        )
+        .await
+        {
+            Ok(Ok(s)) => s,
+            Ok(Err(e)) => {
+                tracing::warn!(
+                    "LLM unavailable, not rolling over {}: {}",
+                    conversation_id,
+                    e
+                );
+                return Ok(0);
+            }
+            Err(_) => {
+                tracing::warn!(
+                    "LLM took over {:?} to summarize, not rolling over {}",
+                    ROLLOVER_SUMMARY_TIMEOUT,
+                    conversation_id
+                );
+                return Ok(0);
+            }
+        };
+
+        // The messages only go once their summary is safely stored
+        self.store_summary_for_period(conversation_id, "daily", &summary, period)
+            .await?;
+        let removed = self
+            .repo
+            .delete_messages(oldest.into_iter().map(|m| m.id).collect())
+            .await?;
+
+        tracing::info!(
+            "Rolled {} messages of {} into a summary",
+            removed,
+            conversation_id
+        );
+        Ok(removed)
+    }
+
+    /// Replace the bodies of a conversation's messages with one summary of
+    /// them, stored like a rollover summary and also as a message so search
+    /// still finds the gist. Left as is when the LLM can't summarize, so no
+    /// text is dropped without a real summary. Returns how many bodies were
+    /// dropped.
+    pub async fn compact(&self, conversation_id: Uuid) -> Result<u64, RepositoryError> {
+        let messages: Vec<Message> = self
+            .repo
+            .get_conversation_messages(conversation_id)
+            .await?
+            .into_iter()
+            .filter(|m| !m.content.is_empty())
+            .collect();
+        let (Some(first), Some(last)) = (messages.first(), messages.last()) else {
+            return Ok(0);
+        };
+        let period = format!(
+            "{}/{}",
+            first.timestamp.format("%Y-%m-%d"),
+            last.timestamp.format("%Y-%m-%d")
+        );
+
+        let messages_text: Vec<String> = messages
+            .iter()
+            .map(|m| format!("[{}] {}: {}", m.timestamp, m.role, m.content))
+            .collect();
+        let summary = match self
+            .llm_bridge
+            .summarize(messages_text, "daily", None, Some(200))
+            .await
+        {
+            Ok(s) => s,
+            Err(e) => {
+                tracing::warn!("LLM unavailable, not compacting {}: {}", conversation_id, e);
+                return Ok(0);
+            }
+        };
+
+        self.store_summary_for_period(conversation_id, "daily", &summary, period)
+            .await?;
+        let compacted = self
+            .repo
+            .compact_messages(
+                conversation_id,
+                messages.iter().map(|m| m.id).collect(),
+                NewMessage {
+                    role: "summary".to_string(),
+                    content: summary,
+                    metadata: serde_json::json!({ "compacted_messages": messages.len() }),
+                    timestamp: Utc::now().naive_utc(),
+                },
+            )
+            .await?;
+
+        tracing::info!(
+            "Compacted {} messages of {} into a summary",
+            compacted,
+            conversation_id
+        );
+        Ok(compacted)
+    }
+
     async fn fetch_messages_from_last_n_days(
         &self,
         conversation_id: Uuid,
@@ -172,6 +319,8 @@ impl HierarchicalSummarizer {
                 timestamp: m.timestamp,
                 embedding_id: m.embedding_id,
                 metadata: m.metadata,
+                embedding_model: m.embedding_model,
+                embedding_dim: m.embedding_dim,
             })
             .collect())
     }
@@ -203,6 +352,29 @@ impl HierarchicalSummarizer {
         conversation_id: Uuid,
         level: &str,
         summary: &str,
+    ) -> Result<(), RepositoryError> {
+        let now = chrono::Utc::now().naive_utc();
+        let period_days = match level {
+            "weekly" => 7,
+            "monthly" => 30,
+            _ => 1,
+        };
+        let period = format!(
+            "{}/{}",
+            (now - Duration::days(period_days)).format("%Y-%m-%d"),
+            now.format("%Y-%m-%d")
+        );
+
+        self.store_summary_for_period(conversation_id, level, summary, period)
+            .await
+    }
+
+    async fn store_summary_for_period(
+        &self,
+        conversation_id: Uuid,
+        level: &str,
+        summary: &str,
+        period: String,
     ) -> Result<(), RepositoryError> {
         use crate::storage::entities::hierarchical_summaries;
         use sea_orm::Set;
@@ -214,7 +386,8 @@ impl HierarchicalSummarizer {
             conversation_id: Set(conversation_id),
             level: Set(level.to_string()),
             summary_text: Set(summary.to_string()),
-            token_count: Set(Some((summary.len() / 4) as i32)),
+            timestamp_range: Set(period),
+            token_count: Set(Some(estimate_tokens(summary) as i32)),
             generated_at: Set(now),
             ..Default::default()
         };
@@ -19,6 +19,11 @@ pub enum ProviderError {
 pub trait EmbeddingProvider: Send + Sync {
     /// Generate an embedding for the given text content
     async fn generate_embedding(&self, content: &str) -> Result<Vec<f32>, ProviderError>;
+
+    /// Name of the model behind the vectors, recorded with each message
+    fn model_name(&self) -> &str {
+        "unknown"
+    }
 }
 
 /// Ollama provider implementation
@@ -73,12 +78,18 @@ impl EmbeddingProvider for OllamaProvider {
 
         Ok(embedding)
     }
+
+    fn model_name(&self) -> &str {
+        &self.model
+    }
 }
 
 /// Mock provider for testing
 pub struct MockProvider {
     pub response: Result<Vec<f32>, ProviderError>,
     pub call_count: std::sync::Arc<std::sync::Mutex<usize>>,
+    /// How long each call takes before responding
+    pub delay: Option<std::time::Duration>,
 }
 
 impl MockProvider {
@@ -87,6 +98,7 @@ impl MockProvider {
         Self {
             response: Ok(embedding),
             call_count: std::sync::Arc::new(std::sync::Mutex::new(0)),
+            delay: None,
         }
     }
 
@@ -95,18 +107,32 @@ impl MockProvider {
         Self {
             response: Err(error),
             call_count: std::sync::Arc::new(std::sync::Mutex::new(0)),
+            delay: None,
         }
     }
+
+    /// Take `delay` to answer each call, e.g. to simulate a slow model
+    pub fn with_delay(mut self, delay: std::time::Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
 }
 
 #[async_trait]
 impl EmbeddingProvider for MockProvider {
     async fn generate_embedding(&self, _content: &str) -> Result<Vec<f32>, ProviderError> {
         *self.call_count.lock().unwrap() += 1;
+        if let Some(delay) = self.delay {
+            tokio::time::sleep(delay).await;
+        }
         // Clone the result to allow multiple calls
         match &self.response {
             Ok(vec) => Ok(vec.clone()),
             Err(err) => Err(err.clone()),
         }
     }
+
+    fn model_name(&self) -> &str {
+        "mock"
+    }
 }
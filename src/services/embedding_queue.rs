@@ -1,6 +1,16 @@
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::mpsc;
-use tracing::info;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::storage::repository::ConversationRepository;
+
+/// Attempts a worker makes at embedding a job's messages before giving up
+const MAX_RETRY_ATTEMPTS: u32 = 3;
+/// Delay before the first attempt; doubles after each failure
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(2);
 
 pub struct EmbeddingJob {
     pub conversation_id: String,
@@ -9,15 +19,26 @@ pub struct EmbeddingJob {
 
 pub struct EmbeddingQueue {
     sender: mpsc::Sender<EmbeddingJob>,
+    enqueued: AtomicU64,
 }
 
 impl EmbeddingQueue {
     pub fn new() -> Self {
+        Self::spawn(None)
+    }
+
+    /// Queue whose workers re-embed each job's messages through `repo`
+    pub fn with_repository(repo: Arc<dyn ConversationRepository>) -> Self {
+        Self::spawn(Some(repo))
+    }
+
+    fn spawn(repo: Option<Arc<dyn ConversationRepository>>) -> Self {
         let (sender, receiver) = mpsc::channel::<EmbeddingJob>(100);
         let receiver = Arc::new(tokio::sync::Mutex::new(receiver));
 
         for worker_id in 0..4 {
             let rx = receiver.clone();
+            let repo = repo.clone();
             tokio::spawn(async move {
                 info!("Embedding worker {} started", worker_id);
                 loop {
@@ -29,7 +50,13 @@ impl EmbeddingQueue {
                                 "Worker {} processing job for conversation {}",
                                 worker_id, job.conversation_id
                             );
-                            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+                            match &repo {
+                                Some(repo) => retry_job(repo.as_ref(), job).await,
+                                None => {
+                                    tokio::time::sleep(tokio::time::Duration::from_millis(100))
+                                        .await
+                                }
+                            }
                         }
                         None => break,
                     }
@@ -37,7 +64,10 @@ impl EmbeddingQueue {
             });
         }
 
-        Self { sender }
+        Self {
+            sender,
+            enqueued: AtomicU64::new(0),
+        }
     }
 
     pub async fn enqueue(
@@ -45,8 +75,14 @@ impl EmbeddingQueue {
         job: EmbeddingJob,
     ) -> Result<(), mpsc::error::SendError<EmbeddingJob>> {
         self.sender.send(job).await?;
+        self.enqueued.fetch_add(1, Ordering::Relaxed);
         Ok(())
     }
+
+    /// Number of jobs accepted since the queue was created
+    pub fn enqueued_jobs(&self) -> u64 {
+        self.enqueued.load(Ordering::Relaxed)
+    }
 }
 
 impl Default for EmbeddingQueue {
@@ -54,3 +90,34 @@ impl Default for EmbeddingQueue {
         Self::new()
     }
 }
+
+/// Embed a job's messages, backing off between attempts while the
+/// embedding provider or vector store stays unavailable
+async fn retry_job(repo: &dyn ConversationRepository, job: EmbeddingJob) {
+    let mut pending: Vec<Uuid> = job
+        .message_ids
+        .iter()
+        .filter_map(|id| Uuid::parse_str(id).ok())
+        .collect();
+    let mut delay = INITIAL_RETRY_DELAY;
+
+    for attempt in 1..=MAX_RETRY_ATTEMPTS {
+        tokio::time::sleep(delay).await;
+
+        match repo.retry_embeddings(pending.clone()).await {
+            Ok(embedded) => pending.retain(|id| !embedded.contains(id)),
+            Err(e) => warn!("Embedding retry {} failed: {}", attempt, e),
+        }
+        if pending.is_empty() {
+            info!("Embedded pending messages for {}", job.conversation_id);
+            return;
+        }
+        delay *= 2;
+    }
+
+    warn!(
+        "Gave up embedding {} message(s) for conversation {}",
+        pending.len(),
+        job.conversation_id
+    );
+}
@@ -2,8 +2,9 @@
 //! Embedding service with provider abstraction
 
 use crate::services::embedding_provider::{EmbeddingProvider, OllamaProvider, ProviderError};
-use crate::storage::chroma_client::ChromaClient;
+use crate::storage::chroma_client::{ChromaClient, ScoredResult};
 use serde_json::{json, Value};
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::AcquireError;
 use tokio::sync::Semaphore;
@@ -25,6 +26,19 @@ pub enum EmbeddingError {
     MaxRetriesExceeded,
     #[error("Provider error: {0}")]
     ProviderError(String),
+    #[error(
+        "Chroma collection '{collection}' holds {stored}-dimensional vectors but the embedding \
+         model produces {model}; reindex into a new collection or switch back to the previous model"
+    )]
+    CollectionDimensionMismatch {
+        collection: String,
+        stored: usize,
+        model: usize,
+    },
+    #[error("Unknown embedding model '{0}'")]
+    UnknownModel(String),
+    #[error("Embedding timed out after {0:?}")]
+    Timeout(Duration),
 }
 
 impl From<AcquireError> for EmbeddingError {
@@ -33,12 +47,177 @@ impl From<AcquireError> for EmbeddingError {
     }
 }
 
+/// Optional cleanup applied to message text before it is embedded.
+/// The stored message content is never modified.
+#[derive(Debug, Clone, Default)]
+pub struct EmbeddingPreprocessing {
+    /// Drop fenced (```) code blocks
+    pub strip_code_blocks: bool,
+    /// Collapse runs of whitespace into single spaces
+    pub collapse_whitespace: bool,
+    /// Keep at most this many whitespace-separated tokens
+    pub max_tokens: Option<usize>,
+}
+
+impl EmbeddingPreprocessing {
+    /// Produce the text to embed. Falls back to the original content if
+    /// preprocessing would leave nothing (e.g. a message that is all code).
+    pub fn apply(&self, content: &str) -> String {
+        let mut text = if self.strip_code_blocks {
+            strip_code_blocks(content)
+        } else {
+            content.to_string()
+        };
+
+        if self.collapse_whitespace {
+            text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        }
+
+        if let Some(max_tokens) = self.max_tokens {
+            text = truncate_tokens(&text, max_tokens);
+        }
+
+        if text.trim().is_empty() {
+            content.to_string()
+        } else {
+            text
+        }
+    }
+}
+
+/// Remove ``` fenced blocks, including the fences. An unterminated fence
+/// swallows the rest of the text.
+fn strip_code_blocks(content: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut in_block = false;
+
+    for line in content.split_inclusive('\n') {
+        if line.trim_start().starts_with("```") {
+            in_block = !in_block;
+            continue;
+        }
+        if !in_block {
+            out.push_str(line);
+        }
+    }
+
+    out
+}
+
+/// Keep the first `max_tokens` whitespace-separated tokens, preserving the
+/// original spacing between them.
+fn truncate_tokens(text: &str, max_tokens: usize) -> String {
+    let mut seen = 0;
+    let mut in_token = false;
+
+    for (idx, ch) in text.char_indices() {
+        if ch.is_whitespace() {
+            in_token = false;
+        } else if !in_token {
+            if seen == max_tokens {
+                return text[..idx].trim_end().to_string();
+            }
+            seen += 1;
+            in_token = true;
+        }
+    }
+
+    text.to_string()
+}
+
+/// Messages longer than this many characters are embedded in chunks so the
+/// whole text stays within the embedding model's context window.
+pub const DEFAULT_CHUNK_CHARS: usize = 4000;
+/// Characters shared between consecutive chunks.
+pub const DEFAULT_CHUNK_OVERLAP: usize = 400;
+
+/// Split text into windows of at most `chunk_chars` characters, each
+/// starting `chunk_chars - overlap` characters after the previous one.
+pub fn chunk_text(text: &str, chunk_chars: usize, overlap: usize) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    if chunk_chars == 0 || chars.len() <= chunk_chars {
+        return vec![text.to_string()];
+    }
+
+    let step = chunk_chars.saturating_sub(overlap).max(1);
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    loop {
+        let end = (start + chunk_chars).min(chars.len());
+        chunks.push(chars[start..end].iter().collect());
+        if end == chars.len() {
+            break;
+        }
+        start += step;
+    }
+
+    chunks
+}
+
+/// The message a vector belongs to: chunk vectors are stored as
+/// `{message_id}:{chunk_index}`, whole messages under the message ID alone.
+pub fn vector_message_id(vector_id: &str) -> &str {
+    vector_id
+        .split_once(':')
+        .map_or(vector_id, |(message_id, _)| message_id)
+}
+
+/// Default number of vectors sent to Chroma per upsert request.
+pub const DEFAULT_UPSERT_BATCH_SIZE: usize = 32;
+
+/// Chroma collection the primary embedding model writes to.
+pub const DEFAULT_COLLECTION: &str = "conversations";
+
+/// One message to embed via `process_messages_batch`.
+#[derive(Debug, Clone)]
+pub struct EmbeddingRequest {
+    pub message_id: Uuid,
+    pub conversation_id: Uuid,
+    pub content: String,
+    pub metadata: Value,
+}
+
+/// Outcome of `process_messages_batch`.
+#[derive(Debug, Default)]
+pub struct BatchEmbeddingReport {
+    /// Messages whose vectors were all stored, in input order
+    pub succeeded: Vec<Uuid>,
+    /// Messages that failed, with the reason
+    pub failed: Vec<(Uuid, String)>,
+    /// Length of the vectors generated, if any were
+    pub dimension: Option<usize>,
+}
+
+/// An extra embedding model written alongside the primary one, searchable by name.
+#[derive(Clone)]
+struct SecondaryModel {
+    name: String,
+    service: Arc<EmbeddingService>,
+}
+
+/// A vector ready to be written to Chroma.
+struct PendingVector {
+    message_id: Uuid,
+    id: String,
+    embedding: Vec<f32>,
+    metadata: Value,
+    document: String,
+}
+
 #[derive(Clone)]
 pub struct EmbeddingService {
     provider: Arc<dyn EmbeddingProvider>,
     chroma: Arc<ChromaClient>,
     semaphore: Arc<Semaphore>,
     max_retries: u32,
+    preprocessing: EmbeddingPreprocessing,
+    chunk_chars: usize,
+    chunk_overlap: usize,
+    upsert_batch_size: usize,
+    collection: String,
+    secondary_models: Vec<SecondaryModel>,
+    /// Limit on each call to the provider, separate from any request timeout
+    timeout: Option<Duration>,
 }
 
 impl EmbeddingService {
@@ -58,6 +237,13 @@ impl EmbeddingService {
             chroma,
             semaphore,
             max_retries,
+            preprocessing: EmbeddingPreprocessing::default(),
+            chunk_chars: DEFAULT_CHUNK_CHARS,
+            chunk_overlap: DEFAULT_CHUNK_OVERLAP,
+            upsert_batch_size: DEFAULT_UPSERT_BATCH_SIZE,
+            collection: DEFAULT_COLLECTION.to_string(),
+            secondary_models: Vec::new(),
+            timeout: None,
         }
     }
 
@@ -72,9 +258,83 @@ impl EmbeddingService {
             chroma,
             semaphore,
             max_retries,
+            preprocessing: EmbeddingPreprocessing::default(),
+            chunk_chars: DEFAULT_CHUNK_CHARS,
+            chunk_overlap: DEFAULT_CHUNK_OVERLAP,
+            upsert_batch_size: DEFAULT_UPSERT_BATCH_SIZE,
+            collection: DEFAULT_COLLECTION.to_string(),
+            secondary_models: Vec::new(),
+            timeout: None,
         }
     }
 
+    /// Preprocess message text before embedding
+    pub fn with_preprocessing(mut self, preprocessing: EmbeddingPreprocessing) -> Self {
+        self.preprocessing = preprocessing;
+        self
+    }
+
+    /// Override the chunk size and overlap (in characters) for long messages
+    pub fn with_chunking(mut self, chunk_chars: usize, chunk_overlap: usize) -> Self {
+        self.chunk_chars = chunk_chars;
+        self.chunk_overlap = chunk_overlap;
+        self
+    }
+
+    /// Override how many vectors are sent to Chroma per upsert request
+    pub fn with_upsert_batch_size(mut self, upsert_batch_size: usize) -> Self {
+        self.upsert_batch_size = upsert_batch_size;
+        self
+    }
+
+    /// Give up on a provider call after `timeout` (zero disables). A message
+    /// that times out is stored without a vector and queued for retry.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = (!timeout.is_zero()).then_some(timeout);
+        self
+    }
+
+    /// Write vectors to (and search) `collection` instead of "conversations"
+    pub fn with_collection(mut self, collection: impl Into<String>) -> Self {
+        self.collection = collection.into();
+        self
+    }
+
+    /// Also embed every message with `service`, which should write to its own
+    /// collection. Searches pick it with `search_messages_in(Some(name), ..)`.
+    pub fn with_secondary_model(
+        mut self,
+        name: impl Into<String>,
+        service: EmbeddingService,
+    ) -> Self {
+        self.secondary_models.push(SecondaryModel {
+            name: name.into(),
+            service: Arc::new(service),
+        });
+        self
+    }
+
+    /// Names of the secondary models, in the order they were added
+    pub fn secondary_model_names(&self) -> Vec<&str> {
+        self.secondary_models
+            .iter()
+            .map(|m| m.name.as_str())
+            .collect()
+    }
+
+    pub fn has_model(&self, name: &str) -> bool {
+        self.secondary_models.iter().any(|m| m.name == name)
+    }
+
+    pub fn collection(&self) -> &str {
+        &self.collection
+    }
+
+    /// Name of the primary model, as reported by its provider
+    pub fn model(&self) -> &str {
+        self.provider.model_name()
+    }
+
     /// Generate embedding for a message and store in Chroma with retry logic
     #[cfg(not(tarpaulin_include))]
     pub async fn process_message_with_retry(
@@ -140,18 +400,157 @@ impl EmbeddingService {
         conversation_id: Uuid,
         metadata: Value,
     ) -> Result<String, EmbeddingError> {
-        let _permit = self.semaphore.acquire().await?;
+        let vectors = self
+            .build_vectors(message_id, content, conversation_id, &metadata)
+            .await?;
+
+        self.ensure_collection_for(&vectors).await?;
+        for batch in vectors.chunks(self.upsert_batch_size.max(1)) {
+            self.upsert_vectors(batch).await?;
+        }
+
+        info!("Successfully stored embedding for message: {}", message_id);
+
+        // Secondary models are for comparison only, so their failures never
+        // fail the write
+        for secondary in &self.secondary_models {
+            if let Err(e) = Box::pin(secondary.service.process_message(
+                message_id,
+                content,
+                conversation_id,
+                metadata.clone(),
+            ))
+            .await
+            {
+                warn!(
+                    "Secondary embedding model '{}' failed for message {}: {}",
+                    secondary.name, message_id, e
+                );
+            }
+        }
+
+        Ok(message_id.to_string())
+    }
+
+    /// Embed many messages, writing vectors to Chroma in groups of
+    /// `upsert_batch_size`. Failures are reported per message rather than
+    /// aborting the run; a message succeeds only if all its vectors were stored.
+    pub async fn process_messages_batch(
+        &self,
+        requests: Vec<EmbeddingRequest>,
+    ) -> BatchEmbeddingReport {
+        for secondary in &self.secondary_models {
+            let report = Box::pin(secondary.service.process_messages_batch(requests.clone())).await;
+            for (message_id, e) in report.failed {
+                warn!(
+                    "Secondary embedding model '{}' failed for message {}: {}",
+                    secondary.name, message_id, e
+                );
+            }
+        }
+
+        let batch_size = self.upsert_batch_size.max(1);
+        let mut report = BatchEmbeddingReport::default();
+        let mut built = Vec::with_capacity(requests.len());
+        let mut pending: Vec<PendingVector> = Vec::new();
+        let mut failed: HashMap<Uuid, String> = HashMap::new();
+        let mut collection_ready = false;
 
+        for request in requests {
+            match self
+                .build_vectors(
+                    request.message_id,
+                    &request.content,
+                    request.conversation_id,
+                    &request.metadata,
+                )
+                .await
+            {
+                Ok(vectors) => {
+                    if let Some(vector) = vectors.first() {
+                        report.dimension.get_or_insert(vector.embedding.len());
+                    }
+                    built.push(request.message_id);
+                    pending.extend(vectors);
+                }
+                Err(e) => {
+                    warn!("Embedding failed for message {}: {}", request.message_id, e);
+                    failed.insert(request.message_id, e.to_string());
+                }
+            }
+
+            while pending.len() >= batch_size {
+                let batch: Vec<PendingVector> = pending.drain(..batch_size).collect();
+                self.flush_batch(&batch, &mut collection_ready, &mut failed)
+                    .await;
+            }
+        }
+
+        if !pending.is_empty() {
+            self.flush_batch(&pending, &mut collection_ready, &mut failed)
+                .await;
+        }
+
+        for message_id in built {
+            if !failed.contains_key(&message_id) {
+                report.succeeded.push(message_id);
+            }
+        }
+        report.failed = failed.into_iter().collect();
+
+        report
+    }
+
+    /// Upsert one batch, recording every message it touches as failed on error
+    async fn flush_batch(
+        &self,
+        batch: &[PendingVector],
+        collection_ready: &mut bool,
+        failed: &mut HashMap<Uuid, String>,
+    ) {
+        let mut result = Ok(());
+        if !*collection_ready {
+            result = self.ensure_collection_for(batch).await;
+            *collection_ready = result.is_ok();
+        }
+        if result.is_ok() {
+            result = self.upsert_vectors(batch).await;
+        }
+
+        if let Err(e) = result {
+            error!(
+                "Chroma batch upsert of {} vectors failed: {}",
+                batch.len(),
+                e
+            );
+            for vector in batch {
+                failed
+                    .entry(vector.message_id)
+                    .or_insert_with(|| e.to_string());
+            }
+        }
+    }
+
+    /// Generate the vector(s) for one message without writing them. Long
+    /// messages are split into overlapping chunks, one vector each.
+    async fn build_vectors(
+        &self,
+        message_id: Uuid,
+        content: &str,
+        conversation_id: Uuid,
+        metadata: &Value,
+    ) -> Result<Vec<PendingVector>, EmbeddingError> {
         debug!("Generating embedding for message: {}", message_id);
 
-        // Generate embedding via provider
-        let embedding = self.generate_embedding(content).await?;
+        let text = self.preprocessing.apply(content);
+        let chunks = chunk_text(&text, self.chunk_chars, self.chunk_overlap);
 
         // Flatten metadata for Chroma (Chroma only accepts flat key-value pairs with simple types)
         let mut chroma_metadata = json!({
             "conversation_id": conversation_id.to_string(),
             "message_id": message_id.to_string(),
             "content_preview": &content[..content.len().min(100)],
+            "embedded_at": chrono::Utc::now().timestamp(),
         });
 
         // Extract and flatten nested metadata fields
@@ -176,37 +575,109 @@ impl EmbeddingService {
             }
         }
 
-        // Store in Chroma
-        let embedding_id = message_id.to_string();
-        self.chroma
-            .ensure_collection("conversations", embedding.len() as i32)
-            .await?;
+        if chunks.len() == 1 {
+            // Chroma keeps the original content as the document
+            let embedding = self.generate_embedding(&text).await?;
+
+            return Ok(vec![PendingVector {
+                message_id,
+                id: message_id.to_string(),
+                embedding,
+                metadata: chroma_metadata,
+                document: content.to_string(),
+            }]);
+        }
+
+        debug!(
+            "Message {} split into {} chunks for embedding",
+            message_id,
+            chunks.len()
+        );
+        chroma_metadata["chunk_count"] = json!(chunks.len());
+
+        let mut vectors = Vec::with_capacity(chunks.len());
+        for (chunk_index, chunk) in chunks.into_iter().enumerate() {
+            let embedding = self.generate_embedding(&chunk).await?;
+
+            let mut chunk_metadata = chroma_metadata.clone();
+            chunk_metadata["chunk_index"] = json!(chunk_index);
+
+            vectors.push(PendingVector {
+                message_id,
+                id: format!("{}:{}", message_id, chunk_index),
+                embedding,
+                metadata: chunk_metadata,
+                document: chunk,
+            });
+        }
+
+        Ok(vectors)
+    }
+
+    async fn ensure_collection_for(&self, vectors: &[PendingVector]) -> Result<(), EmbeddingError> {
+        if let Some(first) = vectors.first() {
+            self.chroma
+                .ensure_collection(&self.collection, first.embedding.len() as i32)
+                .await?;
+        }
+        Ok(())
+    }
 
+    async fn upsert_vectors(&self, vectors: &[PendingVector]) -> Result<(), EmbeddingError> {
         self.chroma
-            .upsert(
-                "conversations",
-                &embedding_id,
-                embedding.clone(),
-                chroma_metadata,
-                Some(content.to_string()),
+            .upsert_batch(
+                &self.collection,
+                vectors.iter().map(|v| v.id.clone()).collect(),
+                vectors.iter().map(|v| v.embedding.clone()).collect(),
+                vectors.iter().map(|v| v.metadata.clone()).collect(),
+                vectors.iter().map(|v| v.document.clone()).collect(),
             )
             .await?;
+        Ok(())
+    }
 
-        info!("Successfully stored embedding for message: {}", message_id);
-
-        Ok(embedding_id)
+    /// Check that the model's vectors fit its existing collection, returning
+    /// the model's dimension. A missing collection, or
+    /// one that hasn't stored anything yet, accepts any dimension.
+    pub async fn verify_collection_dimension(&self) -> Result<usize, EmbeddingError> {
+        let model = self.generate_embedding("dimension probe").await?.len();
+
+        if let Some(info) = self.chroma.collection_info(&self.collection).await? {
+            if let Some(stored) = info.dimension.filter(|&stored| stored != model) {
+                return Err(EmbeddingError::CollectionDimensionMismatch {
+                    collection: info.name,
+                    stored,
+                    model,
+                });
+            }
+        }
+        Ok(model)
     }
 
     /// Generate embedding using configured provider
     pub async fn generate_embedding(&self, content: &str) -> Result<Vec<f32>, EmbeddingError> {
         let _permit = self.semaphore.acquire().await?;
 
-        self.provider
-            .generate_embedding(content)
-            .await
+        self.call_provider(content)
+            .await?
             .map_err(|e| EmbeddingError::ProviderError(e.to_string()))
     }
 
+    /// One provider call, bounded by the configured timeout
+    async fn call_provider(
+        &self,
+        content: &str,
+    ) -> Result<Result<Vec<f32>, ProviderError>, EmbeddingError> {
+        let call = self.provider.generate_embedding(content);
+        match self.timeout {
+            Some(limit) => tokio::time::timeout(limit, call).await.map_err(|_| {
+                warn!("Embedding provider did not answer within {:?}", limit);
+                EmbeddingError::Timeout(limit)
+            }),
+            None => Ok(call.await),
+        }
+    }
+
     /// Generate embedding with retry logic
     pub async fn generate_embedding_with_retry(
         &self,
@@ -216,7 +687,7 @@ impl EmbeddingService {
         let mut last_error = None;
 
         for attempt in 0..max_retries {
-            match self.provider.generate_embedding(content).await {
+            match self.call_provider(content).await? {
                 Ok(embedding) => return Ok(embedding),
                 Err(ProviderError::NoEmbeddings) => {
                     // Don't retry - immediately return NoEmbeddings
@@ -269,20 +740,87 @@ impl EmbeddingService {
         query: &str,
         limit: usize,
         filters: Option<Value>,
-    ) -> Result<Vec<crate::storage::chroma_client::ScoredResult>, EmbeddingError> {
+    ) -> Result<Vec<ScoredResult>, EmbeddingError> {
+        self.search_messages_in(None, query, limit, filters).await
+    }
+
+    /// Semantic search using the named secondary model's collection, or the
+    /// primary model's when `model` is `None`
+    pub async fn search_messages_in(
+        &self,
+        model: Option<&str>,
+        query: &str,
+        limit: usize,
+        filters: Option<Value>,
+    ) -> Result<Vec<ScoredResult>, EmbeddingError> {
+        if let Some(name) = model {
+            let secondary = self
+                .secondary_models
+                .iter()
+                .find(|m| m.name == name)
+                .ok_or_else(|| EmbeddingError::UnknownModel(name.to_string()))?;
+            return Box::pin(secondary.service.search_messages(query, limit, filters)).await;
+        }
+
         // Generate query embedding
         let query_embedding = self.generate_embedding(query).await?;
 
-        // Search in Chroma
+        // Search in Chroma, over-fetching so chunk hits that collapse into one
+        // message still leave `limit` distinct messages
         let results = self
             .chroma
-            .query("conversations", query_embedding, limit as u32, filters)
+            .query(
+                &self.collection,
+                query_embedding,
+                limit.saturating_mul(3) as u32,
+                filters,
+            )
             .await?;
 
-        Ok(results)
+        Ok(merge_chunk_hits(results, limit))
+    }
+
+    /// Remove the vectors of `message_ids`, chunks included, from every
+    /// secondary model's collection. Leftover vectors there only cost space,
+    /// so failures are logged rather than returned.
+    pub async fn delete_from_secondary_models(&self, message_ids: &[String]) {
+        for secondary in &self.secondary_models {
+            if let Err(e) = secondary
+                .service
+                .chroma
+                .delete_for_messages(&secondary.service.collection, message_ids)
+                .await
+            {
+                warn!(
+                    "Failed to remove vectors from secondary model '{}': {}",
+                    secondary.name, e
+                );
+            }
+        }
     }
 }
 
+/// Collapse chunk vectors back to their message, keeping the best-ranked
+/// hit per message (Chroma returns results ordered by distance).
+fn merge_chunk_hits(results: Vec<ScoredResult>, limit: usize) -> Vec<ScoredResult> {
+    let mut merged: Vec<ScoredResult> = Vec::new();
+
+    for mut result in results {
+        if let Some(message_id) = result.metadata["message_id"].as_str() {
+            result.id = message_id.to_string();
+        }
+        if merged.iter().any(|m| m.id == result.id) {
+            continue;
+        }
+        merged.push(result);
+        if merged.len() == limit {
+            break;
+        }
+    }
+
+    merged
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -314,6 +852,26 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_generate_embedding_times_out_at_configured_limit() {
+        let provider =
+            Arc::new(MockProvider::new_success(vec![0.1; 8]).with_delay(Duration::from_secs(5)));
+        let service =
+            EmbeddingService::with_provider(provider, "http://localhost:8000".to_string())
+                .with_timeout(Duration::from_millis(50));
+
+        let started = std::time::Instant::now();
+        let result = service.generate_embedding("long message").await;
+
+        assert!(matches!(
+            result,
+            Err(EmbeddingError::Timeout(limit)) if limit == Duration::from_millis(50)
+        ));
+        let elapsed = started.elapsed();
+        assert!(elapsed >= Duration::from_millis(50));
+        assert!(elapsed < Duration::from_secs(1), "took {:?}", elapsed);
+    }
+
     #[tokio::test]
     async fn test_generate_embedding_with_retry_exhaustion() {
         let provider = Arc::new(MockProvider::new_error(ProviderError::Http(
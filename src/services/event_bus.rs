@@ -0,0 +1,86 @@
+use serde::Serialize;
+use tokio::sync::broadcast;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Events buffered per subscriber before the slowest ones start missing events
+pub const DEFAULT_EVENT_CAPACITY: usize = 256;
+
+/// Something that happened to a stored conversation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MemoryEvent {
+    Created {
+        conversation_id: Uuid,
+    },
+    Updated {
+        conversation_id: Uuid,
+    },
+    Deleted {
+        conversation_id: Uuid,
+    },
+    /// Archived by the pruning engine
+    Pruned {
+        conversation_id: Uuid,
+    },
+}
+
+impl MemoryEvent {
+    /// Name used for the SSE `event:` field
+    pub fn kind(&self) -> &'static str {
+        match self {
+            MemoryEvent::Created { .. } => "created",
+            MemoryEvent::Updated { .. } => "updated",
+            MemoryEvent::Deleted { .. } => "deleted",
+            MemoryEvent::Pruned { .. } => "pruned",
+        }
+    }
+
+    pub fn conversation_id(&self) -> Uuid {
+        match self {
+            MemoryEvent::Created { conversation_id }
+            | MemoryEvent::Updated { conversation_id }
+            | MemoryEvent::Deleted { conversation_id }
+            | MemoryEvent::Pruned { conversation_id } => *conversation_id,
+        }
+    }
+}
+
+/// In-process fan-out of [`MemoryEvent`]s. Each subscriber gets every event
+/// published after it subscribed; one that falls more than the channel
+/// capacity behind skips ahead rather than slowing publishers down.
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<MemoryEvent>,
+}
+
+impl EventBus {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    /// Deliver `event` to current subscribers; a no-op when there are none
+    pub fn publish(&self, event: MemoryEvent) {
+        tracing::debug!(
+            "Memory event {} for {}",
+            event.kind(),
+            event.conversation_id()
+        );
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<MemoryEvent> {
+        self.sender.subscribe()
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new(DEFAULT_EVENT_CAPACITY)
+    }
+}
@@ -1,17 +1,84 @@
 use anyhow::{Context, Result};
 use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
 use serde::{Deserialize, Serialize};
+use std::io::Read;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::fs;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, Semaphore};
 use uuid::Uuid;
 
-use crate::models::internal::{NewConversation, NewMessage};
+use crate::config::ImportMode;
+use crate::models::folder_tree::normalize_folder;
+use crate::models::internal::{Attachment, NewConversation, NewMessage, ATTACHMENTS_KEY};
+use crate::orchestrator::label_intelligence::LabelIntelligence;
+use crate::services::event_bus::{EventBus, MemoryEvent};
+use crate::services::llm_bridge_client::LlmBridgeClient;
 use crate::storage::repository::ConversationRepository;
 use crate::storage::repository::Stats;
 use std::collections::HashMap;
 
+/// Entries inside an export archive that hold conversations; media and
+/// account files shipped alongside them are skipped
+const ZIP_EXPORT_FILES: &[&str] = &["conversations.json"];
+
+/// Extensions the importer recognises; files without an extension are
+/// imported too, their format sniffed from the content
+const IMPORT_EXTENSIONS: &[&str] = &["json", "xml", "md", "txt", "zip"];
+
+/// Leading bytes of a ZIP archive
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+
+/// Whether a file dropped in the import directory should be picked up:
+/// a recognised extension, or none at all (hidden files excluded)
+fn is_import_candidate(path: &Path) -> bool {
+    match path.extension().and_then(|s| s.to_str()) {
+        Some(ext) => IMPORT_EXTENSIONS.contains(&ext),
+        None => path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .is_some_and(|name| !name.starts_with('.')),
+    }
+}
+
+/// Files imported at once by default; further files wait for a free slot
+pub const DEFAULT_IMPORT_CONCURRENCY: usize = 4;
+
+/// Subdirectory of each watched directory that imported files are moved to
+const IMPORTED_DIR: &str = "imported";
+
+/// Paths currently being imported. A dropped file raises several events
+/// (create, then one or more modifies), and each would otherwise start its
+/// own import of the same file.
+#[derive(Clone, Default)]
+struct InFlightPaths(Arc<std::sync::Mutex<std::collections::HashSet<PathBuf>>>);
+
+impl InFlightPaths {
+    /// Claim `path`, or `None` if it is already being imported. The claim
+    /// is released when the guard drops.
+    fn claim(&self, path: &Path) -> Option<InFlightGuard> {
+        let newly_claimed = self.0.lock().unwrap().insert(path.to_path_buf());
+        newly_claimed.then(|| InFlightGuard {
+            paths: self.clone(),
+            path: path.to_path_buf(),
+        })
+    }
+}
+
+struct InFlightGuard {
+    paths: InFlightPaths,
+    path: PathBuf,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.paths.0.lock().unwrap().remove(&self.path);
+    }
+}
+
+/// Namespace for conversation IDs derived in `ImportMode::Deterministic`
+const IMPORT_ID_NAMESPACE: Uuid = Uuid::from_u128(0x9bb7_1534_e415_4a44_9953_8f1b_1ad9_5ce6);
+
 // ============================================
 // ChatGPT Export Format
 // ============================================
@@ -40,17 +107,106 @@ struct ChatGptMessage {
     author: ChatGptAuthor,
     create_time: Option<f64>,
     content: ChatGptContent,
+    #[serde(default)]
+    metadata: Option<serde_json::Value>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 struct ChatGptAuthor {
     role: String,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    metadata: Option<serde_json::Value>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 struct ChatGptContent {
     content_type: String,
-    parts: Option<Vec<String>>,
+    /// Mostly strings; multimodal messages also carry objects (e.g. images)
+    #[serde(default)]
+    parts: Option<Vec<serde_json::Value>>,
+    /// Body of `code`, `execution_output` and quote content
+    #[serde(default)]
+    text: Option<String>,
+    /// Body of browsing tool results
+    #[serde(default)]
+    result: Option<String>,
+    #[serde(default)]
+    language: Option<String>,
+}
+
+impl ChatGptContent {
+    /// All text parts joined by newlines, plus any non-text parts
+    fn flatten(&self) -> (String, Vec<serde_json::Value>) {
+        let mut texts = Vec::new();
+        let mut attachments = Vec::new();
+
+        for part in self.parts.iter().flatten() {
+            match part {
+                serde_json::Value::String(text) => texts.push(text.clone()),
+                serde_json::Value::Null => {}
+                other => attachments.push(other.clone()),
+            }
+        }
+        texts.extend(self.text.iter().chain(&self.result).cloned());
+
+        (texts.join("\n"), attachments)
+    }
+}
+
+/// References for a message's non-text parts, named and typed from the
+/// files listed in the message metadata. Listed files no part points at,
+/// such as uploaded documents, are included as well.
+fn chatgpt_attachments(
+    parts: &[serde_json::Value],
+    metadata: Option<&serde_json::Value>,
+) -> Vec<Attachment> {
+    let field = |value: &serde_json::Value, key: &str| {
+        value
+            .get(key)
+            .and_then(serde_json::Value::as_str)
+            .map(str::to_string)
+    };
+    let listed: Vec<&serde_json::Value> = metadata
+        .and_then(|m| m.get("attachments"))
+        .and_then(serde_json::Value::as_array)
+        .map(|files| files.iter().collect())
+        .unwrap_or_default();
+    let mut referenced = vec![false; listed.len()];
+    let mut attachments = Vec::new();
+
+    for part in parts {
+        let url = field(part, "asset_pointer").or_else(|| field(part, "url"));
+        // Pointers look like `file-service://<file id>`
+        let file = url.as_deref().and_then(|url| {
+            let id = url.rsplit("://").next()?;
+            listed
+                .iter()
+                .position(|f| field(f, "id").as_deref() == Some(id))
+        });
+        if let Some(i) = file {
+            referenced[i] = true;
+        }
+        let listed_field = |key: &str| file.and_then(|i| field(listed[i], key));
+
+        attachments.push(Attachment {
+            filename: field(part, "name").or_else(|| listed_field("name")),
+            mime: field(part, "mime_type").or_else(|| listed_field("mime_type")),
+            url,
+        });
+    }
+
+    for (file, _) in listed.iter().zip(referenced).filter(|(_, r)| !r) {
+        attachments.push(Attachment {
+            filename: field(file, "name"),
+            mime: field(file, "mime_type"),
+            url: None,
+        });
+    }
+
+    attachments.retain(|a| *a != Attachment::default());
+    attachments
 }
 
 // ============================================
@@ -75,6 +231,60 @@ struct ClaudeMessage {
     role: String,
     content: String,
     timestamp: Option<String>,
+    /// Uploaded documents; their extracted text is not imported
+    #[serde(default)]
+    attachments: Vec<ClaudeAttachment>,
+    /// Uploaded images and other files
+    #[serde(default)]
+    files: Vec<ClaudeAttachment>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClaudeAttachment {
+    #[serde(default)]
+    file_name: Option<String>,
+    #[serde(default)]
+    file_type: Option<String>,
+}
+
+// ============================================
+// Sekha Export Format (MCP memory_export, json)
+// ============================================
+
+#[derive(Debug, Deserialize)]
+struct SekhaExport {
+    conversation: SekhaExportConversation,
+    messages: Vec<SekhaExportMessage>,
+}
+
+/// A saved `memory_export` tool response, with the export under `data`
+#[derive(Debug, Deserialize)]
+struct SekhaExportResponse {
+    data: SekhaExport,
+}
+
+#[derive(Debug, Deserialize)]
+struct SekhaExportConversation {
+    id: Option<String>,
+    label: Option<String>,
+    created_at: Option<String>,
+    updated_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SekhaExportMessage {
+    role: String,
+    content: String,
+    timestamp: Option<chrono::NaiveDateTime>,
+    #[serde(default)]
+    metadata: Option<serde_json::Value>,
+}
+
+/// Conversation timestamps are exported in `NaiveDateTime`'s display form
+fn parse_export_time(ts: &str) -> Option<chrono::NaiveDateTime> {
+    chrono::NaiveDateTime::parse_from_str(ts, "%Y-%m-%d %H:%M:%S%.f")
+        .or_else(|_| ts.parse())
+        .ok()
 }
 
 // ============================================
@@ -84,10 +294,14 @@ struct ClaudeMessage {
 #[derive(Debug, Clone)]
 struct ParsedConversation {
     title: String,
+    /// Whether `title` came from the source rather than a filename or placeholder
+    has_title: bool,
     messages: Vec<ParsedMessage>,
     created_at: chrono::NaiveDateTime,
     updated_at: chrono::NaiveDateTime,
     source: ImportSource,
+    /// Identity of the conversation within its source export
+    source_key: String,
 }
 
 #[derive(Debug, Clone)]
@@ -95,20 +309,51 @@ struct ParsedMessage {
     role: String,
     content: String,
     timestamp: chrono::NaiveDateTime,
+    /// Source-specific details, merged into the stored message metadata
+    metadata: serde_json::Map<String, serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 enum ImportSource {
     ChatGPT,
     Claude,
+    Sekha,
     Unknown,
 }
 
+impl ImportSource {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ImportSource::ChatGPT => "chatgpt",
+            ImportSource::Claude => "claude",
+            ImportSource::Sekha => "sekha",
+            ImportSource::Unknown => "unknown",
+        }
+    }
+}
+
+/// Identity of a conversation from a file that carries no ID of its own:
+/// the path it was imported from, so files sharing a title stay apart
+fn file_source_key(path: &Path) -> String {
+    format!("file:{}", path.display())
+}
+
+/// The export's own conversation ID when it has one, otherwise the title
+/// and original creation time
+fn source_key(source_id: Option<&str>, title: &str, created: Option<String>) -> String {
+    match source_id {
+        Some(id) => id.to_string(),
+        None => format!("{}@{}", title, created.unwrap_or_default()),
+    }
+}
+
 // ============================================
 // File Watcher
 // ============================================
 pub struct ImportWatcher {
-    watch_path: PathBuf,
+    /// Watched directories, each with the folder its imports are stored in
+    /// (`None` keeps the per-source `/imports/<source>` folder)
+    directories: Vec<(PathBuf, Option<String>)>,
     processor: Arc<ImportProcessor>,
 }
 
@@ -116,30 +361,118 @@ impl ImportWatcher {
     #[cfg(not(tarpaulin_include))]
     pub fn new(watch_path: PathBuf, repo: Arc<dyn ConversationRepository + Send + Sync>) -> Self {
         Self {
-            watch_path,
+            directories: vec![(watch_path, None)],
+            processor: Arc::new(ImportProcessor::new(repo)),
+        }
+    }
+
+    /// Watch a list of directories, each optionally mapped to the folder
+    /// its imports are stored in
+    pub fn with_directories(
+        repo: Arc<dyn ConversationRepository + Send + Sync>,
+        directories: Vec<(PathBuf, Option<String>)>,
+    ) -> Self {
+        Self {
+            directories,
             processor: Arc::new(ImportProcessor::new(repo)),
         }
     }
 
+    /// Also watch `watch_path`, storing its imports in `folder` if given
+    pub fn with_directory(mut self, watch_path: PathBuf, folder: Option<String>) -> Self {
+        self.directories.push((watch_path, folder));
+        self
+    }
+
+    /// Choose how conversations imported by this watcher are assigned IDs
+    pub fn with_import_mode(mut self, mode: ImportMode) -> Self {
+        self.processor = Arc::new((*self.processor).clone().with_import_mode(mode));
+        self
+    }
+
+    /// Derive labels with the LLM for imports that carry no title
+    pub fn with_label_derivation(mut self, llm_bridge: Arc<LlmBridgeClient>) -> Self {
+        self.processor = Arc::new((*self.processor).clone().with_label_derivation(llm_bridge));
+        self
+    }
+
+    /// Import at most `max` files at once
+    pub fn with_max_concurrency(mut self, max: usize) -> Self {
+        self.processor = Arc::new((*self.processor).clone().with_max_concurrency(max));
+        self
+    }
+
+    /// Rebuild the full-text index once per imported file
+    pub fn with_deferred_fts(mut self, defer: bool) -> Self {
+        self.processor = Arc::new((*self.processor).clone().with_deferred_fts(defer));
+        self
+    }
+
+    /// Announce imported conversations on the event bus
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.processor = Arc::new((*self.processor).clone().with_events(events));
+        self
+    }
+
     #[cfg(not(tarpaulin_include))]
     pub fn processor(&self) -> Arc<ImportProcessor> {
         self.processor.clone()
     }
 
-    /// Start watching the import directory for new files
+    /// The watched directories and their folder mappings
+    pub fn directories(&self) -> &[(PathBuf, Option<String>)] {
+        &self.directories
+    }
+
+    /// The processor for one directory; clones share the concurrency limit,
+    /// so all directories together stay within it
+    fn processor_for(&self, folder: &Option<String>) -> Arc<ImportProcessor> {
+        match folder {
+            Some(folder) => Arc::new(
+                (*self.processor)
+                    .clone()
+                    .with_default_folder(folder.clone()),
+            ),
+            None => self.processor.clone(),
+        }
+    }
+
+    /// Start watching every import directory for new files
     #[cfg(not(tarpaulin_include))]
     pub async fn watch(&self) -> Result<()> {
+        let mut watchers = tokio::task::JoinSet::new();
+        for (watch_path, folder) in &self.directories {
+            let watch_path = watch_path.clone();
+            let processor = self.processor_for(folder);
+            watchers.spawn(async move {
+                let result = Self::watch_directory(watch_path.clone(), processor).await;
+                if let Err(e) = &result {
+                    tracing::error!("❌ Watcher for {} failed: {}", watch_path.display(), e);
+                }
+                result
+            });
+        }
+
+        // A directory that fails to set up doesn't stop the others
+        let mut last_error = None;
+        while let Some(joined) = watchers.join_next().await {
+            if let Ok(Err(e)) = joined {
+                last_error = Some(e);
+            }
+        }
+
+        last_error.map_or(Ok(()), Err)
+    }
+
+    async fn watch_directory(watch_path: PathBuf, processor: Arc<ImportProcessor>) -> Result<()> {
         // Ensure directories exist
-        self.ensure_directories().await?;
+        Self::ensure_directories(&watch_path).await?;
 
         // Create channel for file events
         let (tx, mut rx) = mpsc::channel(100);
 
-        // Clone for async move
-        let processor = self.processor.clone();
-
         // ✅ CORRECT: Spawn as Tokio task, not std::thread
-        let watch_path = self.watch_path.clone();
+        let notify_path = watch_path.clone();
         tokio::spawn(async move {
             let tx_clone = tx.clone();
 
@@ -150,10 +483,8 @@ impl ImportWatcher {
                         if let Ok(event) = res {
                             if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
                                 for path in event.paths {
-                                    if matches!(
-                                        path.extension().and_then(|s| s.to_str()),
-                                        Some("json") | Some("xml") | Some("md") | Some("txt")
-                                    ) {
+                                    // A new subdirectory has no extension either
+                                    if path.is_file() && is_import_candidate(&path) {
                                         // blocking_send works in any context
                                         let _ = tx_clone.blocking_send(path);
                                     }
@@ -166,10 +497,10 @@ impl ImportWatcher {
                 .expect("Failed to create watcher");
 
                 watcher
-                    .watch(&watch_path, RecursiveMode::NonRecursive)
+                    .watch(&notify_path, RecursiveMode::NonRecursive)
                     .expect("Failed to watch directory");
 
-                tracing::info!("📁 Watching for imports in: {}", watch_path.display());
+                tracing::info!("📁 Watching for imports in: {}", notify_path.display());
 
                 // Keep blocking task alive
                 std::thread::park();
@@ -180,50 +511,69 @@ impl ImportWatcher {
         });
 
         // Process initial files already in directory
-        self.process_existing_files().await?;
+        Self::process_existing_files(&watch_path, &processor).await?;
 
         // Process new files as they arrive
+        let in_flight = InFlightPaths::default();
         while let Some(path) = rx.recv().await {
+            let Some(claim) = in_flight.claim(&path) else {
+                tracing::debug!("Already importing {}", path.display());
+                continue;
+            };
             tracing::info!("📥 New file detected: {}", path.display());
 
-            // Small delay to ensure file is fully written
-            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+            // The processor caps how many of these run at once
+            let processor = processor.clone();
+            tokio::spawn(async move {
+                let _claim = claim;
+                // Small delay to ensure file is fully written
+                tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
 
-            if let Err(e) = processor.process_file(&path).await {
-                tracing::error!("❌ Failed to process {}: {}", path.display(), e);
-            }
+                // A late event for a file that has already been imported
+                if !path.exists() {
+                    return;
+                }
+                if let Err(e) = processor.process_file(&path).await {
+                    tracing::error!("❌ Failed to process {}: {}", path.display(), e);
+                }
+            });
         }
 
         Ok(())
     }
 
+    /// Import the files already waiting in every watched directory, each
+    /// into its directory's folder
+    pub async fn import_existing_files(&self) -> Result<()> {
+        for (watch_path, folder) in &self.directories {
+            Self::ensure_directories(watch_path).await?;
+            Self::process_existing_files(watch_path, &self.processor_for(folder)).await?;
+        }
+        Ok(())
+    }
+
     #[cfg(not(tarpaulin_include))]
-    async fn ensure_directories(&self) -> Result<()> {
-        fs::create_dir_all(&self.watch_path).await?;
+    async fn ensure_directories(watch_path: &Path) -> Result<()> {
+        fs::create_dir_all(watch_path).await?;
 
-        let imported_path = self.watch_path.parent().unwrap().join("imported");
-        fs::create_dir_all(&imported_path).await?;
+        fs::create_dir_all(watch_path.join(IMPORTED_DIR)).await?;
 
         tracing::info!("✅ Import directories ready");
         Ok(())
     }
 
     #[cfg(not(tarpaulin_include))]
-    async fn process_existing_files(&self) -> Result<()> {
-        let mut entries = fs::read_dir(&self.watch_path).await?;
+    async fn process_existing_files(watch_path: &Path, processor: &ImportProcessor) -> Result<()> {
+        let mut entries = fs::read_dir(watch_path).await?;
 
         while let Some(entry) = entries.next_entry().await? {
             let path = entry.path();
 
-            if path.is_file() {
-                if let Some(ext) = path.extension() {
-                    if ext == "json" || ext == "xml" {
-                        tracing::info!("📄 Processing existing file: {}", path.display());
+            if path.is_file() && is_import_candidate(&path) {
+                tracing::info!("📄 Processing existing file: {}", path.display());
 
-                        if let Err(e) = self.processor.process_file(&path).await {
-                            tracing::error!("❌ Failed to process {}: {}", path.display(), e);
-                        }
-                    }
+                if let Err(e) = processor.process_file(&path).await {
+                    tracing::error!("❌ Failed to process {}: {}", path.display(), e);
                 }
             }
         }
@@ -239,11 +589,73 @@ impl ImportWatcher {
 #[derive(Clone)]
 pub struct ImportProcessor {
     repo: Arc<dyn ConversationRepository + Send + Sync>,
+    mode: ImportMode,
+    labeler: Option<Arc<LabelIntelligence>>,
+    /// Index each file's messages for FTS once at the end instead of per message
+    defer_fts: bool,
+    /// Shared by clones, so every copy of a processor counts toward one limit
+    permits: Arc<Semaphore>,
+    /// Folder for every imported conversation, instead of `/imports/<source>`
+    default_folder: Option<String>,
+    /// Where imported conversations are announced, so caches drop stale results
+    events: Option<EventBus>,
 }
 
 impl ImportProcessor {
     pub fn new(repo: Arc<dyn ConversationRepository + Send + Sync>) -> Self {
-        Self { repo }
+        Self {
+            repo,
+            mode: ImportMode::default(),
+            labeler: None,
+            defer_fts: false,
+            permits: Arc::new(Semaphore::new(DEFAULT_IMPORT_CONCURRENCY)),
+            default_folder: None,
+            events: None,
+        }
+    }
+
+    /// Import at most `max` files at once (minimum 1); callers beyond the
+    /// limit wait in `process_file` until a running import finishes
+    pub fn with_max_concurrency(mut self, max: usize) -> Self {
+        self.permits = Arc::new(Semaphore::new(max.max(1)));
+        self
+    }
+
+    /// Choose how imported conversations are assigned IDs
+    pub fn with_import_mode(mut self, mode: ImportMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Label conversations whose source has no title from their first
+    /// messages, keeping the filename or placeholder if the bridge is down
+    pub fn with_label_derivation(mut self, llm_bridge: Arc<LlmBridgeClient>) -> Self {
+        self.labeler = Some(Arc::new(LabelIntelligence::new(
+            self.repo.clone(),
+            llm_bridge,
+        )));
+        self
+    }
+
+    /// Store each file's messages without indexing them for full-text search,
+    /// then index everything stored meanwhile once the file is done
+    pub fn with_deferred_fts(mut self, defer: bool) -> Self {
+        self.defer_fts = defer;
+        self
+    }
+
+    /// Store every imported conversation in `folder` rather than the
+    /// per-source `/imports/<source>` folder. The folder is normalized like
+    /// API input; a blank one keeps the per-source folder.
+    pub fn with_default_folder(mut self, folder: impl Into<String>) -> Self {
+        self.default_folder = normalize_folder(&folder.into());
+        self
+    }
+
+    /// Publish a `Created` event for every imported conversation
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = Some(events);
+        self
     }
 
     pub fn repo(&self) -> Arc<dyn ConversationRepository> {
@@ -251,24 +663,59 @@ impl ImportProcessor {
     }
 
     pub async fn process_file(&self, path: &Path) -> Result<()> {
-        tracing::info!("🔍 Processing file: {}", path.display());
-
-        // Read file content
-        let content = fs::read_to_string(path)
+        let _permit = self
+            .permits
+            .acquire()
             .await
-            .context("Failed to read file")?;
+            .context("Import processor shut down")?;
+
+        tracing::info!("🔍 Processing file: {}", path.display());
 
-        // Detect format and parse
-        let conversations = self.parse_file(&content, path)?;
+        // Read file content, detect format and parse
+        let bytes = fs::read(path).await.context("Failed to read file")?;
+        let conversations = match path.extension().and_then(|s| s.to_str()) {
+            Some("zip") => self.parse_zip(&bytes)?,
+            Some(ext) if IMPORT_EXTENSIONS.contains(&ext) => {
+                let content = String::from_utf8(bytes).context("Failed to read file")?;
+                self.parse_file(&content, path)?
+            }
+            // No (or an unknown) extension: sniff the content
+            _ if bytes.starts_with(ZIP_MAGIC) => {
+                tracing::info!("🔎 Sniffed ZIP archive");
+                self.parse_zip(&bytes)?
+            }
+            _ => {
+                let content = String::from_utf8(bytes).with_context(|| {
+                    format!("Unknown export format for file: {}", path.display())
+                })?;
+                self.parse_file(&content, path)?
+            }
+        };
 
         tracing::info!("📊 Found {} conversations", conversations.len());
 
+        let deferred_after = if self.defer_fts {
+            Some(
+                self.repo
+                    .defer_fts_indexing()
+                    .await
+                    .context("Failed to defer FTS indexing")?,
+            )
+        } else {
+            None
+        };
+
         // Store each conversation
         let mut imported_count = 0;
         for conv in conversations {
             match self.import_conversation(conv).await {
                 Ok(id) => {
                     imported_count += 1;
+                    if let Some(events) = &self.events {
+                        events.publish(MemoryEvent::Created {
+                            conversation_id: id,
+                        });
+                    }
                     tracing::info!("✅ Imported conversation: {}", id);
                 }
                 Err(e) => {
@@ -277,6 +724,15 @@ impl ImportProcessor {
             }
         }
 
+        if let Some(after_rowid) = deferred_after {
+            let indexed = self
+                .repo
+                .index_fts_since(after_rowid)
+                .await
+                .context("Failed to index imported messages for FTS")?;
+            tracing::info!("🔎 Indexed {} imported messages for FTS", indexed);
+        }
+
         // Move processed file
         self.move_to_imported(path).await?;
 
@@ -310,7 +766,7 @@ impl ImportProcessor {
             || content.trim_start().starts_with("<conversation")
         {
             tracing::info!("🧠 Detected Claude export format");
-            return self.parse_claude_export(content);
+            return self.parse_claude_export(content, path);
         }
 
         // Try Claude JSON format
@@ -323,27 +779,90 @@ impl ImportProcessor {
                 .collect());
         }
 
+        // Try our own memory_export JSON, bare or as the saved tool response
+        let sekha_export = serde_json::from_str::<SekhaExport>(content).or_else(|_| {
+            serde_json::from_str::<SekhaExportResponse>(content).map(|response| response.data)
+        });
+        if let Ok(export) = sekha_export {
+            tracing::info!("🗄️ Detected Sekha export format");
+            return Ok(vec![self.parse_sekha_export(export)]);
+        }
+
         // Try Markdown format (ChatGPT exports) - ADD THIS
         if path.extension().and_then(|s| s.to_str()) == Some("md") {
             tracing::info!("📝 Detected Markdown export format");
-            let filename = path.file_name().unwrap().to_str().unwrap();
-            return Ok(vec![self.parse_markdown_export(content, filename)?]);
+            return Ok(vec![self.parse_markdown_export(content, path)?]);
         }
 
         // Try TXT format (custom) - ADD THIS
         if path.extension().and_then(|s| s.to_str()) == Some("txt") {
             tracing::info!("📄 Detected TXT export format");
-            let filename = path.file_name().unwrap().to_str().unwrap();
-            return Ok(vec![self.parse_txt_export(content, filename)?]);
+            return Ok(vec![self.parse_txt_export(content, path)?]);
+        }
+
+        // No recognised extension: fall back to a plain-text transcript, as
+        // long as it holds at least one message
+        let extension = path.extension().and_then(|s| s.to_str());
+        if !extension.is_some_and(|ext| IMPORT_EXTENSIONS.contains(&ext)) {
+            let parsed = self.parse_txt_export(content, path)?;
+            if !parsed.messages.is_empty() {
+                tracing::info!("🔎 Sniffed plain-text transcript");
+                return Ok(vec![parsed]);
+            }
         }
 
         anyhow::bail!("Unknown export format for file: {}", path.display())
     }
 
+    /// Parse the known export files inside a ZIP archive, extracted in memory
+    fn parse_zip(&self, bytes: &[u8]) -> Result<Vec<ParsedConversation>> {
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes))
+            .context("Failed to open ZIP archive")?;
+
+        let mut conversations = Vec::new();
+        let mut found_export = false;
+        for index in 0..archive.len() {
+            let mut entry = archive.by_index(index)?;
+            let name = entry.name().to_string();
+            let file_name = Path::new(&name)
+                .file_name()
+                .and_then(|s| s.to_str())
+                .unwrap_or_default();
+
+            if entry.is_dir() || !ZIP_EXPORT_FILES.contains(&file_name) {
+                tracing::debug!("Skipping unsupported ZIP entry: {}", name);
+                continue;
+            }
+            found_export = true;
+
+            tracing::info!("🗜️ Reading {} from ZIP archive", name);
+            let mut content = String::new();
+            entry
+                .read_to_string(&mut content)
+                .with_context(|| format!("Failed to read {} from ZIP archive", name))?;
+
+            match self.parse_file(&content, Path::new(&name)) {
+                Ok(parsed) => conversations.extend(parsed),
+                Err(e) => tracing::warn!("⚠️ Skipping {} in ZIP archive: {}", name, e),
+            }
+        }
+
+        if !found_export {
+            anyhow::bail!("No conversation export found in ZIP archive");
+        }
+        Ok(conversations)
+    }
+
     fn parse_chatgpt_export(&self, export: ChatGptExport) -> Result<ParsedConversation> {
+        let has_title = export.title.is_some();
         let title = export
             .title
             .unwrap_or_else(|| "Untitled ChatGPT Conversation".to_string());
+        let key = source_key(
+            export.conversation_id.as_deref(),
+            &title,
+            export.create_time.map(|ts| ts.to_string()),
+        );
 
         // Build conversation tree from mapping
         let mut messages = Vec::new();
@@ -379,10 +898,12 @@ impl ImportProcessor {
 
         Ok(ParsedConversation {
             title,
+            has_title,
             messages,
             created_at,
             updated_at,
             source: ImportSource::ChatGPT,
+            source_key: key,
         })
     }
 
@@ -395,27 +916,52 @@ impl ImportProcessor {
         if let Some(node) = mapping.get(node_id) {
             // Add message if it exists and has content
             if let Some(msg) = &node.message {
-                if let Some(parts) = &msg.content.parts {
-                    if !parts.is_empty() {
-                        let content = parts.join("\n");
-
-                        // Filter out empty messages
-                        if !content.trim().is_empty() {
-                            let timestamp = msg
-                                .create_time
-                                .and_then(|ts| {
-                                    chrono::DateTime::from_timestamp(ts as i64, 0)
-                                        .map(|dt| dt.naive_utc())
-                                })
-                                .unwrap_or_else(|| chrono::Utc::now().naive_utc());
-
-                            messages.push(ParsedMessage {
-                                role: msg.author.role.clone(),
-                                content,
-                                timestamp,
-                            });
-                        }
+                let (mut content, parts) = msg.content.flatten();
+                let attachments = chatgpt_attachments(&parts, msg.metadata.as_ref());
+
+                // Skip truly empty messages; keep attachment-only ones
+                if !content.trim().is_empty() || !attachments.is_empty() {
+                    if content.trim().is_empty() {
+                        content = format!("[{} attachment(s)]", attachments.len());
+                    }
+
+                    let timestamp = msg
+                        .create_time
+                        .and_then(|ts| {
+                            chrono::DateTime::from_timestamp(ts as i64, 0).map(|dt| dt.naive_utc())
+                        })
+                        .unwrap_or_else(|| chrono::Utc::now().naive_utc());
+
+                    let mut metadata = serde_json::Map::new();
+                    metadata.insert(
+                        "content_type".to_string(),
+                        serde_json::json!(msg.content.content_type),
+                    );
+                    metadata.insert(
+                        "author".to_string(),
+                        serde_json::json!({
+                            "role": msg.author.role,
+                            "name": msg.author.name,
+                            "metadata": msg.author.metadata,
+                        }),
+                    );
+                    if let Some(language) = &msg.content.language {
+                        metadata.insert("language".to_string(), serde_json::json!(language));
                     }
+                    if !attachments.is_empty() {
+                        metadata
+                            .insert(ATTACHMENTS_KEY.to_string(), serde_json::json!(attachments));
+                    }
+                    if let Some(message_metadata) = &msg.metadata {
+                        metadata.insert("chatgpt_metadata".to_string(), message_metadata.clone());
+                    }
+
+                    messages.push(ParsedMessage {
+                        role: msg.author.role.clone(),
+                        content,
+                        timestamp,
+                        metadata,
+                    });
                 }
             }
 
@@ -426,7 +972,7 @@ impl ImportProcessor {
         }
     }
 
-    fn parse_claude_export(&self, content: &str) -> Result<Vec<ParsedConversation>> {
+    fn parse_claude_export(&self, content: &str, path: &Path) -> Result<Vec<ParsedConversation>> {
         // Simple XML parser for Claude format
         // Note: For production, use a proper XML parser like quick-xml
 
@@ -435,14 +981,16 @@ impl ImportProcessor {
         // Basic XML parsing (simplified)
         if content.contains("<conversation>") {
             // Parse single conversation
-            let title = self
-                .extract_xml_tag(content, "title")
-                .unwrap_or_else(|| "Untitled Claude Conversation".to_string());
+            let source_title = self.extract_xml_tag(content, "title");
+            let has_title = source_title.is_some();
+            let title = source_title.unwrap_or_else(|| "Untitled Claude Conversation".to_string());
 
             let messages = self.extract_claude_messages_xml(content);
 
             conversations.push(ParsedConversation {
+                source_key: file_source_key(path),
                 title,
+                has_title,
                 messages,
                 created_at: chrono::Utc::now().naive_utc(),
                 updated_at: chrono::Utc::now().naive_utc(),
@@ -454,9 +1002,11 @@ impl ImportProcessor {
     }
 
     fn parse_claude_conversation(&self, conv: ClaudeConversation) -> Result<ParsedConversation> {
+        let has_title = conv.title.is_some();
         let title = conv
             .title
             .unwrap_or_else(|| "Untitled Claude Conversation".to_string());
+        let key = source_key(None, &title, conv.created_at.clone());
 
         let messages: Vec<ParsedMessage> = conv
             .messages
@@ -468,10 +1018,27 @@ impl ImportProcessor {
                     .map(|dt| dt.naive_utc())
                     .unwrap_or_else(|| chrono::Utc::now().naive_utc());
 
+                let attachments: Vec<Attachment> = msg
+                    .attachments
+                    .into_iter()
+                    .chain(msg.files)
+                    .map(|file| Attachment {
+                        filename: file.file_name,
+                        mime: file.file_type,
+                        url: None,
+                    })
+                    .filter(|a| *a != Attachment::default())
+                    .collect();
+                let mut metadata = serde_json::Map::new();
+                if !attachments.is_empty() {
+                    metadata.insert(ATTACHMENTS_KEY.to_string(), serde_json::json!(attachments));
+                }
+
                 ParsedMessage {
                     role: msg.role,
                     content: msg.content,
                     timestamp,
+                    metadata,
                 }
             })
             .collect();
@@ -490,14 +1057,60 @@ impl ImportProcessor {
 
         Ok(ParsedConversation {
             title,
+            has_title,
             messages,
             created_at,
             updated_at,
             source: ImportSource::Claude,
+            source_key: key,
         })
     }
 
-    fn parse_markdown_export(&self, content: &str, filename: &str) -> Result<ParsedConversation> {
+    fn parse_sekha_export(&self, export: SekhaExport) -> ParsedConversation {
+        let conv = export.conversation;
+        let has_title = conv.label.is_some();
+        let title = conv
+            .label
+            .unwrap_or_else(|| "Untitled Sekha Conversation".to_string());
+        let key = source_key(conv.id.as_deref(), &title, conv.created_at.clone());
+
+        let created_at = conv
+            .created_at
+            .as_deref()
+            .and_then(parse_export_time)
+            .unwrap_or_else(|| chrono::Utc::now().naive_utc());
+        let updated_at = conv
+            .updated_at
+            .as_deref()
+            .and_then(parse_export_time)
+            .unwrap_or(created_at);
+
+        let messages = export
+            .messages
+            .into_iter()
+            .map(|msg| ParsedMessage {
+                role: msg.role,
+                content: msg.content,
+                timestamp: msg.timestamp.unwrap_or(created_at),
+                metadata: match msg.metadata {
+                    Some(serde_json::Value::Object(metadata)) => metadata,
+                    _ => serde_json::Map::new(),
+                },
+            })
+            .collect();
+
+        ParsedConversation {
+            title,
+            has_title,
+            messages,
+            created_at,
+            updated_at,
+            source: ImportSource::Sekha,
+            source_key: key,
+        }
+    }
+
+    fn parse_markdown_export(&self, content: &str, path: &Path) -> Result<ParsedConversation> {
         let mut messages = Vec::new();
         let mut current_role = String::new();
         let mut current_content = String::new();
@@ -516,6 +1129,7 @@ impl ImportProcessor {
                         role: current_role.clone(),
                         content: current_content.trim().to_string(),
                         timestamp: chrono::Utc::now().naive_utc(),
+                        metadata: serde_json::Map::new(),
                     });
                 }
 
@@ -539,13 +1153,20 @@ impl ImportProcessor {
                 role: current_role,
                 content: current_content.trim().to_string(),
                 timestamp: chrono::Utc::now().naive_utc(),
+                metadata: serde_json::Map::new(),
             });
         }
 
+        let filename = path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or("import");
         let title = filename.strip_suffix(".md").unwrap_or(filename).to_string();
 
         Ok(ParsedConversation {
+            source_key: file_source_key(path),
             title,
+            has_title: false,
             messages,
             created_at: chrono::Utc::now().naive_utc(),
             updated_at: chrono::Utc::now().naive_utc(),
@@ -553,7 +1174,7 @@ impl ImportProcessor {
         })
     }
 
-    fn parse_txt_export(&self, content: &str, filename: &str) -> Result<ParsedConversation> {
+    fn parse_txt_export(&self, content: &str, path: &Path) -> Result<ParsedConversation> {
         // Simple line-by-line parser for custom format
         // Expected format: "User: message" or "Assistant: message"
         let mut messages = Vec::new();
@@ -567,6 +1188,7 @@ impl ImportProcessor {
                         role: current_role.clone(),
                         content: current_content.trim().to_string(),
                         timestamp: chrono::Utc::now().naive_utc(),
+                        metadata: serde_json::Map::new(),
                     });
                 }
                 current_role = "user".to_string();
@@ -581,6 +1203,7 @@ impl ImportProcessor {
                         role: current_role.clone(),
                         content: current_content.trim().to_string(),
                         timestamp: chrono::Utc::now().naive_utc(),
+                        metadata: serde_json::Map::new(),
                     });
                 }
                 current_role = "assistant".to_string();
@@ -601,16 +1224,23 @@ impl ImportProcessor {
                 role: current_role,
                 content: current_content.trim().to_string(),
                 timestamp: chrono::Utc::now().naive_utc(),
+                metadata: serde_json::Map::new(),
             });
         }
 
+        let filename = path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or("import");
         let title = filename
             .strip_suffix(".txt")
             .unwrap_or(filename)
             .to_string();
 
         Ok(ParsedConversation {
+            source_key: file_source_key(path),
             title,
+            has_title: false,
             messages,
             created_at: chrono::Utc::now().naive_utc(),
             updated_at: chrono::Utc::now().naive_utc(),
@@ -654,6 +1284,7 @@ impl ImportProcessor {
                         role,
                         content,
                         timestamp: chrono::Utc::now().naive_utc(),
+                        metadata: serde_json::Map::new(),
                     });
                 }
             }
@@ -663,34 +1294,45 @@ impl ImportProcessor {
     }
 
     async fn import_conversation(&self, parsed: ParsedConversation) -> Result<Uuid> {
+        let label = self.label_for(&parsed).await;
+        // Fresh imports leave the ID to the repository's ID scheme
+        let id = match self.mode {
+            ImportMode::Fresh => None,
+            ImportMode::Deterministic => Some(Self::deterministic_id(&parsed)),
+        };
+
         let messages: Vec<NewMessage> = parsed
             .messages
             .into_iter()
-            .map(|msg| NewMessage {
-                role: msg.role,
-                content: msg.content,
-                timestamp: msg.timestamp,
-                metadata: serde_json::json!({
-                    "source": match parsed.source {
-                        ImportSource::ChatGPT => "chatgpt",
-                        ImportSource::Claude => "claude",
-                        ImportSource::Unknown => "unknown",
-                    },
-                    "imported_at": chrono::Utc::now().to_rfc3339(),
-                }),
+            .map(|msg| {
+                let mut metadata = msg.metadata;
+                metadata.insert(
+                    "source".to_string(),
+                    serde_json::json!(parsed.source.as_str()),
+                );
+                metadata.insert(
+                    "imported_at".to_string(),
+                    serde_json::json!(chrono::Utc::now().to_rfc3339()),
+                );
+
+                NewMessage {
+                    role: msg.role,
+                    content: msg.content,
+                    timestamp: msg.timestamp,
+                    metadata: serde_json::Value::Object(metadata),
+                }
             })
             .collect();
 
         let word_count: i32 = messages.iter().map(|m| m.content.len() as i32).sum();
 
         let new_conv = NewConversation {
-            id: Some(Uuid::new_v4()),
-            label: parsed.title,
-            folder: match parsed.source {
-                ImportSource::ChatGPT => "/imports/chatgpt".to_string(),
-                ImportSource::Claude => "/imports/claude".to_string(),
-                ImportSource::Unknown => "/imports/unknown".to_string(),
-            },
+            id,
+            label,
+            folder: self
+                .default_folder
+                .clone()
+                .unwrap_or_else(|| format!("/imports/{}", parsed.source.as_str())),
             status: "active".to_string(),
             importance_score: Some(5),
             word_count,
@@ -700,19 +1342,51 @@ impl ImportProcessor {
             messages,
         };
 
-        self.repo
-            .create_with_messages(new_conv)
-            .await
-            .context("Failed to store conversation in database")
+        // Upsert: a re-import replaces the messages the previous run stored
+        let stored = match self.mode {
+            ImportMode::Fresh => self.repo.create_with_messages(new_conv).await,
+            ImportMode::Deterministic => self.repo.upsert_import(new_conv).await,
+        };
+        stored.context("Failed to store conversation in database")
     }
 
+    /// The source's title, or a derived one when it has none and derivation
+    /// is enabled
+    async fn label_for(&self, parsed: &ParsedConversation) -> String {
+        let Some(labeler) = self.labeler.as_ref().filter(|_| !parsed.has_title) else {
+            return parsed.title.clone();
+        };
+
+        let messages: Vec<String> = parsed
+            .messages
+            .iter()
+            .map(|m| format!("{}: {}", m.role, m.content))
+            .collect();
+        match labeler.derive_label(&messages).await {
+            Some(label) => {
+                tracing::info!("🏷️ Derived label '{}' for '{}'", label, parsed.title);
+                label
+            }
+            None => parsed.title.clone(),
+        }
+    }
+
+    /// UUIDv5 from the source and the conversation's identity within it
+    fn deterministic_id(parsed: &ParsedConversation) -> Uuid {
+        let name = format!("{}:{}", parsed.source.as_str(), parsed.source_key);
+        Uuid::new_v5(&IMPORT_ID_NAMESPACE, name.as_bytes())
+    }
+
+    /// Move `path` into its watched directory's `imported/` subdirectory
     async fn move_to_imported(&self, path: &Path) -> Result<()> {
-        let imported_dir = path.parent().unwrap().parent().unwrap().join("imported");
+        let (Some(watch_dir), Some(filename)) = (path.parent(), path.file_name()) else {
+            anyhow::bail!("{} is not a file in a directory", path.display());
+        };
+        let imported_dir = watch_dir.join(IMPORTED_DIR);
         fs::create_dir_all(&imported_dir).await?;
 
-        let filename = path.file_name().unwrap();
         let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
-        let new_filename = format!("{}_{}", timestamp, filename.to_str().unwrap());
+        let new_filename = format!("{}_{}", timestamp, filename.to_string_lossy());
         let new_path = imported_dir.join(new_filename);
 
         fs::rename(path, &new_path).await?;
@@ -739,10 +1413,12 @@ pub async fn run_import_watcher(repo: Arc<dyn ConversationRepository + Send + Sy
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::internal::{Conversation, Message};
-    use crate::storage::repository::{ConversationRepository, RepositoryError, SearchResult};
+    use crate::models::internal::{Conversation, ConversationPatch, Message, StoredSummary};
+    use crate::storage::repository::{
+        ConversationRepository, EmbeddingReconciliation, RepositoryError, SearchResult,
+    };
     use sea_orm::DatabaseConnection;
-    use serde_json::Value;
+    use serde_json::{json, Value};
     use std::sync::Arc;
     use uuid::Uuid;
 
@@ -780,6 +1456,128 @@ mod tests {
         assert_eq!(export.title.unwrap(), "Test Conversation");
     }
 
+    #[test]
+    fn test_parse_chatgpt_multipart_and_non_text_content() {
+        let processor = ImportProcessor::new(Arc::new(MockRepo));
+        let json = r#"{
+            "title": "Rich Content",
+            "mapping": {
+                "root": {"id": "root", "message": null, "parent": null, "children": ["m1"]},
+                "m1": {
+                    "id": "m1",
+                    "message": {
+                        "id": "m1",
+                        "author": {"role": "user", "name": null, "metadata": {"real_author": "user:abc"}},
+                        "content": {
+                            "content_type": "multimodal_text",
+                            "parts": [
+                                {"content_type": "image_asset_pointer", "asset_pointer": "file-service://img1"},
+                                "What does this chart show?",
+                                "Focus on the trend."
+                            ]
+                        }
+                    },
+                    "parent": "root",
+                    "children": ["m2"]
+                },
+                "m2": {
+                    "id": "m2",
+                    "message": {
+                        "id": "m2",
+                        "author": {"role": "assistant"},
+                        "content": {"content_type": "code", "language": "python", "text": "print(1 + 1)"},
+                        "metadata": {"model_slug": "gpt-4o"}
+                    },
+                    "parent": "m1",
+                    "children": ["m3"]
+                },
+                "m3": {
+                    "id": "m3",
+                    "message": {
+                        "id": "m3",
+                        "author": {"role": "tool", "name": "python"},
+                        "content": {"content_type": "execution_output", "text": "   "}
+                    },
+                    "parent": "m2",
+                    "children": []
+                }
+            }
+        }"#;
+
+        let export: ChatGptExport = serde_json::from_str(json).unwrap();
+        let parsed = processor.parse_chatgpt_export(export).unwrap();
+
+        // The whitespace-only tool output is skipped
+        assert_eq!(parsed.messages.len(), 2);
+
+        let question = &parsed.messages[0];
+        assert_eq!(
+            question.content,
+            "What does this chart show?\nFocus on the trend."
+        );
+        assert_eq!(question.metadata["content_type"], "multimodal_text");
+        assert_eq!(
+            question.metadata["author"]["metadata"]["real_author"],
+            "user:abc"
+        );
+        assert_eq!(
+            question.metadata["attachments"][0]["url"],
+            "file-service://img1"
+        );
+
+        let code = &parsed.messages[1];
+        assert_eq!(code.content, "print(1 + 1)");
+        assert_eq!(code.metadata["content_type"], "code");
+        assert_eq!(code.metadata["language"], "python");
+        assert_eq!(code.metadata["chatgpt_metadata"]["model_slug"], "gpt-4o");
+    }
+
+    #[test]
+    fn test_attachment_references_from_exports() {
+        let parts = vec![
+            json!({"content_type": "image_asset_pointer", "asset_pointer": "file-service://file-img", "size_bytes": 1024}),
+            json!({"content_type": "audio_transcription", "text": "hi"}),
+        ];
+        let metadata = json!({"attachments": [
+            {"id": "file-img", "name": "chart.png", "mime_type": "image/png"},
+            {"id": "file-doc", "name": "report.pdf", "mime_type": "application/pdf"}
+        ]});
+
+        let attachments = chatgpt_attachments(&parts, Some(&metadata));
+        assert_eq!(
+            attachments,
+            vec![
+                Attachment {
+                    filename: Some("chart.png".to_string()),
+                    mime: Some("image/png".to_string()),
+                    url: Some("file-service://file-img".to_string()),
+                },
+                Attachment {
+                    filename: Some("report.pdf".to_string()),
+                    mime: Some("application/pdf".to_string()),
+                    url: None,
+                },
+            ]
+        );
+
+        let processor = ImportProcessor::new(Arc::new(MockRepo));
+        let claude = r#"{"conversations": [{"title": "Files", "messages": [
+            {"role": "user", "content": "See attached", "timestamp": null,
+             "attachments": [{"file_name": "notes.txt", "file_type": "text/plain", "extracted_content": "secret"}],
+             "files": [{"file_name": "photo.jpg"}]}
+        ]}]}"#;
+        let parsed = processor
+            .parse_file(claude, Path::new("claude.json"))
+            .unwrap();
+        let metadata = &parsed[0].messages[0].metadata;
+        assert_eq!(metadata["attachments"][0]["filename"], "notes.txt");
+        assert_eq!(metadata["attachments"][0]["mime"], "text/plain");
+        assert_eq!(metadata["attachments"][1]["filename"], "photo.jpg");
+        assert!(!Value::Object(metadata.clone())
+            .to_string()
+            .contains("secret"));
+    }
+
     #[test]
     fn test_extract_xml_tag() {
         let processor = ImportProcessor::new(Arc::new(MockRepo));
@@ -815,12 +1613,15 @@ mod tests {
                     id: "msg1".to_string(),
                     author: ChatGptAuthor {
                         role: "user".to_string(),
+                        ..Default::default()
                     },
                     create_time: Some(1703073600.0),
                     content: ChatGptContent {
                         content_type: "text".to_string(),
-                        parts: Some(vec!["Branch 1".to_string()]),
+                        parts: Some(vec![Value::from("Branch 1")]),
+                        ..Default::default()
                     },
+                    metadata: None,
                 }),
                 parent: Some("root".to_string()),
                 children: vec![],
@@ -835,12 +1636,15 @@ mod tests {
                     id: "msg2".to_string(),
                     author: ChatGptAuthor {
                         role: "assistant".to_string(),
+                        ..Default::default()
                     },
                     create_time: Some(1703073700.0),
                     content: ChatGptContent {
                         content_type: "text".to_string(),
-                        parts: Some(vec!["Branch 2".to_string()]),
+                        parts: Some(vec![Value::from("Branch 2")]),
+                        ..Default::default()
                     },
+                    metadata: None,
                 }),
                 parent: Some("root".to_string()),
                 children: vec![],
@@ -890,7 +1694,7 @@ mod tests {
     No role markers here
     Just plain text"#;
 
-        let result = processor.parse_markdown_export(content, "no_roles.md");
+        let result = processor.parse_markdown_export(content, Path::new("no_roles.md"));
         assert!(result.is_ok());
 
         let conv = result.unwrap();
@@ -923,6 +1727,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_extensionless_files_are_import_candidates() {
+        assert!(is_import_candidate(Path::new("/import/export.json")));
+        assert!(is_import_candidate(Path::new("/import/conversations")));
+        assert!(!is_import_candidate(Path::new("/import/.DS_Store")));
+        assert!(!is_import_candidate(Path::new("/import/report.pdf")));
+
+        // Without an extension, a transcript falls back to plain text
+        let processor = ImportProcessor::new(Arc::new(MockRepo));
+        let parsed = processor
+            .parse_file(
+                "User: hi\nAssistant: hello",
+                Path::new("/import/transcript"),
+            )
+            .unwrap();
+        assert_eq!(parsed[0].messages.len(), 2);
+    }
+
+    #[test]
+    fn test_in_flight_paths_claim_each_path_once() {
+        let in_flight = InFlightPaths::default();
+        let path = Path::new("/import/export.json");
+
+        // The create and modify events for one drop share a single import
+        let claim = in_flight.claim(path).unwrap();
+        assert!(in_flight.claim(path).is_none());
+        assert!(in_flight.claim(Path::new("/import/other.json")).is_some());
+
+        // Once that import is done, a new drop of the same name is picked up
+        drop(claim);
+        assert!(in_flight.claim(path).is_some());
+    }
+
     #[test]
     fn test_parse_file_empty_content() {
         let processor = ImportProcessor::new(Arc::new(MockRepo));
@@ -964,7 +1801,7 @@ mod tests {
             </conversation>
         "#;
 
-        let result = processor.parse_claude_export(xml);
+        let result = processor.parse_claude_export(xml, Path::new("export.xml"));
         assert!(result.is_ok());
 
         let conversations = result.unwrap();
@@ -988,7 +1825,7 @@ mod tests {
             </root>
         "#;
 
-        let result = processor.parse_claude_export(xml);
+        let result = processor.parse_claude_export(xml, Path::new("export.xml"));
         assert!(result.is_ok());
 
         let conversations = result.unwrap();
@@ -1012,7 +1849,7 @@ mod tests {
             </conversation>
         "#;
 
-        let result = processor.parse_claude_export(xml);
+        let result = processor.parse_claude_export(xml, Path::new("export.xml"));
         assert!(result.is_ok());
 
         let conversations = result.unwrap();
@@ -1034,7 +1871,7 @@ mod tests {
             </conversation>
         "#;
 
-        let result = processor.parse_claude_export(xml);
+        let result = processor.parse_claude_export(xml, Path::new("export.xml"));
         assert!(result.is_ok());
 
         let conversations = result.unwrap();
@@ -1181,14 +2018,14 @@ mod tests {
         let processor = ImportProcessor::new(Arc::new(MockRepo));
 
         // Empty content
-        let result = processor.parse_txt_export("", "empty.txt");
+        let result = processor.parse_txt_export("", Path::new("empty.txt"));
         assert!(result.is_ok());
         assert_eq!(result.unwrap().messages.len(), 0);
 
         // Only malformed lines
         let result = processor.parse_txt_export(
             "No colon here\nAlso no colon\nStill no colon",
-            "malformed.txt",
+            Path::new("malformed.txt"),
         );
         assert!(result.is_ok());
         assert_eq!(result.unwrap().messages.len(), 0);
@@ -1210,10 +2047,53 @@ mod tests {
             Ok(conv.id.unwrap_or_else(Uuid::new_v4))
         }
 
+        async fn create_with_message_ids(
+            &self,
+            conv: NewConversation,
+        ) -> Result<crate::storage::repository::CreatedConversation, RepositoryError> {
+            Ok(crate::storage::repository::CreatedConversation {
+                id: conv.id.unwrap_or_else(Uuid::new_v4),
+                message_ids: conv.messages.iter().map(|_| Uuid::new_v4()).collect(),
+                word_count: conv.word_count,
+            })
+        }
+
+        async fn create_with_tags(
+            &self,
+            conv: NewConversation,
+            _tags: Vec<String>,
+        ) -> Result<crate::storage::repository::CreatedConversation, RepositoryError> {
+            self.create_with_message_ids(conv).await
+        }
+
+        async fn upsert_import(&self, conv: NewConversation) -> Result<Uuid, RepositoryError> {
+            self.create_with_messages(conv).await
+        }
+
+        async fn append_messages(
+            &self,
+            _conversation_id: Uuid,
+            messages: Vec<NewMessage>,
+        ) -> Result<crate::storage::repository::AppendedMessages, RepositoryError> {
+            Ok(crate::storage::repository::AppendedMessages {
+                message_ids: messages.iter().map(|_| Uuid::new_v4()).collect(),
+                message_count: messages.len() as u64,
+                word_count: 0,
+            })
+        }
+
         async fn delete(&self, _id: Uuid) -> Result<(), RepositoryError> {
             Ok(())
         }
 
+        async fn delete_many(&self, ids: Vec<Uuid>) -> Result<(Vec<Uuid>, usize), RepositoryError> {
+            Ok((ids, 0))
+        }
+
+        async fn delete_messages(&self, _message_ids: Vec<Uuid>) -> Result<u64, RepositoryError> {
+            Ok(0)
+        }
+
         async fn count_by_label(&self, _label: &str) -> Result<u64, RepositoryError> {
             Ok(0)
         }
@@ -1226,6 +2106,15 @@ mod tests {
             Ok(0)
         }
 
+        async fn count_with_filters(
+            &self,
+            _label: Option<String>,
+            _folder: Option<String>,
+            _status: Option<String>,
+        ) -> Result<u64, RepositoryError> {
+            Ok(0)
+        }
+
         async fn find_by_id(&self, _id: Uuid) -> Result<Option<Conversation>, RepositoryError> {
             Ok(None)
         }
@@ -1239,6 +2128,14 @@ mod tests {
             Ok(Vec::new())
         }
 
+        async fn find_by_label_fuzzy(
+            &self,
+            _label: &str,
+            _limit: u64,
+        ) -> Result<Vec<Conversation>, RepositoryError> {
+            Ok(Vec::new())
+        }
+
         async fn get_message_list(
             &self,
             _conversation_id: Uuid,
@@ -1257,6 +2154,32 @@ mod tests {
             Ok(None)
         }
 
+        async fn update_message_metadata(
+            &self,
+            message_id: Uuid,
+            _metadata: serde_json::Value,
+            _merge: bool,
+        ) -> Result<Message, RepositoryError> {
+            Err(RepositoryError::NotFound(message_id.to_string()))
+        }
+
+        async fn compact_messages(
+            &self,
+            _conversation_id: Uuid,
+            _message_ids: Vec<Uuid>,
+            _summary: NewMessage,
+        ) -> Result<u64, RepositoryError> {
+            Ok(0)
+        }
+
+        async fn move_message(
+            &self,
+            message_id: Uuid,
+            _target_conversation_id: Uuid,
+        ) -> Result<Message, RepositoryError> {
+            Err(RepositoryError::NotFound(message_id.to_string()))
+        }
+
         async fn find_recent_messages(
             &self,
             _conversation_id: Uuid,
@@ -1268,6 +2191,7 @@ mod tests {
         async fn find_with_filters(
             &self,
             _filter: Option<String>,
+            _has_role: Option<String>,
             _limit: usize,
             _offset: u32,
         ) -> Result<(Vec<Conversation>, u64), RepositoryError> {
@@ -1283,6 +2207,18 @@ mod tests {
             Ok(())
         }
 
+        async fn find_by_ids(&self, _ids: Vec<Uuid>) -> Result<Vec<Conversation>, RepositoryError> {
+            Ok(vec![])
+        }
+
+        async fn find_updated_before(
+            &self,
+            _ids: Vec<Uuid>,
+            _cutoff: chrono::NaiveDateTime,
+        ) -> Result<Vec<Uuid>, RepositoryError> {
+            Ok(vec![])
+        }
+
         async fn update_status(&self, _id: Uuid, _status: &str) -> Result<(), RepositoryError> {
             Ok(())
         }
@@ -1291,6 +2227,72 @@ mod tests {
             Ok(())
         }
 
+        async fn set_context_pinned(
+            &self,
+            _id: Uuid,
+            _pinned: bool,
+        ) -> Result<(), RepositoryError> {
+            Ok(())
+        }
+
+        async fn touch(&self, _id: Uuid) -> Result<(), RepositoryError> {
+            Ok(())
+        }
+
+        async fn record_access(
+            &self,
+            _id: Uuid,
+            _at: chrono::NaiveDateTime,
+        ) -> Result<(), RepositoryError> {
+            Ok(())
+        }
+
+        async fn increment_session(&self, _id: Uuid) -> Result<(), RepositoryError> {
+            Ok(())
+        }
+
+        async fn apply_patch(
+            &self,
+            id: Uuid,
+            _patch: ConversationPatch,
+        ) -> Result<Conversation, RepositoryError> {
+            Err(RepositoryError::NotFound(id.to_string()))
+        }
+
+        async fn find_history(
+            &self,
+            _id: Uuid,
+        ) -> Result<Vec<crate::models::internal::ConversationChange>, RepositoryError> {
+            Ok(vec![])
+        }
+
+        async fn bulk_move(
+            &self,
+            _ids: Vec<Uuid>,
+            _folder: &str,
+            _dry_run: bool,
+        ) -> Result<Vec<Uuid>, RepositoryError> {
+            Ok(vec![])
+        }
+
+        async fn bulk_update_status(
+            &self,
+            _ids: Vec<Uuid>,
+            _status: &str,
+            _dry_run: bool,
+        ) -> Result<Vec<Uuid>, RepositoryError> {
+            Ok(vec![])
+        }
+
+        async fn rename_folder(
+            &self,
+            _from: &str,
+            _to: &str,
+            _dry_run: bool,
+        ) -> Result<Vec<Uuid>, RepositoryError> {
+            Ok(vec![])
+        }
+
         async fn count_messages_in_conversation(
             &self,
             _conversation_id: Uuid,
@@ -1298,14 +2300,35 @@ mod tests {
             Ok(0)
         }
 
+        async fn claim_summary_checkpoint(
+            &self,
+            _conversation_id: Uuid,
+            _every: u64,
+        ) -> Result<Option<u64>, RepositoryError> {
+            Ok(None)
+        }
+
         async fn full_text_search(
             &self,
             _query: &str,
             _limit: usize,
+            _after: Option<chrono::NaiveDateTime>,
+            _before: Option<chrono::NaiveDateTime>,
+            _conversation_id: Option<Uuid>,
         ) -> Result<Vec<Message>, RepositoryError> {
             Ok(Vec::new())
         }
 
+        async fn full_text_search_count(
+            &self,
+            _query: &str,
+            _after: Option<chrono::NaiveDateTime>,
+            _before: Option<chrono::NaiveDateTime>,
+            _conversation_id: Option<Uuid>,
+        ) -> Result<u64, RepositoryError> {
+            Ok(0)
+        }
+
         async fn semantic_search(
             &self,
             _query: &str,
@@ -1315,6 +2338,20 @@ mod tests {
             Ok(Vec::new())
         }
 
+        async fn semantic_search_with_model(
+            &self,
+            _query: &str,
+            _limit: usize,
+            _filters: Option<Value>,
+            _model: Option<String>,
+        ) -> Result<(Vec<SearchResult>, crate::storage::repository::SearchSource), RepositoryError>
+        {
+            Ok((
+                Vec::new(),
+                crate::storage::repository::SearchSource::Vectors,
+            ))
+        }
+
         async fn get_stats(
             &self,
             _folder: Option<String>,
@@ -1355,6 +2392,156 @@ mod tests {
             Ok(Vec::new())
         }
 
+        async fn suggest(
+            &self,
+            _prefix: &str,
+            _limit: usize,
+        ) -> Result<crate::models::internal::SearchSuggestions, RepositoryError> {
+            Ok(Default::default())
+        }
+
+        async fn reindex_fts(&self) -> Result<u64, RepositoryError> {
+            Ok(0)
+        }
+
+        async fn defer_fts_indexing(&self) -> Result<i64, RepositoryError> {
+            Ok(0)
+        }
+
+        async fn index_fts_since(&self, _after_rowid: i64) -> Result<u64, RepositoryError> {
+            Ok(0)
+        }
+
+        async fn find_summaries(
+            &self,
+            _conversation_id: Uuid,
+        ) -> Result<Vec<StoredSummary>, RepositoryError> {
+            Ok(Vec::new())
+        }
+
+        async fn find_tags(
+            &self,
+            _conversation_id: Uuid,
+        ) -> Result<Vec<crate::models::internal::StoredTag>, RepositoryError> {
+            Ok(Vec::new())
+        }
+
+        async fn add_tags(
+            &self,
+            _conversation_id: Uuid,
+            _tags: Vec<String>,
+        ) -> Result<(), RepositoryError> {
+            Ok(())
+        }
+
+        async fn add_tags_many(
+            &self,
+            _conversation_ids: Vec<Uuid>,
+            _tags: Vec<String>,
+        ) -> Result<Vec<crate::models::internal::TagUpdate>, RepositoryError> {
+            Ok(vec![])
+        }
+
+        async fn remove_tag_from_many(
+            &self,
+            _conversation_ids: Vec<Uuid>,
+            _tag: &str,
+        ) -> Result<Vec<crate::models::internal::TagUpdate>, RepositoryError> {
+            Ok(vec![])
+        }
+
+        async fn find_labels(
+            &self,
+            _conversation_id: Uuid,
+        ) -> Result<Vec<crate::models::internal::ConversationLabel>, RepositoryError> {
+            Ok(vec![])
+        }
+
+        async fn add_label(
+            &self,
+            _conversation_id: Uuid,
+            _label: &str,
+            _weight: f64,
+        ) -> Result<(), RepositoryError> {
+            Ok(())
+        }
+
+        async fn remove_label(
+            &self,
+            _conversation_id: Uuid,
+            _label: &str,
+        ) -> Result<bool, RepositoryError> {
+            Ok(false)
+        }
+
+        async fn find_by_any_label(
+            &self,
+            _labels: &[String],
+        ) -> Result<Vec<(Conversation, f64)>, RepositoryError> {
+            Ok(vec![])
+        }
+
+        async fn save_template(
+            &self,
+            template: crate::models::internal::ConversationTemplate,
+        ) -> Result<crate::models::internal::ConversationTemplate, RepositoryError> {
+            Ok(template)
+        }
+
+        async fn find_template(
+            &self,
+            _name: &str,
+        ) -> Result<Option<crate::models::internal::ConversationTemplate>, RepositoryError>
+        {
+            Ok(None)
+        }
+
+        async fn list_templates(
+            &self,
+        ) -> Result<Vec<crate::models::internal::ConversationTemplate>, RepositoryError> {
+            Ok(Vec::new())
+        }
+
+        async fn archive_to_disk(
+            &self,
+            id: Uuid,
+            _dir: &std::path::Path,
+        ) -> Result<crate::models::internal::OffloadRecord, RepositoryError> {
+            Err(RepositoryError::NotFound(id.to_string()))
+        }
+
+        async fn restore_from_disk(&self, id: Uuid) -> Result<Conversation, RepositoryError> {
+            Err(RepositoryError::NotFound(id.to_string()))
+        }
+
+        async fn retry_embeddings(
+            &self,
+            _message_ids: Vec<Uuid>,
+        ) -> Result<Vec<Uuid>, RepositoryError> {
+            Ok(Vec::new())
+        }
+
+        async fn reconcile_embeddings(&self) -> Result<EmbeddingReconciliation, RepositoryError> {
+            Ok(EmbeddingReconciliation::default())
+        }
+
+        async fn reembed_scope(
+            &self,
+            _filter: crate::models::internal::ConversationFilter,
+        ) -> Result<crate::storage::repository::ReembedReport, RepositoryError> {
+            Ok(Default::default())
+        }
+
+        async fn recompute_aggregates(
+            &self,
+        ) -> Result<crate::storage::repository::AggregateRecompute, RepositoryError> {
+            Ok(Default::default())
+        }
+
+        async fn schema_version(&self) -> Result<Option<String>, RepositoryError> {
+            Ok(None)
+        }
+
         fn get_db(&self) -> &DatabaseConnection {
             panic!("MockRepo::get_db() should not be called in tests")
         }
@@ -1371,5 +2558,18 @@ mod tests {
         async fn get_all_folders(&self) -> Result<Vec<String>, RepositoryError> {
             Ok(Vec::new())
         }
+
+        async fn folder_counts(&self) -> Result<Vec<(String, u64)>, RepositoryError> {
+            Ok(Vec::new())
+        }
+
+        async fn conversation_timeline(
+            &self,
+            _bucket: crate::storage::repository::TimeBucket,
+            _from: Option<chrono::NaiveDate>,
+            _to: Option<chrono::NaiveDate>,
+        ) -> Result<Vec<crate::storage::repository::TimelineBucket>, RepositoryError> {
+            Ok(Vec::new())
+        }
     }
 }
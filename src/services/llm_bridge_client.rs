@@ -1,9 +1,13 @@
 // use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 // use serde_json::Value;
-// use std::sync::Arc;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
 // use uuid::Uuid;
 
+/// LLM calls in flight at once by default; further calls wait their turn
+pub const DEFAULT_LLM_CONCURRENCY: usize = 8;
+
 #[derive(Debug, thiserror::Error)]
 pub enum LlmBridgeError {
     #[error("HTTP error: {0}")]
@@ -18,6 +22,9 @@ pub enum LlmBridgeError {
 pub struct LlmBridgeClient {
     client: reqwest::Client,
     base_url: String,
+    /// Bounds model calls so a burst of requests queues here instead of
+    /// piling onto the bridge; shared by clones
+    permits: Arc<Semaphore>,
 }
 
 impl LlmBridgeClient {
@@ -25,14 +32,23 @@ impl LlmBridgeClient {
         Self {
             client: reqwest::Client::new(),
             base_url,
+            permits: Arc::new(Semaphore::new(DEFAULT_LLM_CONCURRENCY)),
         }
     }
 
+    /// Run at most `max` model calls (embed, summarize, score, rerank) at
+    /// once (minimum 1). Health checks and model listing are not limited.
+    pub fn with_max_concurrency(mut self, max: usize) -> Self {
+        self.permits = Arc::new(Semaphore::new(max.max(1)));
+        self
+    }
+
     pub async fn embed_text(
         &self,
         text: &str,
         model: Option<&str>,
     ) -> Result<Vec<f32>, LlmBridgeError> {
+        let _permit = self.permits.acquire().await.ok();
         let request = EmbedRequest {
             text: text.to_string(),
             model: model.map(|s| s.to_string()),
@@ -63,6 +79,7 @@ impl LlmBridgeClient {
         model: Option<&str>,
         max_words: Option<u32>,
     ) -> Result<String, LlmBridgeError> {
+        let _permit = self.permits.acquire().await.ok();
         let request = SummarizeRequest {
             messages,
             level: level.to_string(),
@@ -94,6 +111,7 @@ impl LlmBridgeClient {
         context: Option<&str>,
         model: Option<&str>,
     ) -> Result<f32, LlmBridgeError> {
+        let _permit = self.permits.acquire().await.ok();
         let request = ScoreImportanceRequest {
             message: message.to_string(),
             context: context.map(|s| s.to_string()),
@@ -118,6 +136,41 @@ impl LlmBridgeClient {
         Ok(score_response.score)
     }
 
+    /// Order `documents` by relevance to `query`, returning indices into
+    /// `documents` (most relevant first, at most `top_k`)
+    pub async fn rerank(
+        &self,
+        query: &str,
+        documents: Vec<String>,
+        top_k: usize,
+        model: Option<&str>,
+    ) -> Result<Vec<usize>, LlmBridgeError> {
+        let _permit = self.permits.acquire().await.ok();
+        let request = RerankRequest {
+            query: query.to_string(),
+            documents,
+            top_k,
+            model: model.map(|s| s.to_string()),
+        };
+
+        let response = self
+            .client
+            .post(format!("{}/rerank", self.base_url))
+            .json(&request)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(LlmBridgeError::ApiError {
+                status: response.status().as_u16(),
+                message: response.text().await.unwrap_or_default(),
+            });
+        }
+
+        let rerank_response: RerankResponse = response.json().await?;
+        Ok(rerank_response.ranking)
+    }
+
     pub async fn list_models(&self) -> Result<Vec<String>, LlmBridgeError> {
         let response = self
             .client
@@ -203,3 +256,18 @@ struct ScoreImportanceResponse {
     reasoning: Option<String>,
     model: String,
 }
+
+#[derive(Serialize)]
+struct RerankRequest {
+    query: String,
+    documents: Vec<String>,
+    top_k: usize,
+    model: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct RerankResponse {
+    ranking: Vec<usize>,
+    model: String,
+}
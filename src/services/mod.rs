@@ -1,14 +1,20 @@
 pub mod embedding_provider;
 pub mod embedding_queue;
 pub mod embedding_service;
+pub mod event_bus;
 pub mod file_watcher;
 pub mod llm_bridge_client;
+pub mod search_cache;
+pub mod task_tracker;
 
 // Re-export for convenience
 pub use embedding_provider::{EmbeddingProvider, MockProvider, OllamaProvider};
 pub use embedding_queue::EmbeddingJob;
 pub use embedding_service::EmbeddingService;
+pub use event_bus::{EventBus, MemoryEvent};
 pub use llm_bridge_client::LlmBridgeClient;
+pub use search_cache::SearchCache;
+pub use task_tracker::TaskTracker;
 
 // Orchestrator services (needed for MCP)
 pub use crate::orchestrator::context_assembly::ContextAssembler;
@@ -0,0 +1,320 @@
+use crate::services::event_bus::{EventBus, MemoryEvent};
+use crate::storage::repository::SearchResult;
+use serde_json::Value as JsonValue;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast::{self, error::TryRecvError};
+
+/// Distinct queries kept before the least recently used is evicted
+pub const DEFAULT_SEARCH_CACHE_CAPACITY: usize = 256;
+
+/// Identifies one semantic search: same query, limit, filters and model
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SearchCacheKey {
+    query: String,
+    limit: usize,
+    filters_hash: u64,
+    model: Option<String>,
+}
+
+impl SearchCacheKey {
+    pub fn new(
+        query: &str,
+        limit: usize,
+        filters: Option<&JsonValue>,
+        model: Option<&str>,
+    ) -> Self {
+        let mut hasher = DefaultHasher::new();
+        if let Some(filters) = filters {
+            hash_json(filters, &mut hasher);
+        }
+
+        Self {
+            query: query.to_string(),
+            limit,
+            filters_hash: hasher.finish(),
+            model: model.map(str::to_string),
+        }
+    }
+}
+
+/// Hash `value` with object keys in sorted order, so filters written in a
+/// different order share a cache entry
+fn hash_json(value: &JsonValue, hasher: &mut DefaultHasher) {
+    match value {
+        JsonValue::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            for key in keys {
+                key.hash(hasher);
+                hash_json(&map[key], hasher);
+            }
+        }
+        JsonValue::Array(items) => {
+            items.len().hash(hasher);
+            items.iter().for_each(|item| hash_json(item, hasher));
+        }
+        scalar => scalar.to_string().hash(hasher),
+    }
+}
+
+struct CacheEntry {
+    results: Vec<SearchResult>,
+    stored_at: Instant,
+    last_used: u64,
+}
+
+struct CacheState {
+    entries: HashMap<SearchCacheKey, CacheEntry>,
+    /// Bumped on every lookup; entries remember when they were last used
+    clock: u64,
+    /// Conversation events since the last look; any event empties the cache
+    events: Option<broadcast::Receiver<MemoryEvent>>,
+}
+
+impl CacheState {
+    /// Apply pending conversation events, returning whether there were any
+    fn drain_events(&mut self) -> bool {
+        let Some(events) = self.events.as_mut() else {
+            return false;
+        };
+
+        let mut changed = false;
+        while let Ok(_) | Err(TryRecvError::Lagged(_)) = events.try_recv() {
+            changed = true;
+        }
+        if changed {
+            self.entries.clear();
+        }
+        changed
+    }
+}
+
+/// Short-lived LRU cache of semantic search results, so repeated identical
+/// queries skip the embedding call and the vector store. Entries expire
+/// after the TTL and the whole cache is dropped whenever a conversation is
+/// created, updated or deleted.
+///
+/// Events are read from the bus when the cache is used rather than by a
+/// background task, so a search made after a write never sees the old
+/// results.
+pub struct SearchCache {
+    ttl: Duration,
+    capacity: usize,
+    state: Mutex<CacheState>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl SearchCache {
+    pub fn new(ttl: Duration, capacity: usize, events: &EventBus) -> Self {
+        Self {
+            ttl,
+            capacity,
+            state: Mutex::new(CacheState {
+                entries: HashMap::new(),
+                clock: 0,
+                events: Some(events.subscribe()),
+            }),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// A cache that never stores anything
+    pub fn disabled() -> Self {
+        Self {
+            ttl: Duration::ZERO,
+            capacity: 0,
+            state: Mutex::new(CacheState {
+                entries: HashMap::new(),
+                clock: 0,
+                events: None,
+            }),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.ttl.is_zero() && self.capacity > 0
+    }
+
+    /// Cached results for `key`, if stored within the TTL and nothing has
+    /// changed since
+    pub fn get(&self, key: &SearchCacheKey) -> Option<Vec<SearchResult>> {
+        if !self.is_enabled() {
+            return None;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        state.drain_events();
+        state.clock += 1;
+        let clock = state.clock;
+
+        let fresh = match state.entries.get_mut(key) {
+            Some(entry) if entry.stored_at.elapsed() < self.ttl => {
+                entry.last_used = clock;
+                Some(entry.results.clone())
+            }
+            Some(_) => {
+                state.entries.remove(key);
+                None
+            }
+            None => None,
+        };
+
+        let counter = if fresh.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        fresh
+    }
+
+    /// Store `results` for `key`, evicting the least recently used entry when
+    /// full. Skipped if a conversation changed since the matching `get`, as
+    /// the results may predate the change.
+    pub fn insert(&self, key: SearchCacheKey, results: Vec<SearchResult>) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        if state.drain_events() {
+            return;
+        }
+
+        if !state.entries.contains_key(&key) && state.entries.len() >= self.capacity {
+            let oldest = state
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                state.entries.remove(&oldest);
+            }
+        }
+
+        let last_used = state.clock;
+        state.entries.insert(
+            key,
+            CacheEntry {
+                results,
+                stored_at: Instant::now(),
+                last_used,
+            },
+        );
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Lookups answered from the cache since startup
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Lookups that had to run the search since startup
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+}
+
+impl Default for SearchCache {
+    fn default() -> Self {
+        Self::disabled()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn result(content: &str) -> SearchResult {
+        SearchResult {
+            conversation_id: Uuid::new_v4(),
+            message_id: Uuid::new_v4(),
+            score: 0.9,
+            role: "user".to_string(),
+            content: content.to_string(),
+            metadata: serde_json::json!({}),
+            label: "Label".to_string(),
+            folder: "/".to_string(),
+            timestamp: chrono::Utc::now().naive_utc(),
+        }
+    }
+
+    fn key(query: &str) -> SearchCacheKey {
+        SearchCacheKey::new(query, 10, None, None)
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let bus = EventBus::default();
+        let cache = SearchCache::new(Duration::from_secs(60), 2, &bus);
+
+        cache.insert(key("a"), vec![result("a")]);
+        cache.insert(key("b"), vec![result("b")]);
+        // Using "a" leaves "b" as the eviction candidate
+        assert!(cache.get(&key("a")).is_some());
+        cache.insert(key("c"), vec![result("c")]);
+
+        assert!(cache.get(&key("a")).is_some());
+        assert!(cache.get(&key("b")).is_none());
+        assert!(cache.get(&key("c")).is_some());
+        assert_eq!((cache.hits(), cache.misses()), (3, 1));
+    }
+
+    #[test]
+    fn test_events_and_ttl_invalidate() {
+        let bus = EventBus::default();
+        let cache = SearchCache::new(Duration::from_secs(60), 8, &bus);
+
+        cache.insert(key("a"), vec![result("a")]);
+        bus.publish(MemoryEvent::Updated {
+            conversation_id: Uuid::new_v4(),
+        });
+        assert!(cache.get(&key("a")).is_none());
+        assert!(cache.is_empty());
+
+        // A write between a miss and the insert keeps the results out
+        bus.publish(MemoryEvent::Created {
+            conversation_id: Uuid::new_v4(),
+        });
+        cache.insert(key("a"), vec![result("a")]);
+        assert!(cache.is_empty());
+
+        let expiring = SearchCache::new(Duration::from_millis(1), 8, &bus);
+        expiring.insert(key("a"), vec![result("a")]);
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(expiring.get(&key("a")).is_none());
+    }
+
+    #[test]
+    fn test_filters_are_part_of_the_key() {
+        let filters = serde_json::json!({"folder": "/work", "label": "x"});
+        let reordered = serde_json::json!({"label": "x", "folder": "/work"});
+
+        assert_eq!(
+            SearchCacheKey::new("q", 5, Some(&filters), None),
+            SearchCacheKey::new("q", 5, Some(&reordered), None)
+        );
+        assert_ne!(
+            SearchCacheKey::new("q", 5, Some(&filters), None),
+            SearchCacheKey::new("q", 5, None, None)
+        );
+        assert_ne!(key("q"), SearchCacheKey::new("q", 5, None, Some("minilm")));
+    }
+}
@@ -0,0 +1,133 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Finished tasks kept for status lookups before the oldest are forgotten
+pub const MAX_FINISHED_TASKS: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskState {
+    Running,
+    Completed,
+    Failed,
+}
+
+/// Progress of one background task started through the API
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TaskStatus {
+    pub id: Uuid,
+    /// What the task does, e.g. `reembed_scope`
+    pub kind: String,
+    pub state: TaskState,
+    #[schema(value_type = String, format = DateTime)]
+    pub started_at: NaiveDateTime,
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub finished_at: Option<NaiveDateTime>,
+    /// Task-specific outcome once completed
+    pub result: Option<Value>,
+    pub error: Option<String>,
+}
+
+/// In-memory registry of background tasks, so clients can poll for the
+/// outcome of work that outlives their request
+#[derive(Debug, Default)]
+pub struct TaskTracker {
+    inner: Mutex<Tasks>,
+}
+
+#[derive(Debug, Default)]
+struct Tasks {
+    by_id: HashMap<Uuid, TaskStatus>,
+    /// Finished task IDs, oldest first
+    finished: VecDeque<Uuid>,
+}
+
+impl TaskTracker {
+    /// Register a running task of `kind`, returning its ID
+    pub fn start(&self, kind: &str) -> Uuid {
+        let id = Uuid::new_v4();
+        let mut tasks = self.inner.lock().unwrap();
+        while tasks.finished.len() >= MAX_FINISHED_TASKS {
+            if let Some(oldest) = tasks.finished.pop_front() {
+                tasks.by_id.remove(&oldest);
+            }
+        }
+        tasks.by_id.insert(
+            id,
+            TaskStatus {
+                id,
+                kind: kind.to_string(),
+                state: TaskState::Running,
+                started_at: chrono::Utc::now().naive_utc(),
+                finished_at: None,
+                result: None,
+                error: None,
+            },
+        );
+        id
+    }
+
+    pub fn complete(&self, id: Uuid, result: Value) {
+        self.finish(id, TaskState::Completed, Some(result), None);
+    }
+
+    pub fn fail(&self, id: Uuid, error: String) {
+        self.finish(id, TaskState::Failed, None, Some(error));
+    }
+
+    pub fn get(&self, id: Uuid) -> Option<TaskStatus> {
+        self.inner.lock().unwrap().by_id.get(&id).cloned()
+    }
+
+    fn finish(&self, id: Uuid, state: TaskState, result: Option<Value>, error: Option<String>) {
+        let mut tasks = self.inner.lock().unwrap();
+        let Some(task) = tasks.by_id.get_mut(&id) else {
+            return;
+        };
+        task.state = state;
+        task.finished_at = Some(chrono::Utc::now().naive_utc());
+        task.result = result;
+        task.error = error;
+        tasks.finished.push_back(id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_task_moves_from_running_to_completed() {
+        let tracker = TaskTracker::default();
+        let id = tracker.start("reembed_scope");
+        assert_eq!(tracker.get(id).unwrap().state, TaskState::Running);
+
+        tracker.complete(id, json!({"messages_reembedded": 3}));
+        let task = tracker.get(id).unwrap();
+        assert_eq!(task.state, TaskState::Completed);
+        assert_eq!(task.result, Some(json!({"messages_reembedded": 3})));
+        assert!(task.finished_at.is_some());
+    }
+
+    #[test]
+    fn test_oldest_finished_tasks_are_forgotten() {
+        let tracker = TaskTracker::default();
+        let running = tracker.start("long");
+        let first = tracker.start("short");
+        tracker.fail(first, "boom".to_string());
+        for _ in 1..MAX_FINISHED_TASKS {
+            let id = tracker.start("short");
+            tracker.complete(id, json!(null));
+        }
+
+        tracker.start("short");
+        assert!(tracker.get(first).is_none());
+        assert!(tracker.get(running).is_some());
+    }
+}
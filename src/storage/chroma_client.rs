@@ -49,6 +49,26 @@ struct ChromaQueryResponse {
     documents: Option<Vec<Vec<String>>>,
 }
 
+#[derive(Debug, Deserialize)]
+struct ChromaGetResponse {
+    ids: Vec<String>,
+    embeddings: Option<Vec<Vec<f32>>>,
+    metadatas: Option<Vec<Value>>,
+}
+
+/// Vector IDs fetched per request by [`ChromaClient::list_ids`]
+const LIST_PAGE_SIZE: usize = 1000;
+
+/// What Chroma reports about a collection
+#[derive(Debug, Clone, PartialEq)]
+pub struct CollectionInfo {
+    pub id: String,
+    pub name: String,
+    /// Vector dimension, unknown until the collection has stored a vector
+    /// unless it was recorded in the metadata at creation
+    pub dimension: Option<usize>,
+}
+
 /// Rust-native ChromaDB client using HTTP API v2
 pub struct ChromaClient {
     base_url: String,
@@ -157,7 +177,6 @@ impl ChromaClient {
         document: Option<String>,
     ) -> Result<(), ChromaError> {
         let collection_id = self.get_collection_id(collection).await?;
-        let url = self.collection_operation_url(&collection_id, "upsert");
 
         let request = ChromaUpsertRequest {
             ids: vec![id.to_string()],
@@ -166,11 +185,42 @@ impl ChromaClient {
             documents: document.map(|d| vec![d]),
         };
 
-        let response = self.client.post(&url).json(&request).send().await?;
+        self.send_upsert(&collection_id, &request).await
+    }
+
+    /// Store or update several vectors in one request
+    pub async fn upsert_batch(
+        &self,
+        collection: &str,
+        ids: Vec<String>,
+        embeddings: Vec<Vec<f32>>,
+        metadatas: Vec<Value>,
+        documents: Vec<String>,
+    ) -> Result<(), ChromaError> {
+        let collection_id = self.get_collection_id(collection).await?;
+
+        let request = ChromaUpsertRequest {
+            ids,
+            embeddings,
+            metadatas: Some(metadatas),
+            documents: Some(documents),
+        };
+
+        self.send_upsert(&collection_id, &request).await
+    }
+
+    async fn send_upsert(
+        &self,
+        collection_id: &str,
+        request: &ChromaUpsertRequest,
+    ) -> Result<(), ChromaError> {
+        let url = self.collection_operation_url(collection_id, "upsert");
+
+        let response = self.client.post(&url).json(request).send().await?;
 
         match response.status() {
             StatusCode::OK | StatusCode::CREATED => {
-                tracing::trace!("Successfully upserted vector: {}", id);
+                tracing::trace!("Successfully upserted {} vectors", request.ids.len());
                 Ok(())
             }
             status => {
@@ -210,6 +260,9 @@ impl ChromaClient {
             }
             status => {
                 let message = response.text().await?;
+                if let Some((expected, actual)) = parse_dimension_mismatch(&message) {
+                    return Err(ChromaError::DimensionMismatch { expected, actual });
+                }
                 Err(ChromaError::ApiError {
                     status: status.as_u16(),
                     message,
@@ -242,6 +295,234 @@ impl ChromaClient {
         }
     }
 
+    /// IDs of every vector stored for `message_ids`, whether the message was
+    /// embedded whole or as `{id}:{n}` chunks
+    pub async fn ids_for_messages(
+        &self,
+        collection: &str,
+        message_ids: &[String],
+    ) -> Result<Vec<String>, ChromaError> {
+        if message_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let collection_id = self.get_collection_id(collection).await?;
+        let url = self.collection_operation_url(&collection_id, "get");
+
+        let mut ids = Vec::new();
+        for batch in message_ids.chunks(LIST_PAGE_SIZE) {
+            let body = json!({
+                "where": { "message_id": { "$in": batch } },
+                "include": []
+            });
+
+            let response = self.client.post(&url).json(&body).send().await?;
+
+            match response.status() {
+                StatusCode::OK => {
+                    let page: ChromaGetResponse = response.json().await?;
+                    ids.extend(page.ids);
+                }
+                status => {
+                    let message = response.text().await?;
+                    return Err(ChromaError::ApiError {
+                        status: status.as_u16(),
+                        message,
+                    });
+                }
+            }
+        }
+        Ok(ids)
+    }
+
+    /// Delete every vector stored for `message_ids`, chunks included, and
+    /// return how many were removed
+    pub async fn delete_for_messages(
+        &self,
+        collection: &str,
+        message_ids: &[String],
+    ) -> Result<usize, ChromaError> {
+        let ids = self.ids_for_messages(collection, message_ids).await?;
+        if ids.is_empty() {
+            return Ok(0);
+        }
+
+        let removed = ids.len();
+        self.delete(collection, ids).await?;
+        Ok(removed)
+    }
+
+    /// IDs of every vector in `collection`, fetched a page at a time
+    pub async fn list_ids(&self, collection: &str) -> Result<Vec<String>, ChromaError> {
+        let vectors = self.list_vectors(collection, false).await?;
+        Ok(vectors.into_iter().map(|(id, _)| id).collect())
+    }
+
+    /// IDs of every vector in `collection` with the unix time it was embedded
+    /// at, `None` for vectors stored before that was recorded
+    pub async fn list_ids_embedded_at(
+        &self,
+        collection: &str,
+    ) -> Result<Vec<(String, Option<i64>)>, ChromaError> {
+        let vectors = self.list_vectors(collection, true).await?;
+        Ok(vectors
+            .into_iter()
+            .map(|(id, metadata)| {
+                let embedded_at = metadata.and_then(|m| m["embedded_at"].as_i64());
+                (id, embedded_at)
+            })
+            .collect())
+    }
+
+    async fn list_vectors(
+        &self,
+        collection: &str,
+        with_metadata: bool,
+    ) -> Result<Vec<(String, Option<Value>)>, ChromaError> {
+        let collection_id = self.get_collection_id(collection).await?;
+        let url = self.collection_operation_url(&collection_id, "get");
+        let include: &[&str] = if with_metadata { &["metadatas"] } else { &[] };
+
+        let mut vectors = Vec::new();
+        loop {
+            let body = json!({
+                "limit": LIST_PAGE_SIZE,
+                "offset": vectors.len(),
+                "include": include
+            });
+
+            let response = self.client.post(&url).json(&body).send().await?;
+
+            match response.status() {
+                StatusCode::OK => {
+                    let page: ChromaGetResponse = response.json().await?;
+                    let fetched = page.ids.len();
+                    let mut metadatas = page.metadatas.unwrap_or_default().into_iter();
+                    vectors.extend(page.ids.into_iter().map(|id| (id, metadatas.next())));
+                    if fetched < LIST_PAGE_SIZE {
+                        return Ok(vectors);
+                    }
+                }
+                status => {
+                    let message = response.text().await?;
+                    return Err(ChromaError::ApiError {
+                        status: status.as_u16(),
+                        message,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Fetch the stored vector for `id`, or `None` if the collection has no such entry
+    pub async fn get_embedding(
+        &self,
+        collection: &str,
+        id: &str,
+    ) -> Result<Option<Vec<f32>>, ChromaError> {
+        let collection_id = self.get_collection_id(collection).await?;
+        let url = self.collection_operation_url(&collection_id, "get");
+
+        let body = json!({ "ids": [id], "include": ["embeddings"] });
+
+        let response = self.client.post(&url).json(&body).send().await?;
+
+        match response.status() {
+            StatusCode::OK => {
+                let get_response: ChromaGetResponse = response.json().await?;
+                let embedding = get_response
+                    .ids
+                    .iter()
+                    .position(|stored| stored == id)
+                    .and_then(|idx| get_response.embeddings?.into_iter().nth(idx));
+                Ok(embedding)
+            }
+            status => {
+                let message = response.text().await?;
+                Err(ChromaError::ApiError {
+                    status: status.as_u16(),
+                    message,
+                })
+            }
+        }
+    }
+
+    /// Fetch every vector stored for `message_id` by its metadata, as
+    /// `(vector_id, embedding)` pairs with chunks in chunk order
+    pub async fn get_message_embeddings(
+        &self,
+        collection: &str,
+        message_id: &str,
+    ) -> Result<Vec<(String, Vec<f32>)>, ChromaError> {
+        let collection_id = self.get_collection_id(collection).await?;
+        let url = self.collection_operation_url(&collection_id, "get");
+
+        let body = json!({
+            "where": { "message_id": message_id },
+            "include": ["embeddings"]
+        });
+
+        let response = self.client.post(&url).json(&body).send().await?;
+
+        match response.status() {
+            StatusCode::OK => {
+                let get_response: ChromaGetResponse = response.json().await?;
+                let mut vectors: Vec<(String, Vec<f32>)> = get_response
+                    .ids
+                    .into_iter()
+                    .zip(get_response.embeddings.unwrap_or_default())
+                    .collect();
+                vectors.sort_by_key(|(id, _)| {
+                    id.rsplit_once(':')
+                        .and_then(|(_, index)| index.parse::<usize>().ok())
+                });
+                Ok(vectors)
+            }
+            status => {
+                let message = response.text().await?;
+                Err(ChromaError::ApiError {
+                    status: status.as_u16(),
+                    message,
+                })
+            }
+        }
+    }
+
+    /// Look up a collection by name, or `None` if it doesn't exist
+    pub async fn collection_info(&self, name: &str) -> Result<Option<CollectionInfo>, ChromaError> {
+        let url = self.collection_url(name);
+
+        let response = self.client.get(&url).send().await?;
+
+        match response.status() {
+            StatusCode::OK => {
+                let collection: Value = response.json().await?;
+                let id = collection["id"]
+                    .as_str()
+                    .ok_or_else(|| ChromaError::CollectionNotFound(name.to_string()))?;
+                // Chroma tracks the dimension once vectors exist; collections
+                // we create also record it in their metadata
+                let dimension = collection["dimension"]
+                    .as_u64()
+                    .or_else(|| collection["metadata"]["dimension"].as_u64())
+                    .map(|d| d as usize);
+                Ok(Some(CollectionInfo {
+                    id: id.to_string(),
+                    name: name.to_string(),
+                    dimension,
+                }))
+            }
+            StatusCode::NOT_FOUND => Ok(None),
+            status => {
+                let message = response.text().await?;
+                Err(ChromaError::ApiError {
+                    status: status.as_u16(),
+                    message,
+                })
+            }
+        }
+    }
+
     /// Get collection ID by name
     async fn get_collection_id(&self, name: &str) -> Result<String, ChromaError> {
         let url = self.collection_url(name);
@@ -312,12 +593,63 @@ impl ChromaClient {
     }
 }
 
+/// Expected and actual dimension from a Chroma error such as "Collection
+/// expecting embedding with dimension of 768, got 384"
+fn parse_dimension_mismatch(message: &str) -> Option<(usize, usize)> {
+    let leading_number = |text: &str| {
+        let digits: String = text
+            .trim_start()
+            .chars()
+            .take_while(char::is_ascii_digit)
+            .collect();
+        digits.parse().ok()
+    };
+
+    let rest = message.split("dimension of ").nth(1)?;
+    let expected = leading_number(rest)?;
+    let actual = leading_number(rest.split("got ").nth(1)?)?;
+    Some((expected, actual))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use wiremock::matchers::{method, path};
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
+    #[tokio::test]
+    async fn test_query_reports_dimension_mismatch() {
+        let mock_server = MockServer::start().await;
+        let client = ChromaClient::new(mock_server.uri());
+        let collections = "/api/v2/tenants/default_tenant/databases/default_database/collections";
+
+        Mock::given(method("GET"))
+            .and(path(format!("{}/conversations", collections)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "id": "col-1" })))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path(format!("{}/col-1/query", collections)))
+            .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+                "error": "InvalidArgumentError",
+                "message": "Collection expecting embedding with dimension of 768, got 384"
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let err = client
+            .query("conversations", vec![0.0; 384], 5, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ChromaError::DimensionMismatch {
+                expected: 768,
+                actual: 384
+            }
+        ));
+    }
+
     #[tokio::test]
     async fn test_ensure_collection_creates_if_not_exists() {
         let mock_server = MockServer::start().await;
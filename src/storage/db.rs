@@ -1,9 +1,14 @@
 use once_cell::sync::Lazy;
-use sea_orm::{ConnectionTrait, Database, DatabaseConnection, DbErr};
+use sea_orm::{ConnectionTrait, Database, DatabaseConnection, DbErr, Statement};
 use sea_orm_migration::SchemaManager;
+use std::path::Path;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
+/// Triggers keeping `messages_fts` in step with `messages`
+pub(crate) const FTS_TRIGGERS: &str =
+    include_str!("../../migrations/007_create_fts_triggers_only.sql");
+
 static DB_CONN: Lazy<Arc<Mutex<Option<DatabaseConnection>>>> =
     Lazy::new(|| Arc::new(Mutex::new(None)));
 
@@ -15,10 +20,7 @@ pub async fn init_db(database_url: &str) -> Result<DatabaseConnection, DbErr> {
         Database::connect(database_url)
             .await
             .map_err(|e| DbErr::Custom(format!("Connection failed: {}", e)))?
-    } else if let Some(path_str) = database_url.strip_prefix("sqlite://") {
-        let path_str = path_str.split('?').next().unwrap_or(path_str);
-        let path = std::path::Path::new(path_str);
-
+    } else if let Some(path) = sqlite_file_path(database_url) {
         if let Some(parent) = path.parent() {
             if !parent.exists() {
                 std::fs::create_dir_all(parent)
@@ -85,11 +87,7 @@ pub async fn init_db(database_url: &str) -> Result<DatabaseConnection, DbErr> {
         .await?;
 
         for i in 1..=migrations.len() {
-            db.execute_unprepared(&format!(
-                "INSERT INTO seaql_migrations (version) VALUES ('{}')",
-                format!("m20241211_{:08}", i * 100000)
-            ))
-            .await?;
+            record_migration(&db, i).await?;
         }
     } else {
         tracing::info!("Migrations already applied, skipping");
@@ -107,6 +105,68 @@ pub async fn init_db(database_url: &str) -> Result<DatabaseConnection, DbErr> {
     )
     .await?;
 
+    // A deferred bulk import that never finished leaves the insert trigger
+    // dropped; restore it, though messages stored meanwhile stay unindexed
+    if !has_trigger(&db, "messages_ai").await? {
+        tracing::warn!(
+            "FTS insert trigger missing, likely from an interrupted import; \
+             run POST /api/v1/maintenance/reindex-fts to index stored messages"
+        );
+        db.execute_unprepared(FTS_TRIGGERS).await?;
+    }
+
+    // Tables added after the initial schema are created idempotently so
+    // databases that already ran the first-run migrations pick them up too
+    db.execute_unprepared(include_str!(
+        "../../migrations/008_create_offloaded_conversations.sql"
+    ))
+    .await?;
+    db.execute_unprepared(include_str!(
+        "../../migrations/009_create_summary_checkpoints.sql"
+    ))
+    .await?;
+    db.execute_unprepared(include_str!(
+        "../../migrations/012_create_conversation_history.sql"
+    ))
+    .await?;
+    db.execute_unprepared(include_str!(
+        "../../migrations/013_create_conversation_templates.sql"
+    ))
+    .await?;
+    db.execute_unprepared(include_str!(
+        "../../migrations/015_create_conversation_labels.sql"
+    ))
+    .await?;
+    // SQLite has no ADD COLUMN IF NOT EXISTS, so check first
+    if !has_column(&db, "conversations", "context_pinned").await? {
+        db.execute_unprepared(include_str!("../../migrations/010_add_context_pinned.sql"))
+            .await?;
+    }
+    if !has_column(&db, "messages", "seq").await? {
+        db.execute_unprepared(include_str!("../../migrations/011_add_message_seq.sql"))
+            .await?;
+    }
+    if !has_column(&db, "conversations", "last_accessed_at").await? {
+        db.execute_unprepared(include_str!(
+            "../../migrations/014_add_last_accessed_at.sql"
+        ))
+        .await?;
+    }
+    if !has_column(&db, "messages", "embedding_model").await? {
+        db.execute_unprepared(include_str!(
+            "../../migrations/016_add_message_embedding_provenance.sql"
+        ))
+        .await?;
+    }
+    db.execute_unprepared(include_str!(
+        "../../migrations/017_drop_messages_updated_at_trigger.sql"
+    ))
+    .await?;
+    // Everything above is in place now, so the tracking table can say so
+    for i in FIRST_RUN_MIGRATIONS + 1..=LATEST_MIGRATION {
+        record_migration(&db, i).await?;
+    }
+
     // Store connection
     let mut conn = DB_CONN.lock().await;
     *conn = Some(db.clone());
@@ -114,10 +174,164 @@ pub async fn init_db(database_url: &str) -> Result<DatabaseConnection, DbErr> {
     Ok(db)
 }
 
+/// Migrations applied by the first-run branch of [`init_db`]; later ones are
+/// applied idempotently on every start
+const FIRST_RUN_MIGRATIONS: usize = 7;
+
+/// Number of the newest file in `migrations/`
+pub const LATEST_MIGRATION: usize = 17;
+
+/// `seaql_migrations` version recorded for migration file `number`
+pub fn migration_version(number: usize) -> String {
+    format!("m20241211_{:08}", number * 100000)
+}
+
+/// Mark migration `number` as applied, if it isn't already
+async fn record_migration(db: &DatabaseConnection, number: usize) -> Result<(), DbErr> {
+    db.execute_raw(Statement::from_sql_and_values(
+        db.get_database_backend(),
+        "INSERT OR IGNORE INTO seaql_migrations (version) VALUES (?)",
+        [migration_version(number).into()],
+    ))
+    .await?;
+    Ok(())
+}
+
+/// Whether `table` has a column called `column`
+async fn has_column(db: &DatabaseConnection, table: &str, column: &str) -> Result<bool, DbErr> {
+    let row = db
+        .query_one_raw(Statement::from_sql_and_values(
+            db.get_database_backend(),
+            "SELECT COUNT(*) AS n FROM pragma_table_info(?) WHERE name = ?",
+            [table.into(), column.into()],
+        ))
+        .await?;
+
+    Ok(match row {
+        Some(row) => row.try_get::<i64>("", "n")? > 0,
+        None => false,
+    })
+}
+
+async fn has_trigger(db: &DatabaseConnection, name: &str) -> Result<bool, DbErr> {
+    let row = db
+        .query_one_raw(Statement::from_sql_and_values(
+            db.get_database_backend(),
+            "SELECT COUNT(*) AS n FROM sqlite_master WHERE type = 'trigger' AND name = ?",
+            [name.into()],
+        ))
+        .await?;
+
+    Ok(match row {
+        Some(row) => row.try_get::<i64>("", "n")? > 0,
+        None => false,
+    })
+}
+
+/// Open a read-only connection pool against the file-backed SQLite database
+/// at `database_url`. Writers keep using the primary from [`init_db`]; with
+/// WAL enabled there, readers on this pool don't block them.
+pub async fn init_read_db(database_url: &str) -> Result<DatabaseConnection, DbErr> {
+    let path = sqlite_file_path(database_url).ok_or_else(|| {
+        DbErr::Custom("Read connection needs a file-backed sqlite:// URL".to_string())
+    })?;
+    if !path.exists() {
+        return Err(DbErr::Custom(format!(
+            "Read database {} does not exist",
+            path.display()
+        )));
+    }
+
+    let read_only_url = read_only_url(database_url);
+    tracing::info!("Connecting read-only to database: {}", read_only_url);
+
+    let db = Database::connect(&read_only_url)
+        .await
+        .map_err(|e| DbErr::Custom(format!("Read connection failed: {}", e)))?;
+    db.execute_unprepared("PRAGMA query_only=ON;").await?;
+
+    Ok(db)
+}
+
+/// `database_url` with its `mode` parameter, if any, replaced by `mode=ro`
+fn read_only_url(database_url: &str) -> String {
+    let (base, query) = database_url.split_once('?').unwrap_or((database_url, ""));
+    let params: Vec<&str> = query
+        .split('&')
+        .filter(|param| !param.is_empty() && param.split('=').next() != Some("mode"))
+        .chain(["mode=ro"])
+        .collect();
+    format!("{}?{}", base, params.join("&"))
+}
+
 pub async fn get_connection() -> Option<DatabaseConnection> {
     DB_CONN.lock().await.clone()
 }
 
+/// Path of the SQLite file behind `database_url`, or `None` if it isn't a
+/// file-backed `sqlite://` URL.
+pub fn sqlite_file_path(database_url: &str) -> Option<&Path> {
+    let path_str = database_url.strip_prefix("sqlite://")?;
+    let path_str = path_str.split('?').next().unwrap_or(path_str);
+    Some(Path::new(path_str))
+}
+
+/// Result of [`optimize`]. Sizes are `None` for in-memory databases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OptimizeReport {
+    pub size_before: Option<u64>,
+    pub size_after: Option<u64>,
+    pub vacuumed: bool,
+}
+
+/// Reclaim free pages and refresh query planner statistics.
+///
+/// Runs `VACUUM`, `ANALYZE` and `PRAGMA optimize`. `VACUUM` is skipped for
+/// in-memory databases, where there is no file to shrink.
+pub async fn optimize(
+    db: &DatabaseConnection,
+    database_url: &str,
+) -> Result<OptimizeReport, DbErr> {
+    let path = sqlite_file_path(database_url);
+    let file_size = || {
+        path.and_then(|p| std::fs::metadata(p).ok())
+            .map(|m| m.len())
+    };
+
+    let size_before = file_size();
+
+    let vacuumed = path.is_some();
+    if vacuumed {
+        db.execute_unprepared("VACUUM;").await?;
+        // Fold the WAL back into the main file so the new size is visible
+        db.execute_unprepared("PRAGMA wal_checkpoint(TRUNCATE);")
+            .await?;
+    }
+    db.execute_unprepared("ANALYZE;").await?;
+    db.execute_unprepared("PRAGMA optimize;").await?;
+
+    Ok(OptimizeReport {
+        size_before,
+        size_after: file_size(),
+        vacuumed,
+    })
+}
+
+/// Name of the most recently applied migration, if the tracking table exists.
+pub async fn schema_version(db: &DatabaseConnection) -> Result<Option<String>, DbErr> {
+    let row = db
+        .query_one_raw(Statement::from_string(
+            db.get_database_backend(),
+            "SELECT version FROM seaql_migrations ORDER BY version DESC LIMIT 1",
+        ))
+        .await?;
+
+    match row {
+        Some(row) => Ok(Some(row.try_get::<String>("", "version")?)),
+        None => Ok(None),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -173,4 +387,72 @@ mod tests {
 
         assert!(result.rows_affected() > 0);
     }
+
+    #[tokio::test]
+    async fn test_init_db_records_every_migration() {
+        let temp_dir = TempDir::new().unwrap();
+        let url = format!("sqlite://{}", temp_dir.path().join("test.db").display());
+
+        let db = init_db(&url).await.unwrap();
+        assert_eq!(
+            schema_version(&db).await.unwrap(),
+            Some(migration_version(LATEST_MIGRATION))
+        );
+
+        // Reopening records nothing twice
+        drop(db);
+        let db = init_db(&url).await.unwrap();
+        let row = db
+            .query_one_raw(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                "SELECT COUNT(*) AS n FROM seaql_migrations",
+            ))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            row.try_get::<i64>("", "n").unwrap(),
+            LATEST_MIGRATION as i64
+        );
+    }
+
+    #[tokio::test]
+    async fn test_optimize_file_db_vacuums() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let url = format!("sqlite://{}", db_path.display());
+
+        let db = init_db(&url).await.unwrap();
+        let report = optimize(&db, &url).await.unwrap();
+
+        assert!(report.vacuumed);
+        assert!(report.size_before.is_some());
+        assert!(report.size_after.unwrap() > 0);
+    }
+
+    #[tokio::test]
+    async fn test_optimize_memory_db_skips_vacuum() {
+        let db = init_db("sqlite::memory:").await.unwrap();
+        let report = optimize(&db, "sqlite::memory:").await.unwrap();
+
+        assert!(!report.vacuumed);
+        assert_eq!(report.size_before, None);
+        assert_eq!(report.size_after, None);
+    }
+
+    #[test]
+    fn test_read_only_url_replaces_mode() {
+        assert_eq!(
+            read_only_url("sqlite://data/sekha.db"),
+            "sqlite://data/sekha.db?mode=ro"
+        );
+        assert_eq!(
+            read_only_url("sqlite://data/sekha.db?mode=rwc"),
+            "sqlite://data/sekha.db?mode=ro"
+        );
+        assert_eq!(
+            read_only_url("sqlite://data/sekha.db?cache=shared&mode=rwc"),
+            "sqlite://data/sekha.db?cache=shared&mode=ro"
+        );
+    }
 }
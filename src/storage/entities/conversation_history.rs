@@ -0,0 +1,41 @@
+//! `SeaORM` Entity for the audit log of conversation label/folder/status/importance changes
+
+use chrono::NaiveDateTime;
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "conversation_history")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub conversation_id: Uuid,
+    #[sea_orm(column_type = "Text")]
+    pub field: String,
+    #[sea_orm(column_type = "Text")]
+    pub old_value: String,
+    #[sea_orm(column_type = "Text")]
+    pub new_value: String,
+    #[sea_orm(column_type = "Timestamp")]
+    pub changed_at: NaiveDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::conversations::Entity",
+        from = "Column::ConversationId",
+        to = "super::conversations::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Conversations,
+}
+
+impl Related<super::conversations::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Conversations.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,30 @@
+//! `SeaORM` Entity for named templates that new conversations are created from
+
+use chrono::NaiveDateTime;
+use sea_orm::entity::prelude::*;
+use serde_json::Value;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "conversation_templates")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false, column_type = "Text")]
+    pub name: String,
+    #[sea_orm(column_type = "Text")]
+    pub label_pattern: String,
+    #[sea_orm(column_type = "Text")]
+    pub folder: String,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub system_message: Option<String>,
+    /// JSON array of tag strings
+    #[sea_orm(column_type = "Json")]
+    pub tags: Value,
+    #[sea_orm(column_type = "Timestamp")]
+    pub created_at: NaiveDateTime,
+    #[sea_orm(column_type = "Timestamp")]
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -22,6 +22,9 @@ pub struct Model {
     pub importance_score: i32, // CHANGED: i64 → i32
     pub word_count: i32,       // CHANGED: i64 → i32
     pub session_count: i32,    // CHANGED: i64 → i32
+    pub context_pinned: bool,
+    #[sea_orm(column_type = "Timestamp", nullable)]
+    pub last_accessed_at: Option<NaiveDateTime>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
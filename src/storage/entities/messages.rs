@@ -21,6 +21,14 @@ pub struct Model {
     pub embedding_id: Option<String>, // Keep as String (Chroma ID, not UUID)
     #[sea_orm(column_type = "Json", nullable)] // CHANGED: Text → Json
     pub metadata: Option<Value>, // CHANGED: String → Value
+    /// Insertion order within the conversation; breaks timestamp ties
+    pub seq: i64,
+    /// Model that produced the stored vector
+    #[sea_orm(nullable)]
+    pub embedding_model: Option<String>,
+    /// Length of the stored vector
+    #[sea_orm(nullable)]
+    pub embedding_dim: Option<i32>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
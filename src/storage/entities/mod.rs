@@ -2,8 +2,13 @@
 
 pub mod prelude;
 
+pub mod conversation_history;
+pub mod conversation_labels;
+pub mod conversation_templates;
 pub mod conversations;
 pub mod hierarchical_summaries;
 pub mod knowledge_graph_edges;
 pub mod messages;
+pub mod offloaded_conversations;
 pub mod semantic_tags;
+pub mod summary_checkpoints;
@@ -0,0 +1,26 @@
+//! `SeaORM` Entity for conversations offloaded to cold storage
+
+use chrono::NaiveDateTime;
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "offloaded_conversations")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub conversation_id: Uuid,
+    #[sea_orm(column_type = "Text")]
+    pub label: String,
+    #[sea_orm(column_type = "Text")]
+    pub folder: String,
+    #[sea_orm(column_type = "Text")]
+    pub file_path: String,
+    pub message_count: i32,
+    #[sea_orm(column_type = "Timestamp")]
+    pub offloaded_at: NaiveDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
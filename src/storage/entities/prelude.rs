@@ -1,7 +1,12 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 2.0.0-rc.20
 
+pub use super::conversation_history::Entity as ConversationHistory;
+pub use super::conversation_labels::Entity as ConversationLabels;
+pub use super::conversation_templates::Entity as ConversationTemplates;
 pub use super::conversations::Entity as Conversations;
 pub use super::hierarchical_summaries::Entity as HierarchicalSummaries;
 pub use super::knowledge_graph_edges::Entity as KnowledgeGraphEdges;
 pub use super::messages::Entity as Messages;
+pub use super::offloaded_conversations::Entity as OffloadedConversations;
 pub use super::semantic_tags::Entity as SemanticTags;
+pub use super::summary_checkpoints::Entity as SummaryCheckpoints;
@@ -3,19 +3,39 @@ use mockall::automock;
 
 use async_trait::async_trait;
 use sea_orm::{
-    prelude::*, DatabaseBackend, FromQueryResult, IntoActiveModel, QueryFilter, QueryOrder,
+    prelude::*,
+    sea_query::{Expr, LikeExpr, Query},
+    Condition, DatabaseBackend, FromQueryResult, IntoActiveModel, QueryFilter, QueryOrder,
     QuerySelect, Set, Statement, TransactionTrait, Value,
 };
 use serde_json::json;
 use serde_json::Value as JsonValue;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 use std::sync::Arc;
 use uuid::Uuid;
 
+use crate::config::{FtsRanking, IdScheme, SearchFallback};
 use crate::init_db;
-use crate::models::internal::{Conversation, Message, NewConversation, NewMessage};
-use crate::services::embedding_service::EmbeddingService;
-use crate::storage::chroma_client::ChromaClient;
-use crate::storage::entities::{conversations, messages};
+use crate::models::ids::new_id;
+use crate::models::importance;
+use crate::models::internal::{
+    Conversation, ConversationChange, ConversationFilter, ConversationLabel, ConversationPatch,
+    ConversationTemplate, Message, NewConversation, NewMessage, OffloadRecord, SearchSuggestions,
+    StoredSummary, StoredTag, Suggestion, TagUpdate, COMPACTED_KEY, EMBEDDING_SKIPPED_KEY,
+    RESERVED_METADATA_KEYS,
+};
+use crate::models::redaction::Redactor;
+use crate::services::embedding_service::{
+    vector_message_id, BatchEmbeddingReport, EmbeddingError, EmbeddingRequest, EmbeddingService,
+    DEFAULT_COLLECTION,
+};
+use crate::storage::chroma_client::{ChromaClient, ChromaError};
+use crate::storage::db::FTS_TRIGGERS;
+use crate::storage::entities::{
+    conversation_history, conversation_labels, conversation_templates, conversations,
+    hierarchical_summaries, messages, offloaded_conversations, semantic_tags, summary_checkpoints,
+};
 
 #[tokio::test]
 async fn test_create_message_with_fts_indexing() {
@@ -68,7 +88,10 @@ async fn test_create_message_with_fts_indexing() {
     assert_eq!(message.role, "user");
 
     // Verify: FTS index was created by searching for the content
-    let search_results = repo.full_text_search("FTS indexing", 10).await.unwrap();
+    let search_results = repo
+        .full_text_search("FTS indexing", 10, None, None, None)
+        .await
+        .unwrap();
     assert_eq!(search_results.len(), 1);
     assert_eq!(search_results[0].id, msg_id);
 
@@ -91,6 +114,111 @@ pub enum RepositoryError {
     EmbeddingError(String),
     #[error("Invalid input: {0}")]
     InvalidInput(String),
+    #[error("Archive error: {0}")]
+    ArchiveError(String),
+}
+
+/// Outcome of cross-checking stored messages against the vector store
+#[derive(Debug, Default)]
+pub struct EmbeddingReconciliation {
+    /// Vectors deleted because no message refers to them
+    pub orphaned_vectors_removed: usize,
+    /// Messages with no vector in the store, by conversation. Stale
+    /// `embedding_id`s on them have been cleared so they can be re-embedded.
+    pub missing_vectors: HashMap<Uuid, Vec<Uuid>>,
+}
+
+/// Width of the buckets in a statistics timeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeBucket {
+    Day,
+    /// ISO weeks, starting on Monday
+    Week,
+    Month,
+}
+
+impl TimeBucket {
+    pub fn parse(bucket: &str) -> Option<Self> {
+        match bucket {
+            "day" => Some(Self::Day),
+            "week" => Some(Self::Week),
+            "month" => Some(Self::Month),
+            _ => None,
+        }
+    }
+
+    /// SQLite expression for the first day of the bucket `column` falls in.
+    /// Timestamps are stored as text; `date()` parses them whether they use
+    /// a space or `T` separator and however many fractional digits they carry
+    fn start_of(self, column: &str) -> String {
+        match self {
+            Self::Day => format!("date({column})"),
+            // Back up six days, then forward to the next Monday: the Monday
+            // on or before the timestamp
+            Self::Week => format!("date({column}, '-6 days', 'weekday 1')"),
+            Self::Month => format!("date({column}, 'start of month')"),
+        }
+    }
+}
+
+/// Conversations and messages created within one timeline bucket
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct TimelineBucket {
+    /// First day of the bucket, `YYYY-MM-DD`
+    pub start: String,
+    pub conversations: u64,
+    pub messages: u64,
+}
+
+/// Outcome of re-embedding the messages of the conversations in a scope
+#[derive(Debug, Default, serde::Serialize)]
+pub struct ReembedReport {
+    pub conversations: usize,
+    pub messages_reembedded: usize,
+    /// Messages whose new vector couldn't be generated or stored; they are
+    /// left without one until embeddings are retried
+    pub messages_failed: usize,
+}
+
+/// Conversation stored by [`ConversationRepository::create_with_message_ids`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreatedConversation {
+    pub id: Uuid,
+    /// IDs of the new messages, in input order
+    pub message_ids: Vec<Uuid>,
+    /// The stored `word_count`, counted on the redacted content
+    pub word_count: i32,
+}
+
+/// Messages stored by [`ConversationRepository::append_messages`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppendedMessages {
+    /// IDs of the new messages, in input order
+    pub message_ids: Vec<Uuid>,
+    /// Messages in the conversation afterwards
+    pub message_count: u64,
+    /// The conversation's `word_count` afterwards
+    pub word_count: i32,
+}
+
+/// Which index answered a semantic search
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchSource {
+    Vectors,
+    /// Full-text matches, because embeddings are disabled or the vector
+    /// store couldn't be queried
+    FullText,
+    /// No results, because the vector store couldn't be queried and the
+    /// fallback policy is to return nothing
+    Unavailable,
+}
+
+/// Outcome of recomputing conversation aggregates from their messages
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct AggregateRecompute {
+    /// Conversations whose stored `word_count` had drifted
+    pub conversations_corrected: u64,
+    pub messages_counted: u64,
 }
 
 #[derive(Debug, serde::Serialize)]
@@ -101,6 +229,182 @@ pub struct Stats {
     pub groups: Vec<String>, // Contains folders OR labels based on group_type
 }
 
+/// Text format SQLite stores message timestamps in
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.f";
+
+/// Shared by FTS search and count: `?1` is the MATCH query, `?2`/`?3` the
+/// optional `after`/`before` bounds and `?4` the optional conversation.
+/// Joining the index (rather than filtering on its rowids) lets search
+/// rank by `bm25()`.
+const FTS_MATCH_FILTER: &str = r#"
+            JOIN messages_fts ON messages_fts.rowid = m.rowid
+            WHERE messages_fts MATCH ?1
+            AND (?2 IS NULL OR m.timestamp >= ?2)
+            AND (?3 IS NULL OR m.timestamp < ?3)
+            AND (?4 IS NULL OR m.conversation_id = ?4)"#;
+
+/// Errors SQLite raises for a malformed FTS5 MATCH expression
+const FTS_SYNTAX_ERRORS: [&str; 3] = [
+    "fts5: syntax error",
+    "unterminated string",
+    "no such column",
+];
+
+/// Report a MATCH expression FTS5 couldn't parse as bad input rather than a
+/// database failure
+fn fts_query_error(query: &str, err: sea_orm::DbErr) -> RepositoryError {
+    let message = err.to_string();
+    if FTS_SYNTAX_ERRORS.iter().any(|e| message.contains(e)) {
+        RepositoryError::InvalidInput(format!("invalid full-text query: {}", query))
+    } else {
+        err.into()
+    }
+}
+
+/// Reads closer together than this are recorded once, so a conversation
+/// fetched in a loop doesn't turn every read into a write
+const ACCESS_RECORD_INTERVAL_SECS: i64 = 60;
+
+/// Bumped whenever the layout of [`OffloadedConversation`] changes
+const OFFLOAD_FORMAT_VERSION: u32 = 1;
+
+/// On-disk layout of a conversation offloaded by `archive_to_disk`
+#[derive(serde::Serialize, serde::Deserialize)]
+struct OffloadedConversation {
+    format_version: u32,
+    conversation: Conversation,
+    messages: Vec<Message>,
+    summaries: Vec<StoredSummary>,
+    /// Missing from files written before tags were carried along
+    #[serde(default)]
+    tags: Vec<StoredTag>,
+    /// Missing from files written before labels were carried along
+    #[serde(default)]
+    labels: Vec<ConversationLabel>,
+    /// Missing from files written before the audit log was carried along
+    #[serde(default)]
+    history: Vec<ConversationChange>,
+}
+
+/// The vector stored for a message and the model that produced it
+struct StoredEmbedding {
+    id: String,
+    model: String,
+    dim: Option<i32>,
+}
+
+/// Embedding request for a message that is already stored
+fn embedding_request(msg: &Message) -> EmbeddingRequest {
+    EmbeddingRequest {
+        message_id: msg.id,
+        conversation_id: msg.conversation_id,
+        content: msg.content.clone(),
+        metadata: json!({
+            "role": msg.role.clone(),
+            "conversation_id": msg.conversation_id.to_string(),
+            "timestamp": msg.timestamp,
+        }),
+    }
+}
+
+/// Write via a temporary file and rename, so readers never see a partial file
+async fn write_file_atomically(path: &Path, contents: &[u8]) -> Result<(), RepositoryError> {
+    let io_error =
+        |e: std::io::Error| RepositoryError::ArchiveError(format!("{}: {}", path.display(), e));
+
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await.map_err(io_error)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    tokio::fs::write(&tmp, contents).await.map_err(io_error)?;
+    tokio::fs::rename(&tmp, path).await.map_err(io_error)
+}
+
+/// `ids` without repeats, first occurrence kept
+fn dedup_ids(ids: Vec<Uuid>) -> Vec<Uuid> {
+    let mut seen = HashSet::new();
+    ids.into_iter().filter(|id| seen.insert(*id)).collect()
+}
+
+/// LIKE pattern for values starting with `prefix`, with `%` and `_` in it
+/// matched literally
+fn like_prefix(prefix: &str) -> LikeExpr {
+    let escaped = prefix
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    LikeExpr::new(format!("{}%", escaped)).escape('\\')
+}
+
+/// `word_count` contribution of messages as they will be stored
+fn stored_word_count(prepared: &[(Uuid, NewMessage, Option<StoredEmbedding>)]) -> i32 {
+    prepared
+        .iter()
+        .map(|(_, msg, _)| msg.content.len() as i32)
+        .sum()
+}
+
+/// Sum the counts of repeated values, then keep the `limit` most used,
+/// ties in alphabetical order
+fn rank_suggestions(
+    counts: impl IntoIterator<Item = (String, i64)>,
+    limit: usize,
+) -> Vec<Suggestion> {
+    let mut totals: HashMap<String, u64> = HashMap::new();
+    for (value, count) in counts {
+        *totals.entry(value).or_default() += count as u64;
+    }
+
+    let mut suggestions: Vec<Suggestion> = totals
+        .into_iter()
+        .map(|(value, count)| Suggestion { value, count })
+        .collect();
+    suggestions.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+    suggestions.truncate(limit);
+    suggestions
+}
+
+/// Timestamps are stored as text, so range bounds must use the same format
+fn fts_time_bound(ts: Option<chrono::NaiveDateTime>) -> Value {
+    Value::String(ts.map(|ts| ts.format(TIMESTAMP_FORMAT).to_string()))
+}
+
+/// Turn a natural-language query into an FTS5 MATCH expression. Each word is
+/// quoted so punctuation can't trip FTS5 syntax, and any word may match.
+pub fn fts_match_query(query: &str) -> String {
+    fts_match_query_filtered(query, &[])
+}
+
+/// [`fts_match_query`] leaving out `stop_words` (matched ignoring case). A
+/// query made up only of stop words keeps them rather than matching nothing.
+pub fn fts_match_query_filtered(query: &str, stop_words: &[String]) -> String {
+    let words: Vec<String> = query
+        .split_whitespace()
+        .map(|w| {
+            w.chars()
+                .filter(|c| c.is_alphanumeric())
+                .collect::<String>()
+        })
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    let is_stop_word = |w: &String| {
+        let w = w.to_lowercase();
+        stop_words.iter().any(|s| s.to_lowercase() == w)
+    };
+    let kept: Vec<&String> = words.iter().filter(|w| !is_stop_word(w)).collect();
+    let kept = if kept.is_empty() {
+        words.iter().collect()
+    } else {
+        kept
+    };
+
+    kept.into_iter()
+        .map(|w| format!("\"{}\"", w))
+        .collect::<Vec<_>>()
+        .join(" OR ")
+}
+
 // ============================================
 // TRAIT DEFINITION
 // ============================================
@@ -109,10 +413,59 @@ pub struct Stats {
 pub trait ConversationRepository: Send + Sync {
     async fn create(&self, conv: Conversation) -> Result<Uuid, RepositoryError>;
     async fn create_with_messages(&self, conv: NewConversation) -> Result<Uuid, RepositoryError>;
+    /// Like `create_with_messages`, also returning the new message IDs in
+    /// input order and the stored totals
+    async fn create_with_message_ids(
+        &self,
+        conv: NewConversation,
+    ) -> Result<CreatedConversation, RepositoryError>;
+    /// Like `create_with_message_ids`, also attaching user-assigned `tags` in
+    /// the same transaction
+    async fn create_with_tags(
+        &self,
+        conv: NewConversation,
+        tags: Vec<String>,
+    ) -> Result<CreatedConversation, RepositoryError>;
+    /// Store an imported conversation under `conv.id`, which is required. An
+    /// earlier import with that ID has its messages replaced in the same
+    /// transaction; its label, folder, status, importance, pin, tags and
+    /// labels are kept, as the user may have changed them since.
+    async fn upsert_import(&self, conv: NewConversation) -> Result<Uuid, RepositoryError>;
+    /// Add messages to an existing conversation, returning their IDs in input
+    /// order along with the conversation's totals afterwards
+    async fn append_messages(
+        &self,
+        conversation_id: Uuid,
+        messages: Vec<NewMessage>,
+    ) -> Result<AppendedMessages, RepositoryError>;
     async fn delete(&self, id: Uuid) -> Result<(), RepositoryError>;
+    /// Delete many conversations and their vectors, returning the IDs that
+    /// existed and the number of vectors Chroma held for them, chunks included
+    async fn delete_many(&self, ids: Vec<Uuid>) -> Result<(Vec<Uuid>, usize), RepositoryError>;
+    /// Delete individual messages and their vectors, returning how many rows
+    /// went. Their conversations' word counts are recounted in the same
+    /// transaction.
+    async fn delete_messages(&self, message_ids: Vec<Uuid>) -> Result<u64, RepositoryError>;
+    /// Empty the bodies of `message_ids`, keeping their rows and metadata,
+    /// and append `summary` to the conversation in their place. The emptied
+    /// messages lose their vectors; the summary is embedded and indexed like
+    /// any new message. Returns how many bodies were dropped.
+    async fn compact_messages(
+        &self,
+        conversation_id: Uuid,
+        message_ids: Vec<Uuid>,
+        summary: NewMessage,
+    ) -> Result<u64, RepositoryError>;
     async fn count_by_label(&self, label: &str) -> Result<u64, RepositoryError>;
     async fn count_by_folder(&self, folder: &str) -> Result<u64, RepositoryError>;
     async fn count_all(&self) -> Result<u64, RepositoryError>;
+    /// Count conversations matching every filter given; `None` matches any
+    async fn count_with_filters(
+        &self,
+        label: Option<String>,
+        folder: Option<String>,
+        status: Option<String>,
+    ) -> Result<u64, RepositoryError>;
     async fn find_by_id(&self, id: Uuid) -> Result<Option<Conversation>, RepositoryError>;
     async fn find_by_label(
         &self,
@@ -120,6 +473,14 @@ pub trait ConversationRepository: Send + Sync {
         limit: u64,
         offset: u64,
     ) -> Result<Vec<Conversation>, RepositoryError>;
+    /// Conversations whose label is close to `label`, ignoring case, so typos
+    /// still find something. Closest labels come first, then most recently
+    /// updated.
+    async fn find_by_label_fuzzy(
+        &self,
+        label: &str,
+        limit: u64,
+    ) -> Result<Vec<Conversation>, RepositoryError>;
 
     async fn get_conversation_messages(
         &self,
@@ -127,6 +488,24 @@ pub trait ConversationRepository: Send + Sync {
     ) -> Result<Vec<Message>, RepositoryError>;
 
     async fn find_message_by_id(&self, id: Uuid) -> Result<Option<Message>, RepositoryError>;
+    /// Replace a message's metadata, or shallow-merge it into the existing
+    /// object when `merge` is set. `metadata` must be a JSON object and can't
+    /// set [`RESERVED_METADATA_KEYS`], which a replace keeps as they were.
+    async fn update_message_metadata(
+        &self,
+        message_id: Uuid,
+        metadata: JsonValue,
+        merge: bool,
+    ) -> Result<Message, RepositoryError>;
+
+    /// Reassign a message to another conversation, moving its word count
+    /// along with it and updating its vectors' conversation. If that update
+    /// fails the message is marked unembedded, to be embedded again.
+    async fn move_message(
+        &self,
+        message_id: Uuid,
+        target_conversation_id: Uuid,
+    ) -> Result<Message, RepositoryError>;
 
     async fn find_recent_messages(
         &self,
@@ -134,9 +513,12 @@ pub trait ConversationRepository: Send + Sync {
         limit: usize,
     ) -> Result<Vec<Message>, RepositoryError>;
 
+    /// Conversations whose label contains `filter` and, with `has_role`, that
+    /// have at least one message with that role; newest first
     async fn find_with_filters(
         &self,
         filter: Option<String>,
+        has_role: Option<String>,
         limit: usize,
         offset: u32,
     ) -> Result<(Vec<Conversation>, u64), RepositoryError>;
@@ -165,6 +547,16 @@ pub trait ConversationRepository: Send + Sync {
     ) -> Result<Stats, Box<dyn std::error::Error>>;
 
     async fn get_all_folders(&self) -> Result<Vec<String>, RepositoryError>;
+    /// Distinct folders with the number of conversations directly in each
+    async fn folder_counts(&self) -> Result<Vec<(String, u64)>, RepositoryError>;
+    /// Conversations (by `created_at`) and messages (by `timestamp`) per
+    /// bucket between `from` and `to` inclusive; buckets with neither are left out
+    async fn conversation_timeline(
+        &self,
+        bucket: TimeBucket,
+        from: Option<chrono::NaiveDate>,
+        to: Option<chrono::NaiveDate>,
+    ) -> Result<Vec<TimelineBucket>, RepositoryError>;
 
     async fn find_by_folder(
         &self,
@@ -173,18 +565,96 @@ pub trait ConversationRepository: Send + Sync {
         offset: u64,
     ) -> Result<Vec<Conversation>, RepositoryError>;
 
+    /// The conversations among `ids` that exist, in no particular order
+    async fn find_by_ids(&self, ids: Vec<Uuid>) -> Result<Vec<Conversation>, RepositoryError>;
+
+    /// Conversations among `ids` last updated before `cutoff`
+    async fn find_updated_before(
+        &self,
+        ids: Vec<Uuid>,
+        cutoff: chrono::NaiveDateTime,
+    ) -> Result<Vec<Uuid>, RepositoryError>;
+
     async fn update_status(&self, id: Uuid, status: &str) -> Result<(), RepositoryError>;
+    /// Set the stored importance (see [`crate::models::importance`] for the scale)
     async fn update_importance(&self, id: Uuid, score: i32) -> Result<(), RepositoryError>;
+    /// Mark a conversation as always (or no longer) included in assembled context
+    async fn set_context_pinned(&self, id: Uuid, pinned: bool) -> Result<(), RepositoryError>;
+    /// Bump `updated_at` to now without changing any content
+    async fn touch(&self, id: Uuid) -> Result<(), RepositoryError>;
+    /// Record that the conversation was read at `at`, leaving `updated_at`
+    /// alone. Reads within a minute of the last recorded one aren't written.
+    async fn record_access(
+        &self,
+        id: Uuid,
+        at: chrono::NaiveDateTime,
+    ) -> Result<(), RepositoryError>;
+    /// Record that a new session started in this conversation
+    async fn increment_session(&self, id: Uuid) -> Result<(), RepositoryError>;
+    async fn apply_patch(
+        &self,
+        id: Uuid,
+        patch: ConversationPatch,
+    ) -> Result<Conversation, RepositoryError>;
+    /// Label, folder, status and importance changes to a conversation, oldest first
+    async fn find_history(&self, id: Uuid) -> Result<Vec<ConversationChange>, RepositoryError>;
+    /// Move conversations into `folder`, returning the IDs that changed.
+    /// With `dry_run` nothing is written.
+    async fn bulk_move(
+        &self,
+        ids: Vec<Uuid>,
+        folder: &str,
+        dry_run: bool,
+    ) -> Result<Vec<Uuid>, RepositoryError>;
+    /// Set the status of many conversations, returning the IDs that changed.
+    /// With `dry_run` nothing is written.
+    async fn bulk_update_status(
+        &self,
+        ids: Vec<Uuid>,
+        status: &str,
+        dry_run: bool,
+    ) -> Result<Vec<Uuid>, RepositoryError>;
+    /// Rename folder `from` (and its subfolders) to `to`, returning the IDs
+    /// that changed. With `dry_run` nothing is written. The root folder `/`
+    /// cannot be renamed.
+    async fn rename_folder(
+        &self,
+        from: &str,
+        to: &str,
+        dry_run: bool,
+    ) -> Result<Vec<Uuid>, RepositoryError>;
     async fn count_messages_in_conversation(
         &self,
         conversation_id: Uuid,
     ) -> Result<u64, RepositoryError>;
 
+    /// Claim an automatic summary once a conversation has gained `every`
+    /// messages since the last one, recording the new message count.
+    /// Returns that count, or `None` when no summary is due.
+    async fn claim_summary_checkpoint(
+        &self,
+        conversation_id: Uuid,
+        every: u64,
+    ) -> Result<Option<u64>, RepositoryError>;
+
+    /// FTS5 search over message content, optionally limited to messages
+    /// with `after <= timestamp < before` and to a single conversation
     async fn full_text_search(
         &self,
         query: &str,
         limit: usize,
+        after: Option<chrono::NaiveDateTime>,
+        before: Option<chrono::NaiveDateTime>,
+        conversation_id: Option<Uuid>,
     ) -> Result<Vec<Message>, RepositoryError>;
+    /// Total number of messages `full_text_search` would match without a limit
+    async fn full_text_search_count(
+        &self,
+        query: &str,
+        after: Option<chrono::NaiveDateTime>,
+        before: Option<chrono::NaiveDateTime>,
+        conversation_id: Option<Uuid>,
+    ) -> Result<u64, RepositoryError>;
 
     async fn semantic_search(
         &self,
@@ -192,19 +662,200 @@ pub trait ConversationRepository: Send + Sync {
         limit: usize,
         filters: Option<JsonValue>,
     ) -> Result<Vec<SearchResult>, RepositoryError>;
+    /// `semantic_search` against the named secondary embedding model's
+    /// collection; `None` uses the primary model. Also reports whether the
+    /// vectors or the full-text fallback answered.
+    async fn semantic_search_with_model(
+        &self,
+        query: &str,
+        limit: usize,
+        filters: Option<JsonValue>,
+        model: Option<String>,
+    ) -> Result<(Vec<SearchResult>, SearchSource), RepositoryError>;
 
     async fn get_all_labels(&self) -> Result<Vec<String>, RepositoryError>;
 
+    /// Labels, folders and tags starting with `prefix` (case-insensitive),
+    /// at most `limit` of each, ranked by how many conversations use them
+    async fn suggest(
+        &self,
+        prefix: &str,
+        limit: usize,
+    ) -> Result<SearchSuggestions, RepositoryError>;
+
+    /// Rebuild `messages_fts` from `messages`, returning the number of rows
+    /// indexed. Resumes per-message indexing if it was deferred.
+    async fn reindex_fts(&self) -> Result<u64, RepositoryError>;
+    /// Stop indexing inserted messages into `messages_fts`, so bulk imports
+    /// skip the per-row work. Returns the last message rowid already indexed,
+    /// to hand to [`index_fts_since`](Self::index_fts_since) afterwards.
+    async fn defer_fts_indexing(&self) -> Result<i64, RepositoryError>;
+    /// Resume per-message indexing and index the messages stored after
+    /// `after_rowid` that aren't indexed yet, returning how many were added.
+    /// Indexing resumes even if catching up fails.
+    async fn index_fts_since(&self, after_rowid: i64) -> Result<u64, RepositoryError>;
+
+    async fn find_summaries(
+        &self,
+        conversation_id: Uuid,
+    ) -> Result<Vec<StoredSummary>, RepositoryError>;
+    /// Semantic tags extracted for a conversation, most confident first
+    async fn find_tags(&self, conversation_id: Uuid) -> Result<Vec<StoredTag>, RepositoryError>;
+    /// Attach user-assigned tags (confidence 1.0) to a conversation
+    async fn add_tags(
+        &self,
+        conversation_id: Uuid,
+        tags: Vec<String>,
+    ) -> Result<(), RepositoryError>;
+    /// Attach user-assigned tags to many conversations in one transaction,
+    /// skipping tags a conversation already carries. One result per
+    /// requested ID, in request order.
+    async fn add_tags_many(
+        &self,
+        conversation_ids: Vec<Uuid>,
+        tags: Vec<String>,
+    ) -> Result<Vec<TagUpdate>, RepositoryError>;
+    /// Remove `tag` from many conversations in one transaction. One result
+    /// per requested ID, in request order.
+    async fn remove_tag_from_many(
+        &self,
+        conversation_ids: Vec<Uuid>,
+        tag: &str,
+    ) -> Result<Vec<TagUpdate>, RepositoryError>;
+
+    /// Curated labels on a conversation besides its primary one, heaviest
+    /// first
+    async fn find_labels(
+        &self,
+        conversation_id: Uuid,
+    ) -> Result<Vec<ConversationLabel>, RepositoryError>;
+    /// Give a conversation an additional label, or change its weight if it
+    /// already has it
+    async fn add_label(
+        &self,
+        conversation_id: Uuid,
+        label: &str,
+        weight: f64,
+    ) -> Result<(), RepositoryError>;
+    /// Take an additional label off a conversation, returning whether it
+    /// had it. The primary label is changed with `update_label` instead.
+    async fn remove_label(
+        &self,
+        conversation_id: Uuid,
+        label: &str,
+    ) -> Result<bool, RepositoryError>;
+    /// Conversations whose primary or additional labels include any of
+    /// `labels` (exact match), with the weight of their best match, heaviest
+    /// first
+    async fn find_by_any_label(
+        &self,
+        labels: &[String],
+    ) -> Result<Vec<(Conversation, f64)>, RepositoryError>;
+
+    /// Create or replace the template called `template.name`
+    async fn save_template(
+        &self,
+        template: ConversationTemplate,
+    ) -> Result<ConversationTemplate, RepositoryError>;
+    async fn find_template(
+        &self,
+        name: &str,
+    ) -> Result<Option<ConversationTemplate>, RepositoryError>;
+    /// All templates, by name
+    async fn list_templates(&self) -> Result<Vec<ConversationTemplate>, RepositoryError>;
+
+    /// Move a conversation, its messages, summaries, tags, labels and change
+    /// history to a JSON file in `dir`, removing them from the database and
+    /// vector store. A pointer row
+    /// records where the file went.
+    async fn archive_to_disk(&self, id: Uuid, dir: &Path)
+        -> Result<OffloadRecord, RepositoryError>;
+    /// Bring an offloaded conversation back from disk, re-embedding its messages
+    async fn restore_from_disk(&self, id: Uuid) -> Result<Conversation, RepositoryError>;
+    /// Embed any of `message_ids` still missing a vector, returning the IDs
+    /// that were embedded this time
+    async fn retry_embeddings(&self, message_ids: Vec<Uuid>) -> Result<Vec<Uuid>, RepositoryError>;
+    /// Delete vectors no message refers to and find messages whose vector is
+    /// missing, e.g. after a crash between the database and vector store writes.
+    /// Vectors embedded in the last few minutes are kept, as their messages
+    /// may still be being written.
+    async fn reconcile_embeddings(&self) -> Result<EmbeddingReconciliation, RepositoryError>;
+    /// Generate fresh vectors for every message of the conversations matching
+    /// `filter`, e.g. after changing embedding preprocessing
+    async fn reembed_scope(
+        &self,
+        filter: ConversationFilter,
+    ) -> Result<ReembedReport, RepositoryError>;
+    /// Recompute every conversation's `word_count` from its messages and
+    /// count the messages along the way. `session_count` isn't touched: it
+    /// records sessions as they were started, which messages can't recover.
+    async fn recompute_aggregates(&self) -> Result<AggregateRecompute, RepositoryError>;
+
+    /// Latest applied migration, or `None` before any have been recorded
+    async fn schema_version(&self) -> Result<Option<String>, RepositoryError>;
+
     fn get_db(&self) -> &DatabaseConnection;
 }
 
 // ============================================
 // IMPLEMENTATION STRUCT
 // ============================================
+/// Default idle time after which appended messages start a new session
+pub const DEFAULT_SESSION_GAP_MINUTES: i64 = 30;
+
+/// Conversations loaded per batch when recomputing aggregates
+const RECOMPUTE_BATCH_SIZE: u64 = 200;
+
+/// How recently a vector can have been embedded for reconciliation to still
+/// leave it alone when no message references it
+const RECONCILE_GRACE_SECS: i64 = 300;
+
+/// How many more full-text matches are fetched when filters will discard some
+const FILTERED_FTS_FETCH_FACTOR: usize = 4;
+
+/// Lowest normalized Levenshtein similarity for a label to count as a fuzzy
+/// match; labels containing the query (or contained by it) always match
+pub const FUZZY_LABEL_MIN_SIMILARITY: f64 = 0.7;
+
+/// How close `candidate` is to `query` on a 0.0-1.0 scale, ignoring case, or
+/// `None` if it isn't a fuzzy match at all
+pub fn label_similarity(query: &str, candidate: &str) -> Option<f64> {
+    let query = query.trim().to_lowercase();
+    let candidate = candidate.trim().to_lowercase();
+    if query.is_empty() || candidate.is_empty() {
+        return None;
+    }
+
+    let similarity = strsim::normalized_levenshtein(&query, &candidate);
+    if similarity >= FUZZY_LABEL_MIN_SIMILARITY
+        || candidate.contains(&query)
+        || query.contains(&candidate)
+    {
+        Some(similarity)
+    } else {
+        None
+    }
+}
+
 pub struct SeaOrmConversationRepository {
     db: DatabaseConnection,
+    /// Used by list, count, lookup and search paths; the primary unless a
+    /// read-only connection was supplied
+    read_db: DatabaseConnection,
     chroma: Arc<ChromaClient>,
     embedding_service: Arc<EmbeddingService>,
+    session_gap: chrono::Duration,
+    search_fallback: SearchFallback,
+    /// Words dropped from natural-language FTS queries
+    fts_stop_words: Vec<String>,
+    fts_ranking: FtsRanking,
+    /// Scrubs message content before it is stored or embedded
+    redactor: Redactor,
+    /// Messages with fewer (trimmed) characters are stored without a vector
+    min_embed_length: usize,
+    embeddings_enabled: bool,
+    /// How IDs for new conversations and messages are generated
+    id_scheme: IdScheme,
 }
 
 impl SeaOrmConversationRepository {
@@ -214,375 +865,2622 @@ impl SeaOrmConversationRepository {
         embedding_service: Arc<EmbeddingService>,
     ) -> Self {
         Self {
+            read_db: db.clone(),
             db,
             chroma,
             embedding_service,
+            session_gap: chrono::Duration::minutes(DEFAULT_SESSION_GAP_MINUTES),
+            search_fallback: SearchFallback::default(),
+            fts_stop_words: Vec::new(),
+            fts_ranking: FtsRanking::default(),
+            redactor: Redactor::default(),
+            min_embed_length: 0,
+            embeddings_enabled: true,
+            id_scheme: IdScheme::default(),
         }
     }
-}
 
-#[async_trait]
-impl ConversationRepository for SeaOrmConversationRepository {
-    fn get_db(&self) -> &DatabaseConnection {
-        &self.db
+    /// Serve read paths from `read_db` (see [`crate::storage::db::init_read_db`])
+    /// so they don't compete with writes on the primary connection
+    pub fn with_read_connection(mut self, read_db: DatabaseConnection) -> Self {
+        self.read_db = read_db;
+        self
     }
 
-    async fn create(&self, conv: Conversation) -> Result<Uuid, RepositoryError> {
-        use sea_orm::Set;
+    /// Generate IDs for new conversations and messages under `scheme`
+    pub fn with_id_scheme(mut self, scheme: IdScheme) -> Self {
+        self.id_scheme = scheme;
+        self
+    }
 
-        let active_model = conversations::ActiveModel {
-            id: Set(conv.id),
-            label: Set(conv.label),
-            folder: Set(conv.folder),
-            status: Set(conv.status),
-            importance_score: Set(conv.importance_score),
-            word_count: Set(conv.word_count),
-            session_count: Set(conv.session_count),
-            created_at: Set(conv.created_at),
-            updated_at: Set(conv.updated_at),
-        };
+    /// Choose what `semantic_search` does when the vector store fails
+    pub fn with_search_fallback(mut self, search_fallback: SearchFallback) -> Self {
+        self.search_fallback = search_fallback;
+        self
+    }
 
-        active_model.insert(&self.db).await.map_err(|e| {
-            tracing::error!("Failed to insert conversation: {:?}", e);
-            RepositoryError::DbError(e)
-        })?;
+    /// Ignore these words when turning search text into an FTS query
+    pub fn with_fts_stop_words(mut self, stop_words: Vec<String>) -> Self {
+        self.fts_stop_words = stop_words;
+        self
+    }
 
-        tracing::info!("Created conversation: {}", conv.id);
-        Ok(conv.id)
+    /// Order full-text matches by `ranking` instead of plain BM25
+    pub fn with_fts_ranking(mut self, ranking: FtsRanking) -> Self {
+        self.fts_ranking = ranking;
+        self
     }
 
-    async fn create_with_messages(&self, conv: NewConversation) -> Result<Uuid, RepositoryError> {
-        let conv_id = conv.id.unwrap_or_else(Uuid::new_v4);
-        let word_count_calc: i32 = conv.messages.iter().map(|m| m.content.len() as i32).sum();
-
-        // Extract fields before moving conv
-        let importance_score = conv.importance_score.unwrap_or(5);
-        let session_count = conv.session_count.unwrap_or(1);
-        let created_at = conv.created_at;
-        let updated_at = conv.updated_at;
-        let label = conv.label;
-        let folder = conv.folder;
-        let status = conv.status;
-        let messages = conv.messages; // Move messages here
+    /// Redact new message content with `redactor` before it is stored or embedded
+    pub fn with_redactor(mut self, redactor: Redactor) -> Self {
+        self.redactor = redactor;
+        self
+    }
 
-        let conversation = conversations::ActiveModel {
-            id: Set(conv_id),
-            label: Set(label),
-            folder: Set(folder),
-            status: Set(status),
-            importance_score: Set(importance_score as i32),
-            word_count: Set(word_count_calc),
-            session_count: Set(session_count),
-            created_at: Set(created_at),
-            updated_at: Set(updated_at),
-        };
+    /// Store messages shorter than `chars` characters (ignoring surrounding
+    /// whitespace) without an embedding; they stay full-text searchable
+    pub fn with_min_embed_length(mut self, chars: usize) -> Self {
+        self.min_embed_length = chars;
+        self
+    }
 
-        conversation.insert(&self.db).await.map_err(|e| {
-            tracing::error!("Failed to insert conversation: {:?}", e);
-            RepositoryError::DbError(e)
-        })?;
+    /// Whether `msg` is too short to embed; if so, the reason is recorded
+    /// in its metadata
+    fn skip_short_message(&self, msg: &mut NewMessage) -> bool {
+        if !self.embeddings_enabled || msg.content.trim().chars().count() >= self.min_embed_length {
+            return false;
+        }
 
-        tracing::info!("Created conversation: {}", conv_id);
+        if !msg.metadata.is_object() {
+            msg.metadata = json!({});
+        }
+        msg.metadata[EMBEDDING_SKIPPED_KEY] = json!("below_min_length");
+        true
+    }
 
-        // Process messages with explicit error handling and embedding generation
-        for (idx, msg) in messages.into_iter().enumerate() {
-            let msg_id = Uuid::new_v4();
-            let now = chrono::Utc::now().naive_utc();
+    /// Apply the redactor to `msg`, counting replacements in its metadata
+    fn redact_message(&self, mut msg: NewMessage) -> NewMessage {
+        if self.redactor.is_empty() {
+            return msg;
+        }
 
-            // Clone content for embedding
-            let content_clone = msg.content.clone();
+        let (content, redactions) = self.redactor.redact(&msg.content);
+        if redactions > 0 {
+            msg.content = content;
+            if !msg.metadata.is_object() {
+                msg.metadata = json!({});
+            }
+            msg.metadata["redactions"] = json!(redactions);
+        }
+        msg
+    }
 
-            // Generate embedding via service (graceful degradation if service is down)
-            let embedding_id = match self
-                .embedding_service
-                .process_message(
-                    msg_id,
-                    &content_clone,
-                    conv_id,
-                    serde_json::json!({
-                        "role": msg.role.clone(),
-                        "conversation_id": conv_id.to_string(),
-                        "timestamp": now,
-                    }),
-                )
-                .await
-            {
-                Ok(id) => Some(ToString::to_string(&id)),
-                Err(e) => {
-                    tracing::warn!("Embedding generation failed (ok in tests): {}", e);
-                    None
-                }
-            };
+    /// With embeddings disabled, messages are stored without vectors and
+    /// `semantic_search` answers from the full-text index
+    pub fn with_embeddings_enabled(mut self, enabled: bool) -> Self {
+        self.embeddings_enabled = enabled;
+        self
+    }
 
-            // Capture before move
-            let has_embedding = embedding_id.is_some();
+    /// Embed `requests`, or report nothing embedded when embeddings are off
+    async fn embed_batch(&self, requests: Vec<EmbeddingRequest>) -> BatchEmbeddingReport {
+        if !self.embeddings_enabled {
+            return BatchEmbeddingReport::default();
+        }
+        self.embedding_service
+            .process_messages_batch(requests)
+            .await
+    }
 
-            let message = messages::ActiveModel {
-                id: Set(msg_id),
-                conversation_id: Set(conv_id),
-                role: Set(msg.role),
-                content: Set(msg.content),
-                timestamp: Set(msg.timestamp),
-                embedding_id: Set(embedding_id),
-                metadata: Set(Some(msg.metadata)),
-            };
+    /// Full-text matches for a free-form query, shaped like semantic results
+    /// and limited by the same `where` filters
+    async fn fts_search_results(
+        &self,
+        query: &str,
+        limit: usize,
+        filters: Option<&JsonValue>,
+    ) -> Result<Vec<SearchResult>, RepositoryError> {
+        let fts_query = fts_match_query_filtered(query, &self.fts_stop_words);
+        if fts_query.is_empty() {
+            return Ok(vec![]);
+        }
 
-            if let Err(e) = message.insert(&self.db).await {
-                tracing::error!("Failed to insert message {}: {:?}", idx, e);
-                return Err(RepositoryError::DbError(e));
-            }
+        // A single conversation can be narrowed in SQL; other filters are
+        // checked per message, so fetch extra to keep the page close to full
+        let conversation_id = filters
+            .and_then(|f| f.get("conversation_id"))
+            .and_then(JsonValue::as_str)
+            .and_then(|id| Uuid::parse_str(id).ok());
+        let fetch = if filters.is_some() {
+            limit.saturating_mul(FILTERED_FTS_FETCH_FACTOR)
+        } else {
+            limit
+        };
+        let messages: Vec<Message> = self
+            .full_text_search(&fts_query, fetch, None, None, conversation_id)
+            .await?
+            .into_iter()
+            .filter(|m| filters.map_or(true, |f| matches_where(f, &vector_metadata(m))))
+            .take(limit)
+            .collect();
 
-            // FTS insertion is handled automatically by database triggers (see 007_create_fts.sql)
-            // No need for manual FTS insertion here
+        let conversations: HashMap<Uuid, conversations::Model> = conversations::Entity::find()
+            .filter(conversations::Column::Id.is_in(messages.iter().map(|m| m.conversation_id)))
+            .all(&self.read_db)
+            .await?
+            .into_iter()
+            .map(|c| (c.id, c))
+            .collect();
 
-            tracing::debug!(
-                "Inserted message {} for conversation {} with embedding: {}",
-                msg_id,
-                conv_id,
-                has_embedding
-            );
-        }
+        Ok(messages
+            .into_iter()
+            .filter_map(|message| {
+                let conversation = conversations.get(&message.conversation_id)?;
+                Some(SearchResult {
+                    conversation_id: conversation.id,
+                    message_id: message.id,
+                    score: 0.0,
+                    role: message.role,
+                    content: message.content,
+                    metadata: message.metadata.unwrap_or_else(|| json!({})),
+                    label: conversation.label.clone(),
+                    folder: conversation.folder.clone(),
+                    timestamp: message.timestamp,
+                })
+            })
+            .collect())
+    }
 
-        Ok(conv_id)
+    /// Override how long a conversation must be idle before appended
+    /// messages count as a new session
+    pub fn with_session_gap_minutes(mut self, minutes: i64) -> Self {
+        self.session_gap = chrono::Duration::minutes(minutes);
+        self
     }
 
-    async fn delete(&self, id: Uuid) -> Result<(), RepositoryError> {
-        if let Ok(Some(_conv)) = self.find_by_id(id).await {
-            let messages = messages::Entity::find()
-                .filter(messages::Column::ConversationId.eq(id))
-                .all(&self.db)
-                .await?;
+    /// Highest `messages` rowid, or 0 when there are none
+    async fn max_message_rowid<C: ConnectionTrait>(conn: &C) -> Result<i64, RepositoryError> {
+        #[derive(FromQueryResult)]
+        struct MaxRowid {
+            max_rowid: Option<i64>,
+        }
 
-            let embedding_ids: Vec<String> = messages
-                .into_iter()
-                .filter_map(|m| m.embedding_id)
-                .collect();
+        Ok(MaxRowid::find_by_statement(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            "SELECT MAX(rowid) AS max_rowid FROM messages",
+        ))
+        .one(conn)
+        .await?
+        .and_then(|r| r.max_rowid)
+        .unwrap_or(0))
+    }
 
-            if !embedding_ids.is_empty() {
-                self.chroma.delete("messages", embedding_ids).await?;
-            }
+    /// Index messages with `after < rowid <= upto` that `messages_fts` doesn't
+    /// hold yet, a batch at a time
+    async fn index_fts_range<C: ConnectionTrait>(
+        conn: &C,
+        after: i64,
+        upto: i64,
+    ) -> Result<u64, RepositoryError> {
+        const BATCH_SIZE: i64 = 1000;
+
+        let mut indexed = 0;
+        let mut start = after;
+        while start < upto {
+            let result = conn
+                .execute_raw(Statement::from_sql_and_values(
+                    DatabaseBackend::Sqlite,
+                    "INSERT INTO messages_fts(rowid, content) \
+                     SELECT rowid, content FROM messages WHERE rowid > ?1 AND rowid <= ?2 \
+                     AND rowid NOT IN (SELECT rowid FROM messages_fts)",
+                    vec![
+                        Value::BigInt(Some(start)),
+                        Value::BigInt(Some((start + BATCH_SIZE).min(upto))),
+                    ],
+                ))
+                .await?;
+            indexed += result.rows_affected();
+            start += BATCH_SIZE;
         }
+        Ok(indexed)
+    }
 
-        conversations::Entity::delete_by_id(id)
-            .exec(&self.db)
+    /// Which of `ids` name a stored conversation
+    async fn existing_conversation_ids<C: ConnectionTrait>(
+        conn: &C,
+        ids: &[Uuid],
+    ) -> Result<HashSet<Uuid>, RepositoryError> {
+        let models = conversations::Entity::find()
+            .filter(conversations::Column::Id.is_in(ids.iter().copied()))
+            .all(conn)
             .await?;
+        Ok(models.into_iter().map(|m| m.id).collect())
+    }
+
+    /// Set a conversation's word count from the messages it has now
+    async fn recount_words<C: ConnectionTrait>(
+        conn: &C,
+        conversation_id: Uuid,
+    ) -> Result<(), RepositoryError> {
+        // Same measure as `stored_word_count`: the content's length in bytes
+        conn.execute_raw(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            "UPDATE conversations SET word_count = \
+             (SELECT COALESCE(SUM(LENGTH(CAST(content AS BLOB))), 0) \
+             FROM messages WHERE conversation_id = ?1) WHERE id = ?1",
+            vec![conversation_id.into()],
+        ))
+        .await?;
         Ok(())
     }
 
-    async fn count_by_label(&self, label: &str) -> Result<u64, RepositoryError> {
-        let count = conversations::Entity::find()
-            .filter(conversations::Column::Label.contains(label))
-            .count(&self.db)
+    async fn bump_session_count<C: ConnectionTrait>(
+        conn: &C,
+        id: Uuid,
+    ) -> Result<(), RepositoryError> {
+        let result = conversations::Entity::update_many()
+            .col_expr(
+                conversations::Column::SessionCount,
+                // Not imported: ExprTrait would shadow `is_null` on JSON values below
+                sea_orm::sea_query::ExprTrait::add(
+                    Expr::col(conversations::Column::SessionCount),
+                    1,
+                ),
+            )
+            .filter(conversations::Column::Id.eq(id))
+            .exec(conn)
             .await?;
-        Ok(count)
-    }
+
+        if result.rows_affected == 0 {
+            return Err(RepositoryError::NotFound(format!(
+                "Conversation {} not found",
+                id
+            )));
+        }
+        Ok(())
+    }
+
+    /// Point each message that `report` embedded at its new vector, noting
+    /// the model and dimension that produced it
+    async fn record_embedded(&self, report: &BatchEmbeddingReport) -> Result<(), RepositoryError> {
+        let model = self.embedding_service.model();
+        let dim = report.dimension.map(|dim| dim as i32);
+        for msg_id in &report.succeeded {
+            messages::Entity::update_many()
+                .col_expr(
+                    messages::Column::EmbeddingId,
+                    Expr::value(msg_id.to_string()),
+                )
+                .col_expr(messages::Column::EmbeddingModel, Expr::value(model))
+                .col_expr(messages::Column::EmbeddingDim, Expr::value(dim))
+                .filter(messages::Column::Id.eq(*msg_id))
+                .exec(&self.db)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Mark messages as having no vector, so retries and reconciliation pick
+    /// them up again
+    async fn clear_embeddings(&self, message_ids: Vec<Uuid>) -> Result<(), RepositoryError> {
+        if message_ids.is_empty() {
+            return Ok(());
+        }
+
+        messages::Entity::update_many()
+            .col_expr(
+                messages::Column::EmbeddingId,
+                Expr::value(Value::String(None)),
+            )
+            .col_expr(
+                messages::Column::EmbeddingModel,
+                Expr::value(Value::String(None)),
+            )
+            .col_expr(
+                messages::Column::EmbeddingDim,
+                Expr::value(Value::Int(None)),
+            )
+            .filter(messages::Column::Id.is_in(message_ids))
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    /// What to store for `msg_id` after `report`, if it was embedded
+    fn stored_embedding(
+        &self,
+        report: &BatchEmbeddingReport,
+        msg_id: Uuid,
+    ) -> Option<StoredEmbedding> {
+        report.succeeded.contains(&msg_id).then(|| StoredEmbedding {
+            id: msg_id.to_string(),
+            model: self.embedding_service.model().to_string(),
+            dim: report.dimension.map(|dim| dim as i32),
+        })
+    }
+
+    /// Remove every vector of the given messages, chunks included, from the
+    /// primary collection and every secondary model. Returns how many primary
+    /// vectors Chroma removed.
+    async fn remove_vectors(&self, embedding_ids: &[String]) -> Result<usize, RepositoryError> {
+        if embedding_ids.is_empty() {
+            return Ok(0);
+        }
+
+        // Vectors carry their message's id in `message_id` metadata, which
+        // also matches the `{id}:{n}` chunks of long messages
+        let removed = self
+            .chroma
+            .delete_for_messages(DEFAULT_COLLECTION, embedding_ids)
+            .await?;
+        self.embedding_service
+            .delete_from_secondary_models(embedding_ids)
+            .await;
+        Ok(removed)
+    }
+
+    /// Like [`Self::remove_vectors`], but failures are only logged, as a
+    /// stray vector is harmless.
+    async fn drop_vectors(&self, embedding_ids: Vec<String>) {
+        if let Err(e) = self.remove_vectors(&embedding_ids).await {
+            tracing::warn!("Failed to remove vectors: {}", e);
+        }
+    }
+
+    /// Generate embeddings for new messages before any write transaction is
+    /// opened, so the external calls don't hold it (graceful degradation if
+    /// the service is down)
+    async fn embed_new_messages(
+        &self,
+        conversation_id: Uuid,
+        messages: Vec<NewMessage>,
+    ) -> Vec<(Uuid, NewMessage, Option<StoredEmbedding>)> {
+        let now = chrono::Utc::now().naive_utc();
+        let mut messages: Vec<NewMessage> = messages
+            .into_iter()
+            .map(|msg| self.redact_message(msg))
+            .collect();
+        let message_ids: Vec<Uuid> = messages.iter().map(|_| new_id(self.id_scheme)).collect();
+        let skipped: Vec<bool> = messages
+            .iter_mut()
+            .map(|msg| self.skip_short_message(msg))
+            .collect();
+
+        let requests = messages
+            .iter()
+            .zip(&message_ids)
+            .zip(&skipped)
+            .filter(|(_, skip)| !**skip)
+            .map(|((msg, msg_id), _)| EmbeddingRequest {
+                message_id: *msg_id,
+                conversation_id,
+                content: msg.content.clone(),
+                metadata: serde_json::json!({
+                    "role": msg.role.clone(),
+                    "conversation_id": conversation_id.to_string(),
+                    "timestamp": now,
+                }),
+            })
+            .collect();
+
+        let report = self.embed_batch(requests).await;
+        for (msg_id, error) in &report.failed {
+            tracing::warn!(
+                "Embedding generation failed for message {} (ok in tests): {}",
+                msg_id,
+                error
+            );
+        }
+
+        message_ids
+            .into_iter()
+            .zip(messages)
+            .map(|(msg_id, msg)| {
+                let embedding = self.stored_embedding(&report, msg_id);
+                (msg_id, msg, embedding)
+            })
+            .collect()
+    }
+
+    /// IDs matched by a bulk operation's selector, so dry runs and real runs
+    /// agree on exactly which rows are touched
+    async fn affected_ids<C: ConnectionTrait>(
+        conn: &C,
+        select: Select<conversations::Entity>,
+    ) -> Result<Vec<Uuid>, RepositoryError> {
+        let ids = select
+            .select_only()
+            .column(conversations::Column::Id)
+            .order_by_asc(conversations::Column::Id)
+            .into_tuple::<Uuid>()
+            .all(conn)
+            .await?;
+
+        Ok(ids)
+    }
+
+    /// Audit-log each tracked field that differs between `before` and `after`
+    async fn record_changes<C: ConnectionTrait>(
+        conn: &C,
+        before: &conversations::Model,
+        after: &conversations::Model,
+    ) -> Result<(), RepositoryError> {
+        let changed_at = chrono::Utc::now().naive_utc();
+        let fields = [
+            ("label", &before.label, &after.label),
+            ("folder", &before.folder, &after.folder),
+            ("status", &before.status, &after.status),
+            (
+                "importance_score",
+                &before.importance_score.to_string(),
+                &after.importance_score.to_string(),
+            ),
+        ];
+
+        for (field, old_value, new_value) in fields {
+            if old_value == new_value {
+                continue;
+            }
+
+            conversation_history::Entity::insert(conversation_history::ActiveModel {
+                conversation_id: Set(after.id),
+                field: Set(field.to_string()),
+                old_value: Set(old_value.clone()),
+                new_value: Set(new_value.clone()),
+                changed_at: Set(changed_at),
+                ..Default::default()
+            })
+            .exec(conn)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// The `seq` the next message inserted into `conversation_id` should get
+    async fn next_message_seq<C: ConnectionTrait>(
+        conn: &C,
+        conversation_id: Uuid,
+    ) -> Result<i64, RepositoryError> {
+        let max_seq = messages::Entity::find()
+            .select_only()
+            .column_as(messages::Column::Seq.max(), "max_seq")
+            .filter(messages::Column::ConversationId.eq(conversation_id))
+            .into_tuple::<Option<i64>>()
+            .one(conn)
+            .await?
+            .flatten();
+
+        Ok(max_seq.map_or(0, |seq| seq + 1))
+    }
+
+    /// Attach user-assigned `tags` to a conversation
+    async fn insert_tags<C: ConnectionTrait>(
+        conn: &C,
+        conversation_id: Uuid,
+        tags: Vec<String>,
+    ) -> Result<(), RepositoryError> {
+        if tags.is_empty() {
+            return Ok(());
+        }
+
+        let now = chrono::Utc::now().naive_utc();
+        semantic_tags::Entity::insert_many(tags.into_iter().map(|tag| {
+            semantic_tags::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                conversation_id: Set(conversation_id),
+                tag: Set(tag),
+                confidence: Set(1.0),
+                extracted_at: Set(now),
+            }
+        }))
+        .exec(conn)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Row for a new conversation `conv_id`, with its messages split off to be
+    /// embedded. The word count is left for the caller to set from what is stored.
+    fn new_conversation_model(
+        conv_id: Uuid,
+        conv: NewConversation,
+    ) -> (conversations::ActiveModel, Vec<NewMessage>) {
+        let importance_score = conv
+            .importance_score
+            .unwrap_or(importance::DEFAULT_IMPORTANCE);
+        let model = conversations::ActiveModel {
+            id: Set(conv_id),
+            label: Set(conv.label),
+            folder: Set(conv.folder),
+            status: Set(conv.status),
+            importance_score: Set(importance_score as i32),
+            word_count: Set(0),
+            session_count: Set(conv.session_count.unwrap_or(1)),
+            created_at: Set(conv.created_at),
+            updated_at: Set(conv.updated_at),
+            context_pinned: Set(false),
+            last_accessed_at: Set(None),
+        };
+        (model, conv.messages)
+    }
+
+    /// Inserts `prepared` in order, so messages sharing a timestamp come back
+    /// in the order they were given
+    async fn insert_prepared_messages<C: ConnectionTrait>(
+        conn: &C,
+        conversation_id: Uuid,
+        prepared: Vec<(Uuid, NewMessage, Option<StoredEmbedding>)>,
+    ) -> Result<Vec<Uuid>, RepositoryError> {
+        let mut message_ids = Vec::with_capacity(prepared.len());
+        let first_seq = Self::next_message_seq(conn, conversation_id).await?;
+
+        for (idx, (msg_id, msg, embedding)) in prepared.into_iter().enumerate() {
+            let has_embedding = embedding.is_some();
+
+            let message = messages::ActiveModel {
+                id: Set(msg_id),
+                conversation_id: Set(conversation_id),
+                role: Set(msg.role),
+                content: Set(msg.content),
+                timestamp: Set(msg.timestamp),
+                embedding_id: Set(embedding.as_ref().map(|e| e.id.clone())),
+                metadata: Set(Some(msg.metadata)),
+                seq: Set(first_seq + idx as i64),
+                embedding_model: Set(embedding.as_ref().map(|e| e.model.clone())),
+                embedding_dim: Set(embedding.and_then(|e| e.dim)),
+            };
+
+            if let Err(e) = message.insert(conn).await {
+                tracing::error!("Failed to insert message {}: {:?}", idx, e);
+                return Err(RepositoryError::DbError(e));
+            }
+
+            tracing::debug!(
+                "Inserted message {} for conversation {} with embedding: {}",
+                msg_id,
+                conversation_id,
+                has_embedding
+            );
+            message_ids.push(msg_id);
+        }
+
+        Ok(message_ids)
+    }
+}
+
+#[async_trait]
+impl ConversationRepository for SeaOrmConversationRepository {
+    fn get_db(&self) -> &DatabaseConnection {
+        &self.db
+    }
+
+    async fn schema_version(&self) -> Result<Option<String>, RepositoryError> {
+        Ok(crate::storage::db::schema_version(&self.read_db).await?)
+    }
+
+    async fn create(&self, conv: Conversation) -> Result<Uuid, RepositoryError> {
+        use sea_orm::Set;
+
+        let active_model = conversations::ActiveModel {
+            id: Set(conv.id),
+            label: Set(conv.label),
+            folder: Set(conv.folder),
+            status: Set(conv.status),
+            importance_score: Set(conv.importance_score),
+            word_count: Set(conv.word_count),
+            session_count: Set(conv.session_count),
+            created_at: Set(conv.created_at),
+            updated_at: Set(conv.updated_at),
+            context_pinned: Set(conv.context_pinned),
+            last_accessed_at: Set(conv.last_accessed_at),
+        };
+
+        active_model.insert(&self.db).await.map_err(|e| {
+            tracing::error!("Failed to insert conversation: {:?}", e);
+            RepositoryError::DbError(e)
+        })?;
+
+        tracing::info!("Created conversation: {}", conv.id);
+        Ok(conv.id)
+    }
+
+    async fn create_with_messages(&self, conv: NewConversation) -> Result<Uuid, RepositoryError> {
+        Ok(self.create_with_message_ids(conv).await?.id)
+    }
+
+    async fn create_with_message_ids(
+        &self,
+        conv: NewConversation,
+    ) -> Result<CreatedConversation, RepositoryError> {
+        self.create_with_tags(conv, Vec::new()).await
+    }
+
+    async fn create_with_tags(
+        &self,
+        conv: NewConversation,
+        tags: Vec<String>,
+    ) -> Result<CreatedConversation, RepositoryError> {
+        let conv_id = conv.id.unwrap_or_else(|| new_id(self.id_scheme));
+        let (conversation, messages) = Self::new_conversation_model(conv_id, conv);
+        let prepared = self.embed_new_messages(conv_id, messages).await;
+        // Counted on the redacted content that is actually stored
+        let word_count = stored_word_count(&prepared);
+        let conversation = conversations::ActiveModel {
+            word_count: Set(word_count),
+            ..conversation
+        };
+
+        // Conversation, messages and their FTS rows (written by the triggers in
+        // 007_create_fts.sql) become visible together once this commits, so a
+        // count issued after we return always includes this conversation.
+        let txn = self.db.begin().await?;
+
+        conversation.insert(&txn).await.map_err(|e| {
+            tracing::error!("Failed to insert conversation: {:?}", e);
+            RepositoryError::DbError(e)
+        })?;
+
+        let message_ids = Self::insert_prepared_messages(&txn, conv_id, prepared).await?;
+        Self::insert_tags(&txn, conv_id, tags).await?;
+
+        txn.commit().await?;
+        tracing::info!("Created conversation: {}", conv_id);
+
+        Ok(CreatedConversation {
+            id: conv_id,
+            message_ids,
+            word_count,
+        })
+    }
+
+    async fn upsert_import(&self, conv: NewConversation) -> Result<Uuid, RepositoryError> {
+        let conv_id = conv.id.ok_or_else(|| {
+            RepositoryError::InvalidInput("an imported conversation needs an ID".to_string())
+        })?;
+        let (conversation, messages) = Self::new_conversation_model(conv_id, conv);
+        let prepared = self.embed_new_messages(conv_id, messages).await;
+        let word_count = stored_word_count(&prepared);
+
+        let txn = self.db.begin().await?;
+
+        let replaced_vectors = match conversations::Entity::find_by_id(conv_id).one(&txn).await? {
+            Some(existing) => {
+                let embedding_ids: Vec<String> = messages::Entity::find()
+                    .select_only()
+                    .column(messages::Column::EmbeddingId)
+                    .filter(messages::Column::ConversationId.eq(conv_id))
+                    .filter(messages::Column::EmbeddingId.is_not_null())
+                    .into_tuple::<String>()
+                    .all(&txn)
+                    .await?;
+                messages::Entity::delete_many()
+                    .filter(messages::Column::ConversationId.eq(conv_id))
+                    .exec(&txn)
+                    .await?;
+
+                let mut active_model: conversations::ActiveModel = existing.into_active_model();
+                active_model.word_count = Set(word_count);
+                active_model.update(&txn).await?;
+                Some(embedding_ids)
+            }
+            None => {
+                conversations::ActiveModel {
+                    word_count: Set(word_count),
+                    ..conversation
+                }
+                .insert(&txn)
+                .await?;
+                None
+            }
+        };
+        Self::insert_prepared_messages(&txn, conv_id, prepared).await?;
+
+        txn.commit().await?;
+
+        // The old messages are gone, so their vectors only point at nothing
+        if let Some(embedding_ids) = replaced_vectors {
+            tracing::info!("Replaced previously imported conversation {}", conv_id);
+            self.drop_vectors(embedding_ids).await;
+        }
+        Ok(conv_id)
+    }
+
+    async fn append_messages(
+        &self,
+        conversation_id: Uuid,
+        messages: Vec<NewMessage>,
+    ) -> Result<AppendedMessages, RepositoryError> {
+        conversations::Entity::find_by_id(conversation_id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| {
+                RepositoryError::NotFound(format!("Conversation {} not found", conversation_id))
+            })?;
+
+        let last_message = messages::Entity::find()
+            .filter(messages::Column::ConversationId.eq(conversation_id))
+            .order_by_desc(messages::Column::Timestamp)
+            .order_by_desc(messages::Column::Seq)
+            .one(&self.db)
+            .await?;
+        let starts_new_session = match (last_message, messages.iter().map(|m| m.timestamp).min()) {
+            (Some(last), Some(first_new)) => first_new - last.timestamp > self.session_gap,
+            _ => false,
+        };
+
+        let prepared = self.embed_new_messages(conversation_id, messages).await;
+        let added_words = stored_word_count(&prepared);
+
+        let txn = self.db.begin().await?;
+
+        let message_ids = Self::insert_prepared_messages(&txn, conversation_id, prepared).await?;
+        if starts_new_session {
+            Self::bump_session_count(&txn, conversation_id).await?;
+        }
+
+        // Added in SQL, so concurrent appends can't overwrite each other's count
+        conversations::Entity::update_many()
+            .col_expr(
+                conversations::Column::WordCount,
+                sea_orm::sea_query::ExprTrait::add(
+                    Expr::col(conversations::Column::WordCount),
+                    added_words,
+                ),
+            )
+            .col_expr(
+                conversations::Column::UpdatedAt,
+                Expr::value(chrono::Utc::now().naive_utc()),
+            )
+            .filter(conversations::Column::Id.eq(conversation_id))
+            .exec(&txn)
+            .await?;
+        let word_count = conversations::Entity::find_by_id(conversation_id)
+            .select_only()
+            .column(conversations::Column::WordCount)
+            .into_tuple::<i32>()
+            .one(&txn)
+            .await?
+            .unwrap_or(added_words);
+        let message_count = messages::Entity::find()
+            .filter(messages::Column::ConversationId.eq(conversation_id))
+            .count(&txn)
+            .await?;
+
+        txn.commit().await?;
+
+        Ok(AppendedMessages {
+            message_ids,
+            message_count,
+            word_count,
+        })
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<(), RepositoryError> {
+        if let Ok(Some(_conv)) = self.find_by_id(id).await {
+            let messages = messages::Entity::find()
+                .filter(messages::Column::ConversationId.eq(id))
+                .all(&self.db)
+                .await?;
+
+            let embedding_ids: Vec<String> = messages
+                .into_iter()
+                .filter_map(|m| m.embedding_id)
+                .collect();
+
+            self.remove_vectors(&embedding_ids).await?;
+        }
+
+        conversations::Entity::delete_by_id(id)
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete_many(&self, ids: Vec<Uuid>) -> Result<(Vec<Uuid>, usize), RepositoryError> {
+        let existing = Self::affected_ids(
+            &self.db,
+            conversations::Entity::find().filter(conversations::Column::Id.is_in(ids)),
+        )
+        .await?;
+        if existing.is_empty() {
+            return Ok((existing, 0));
+        }
+
+        let embedding_ids: Vec<String> = messages::Entity::find()
+            .select_only()
+            .column(messages::Column::EmbeddingId)
+            .filter(messages::Column::ConversationId.is_in(existing.clone()))
+            .filter(messages::Column::EmbeddingId.is_not_null())
+            .into_tuple::<String>()
+            .all(&self.db)
+            .await?;
+
+        let vectors_removed = self.remove_vectors(&embedding_ids).await?;
+
+        // Messages and summaries go with their conversation via ON DELETE CASCADE
+        let txn = self.db.begin().await?;
+        conversations::Entity::delete_many()
+            .filter(conversations::Column::Id.is_in(existing.clone()))
+            .exec(&txn)
+            .await?;
+        txn.commit().await?;
+
+        Ok((existing, vectors_removed))
+    }
+
+    async fn delete_messages(&self, message_ids: Vec<Uuid>) -> Result<u64, RepositoryError> {
+        if message_ids.is_empty() {
+            return Ok(0);
+        }
+
+        let txn = self.db.begin().await?;
+        let doomed: Vec<(Uuid, Uuid, Option<String>)> = messages::Entity::find()
+            .select_only()
+            .column(messages::Column::Id)
+            .column(messages::Column::ConversationId)
+            .column(messages::Column::EmbeddingId)
+            .filter(messages::Column::Id.is_in(dedup_ids(message_ids)))
+            .into_tuple()
+            .all(&txn)
+            .await?;
+
+        let result = messages::Entity::delete_many()
+            .filter(messages::Column::Id.is_in(doomed.iter().map(|(id, _, _)| *id)))
+            .exec(&txn)
+            .await?;
+        let conversation_ids: HashSet<Uuid> = doomed.iter().map(|(_, c, _)| *c).collect();
+        for conversation_id in conversation_ids {
+            Self::recount_words(&txn, conversation_id).await?;
+        }
+        txn.commit().await?;
+
+        // A leftover vector only points at a message that no longer exists
+        let embedding_ids = doomed.into_iter().filter_map(|(_, _, e)| e).collect();
+        self.drop_vectors(embedding_ids).await;
+
+        Ok(result.rows_affected)
+    }
+
+    async fn compact_messages(
+        &self,
+        conversation_id: Uuid,
+        message_ids: Vec<Uuid>,
+        summary: NewMessage,
+    ) -> Result<u64, RepositoryError> {
+        let model = conversations::Entity::find_by_id(conversation_id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| {
+                RepositoryError::NotFound(format!("Conversation {} not found", conversation_id))
+            })?;
+        let compacted = messages::Entity::find()
+            .filter(messages::Column::ConversationId.eq(conversation_id))
+            .filter(messages::Column::Id.is_in(dedup_ids(message_ids)))
+            .all(&self.db)
+            .await?;
+        if compacted.is_empty() {
+            return Ok(0);
+        }
+
+        let dropped_vectors: Vec<String> = compacted
+            .iter()
+            .filter_map(|m| m.embedding_id.clone())
+            .collect();
+        let removed_words: i32 = compacted.iter().map(|m| m.content.len() as i32).sum();
+        let prepared = self
+            .embed_new_messages(conversation_id, vec![summary])
+            .await;
+        let added_words = stored_word_count(&prepared);
+
+        let txn = self.db.begin().await?;
+
+        let count = compacted.len() as u64;
+        for msg in compacted {
+            let mut metadata = match msg.metadata.clone() {
+                Some(JsonValue::Object(map)) => map,
+                _ => serde_json::Map::new(),
+            };
+            metadata.insert(COMPACTED_KEY.to_string(), json!(true));
+            metadata.insert(EMBEDDING_SKIPPED_KEY.to_string(), json!("compacted"));
+
+            let mut active_message: messages::ActiveModel = msg.into_active_model();
+            active_message.content = Set(String::new());
+            active_message.embedding_id = Set(None);
+            active_message.embedding_model = Set(None);
+            active_message.embedding_dim = Set(None);
+            active_message.metadata = Set(Some(JsonValue::Object(metadata)));
+            active_message.update(&txn).await?;
+        }
+        Self::insert_prepared_messages(&txn, conversation_id, prepared).await?;
+
+        let word_count = model.word_count;
+        let mut active_model: conversations::ActiveModel = model.into_active_model();
+        active_model.word_count = Set((word_count - removed_words).max(0) + added_words);
+        active_model.updated_at = Set(chrono::Utc::now().naive_utc());
+        active_model.update(&txn).await?;
+
+        txn.commit().await?;
+
+        // Only once the bodies are gone, so a failed compaction keeps them
+        // searchable; from here on search only sees the summary
+        self.drop_vectors(dropped_vectors).await;
+
+        Ok(count)
+    }
+
+    async fn count_by_label(&self, label: &str) -> Result<u64, RepositoryError> {
+        let count = conversations::Entity::find()
+            .filter(conversations::Column::Label.contains(label))
+            .count(&self.read_db)
+            .await?;
+        Ok(count)
+    }
 
     async fn count_by_folder(&self, folder: &str) -> Result<u64, RepositoryError> {
         let count = conversations::Entity::find()
             .filter(conversations::Column::Folder.eq(folder))
-            .count(&self.db)
+            .count(&self.read_db)
+            .await?;
+
+        Ok(count)
+    }
+
+    async fn count_all(&self) -> Result<u64, RepositoryError> {
+        let count = conversations::Entity::find().count(&self.read_db).await?;
+
+        Ok(count)
+    }
+
+    async fn count_with_filters(
+        &self,
+        label: Option<String>,
+        folder: Option<String>,
+        status: Option<String>,
+    ) -> Result<u64, RepositoryError> {
+        let mut query = conversations::Entity::find();
+        // Same matching as count_by_label / count_by_folder
+        if let Some(label) = label {
+            query = query.filter(conversations::Column::Label.contains(label.as_str()));
+        }
+        if let Some(folder) = folder {
+            query = query.filter(conversations::Column::Folder.eq(folder));
+        }
+        if let Some(status) = status {
+            query = query.filter(conversations::Column::Status.eq(status));
+        }
+
+        Ok(query.count(&self.read_db).await?)
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<Conversation>, RepositoryError> {
+        let model = conversations::Entity::find_by_id(id)
+            .one(&self.read_db)
+            .await?;
+
+        Ok(model.map(Conversation::from))
+    }
+
+    async fn find_message_by_id(&self, id: Uuid) -> Result<Option<Message>, RepositoryError> {
+        let model = messages::Entity::find_by_id(id).one(&self.read_db).await?;
+
+        Ok(model.map(Message::from))
+    }
+
+    async fn update_message_metadata(
+        &self,
+        message_id: Uuid,
+        metadata: JsonValue,
+        merge: bool,
+    ) -> Result<Message, RepositoryError> {
+        let JsonValue::Object(patch) = metadata else {
+            return Err(RepositoryError::InvalidInput(
+                "metadata must be a JSON object".to_string(),
+            ));
+        };
+
+        if let Some(key) = RESERVED_METADATA_KEYS
+            .iter()
+            .find(|key| patch.contains_key(**key))
+        {
+            return Err(RepositoryError::InvalidInput(format!(
+                "metadata key '{}' is reserved",
+                key
+            )));
+        }
+
+        // Merge against the row this transaction writes, so concurrent
+        // updates can't drop each other's keys
+        let txn = self.db.begin().await?;
+
+        let model = messages::Entity::find_by_id(message_id)
+            .one(&txn)
+            .await?
+            .ok_or_else(|| {
+                RepositoryError::NotFound(format!("Message {} not found", message_id))
+            })?;
+
+        let updated = match (merge, model.metadata.clone()) {
+            (true, Some(JsonValue::Object(mut existing))) => {
+                existing.extend(patch);
+                existing
+            }
+            // Replacing still keeps what the controller recorded
+            (false, Some(JsonValue::Object(existing))) => {
+                let mut replaced: serde_json::Map<String, JsonValue> = existing
+                    .into_iter()
+                    .filter(|(key, _)| RESERVED_METADATA_KEYS.contains(&key.as_str()))
+                    .collect();
+                replaced.extend(patch);
+                replaced
+            }
+            _ => patch,
+        };
+
+        let mut active_model: messages::ActiveModel = model.into_active_model();
+        active_model.metadata = Set(Some(JsonValue::Object(updated)));
+        let updated = active_model.update(&txn).await?;
+
+        txn.commit().await?;
+        Ok(updated.into())
+    }
+
+    async fn move_message(
+        &self,
+        message_id: Uuid,
+        target_conversation_id: Uuid,
+    ) -> Result<Message, RepositoryError> {
+        // Validate against the rows this transaction updates, so a concurrent
+        // move or delete can't slip in between the checks and the writes
+        let txn = self.db.begin().await?;
+
+        let model = messages::Entity::find_by_id(message_id)
+            .one(&txn)
+            .await?
+            .ok_or_else(|| {
+                RepositoryError::NotFound(format!("Message {} not found", message_id))
+            })?;
+        let source_conversation_id = model.conversation_id;
+        if source_conversation_id == target_conversation_id {
+            txn.commit().await?;
+            return Ok(model.into());
+        }
+
+        let conversations = conversations::Entity::find()
+            .filter(
+                conversations::Column::Id.is_in([source_conversation_id, target_conversation_id]),
+            )
+            .all(&txn)
+            .await?;
+        let find = |id: Uuid| {
+            conversations
+                .iter()
+                .find(|c| c.id == id)
+                .cloned()
+                .ok_or_else(|| RepositoryError::NotFound(format!("Conversation {} not found", id)))
+        };
+        let source = find(source_conversation_id)?;
+        let target = find(target_conversation_id)?;
+
+        let words = model.content.len() as i32;
+        let now = chrono::Utc::now().naive_utc();
+
+        let seq = Self::next_message_seq(&txn, target_conversation_id).await?;
+        let mut active_message: messages::ActiveModel = model.into_active_model();
+        active_message.conversation_id = Set(target_conversation_id);
+        active_message.seq = Set(seq);
+        let mut moved: Message = active_message.update(&txn).await?.into();
+
+        for (conv, delta) in [(source, -words), (target, words)] {
+            let word_count = conv.word_count;
+            let mut active_model: conversations::ActiveModel = conv.into_active_model();
+            active_model.word_count = Set((word_count + delta).max(0));
+            active_model.updated_at = Set(now);
+            active_model.update(&txn).await?;
+        }
+
+        txn.commit().await?;
+
+        // Re-embedding rewrites every vector of the message (chunks included)
+        // with the new conversation in its metadata
+        if moved.embedding_id.is_some() {
+            let report = self.embed_batch(vec![embedding_request(&moved)]).await;
+            for (msg_id, error) in &report.failed {
+                tracing::warn!(
+                    "Failed to update vector metadata for moved message {}: {}",
+                    msg_id,
+                    error
+                );
+            }
+            self.record_embedded(&report).await?;
+            // The old vector still names the source conversation; marking the
+            // message unembedded gets it re-embedded instead of trusted
+            if !report.failed.is_empty() {
+                self.clear_embeddings(vec![message_id]).await?;
+                moved.embedding_id = None;
+                moved.embedding_model = None;
+                moved.embedding_dim = None;
+            }
+        }
+
+        Ok(moved)
+    }
+
+    async fn find_by_label(
+        &self,
+        label: &str,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<Conversation>, RepositoryError> {
+        let models = conversations::Entity::find()
+            .filter(conversations::Column::Label.eq(label))
+            .order_by_desc(conversations::Column::UpdatedAt)
+            .order_by_desc(conversations::Column::Id)
+            .limit(limit)
+            .offset(offset)
+            .all(&self.read_db)
+            .await?;
+
+        Ok(models.into_iter().map(Conversation::from).collect())
+    }
+
+    async fn find_by_label_fuzzy(
+        &self,
+        label: &str,
+        limit: u64,
+    ) -> Result<Vec<Conversation>, RepositoryError> {
+        let mut matches: Vec<(String, f64)> = self
+            .get_all_labels()
+            .await?
+            .into_iter()
+            .filter_map(|candidate| {
+                label_similarity(label, &candidate).map(|similarity| (candidate, similarity))
+            })
+            .collect();
+        if matches.is_empty() {
+            return Ok(Vec::new());
+        }
+        matches.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        let rank: HashMap<&str, usize> = matches
+            .iter()
+            .enumerate()
+            .map(|(i, (candidate, _))| (candidate.as_str(), i))
+            .collect();
+
+        let models = conversations::Entity::find()
+            .filter(
+                conversations::Column::Label
+                    .is_in(matches.iter().map(|(candidate, _)| candidate.clone())),
+            )
+            .order_by_desc(conversations::Column::UpdatedAt)
+            .order_by_desc(conversations::Column::Id)
+            .all(&self.read_db)
+            .await?;
+
+        let mut conversations: Vec<Conversation> =
+            models.into_iter().map(Conversation::from).collect();
+        // Stable, so conversations sharing a label stay most recent first
+        conversations.sort_by_key(|c| rank.get(c.label.as_str()).copied().unwrap_or(usize::MAX));
+        conversations.truncate(limit as usize);
+
+        Ok(conversations)
+    }
+
+    async fn find_with_filters(
+        &self,
+        filter: Option<String>,
+        has_role: Option<String>,
+        limit: usize,
+        offset: u32,
+    ) -> Result<(Vec<Conversation>, u64), RepositoryError> {
+        let mut query = conversations::Entity::find();
+
+        if let Some(filter_sql) = filter {
+            query = query.filter(conversations::Column::Label.contains(filter_sql.as_str()));
+        }
+        if let Some(role) = has_role {
+            use sea_orm::sea_query::ExprTrait;
+            query = query.filter(Expr::exists(
+                Query::select()
+                    .expr(Expr::value(1))
+                    .from(messages::Entity)
+                    .and_where(
+                        Expr::col((messages::Entity, messages::Column::ConversationId))
+                            .equals((conversations::Entity, conversations::Column::Id)),
+                    )
+                    .and_where(Expr::col((messages::Entity, messages::Column::Role)).eq(role))
+                    .to_owned(),
+            ));
+        }
+
+        let total = query.clone().count(&self.read_db).await?;
+
+        let results = query
+            .order_by_desc(conversations::Column::UpdatedAt)
+            .order_by_desc(conversations::Column::Id)
+            .limit(limit as u64)
+            .offset(offset as u64)
+            .all(&self.read_db)
+            .await?;
+
+        Ok((results.into_iter().map(Conversation::from).collect(), total))
+    }
+
+    async fn update_label(
+        &self,
+        id: Uuid,
+        new_label: &str,
+        new_folder: &str,
+    ) -> Result<(), RepositoryError> {
+        let txn = self.db.begin().await?;
+        let model = conversations::Entity::find_by_id(id)
+            .one(&txn)
+            .await?
+            .ok_or_else(|| RepositoryError::NotFound(format!("Conversation {} not found", id)))?;
+        let before = model.clone();
+
+        let mut active_model: conversations::ActiveModel = model.into_active_model();
+        active_model.label = Set(new_label.to_string());
+        active_model.folder = Set(new_folder.to_string());
+
+        let updated = active_model.update(&txn).await?;
+        Self::record_changes(&txn, &before, &updated).await?;
+        txn.commit().await?;
+        Ok(())
+    }
+
+    async fn get_all_labels(&self) -> Result<Vec<String>, RepositoryError> {
+        let labels = conversations::Entity::find()
+            .select_only()
+            .column(conversations::Column::Label)
+            .distinct()
+            .order_by_asc(conversations::Column::Label)
+            .into_tuple::<String>()
+            .all(&self.read_db)
+            .await?;
+
+        Ok(labels)
+    }
+
+    async fn suggest(
+        &self,
+        prefix: &str,
+        limit: usize,
+    ) -> Result<SearchSuggestions, RepositoryError> {
+        let primary_labels = conversations::Entity::find()
+            .select_only()
+            .column(conversations::Column::Label)
+            .column_as(conversations::Column::Id.count(), "count")
+            .filter(conversations::Column::Label.like(like_prefix(prefix)))
+            .group_by(conversations::Column::Label)
+            .into_tuple::<(String, i64)>()
+            .all(&self.read_db)
+            .await?;
+        let additional_labels = conversation_labels::Entity::find()
+            .select_only()
+            .column(conversation_labels::Column::Label)
+            .column_as(conversation_labels::Column::ConversationId.count(), "count")
+            .filter(conversation_labels::Column::Label.like(like_prefix(prefix)))
+            .group_by(conversation_labels::Column::Label)
+            .into_tuple::<(String, i64)>()
+            .all(&self.read_db)
+            .await?;
+        // Folders usually start with a slash the user won't bother typing
+        let mut folder_condition =
+            Condition::any().add(conversations::Column::Folder.like(like_prefix(prefix)));
+        if !prefix.starts_with('/') {
+            folder_condition = folder_condition
+                .add(conversations::Column::Folder.like(like_prefix(&format!("/{}", prefix))));
+        }
+        let folders = conversations::Entity::find()
+            .select_only()
+            .column(conversations::Column::Folder)
+            .column_as(conversations::Column::Id.count(), "count")
+            .filter(folder_condition)
+            .group_by(conversations::Column::Folder)
+            .into_tuple::<(String, i64)>()
+            .all(&self.read_db)
+            .await?;
+        let tags = semantic_tags::Entity::find()
+            .select_only()
+            .column(semantic_tags::Column::Tag)
+            .column_as(semantic_tags::Column::ConversationId.count(), "count")
+            .filter(semantic_tags::Column::Tag.like(like_prefix(prefix)))
+            .group_by(semantic_tags::Column::Tag)
+            .into_tuple::<(String, i64)>()
+            .all(&self.read_db)
+            .await?;
+
+        Ok(SearchSuggestions {
+            labels: rank_suggestions(primary_labels.into_iter().chain(additional_labels), limit),
+            folders: rank_suggestions(folders, limit),
+            tags: rank_suggestions(tags, limit),
+        })
+    }
+
+    async fn reindex_fts(&self) -> Result<u64, RepositoryError> {
+        // Run as one transaction so searches never observe a half-built index
+        let txn = self.db.begin().await?;
+
+        // Restores the insert trigger dropped by `defer_fts_indexing`; inside
+        // the transaction so no insert slips between it and the rebuild
+        txn.execute_unprepared(FTS_TRIGGERS).await?;
+        txn.execute_unprepared("DELETE FROM messages_fts").await?;
+        let max_rowid = Self::max_message_rowid(&txn).await?;
+        let indexed = Self::index_fts_range(&txn, 0, max_rowid).await?;
+
+        txn.commit().await?;
+
+        tracing::info!("Reindexed {} messages into messages_fts", indexed);
+        Ok(indexed)
+    }
+
+    async fn defer_fts_indexing(&self) -> Result<i64, RepositoryError> {
+        let txn = self.db.begin().await?;
+        txn.execute_unprepared("DROP TRIGGER IF EXISTS messages_ai")
+            .await?;
+        let start = Self::max_message_rowid(&txn).await?;
+        txn.commit().await?;
+
+        tracing::info!("Deferred FTS indexing of messages after rowid {}", start);
+        Ok(start)
+    }
+
+    async fn index_fts_since(&self, after_rowid: i64) -> Result<u64, RepositoryError> {
+        // Restored first and on its own, so a failure below can't leave new
+        // messages unindexed; rows after `upper` are the trigger's from here
+        let txn = self.db.begin().await?;
+        txn.execute_unprepared(FTS_TRIGGERS).await?;
+        let upper = Self::max_message_rowid(&txn).await?;
+        txn.commit().await?;
+
+        let txn = self.db.begin().await?;
+        let indexed = Self::index_fts_range(&txn, after_rowid, upper).await?;
+        txn.commit().await?;
+
+        tracing::info!(
+            "Indexed {} messages deferred after rowid {}",
+            indexed,
+            after_rowid
+        );
+        Ok(indexed)
+    }
+
+    async fn find_summaries(
+        &self,
+        conversation_id: Uuid,
+    ) -> Result<Vec<StoredSummary>, RepositoryError> {
+        let models = hierarchical_summaries::Entity::find()
+            .filter(hierarchical_summaries::Column::ConversationId.eq(conversation_id))
+            .order_by_asc(hierarchical_summaries::Column::TimestampRange)
+            .all(&self.read_db)
+            .await?;
+
+        let mut summaries: Vec<StoredSummary> =
+            models.into_iter().map(StoredSummary::from).collect();
+
+        // Stable sort keeps period order within each level
+        summaries.sort_by_key(|s| summary_level_rank(&s.level));
+
+        Ok(summaries)
+    }
+
+    async fn find_tags(&self, conversation_id: Uuid) -> Result<Vec<StoredTag>, RepositoryError> {
+        let models = semantic_tags::Entity::find()
+            .filter(semantic_tags::Column::ConversationId.eq(conversation_id))
+            .order_by_desc(semantic_tags::Column::Confidence)
+            .order_by_asc(semantic_tags::Column::Tag)
+            .all(&self.read_db)
+            .await?;
+
+        Ok(models.into_iter().map(StoredTag::from).collect())
+    }
+
+    async fn add_tags(
+        &self,
+        conversation_id: Uuid,
+        tags: Vec<String>,
+    ) -> Result<(), RepositoryError> {
+        Self::insert_tags(&self.db, conversation_id, tags).await
+    }
+
+    async fn add_tags_many(
+        &self,
+        conversation_ids: Vec<Uuid>,
+        tags: Vec<String>,
+    ) -> Result<Vec<TagUpdate>, RepositoryError> {
+        let conversation_ids = dedup_ids(conversation_ids);
+        let mut tags = tags;
+        tags.sort();
+        tags.dedup();
+
+        let txn = self.db.begin().await?;
+        let found = Self::existing_conversation_ids(&txn, &conversation_ids).await?;
+        let existing: HashSet<(Uuid, String)> = semantic_tags::Entity::find()
+            .filter(semantic_tags::Column::ConversationId.is_in(found.iter().copied()))
+            .filter(semantic_tags::Column::Tag.is_in(tags.iter().cloned()))
+            .all(&txn)
+            .await?
+            .into_iter()
+            .map(|t| (t.conversation_id, t.tag))
+            .collect();
+
+        let now = chrono::Utc::now().naive_utc();
+        let mut results = Vec::with_capacity(conversation_ids.len());
+        let mut new_rows = Vec::new();
+        for conversation_id in conversation_ids {
+            let found = found.contains(&conversation_id);
+            let mut changed = 0;
+            if found {
+                for tag in &tags {
+                    if existing.contains(&(conversation_id, tag.clone())) {
+                        continue;
+                    }
+                    new_rows.push(semantic_tags::ActiveModel {
+                        id: Set(Uuid::new_v4()),
+                        conversation_id: Set(conversation_id),
+                        tag: Set(tag.clone()),
+                        confidence: Set(1.0),
+                        extracted_at: Set(now),
+                    });
+                    changed += 1;
+                }
+            }
+            results.push(TagUpdate {
+                conversation_id,
+                found,
+                changed,
+            });
+        }
+
+        if !new_rows.is_empty() {
+            semantic_tags::Entity::insert_many(new_rows)
+                .exec(&txn)
+                .await?;
+        }
+        txn.commit().await?;
+        Ok(results)
+    }
+
+    async fn remove_tag_from_many(
+        &self,
+        conversation_ids: Vec<Uuid>,
+        tag: &str,
+    ) -> Result<Vec<TagUpdate>, RepositoryError> {
+        let conversation_ids = dedup_ids(conversation_ids);
+
+        let txn = self.db.begin().await?;
+        let found = Self::existing_conversation_ids(&txn, &conversation_ids).await?;
+
+        let mut results = Vec::with_capacity(conversation_ids.len());
+        for conversation_id in conversation_ids {
+            let found = found.contains(&conversation_id);
+            let changed = if found {
+                semantic_tags::Entity::delete_many()
+                    .filter(semantic_tags::Column::ConversationId.eq(conversation_id))
+                    .filter(semantic_tags::Column::Tag.eq(tag))
+                    .exec(&txn)
+                    .await?
+                    .rows_affected as usize
+            } else {
+                0
+            };
+            results.push(TagUpdate {
+                conversation_id,
+                found,
+                changed,
+            });
+        }
+
+        txn.commit().await?;
+        Ok(results)
+    }
+
+    async fn find_labels(
+        &self,
+        conversation_id: Uuid,
+    ) -> Result<Vec<ConversationLabel>, RepositoryError> {
+        let models = conversation_labels::Entity::find()
+            .filter(conversation_labels::Column::ConversationId.eq(conversation_id))
+            .order_by_desc(conversation_labels::Column::Weight)
+            .order_by_asc(conversation_labels::Column::Label)
+            .all(&self.read_db)
+            .await?;
+
+        Ok(models.into_iter().map(ConversationLabel::from).collect())
+    }
+
+    async fn add_label(
+        &self,
+        conversation_id: Uuid,
+        label: &str,
+        weight: f64,
+    ) -> Result<(), RepositoryError> {
+        let label = label.trim();
+        if label.is_empty() {
+            return Err(RepositoryError::InvalidInput(
+                "label must not be empty".to_string(),
+            ));
+        }
+        if !(0.0..=1.0).contains(&weight) {
+            return Err(RepositoryError::InvalidInput(format!(
+                "label weight must be between 0.0 and 1.0, got {}",
+                weight
+            )));
+        }
+        if conversations::Entity::find_by_id(conversation_id)
+            .one(&self.db)
+            .await?
+            .is_none()
+        {
+            return Err(RepositoryError::NotFound(format!(
+                "Conversation {} not found",
+                conversation_id
+            )));
+        }
+
+        let model = conversation_labels::ActiveModel {
+            conversation_id: Set(conversation_id),
+            label: Set(label.to_string()),
+            weight: Set(weight),
+            added_at: Set(chrono::Utc::now().naive_utc()),
+        };
+        conversation_labels::Entity::insert(model)
+            .on_conflict(
+                sea_orm::sea_query::OnConflict::columns([
+                    conversation_labels::Column::ConversationId,
+                    conversation_labels::Column::Label,
+                ])
+                .update_column(conversation_labels::Column::Weight)
+                .to_owned(),
+            )
+            .exec(&self.db)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn remove_label(
+        &self,
+        conversation_id: Uuid,
+        label: &str,
+    ) -> Result<bool, RepositoryError> {
+        let result = conversation_labels::Entity::delete_many()
+            .filter(conversation_labels::Column::ConversationId.eq(conversation_id))
+            .filter(conversation_labels::Column::Label.eq(label.trim()))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+
+    async fn find_by_any_label(
+        &self,
+        labels: &[String],
+    ) -> Result<Vec<(Conversation, f64)>, RepositoryError> {
+        if labels.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut weights: HashMap<Uuid, f64> = conversations::Entity::find()
+            .select_only()
+            .column(conversations::Column::Id)
+            .filter(conversations::Column::Label.is_in(labels.iter().cloned()))
+            .into_tuple::<Uuid>()
+            .all(&self.read_db)
+            .await?
+            .into_iter()
+            .map(|id| (id, 1.0))
+            .collect();
+        let additional = conversation_labels::Entity::find()
+            .filter(conversation_labels::Column::Label.is_in(labels.iter().cloned()))
+            .all(&self.read_db)
+            .await?;
+        for label in additional {
+            let weight = weights.entry(label.conversation_id).or_insert(0.0);
+            *weight = weight.max(label.weight);
+        }
+
+        let mut matches: Vec<(Conversation, f64)> = conversations::Entity::find()
+            .filter(conversations::Column::Id.is_in(weights.keys().copied()))
+            .all(&self.read_db)
+            .await?
+            .into_iter()
+            .map(|model| {
+                let weight = weights[&model.id];
+                (Conversation::from(model), weight)
+            })
+            .collect();
+        matches.sort_by(|a, b| {
+            b.1.total_cmp(&a.1)
+                .then_with(|| b.0.updated_at.cmp(&a.0.updated_at))
+        });
+
+        Ok(matches)
+    }
+
+    async fn save_template(
+        &self,
+        template: ConversationTemplate,
+    ) -> Result<ConversationTemplate, RepositoryError> {
+        let existing = conversation_templates::Entity::find_by_id(template.name.clone())
+            .one(&self.db)
+            .await?;
+
+        let model = conversation_templates::ActiveModel {
+            name: Set(template.name),
+            label_pattern: Set(template.label_pattern),
+            folder: Set(template.folder),
+            system_message: Set(template.system_message),
+            tags: Set(json!(template.tags)),
+            // Replacing a template keeps its original creation time
+            created_at: Set(existing
+                .as_ref()
+                .map_or(template.created_at, |m| m.created_at)),
+            updated_at: Set(template.updated_at),
+        };
+        let saved = if existing.is_some() {
+            model.update(&self.db).await?
+        } else {
+            model.insert(&self.db).await?
+        };
+
+        Ok(ConversationTemplate::from(saved))
+    }
+
+    async fn find_template(
+        &self,
+        name: &str,
+    ) -> Result<Option<ConversationTemplate>, RepositoryError> {
+        let model = conversation_templates::Entity::find_by_id(name.to_string())
+            .one(&self.read_db)
+            .await?;
+
+        Ok(model.map(ConversationTemplate::from))
+    }
+
+    async fn list_templates(&self) -> Result<Vec<ConversationTemplate>, RepositoryError> {
+        let models = conversation_templates::Entity::find()
+            .order_by_asc(conversation_templates::Column::Name)
+            .all(&self.read_db)
+            .await?;
+
+        Ok(models.into_iter().map(ConversationTemplate::from).collect())
+    }
+
+    async fn archive_to_disk(
+        &self,
+        id: Uuid,
+        dir: &Path,
+    ) -> Result<OffloadRecord, RepositoryError> {
+        let conversation = conversations::Entity::find_by_id(id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| RepositoryError::NotFound(format!("Conversation {} not found", id)))?;
+        let messages: Vec<Message> = messages::Entity::find()
+            .filter(messages::Column::ConversationId.eq(id))
+            .order_by_asc(messages::Column::Timestamp)
+            .order_by_asc(messages::Column::Seq)
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .map(Message::from)
+            .collect();
+        let summaries = self.find_summaries(id).await?;
+        let tags = self.find_tags(id).await?;
+        let labels = self.find_labels(id).await?;
+        let history = self.find_history(id).await?;
+
+        let embedding_ids: Vec<String> = messages
+            .iter()
+            .filter_map(|m| m.embedding_id.clone())
+            .collect();
+        let archive = OffloadedConversation {
+            format_version: OFFLOAD_FORMAT_VERSION,
+            conversation: conversation.into(),
+            messages,
+            summaries,
+            tags,
+            labels,
+            history,
+        };
+
+        // Write the file before touching the database, so a failed write
+        // leaves the conversation where it was
+        let path = dir.join(format!("{}.json", id));
+        let json = serde_json::to_vec_pretty(&archive)
+            .map_err(|e| RepositoryError::ArchiveError(e.to_string()))?;
+        write_file_atomically(&path, &json).await?;
+
+        let record = offloaded_conversations::ActiveModel {
+            conversation_id: Set(id),
+            label: Set(archive.conversation.label.clone()),
+            folder: Set(archive.conversation.folder.clone()),
+            file_path: Set(path.to_string_lossy().into_owned()),
+            message_count: Set(archive.messages.len() as i32),
+            offloaded_at: Set(chrono::Utc::now().naive_utc()),
+        };
+
+        let txn = self.db.begin().await?;
+        let record = record.insert(&txn).await?;
+        messages::Entity::delete_many()
+            .filter(messages::Column::ConversationId.eq(id))
+            .exec(&txn)
+            .await?;
+        hierarchical_summaries::Entity::delete_many()
+            .filter(hierarchical_summaries::Column::ConversationId.eq(id))
+            .exec(&txn)
+            .await?;
+        semantic_tags::Entity::delete_many()
+            .filter(semantic_tags::Column::ConversationId.eq(id))
+            .exec(&txn)
+            .await?;
+        conversation_labels::Entity::delete_many()
+            .filter(conversation_labels::Column::ConversationId.eq(id))
+            .exec(&txn)
+            .await?;
+        conversation_history::Entity::delete_many()
+            .filter(conversation_history::Column::ConversationId.eq(id))
+            .exec(&txn)
+            .await?;
+        conversations::Entity::delete_by_id(id).exec(&txn).await?;
+        txn.commit().await?;
+
+        // Vectors are rebuilt on restore, so a failure here only leaves
+        // orphaned entries that semantic search already skips
+        if let Err(e) = self.remove_vectors(&embedding_ids).await {
+            tracing::warn!("Failed to remove vectors for offloaded {}: {}", id, e);
+        }
+
+        tracing::info!("Offloaded conversation {} to {}", id, path.display());
+        Ok(record.into())
+    }
+
+    async fn restore_from_disk(&self, id: Uuid) -> Result<Conversation, RepositoryError> {
+        let record = offloaded_conversations::Entity::find_by_id(id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| {
+                RepositoryError::NotFound(format!("Offloaded conversation {} not found", id))
+            })?;
+
+        let bytes = tokio::fs::read(&record.file_path).await.map_err(|e| {
+            RepositoryError::ArchiveError(format!("Failed to read {}: {}", record.file_path, e))
+        })?;
+        let archive: OffloadedConversation = serde_json::from_slice(&bytes)
+            .map_err(|e| RepositoryError::ArchiveError(e.to_string()))?;
+        if archive.format_version != OFFLOAD_FORMAT_VERSION || archive.conversation.id != id {
+            return Err(RepositoryError::ArchiveError(format!(
+                "{} is not an offload file for conversation {}",
+                record.file_path, id
+            )));
+        }
+
+        // Re-embed under the original message IDs so vector hits resolve
+        // to the restored rows
+        let requests = archive.messages.iter().map(embedding_request).collect();
+        let report = self.embed_batch(requests).await;
+        for (msg_id, error) in &report.failed {
+            tracing::warn!(
+                "Embedding generation failed for restored message {} (ok in tests): {}",
+                msg_id,
+                error
+            );
+        }
+
+        let conv = &archive.conversation;
+        let txn = self.db.begin().await?;
+        conversations::ActiveModel {
+            id: Set(conv.id),
+            label: Set(conv.label.clone()),
+            folder: Set(conv.folder.clone()),
+            status: Set(conv.status.clone()),
+            importance_score: Set(conv.importance_score),
+            word_count: Set(conv.word_count),
+            session_count: Set(conv.session_count),
+            created_at: Set(conv.created_at),
+            updated_at: Set(conv.updated_at),
+            context_pinned: Set(conv.context_pinned),
+            last_accessed_at: Set(conv.last_accessed_at),
+        }
+        .insert(&txn)
+        .await?;
+
+        // Archived messages are stored in display order
+        for (seq, msg) in archive.messages.iter().enumerate() {
+            let embedding = self.stored_embedding(&report, msg.id);
+            messages::ActiveModel {
+                id: Set(msg.id),
+                conversation_id: Set(id),
+                role: Set(msg.role.clone()),
+                content: Set(msg.content.clone()),
+                timestamp: Set(msg.timestamp),
+                embedding_id: Set(embedding.as_ref().map(|e| e.id.clone())),
+                metadata: Set(msg.metadata.clone()),
+                seq: Set(seq as i64),
+                embedding_model: Set(embedding.as_ref().map(|e| e.model.clone())),
+                embedding_dim: Set(embedding.and_then(|e| e.dim)),
+            }
+            .insert(&txn)
+            .await?;
+        }
+
+        for summary in &archive.summaries {
+            hierarchical_summaries::ActiveModel {
+                id: Set(summary.id),
+                conversation_id: Set(id),
+                level: Set(summary.level.clone()),
+                summary_text: Set(summary.summary_text.clone()),
+                timestamp_range: Set(summary.period.clone()),
+                generated_at: Set(summary.generated_at),
+                model_used: Set(summary.model_used.clone()),
+                token_count: Set(summary.token_count),
+            }
+            .insert(&txn)
             .await?;
+        }
 
-        Ok(count)
+        for tag in &archive.tags {
+            semantic_tags::ActiveModel {
+                id: Set(tag.id),
+                conversation_id: Set(id),
+                tag: Set(tag.tag.clone()),
+                confidence: Set(tag.confidence),
+                extracted_at: Set(tag.extracted_at),
+            }
+            .insert(&txn)
+            .await?;
+        }
+
+        for label in &archive.labels {
+            conversation_labels::ActiveModel {
+                conversation_id: Set(id),
+                label: Set(label.label.clone()),
+                weight: Set(label.weight),
+                added_at: Set(label.added_at),
+            }
+            .insert(&txn)
+            .await?;
+        }
+
+        // Inserted in their original order, which is the order they're read in
+        for change in &archive.history {
+            conversation_history::Entity::insert(conversation_history::ActiveModel {
+                conversation_id: Set(id),
+                field: Set(change.field.clone()),
+                old_value: Set(change.old_value.clone()),
+                new_value: Set(change.new_value.clone()),
+                changed_at: Set(change.changed_at),
+                ..Default::default()
+            })
+            .exec(&txn)
+            .await?;
+        }
+
+        offloaded_conversations::Entity::delete_by_id(id)
+            .exec(&txn)
+            .await?;
+        txn.commit().await?;
+
+        if let Err(e) = tokio::fs::remove_file(&record.file_path).await {
+            tracing::warn!("Failed to remove offload file {}: {}", record.file_path, e);
+        }
+
+        tracing::info!("Restored conversation {} from {}", id, record.file_path);
+        Ok(archive.conversation)
     }
 
-    async fn count_all(&self) -> Result<u64, RepositoryError> {
-        let count = conversations::Entity::find().count(&self.db).await?;
+    async fn reconcile_embeddings(&self) -> Result<EmbeddingReconciliation, RepositoryError> {
+        // Messages are embedded before their rows are written, so a vector
+        // this recent may belong to a row that isn't visible yet
+        let grace_cutoff = chrono::Utc::now().timestamp() - RECONCILE_GRACE_SECS;
+        let vectors = match self.chroma.list_ids_embedded_at(DEFAULT_COLLECTION).await {
+            Ok(vectors) => vectors,
+            // Nothing has been embedded yet
+            Err(ChromaError::CollectionNotFound(_)) => Vec::new(),
+            Err(e) => return Err(RepositoryError::ChromaError(e.to_string())),
+        };
 
-        Ok(count)
+        let rows: Vec<(Uuid, Uuid, Option<String>, Option<JsonValue>)> = messages::Entity::find()
+            .select_only()
+            .column(messages::Column::Id)
+            .column(messages::Column::ConversationId)
+            .column(messages::Column::EmbeddingId)
+            .column(messages::Column::Metadata)
+            .into_tuple()
+            .all(&self.db)
+            .await?;
+
+        // Chunks of a long message are stored as `{embedding_id}:{n}`, so a
+        // vector is matched to its message by that prefix
+        let referenced: HashSet<&str> = rows
+            .iter()
+            .filter_map(|(_, _, embedding_id, _)| embedding_id.as_deref())
+            .collect();
+        let orphaned: Vec<String> = vectors
+            .iter()
+            .filter(|(id, embedded_at)| {
+                embedded_at.map_or(true, |at| at < grace_cutoff)
+                    && !referenced.contains(vector_message_id(id))
+            })
+            .map(|(id, _)| id.clone())
+            .collect();
+        let embedded: HashSet<&str> = vectors
+            .iter()
+            .map(|(id, _)| vector_message_id(id))
+            .collect();
+        if !orphaned.is_empty() {
+            tracing::info!("Removing {} orphaned vectors", orphaned.len());
+            self.chroma
+                .delete(DEFAULT_COLLECTION, orphaned.clone())
+                .await
+                .map_err(|e| RepositoryError::ChromaError(e.to_string()))?;
+        }
+
+        let mut stale = Vec::new();
+        let mut missing_vectors: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+        for (id, conversation_id, embedding_id, metadata) in &rows {
+            match embedding_id {
+                Some(embedding_id) if embedded.contains(embedding_id.as_str()) => continue,
+                Some(_) => stale.push(*id),
+                None => {}
+            }
+            // Left out of the vector store on purpose, so nothing is missing
+            if metadata
+                .as_ref()
+                .is_some_and(|m| m.get(EMBEDDING_SKIPPED_KEY).is_some())
+            {
+                continue;
+            }
+            missing_vectors
+                .entry(*conversation_id)
+                .or_default()
+                .push(*id);
+        }
+        self.clear_embeddings(stale).await?;
+
+        Ok(EmbeddingReconciliation {
+            orphaned_vectors_removed: orphaned.len(),
+            missing_vectors,
+        })
     }
 
-    async fn find_by_id(&self, id: Uuid) -> Result<Option<Conversation>, RepositoryError> {
-        let model = conversations::Entity::find_by_id(id).one(&self.db).await?;
+    async fn recompute_aggregates(&self) -> Result<AggregateRecompute, RepositoryError> {
+        let mut report = AggregateRecompute::default();
+        let mut after: Option<Uuid> = None;
+        loop {
+            let mut query = conversations::Entity::find()
+                .select_only()
+                .column(conversations::Column::Id)
+                .column(conversations::Column::WordCount)
+                .order_by_asc(conversations::Column::Id)
+                .limit(RECOMPUTE_BATCH_SIZE);
+            if let Some(after) = after {
+                query = query.filter(conversations::Column::Id.gt(after));
+            }
+            let batch: Vec<(Uuid, i32)> = query.into_tuple().all(&self.db).await?;
+            let Some((last_id, _)) = batch.last() else {
+                break;
+            };
+            after = Some(*last_id);
+
+            let rows: Vec<(Uuid, String)> = messages::Entity::find()
+                .select_only()
+                .column(messages::Column::ConversationId)
+                .column(messages::Column::Content)
+                .filter(messages::Column::ConversationId.is_in(batch.iter().map(|(id, _)| *id)))
+                .into_tuple()
+                .all(&self.db)
+                .await?;
+            report.messages_counted += rows.len() as u64;
 
-        Ok(model.map(Conversation::from))
+            let mut actual: HashMap<Uuid, i32> = HashMap::new();
+            for (conversation_id, content) in rows {
+                *actual.entry(conversation_id).or_default() += content.len() as i32;
+            }
+
+            let txn = self.db.begin().await?;
+            for (id, word_count) in &batch {
+                let expected = actual.get(id).copied().unwrap_or(0);
+                if *word_count == expected {
+                    continue;
+                }
+                tracing::info!(
+                    "Correcting word_count of {}: {} -> {}",
+                    id,
+                    word_count,
+                    expected
+                );
+                conversations::Entity::update_many()
+                    .col_expr(conversations::Column::WordCount, Expr::value(expected))
+                    .filter(conversations::Column::Id.eq(*id))
+                    .exec(&txn)
+                    .await?;
+                report.conversations_corrected += 1;
+            }
+            txn.commit().await?;
+        }
+
+        Ok(report)
     }
 
-    async fn find_message_by_id(&self, id: Uuid) -> Result<Option<Message>, RepositoryError> {
-        let model = messages::Entity::find_by_id(id).one(&self.db).await?;
+    async fn retry_embeddings(&self, message_ids: Vec<Uuid>) -> Result<Vec<Uuid>, RepositoryError> {
+        let pending: Vec<Message> = messages::Entity::find()
+            .filter(messages::Column::Id.is_in(message_ids))
+            .filter(messages::Column::EmbeddingId.is_null())
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .map(Message::from)
+            .filter(|m| !m.embedding_skipped())
+            .collect();
+        if pending.is_empty() {
+            return Ok(Vec::new());
+        }
 
-        Ok(model.map(Message::from))
+        let report = self
+            .embed_batch(pending.iter().map(embedding_request).collect())
+            .await;
+        for (msg_id, error) in &report.failed {
+            tracing::warn!("Embedding retry failed for message {}: {}", msg_id, error);
+        }
+
+        self.record_embedded(&report).await?;
+
+        Ok(report.succeeded)
     }
 
-    async fn find_by_label(
+    async fn reembed_scope(
         &self,
-        label: &str,
+        filter: ConversationFilter,
+    ) -> Result<ReembedReport, RepositoryError> {
+        let mut query = conversations::Entity::find()
+            .select_only()
+            .column(conversations::Column::Id)
+            .order_by_asc(conversations::Column::Id);
+        if let Some(folder) = &filter.folder {
+            let prefix = format!("{}/", folder.trim_end_matches('/'));
+            query = query.filter(
+                Condition::any()
+                    .add(conversations::Column::Folder.eq(folder.as_str()))
+                    .add(conversations::Column::Folder.like(like_prefix(&prefix))),
+            );
+        }
+        if let Some(label) = &filter.label {
+            query = query.filter(conversations::Column::Label.eq(label.as_str()));
+        }
+        if let Some(after) = filter.created_after {
+            query = query.filter(conversations::Column::CreatedAt.gte(after));
+        }
+        if let Some(before) = filter.created_before {
+            query = query.filter(conversations::Column::CreatedAt.lt(before));
+        }
+        let conversation_ids: Vec<Uuid> = query.into_tuple().all(&self.db).await?;
+
+        let mut report = ReembedReport {
+            conversations: conversation_ids.len(),
+            ..Default::default()
+        };
+        for conversation_id in conversation_ids {
+            let messages: Vec<Message> = messages::Entity::find()
+                .filter(messages::Column::ConversationId.eq(conversation_id))
+                .all(&self.db)
+                .await?
+                .into_iter()
+                .map(Message::from)
+                .filter(|m| !m.embedding_skipped())
+                .collect();
+            if messages.is_empty() {
+                continue;
+            }
+
+            // A message re-chunked into fewer pieces would otherwise keep its
+            // old trailing chunks, and a whole vector would sit beside new chunks
+            let embedded: Vec<String> = messages
+                .iter()
+                .filter(|m| m.embedding_id.is_some())
+                .map(|m| m.id.to_string())
+                .collect();
+            self.remove_vectors(&embedded).await?;
+
+            let batch = self
+                .embed_batch(messages.iter().map(embedding_request).collect())
+                .await;
+            for (msg_id, error) in &batch.failed {
+                tracing::warn!("Re-embedding failed for message {}: {}", msg_id, error);
+            }
+            self.record_embedded(&batch).await?;
+            // Their old vectors are already gone
+            self.clear_embeddings(batch.failed.iter().map(|(id, _)| *id).collect())
+                .await?;
+            report.messages_reembedded += batch.succeeded.len();
+            report.messages_failed += batch.failed.len();
+        }
+
+        tracing::info!(
+            "Re-embedded {} messages in {} conversations ({} failed)",
+            report.messages_reembedded,
+            report.conversations,
+            report.messages_failed
+        );
+        Ok(report)
+    }
+
+    async fn get_message_list(
+        &self,
+        conversation_id: Uuid,
+    ) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>> {
+        let messages = messages::Entity::find()
+            .filter(messages::Column::ConversationId.eq(conversation_id))
+            .order_by_asc(messages::Column::Timestamp)
+            .order_by_asc(messages::Column::Seq)
+            .all(&self.read_db)
+            .await?;
+
+        Ok(messages
+            .into_iter()
+            .map(|msg| {
+                serde_json::json!({
+                    "id": msg.id,
+                    "role": msg.role,
+                    "content": msg.content,
+                    "timestamp": msg.timestamp,
+                    "metadata": msg.metadata,
+                })
+            })
+            .collect())
+    }
+
+    async fn get_conversation_messages(
+        &self,
+        conversation_id: Uuid,
+    ) -> Result<Vec<Message>, RepositoryError> {
+        let msg_models = messages::Entity::find()
+            .filter(messages::Column::ConversationId.eq(conversation_id))
+            .order_by_asc(messages::Column::Timestamp)
+            .order_by_asc(messages::Column::Seq)
+            .all(&self.read_db)
+            .await?;
+
+        Ok(msg_models.into_iter().map(Message::from).collect())
+    }
+
+    async fn find_recent_messages(
+        &self,
+        conversation_id: Uuid,
+        limit: usize,
+    ) -> Result<Vec<Message>, RepositoryError> {
+        let models = messages::Entity::find()
+            .filter(messages::Column::ConversationId.eq(conversation_id))
+            .order_by_desc(messages::Column::Timestamp)
+            .order_by_desc(messages::Column::Seq)
+            .limit(limit as u64)
+            .all(&self.read_db)
+            .await?;
+
+        Ok(models.into_iter().map(Message::from).collect())
+    }
+
+    async fn find_by_folder(
+        &self,
+        folder: &str,
         limit: u64,
         offset: u64,
     ) -> Result<Vec<Conversation>, RepositoryError> {
         let models = conversations::Entity::find()
-            .filter(conversations::Column::Label.eq(label))
+            .filter(conversations::Column::Folder.eq(folder))
             .order_by_desc(conversations::Column::UpdatedAt)
+            .order_by_desc(conversations::Column::Id)
             .limit(limit)
             .offset(offset)
-            .all(&self.db)
+            .all(&self.read_db)
             .await?;
 
         Ok(models.into_iter().map(Conversation::from).collect())
     }
 
-    async fn find_with_filters(
+    async fn get_all_folders(&self) -> Result<Vec<String>, RepositoryError> {
+        let folders = conversations::Entity::find()
+            .select_only()
+            .column(conversations::Column::Folder)
+            .distinct()
+            .order_by_asc(conversations::Column::Folder)
+            .into_tuple::<String>()
+            .all(&self.read_db)
+            .await?;
+
+        Ok(folders)
+    }
+
+    async fn folder_counts(&self) -> Result<Vec<(String, u64)>, RepositoryError> {
+        let counts = conversations::Entity::find()
+            .select_only()
+            .column(conversations::Column::Folder)
+            .column_as(conversations::Column::Id.count(), "count")
+            .group_by(conversations::Column::Folder)
+            .order_by_asc(conversations::Column::Folder)
+            .into_tuple::<(String, i64)>()
+            .all(&self.read_db)
+            .await?;
+
+        Ok(counts
+            .into_iter()
+            .map(|(folder, count)| (folder, count as u64))
+            .collect())
+    }
+
+    async fn conversation_timeline(
         &self,
-        filter: Option<String>,
-        limit: usize,
-        offset: u32,
-    ) -> Result<(Vec<Conversation>, u64), RepositoryError> {
-        let mut query = conversations::Entity::find();
+        bucket: TimeBucket,
+        from: Option<chrono::NaiveDate>,
+        to: Option<chrono::NaiveDate>,
+    ) -> Result<Vec<TimelineBucket>, RepositoryError> {
+        #[derive(FromQueryResult)]
+        struct BucketCount {
+            start: String,
+            count: i64,
+        }
 
-        if let Some(filter_sql) = filter {
-            query = query.filter(conversations::Column::Label.contains(filter_sql.as_str()));
+        let bound = |date: Option<chrono::NaiveDate>| {
+            Value::String(date.map(|d| d.format("%Y-%m-%d").to_string()))
+        };
+
+        let mut buckets: std::collections::BTreeMap<String, TimelineBucket> = Default::default();
+        for (table, column) in [("conversations", "created_at"), ("messages", "timestamp")] {
+            // Range bounds compare calendar dates, not the raw text, so the
+            // stored separator and precision don't matter
+            let counts = BucketCount::find_by_statement(Statement::from_sql_and_values(
+                DatabaseBackend::Sqlite,
+                format!(
+                    "SELECT {start} AS start, COUNT(*) AS count FROM {table} \
+                     WHERE date({column}) IS NOT NULL \
+                       AND (?1 IS NULL OR date({column}) >= ?1) \
+                       AND (?2 IS NULL OR date({column}) <= ?2) \
+                     GROUP BY 1",
+                    start = bucket.start_of(column),
+                ),
+                vec![bound(from), bound(to)],
+            ))
+            .all(&self.read_db)
+            .await?;
+
+            for row in counts {
+                let entry = buckets
+                    .entry(row.start.clone())
+                    .or_insert_with(|| TimelineBucket {
+                        start: row.start,
+                        conversations: 0,
+                        messages: 0,
+                    });
+                if table == "conversations" {
+                    entry.conversations = row.count as u64;
+                } else {
+                    entry.messages = row.count as u64;
+                }
+            }
         }
 
-        let total = query.clone().count(&self.db).await?;
+        Ok(buckets.into_values().collect())
+    }
 
-        let results = query
-            .order_by_desc(conversations::Column::UpdatedAt)
-            .limit(limit as u64)
-            .offset(offset as u64)
-            .all(&self.db)
+    async fn find_by_ids(&self, ids: Vec<Uuid>) -> Result<Vec<Conversation>, RepositoryError> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let models = conversations::Entity::find()
+            .filter(conversations::Column::Id.is_in(dedup_ids(ids)))
+            .all(&self.read_db)
             .await?;
 
-        Ok((results.into_iter().map(Conversation::from).collect(), total))
+        Ok(models.into_iter().map(Conversation::from).collect())
     }
 
-    async fn update_label(
+    async fn find_updated_before(
         &self,
-        id: Uuid,
-        new_label: &str,
-        new_folder: &str,
-    ) -> Result<(), RepositoryError> {
+        ids: Vec<Uuid>,
+        cutoff: chrono::NaiveDateTime,
+    ) -> Result<Vec<Uuid>, RepositoryError> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let ids = conversations::Entity::find()
+            .select_only()
+            .column(conversations::Column::Id)
+            .filter(conversations::Column::Id.is_in(dedup_ids(ids)))
+            .filter(conversations::Column::UpdatedAt.lt(cutoff))
+            .into_tuple::<Uuid>()
+            .all(&self.read_db)
+            .await?;
+        Ok(ids)
+    }
+
+    async fn update_status(&self, id: Uuid, status: &str) -> Result<(), RepositoryError> {
+        let txn = self.db.begin().await?;
+        let model = conversations::Entity::find_by_id(id)
+            .one(&txn)
+            .await?
+            .ok_or_else(|| RepositoryError::NotFound(format!("Conversation {} not found", id)))?;
+        let before = model.clone();
+
+        let mut active_model: conversations::ActiveModel = model.into_active_model();
+        active_model.status = Set(status.to_string());
+
+        let updated = active_model.update(&txn).await?;
+        Self::record_changes(&txn, &before, &updated).await?;
+        txn.commit().await?;
+        Ok(())
+    }
+
+    async fn update_importance(&self, id: Uuid, score: i32) -> Result<(), RepositoryError> {
+        if !importance::is_valid(score) {
+            return Err(RepositoryError::InvalidInput(format!(
+                "importance {} is outside {}..={}",
+                score,
+                importance::MIN_IMPORTANCE,
+                importance::MAX_IMPORTANCE
+            )));
+        }
+
+        let txn = self.db.begin().await?;
+        let model = conversations::Entity::find_by_id(id)
+            .one(&txn)
+            .await?
+            .ok_or_else(|| RepositoryError::NotFound(format!("Conversation {} not found", id)))?;
+        let before = model.clone();
+
+        let mut active_model: conversations::ActiveModel = model.into_active_model();
+        active_model.importance_score = Set(score as i32);
+
+        let updated = active_model.update(&txn).await?;
+        Self::record_changes(&txn, &before, &updated).await?;
+        txn.commit().await?;
+        Ok(())
+    }
+
+    async fn set_context_pinned(&self, id: Uuid, pinned: bool) -> Result<(), RepositoryError> {
         let model = conversations::Entity::find_by_id(id)
             .one(&self.db)
             .await?
             .ok_or_else(|| RepositoryError::NotFound(format!("Conversation {} not found", id)))?;
 
         let mut active_model: conversations::ActiveModel = model.into_active_model();
-        active_model.label = Set(new_label.to_string());
-        active_model.folder = Set(new_folder.to_string());
+        active_model.context_pinned = Set(pinned);
 
         active_model.update(&self.db).await?;
         Ok(())
     }
 
-    async fn get_all_labels(&self) -> Result<Vec<String>, RepositoryError> {
-        let labels = conversations::Entity::find()
-            .select_only()
-            .column(conversations::Column::Label)
-            .distinct()
-            .order_by_asc(conversations::Column::Label)
-            .into_tuple::<String>()
-            .all(&self.db)
-            .await?;
+    async fn touch(&self, id: Uuid) -> Result<(), RepositoryError> {
+        let model = conversations::Entity::find_by_id(id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| RepositoryError::NotFound(format!("Conversation {} not found", id)))?;
+
+        let mut active_model: conversations::ActiveModel = model.into_active_model();
+        active_model.updated_at = Set(chrono::Utc::now().naive_utc());
 
-        Ok(labels)
+        active_model.update(&self.db).await?;
+        Ok(())
     }
 
-    async fn get_message_list(
+    async fn record_access(
         &self,
-        conversation_id: Uuid,
-    ) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>> {
-        let messages = messages::Entity::find()
-            .filter(messages::Column::ConversationId.eq(conversation_id))
-            .order_by_asc(messages::Column::Timestamp)
-            .all(&self.db)
+        id: Uuid,
+        at: chrono::NaiveDateTime,
+    ) -> Result<(), RepositoryError> {
+        let stale = at - chrono::Duration::seconds(ACCESS_RECORD_INTERVAL_SECS);
+        conversations::Entity::update_many()
+            .col_expr(conversations::Column::LastAccessedAt, Expr::value(at))
+            .filter(conversations::Column::Id.eq(id))
+            .filter(
+                Condition::any()
+                    .add(conversations::Column::LastAccessedAt.is_null())
+                    .add(conversations::Column::LastAccessedAt.lt(stale)),
+            )
+            .exec(&self.db)
             .await?;
+        Ok(())
+    }
 
-        Ok(messages
-            .into_iter()
-            .map(|msg| {
-                serde_json::json!({
-                    "id": msg.id,
-                    "role": msg.role,
-                    "content": msg.content,
-                    "timestamp": msg.timestamp,
-                    "metadata": msg.metadata,
-                })
-            })
-            .collect())
+    async fn increment_session(&self, id: Uuid) -> Result<(), RepositoryError> {
+        Self::bump_session_count(&self.db, id).await
     }
 
-    async fn get_conversation_messages(
+    async fn apply_patch(
         &self,
-        conversation_id: Uuid,
-    ) -> Result<Vec<Message>, RepositoryError> {
-        let msg_models = messages::Entity::find()
-            .filter(messages::Column::ConversationId.eq(conversation_id))
-            .order_by_asc(messages::Column::Timestamp)
-            .all(&self.db)
-            .await?;
+        id: Uuid,
+        patch: ConversationPatch,
+    ) -> Result<Conversation, RepositoryError> {
+        let txn = self.db.begin().await?;
 
-        Ok(msg_models.into_iter().map(Message::from).collect())
+        let model = conversations::Entity::find_by_id(id)
+            .one(&txn)
+            .await?
+            .ok_or_else(|| RepositoryError::NotFound(format!("Conversation {} not found", id)))?;
+
+        // Unpinning is decided against the state read inside this transaction
+        let importance_score = match patch.importance_score {
+            None if patch.unpin && model.importance_score >= importance::PINNED_IMPORTANCE => {
+                Some(importance::DEFAULT_IMPORTANCE)
+            }
+            score => score,
+        };
+        let patch = ConversationPatch {
+            importance_score,
+            unpin: false,
+            ..patch
+        };
+
+        if patch.is_empty() {
+            txn.commit().await?;
+            return Ok(Conversation::from(model));
+        }
+        let before = model.clone();
+
+        let mut active_model: conversations::ActiveModel = model.into_active_model();
+        if let Some(label) = patch.label {
+            active_model.label = Set(label);
+        }
+        if let Some(folder) = patch.folder {
+            active_model.folder = Set(folder);
+        }
+        if let Some(status) = patch.status {
+            active_model.status = Set(status);
+        }
+        if let Some(score) = patch.importance_score {
+            active_model.importance_score = Set(score);
+        }
+        active_model.updated_at = Set(chrono::Utc::now().naive_utc());
+
+        let updated = active_model.update(&txn).await?;
+        Self::record_changes(&txn, &before, &updated).await?;
+        txn.commit().await?;
+
+        Ok(Conversation::from(updated))
     }
 
-    async fn find_recent_messages(
-        &self,
-        conversation_id: Uuid,
-        limit: usize,
-    ) -> Result<Vec<Message>, RepositoryError> {
-        let models = messages::Entity::find()
-            .filter(messages::Column::ConversationId.eq(conversation_id.to_string()))
-            .order_by_desc(messages::Column::Timestamp)
-            .limit(limit as u64)
-            .all(&self.db)
+    async fn find_history(&self, id: Uuid) -> Result<Vec<ConversationChange>, RepositoryError> {
+        let models = conversation_history::Entity::find()
+            .filter(conversation_history::Column::ConversationId.eq(id))
+            .order_by_asc(conversation_history::Column::Id)
+            .all(&self.read_db)
             .await?;
 
-        Ok(models.into_iter().map(Message::from).collect())
+        Ok(models.into_iter().map(ConversationChange::from).collect())
     }
 
-    async fn find_by_folder(
+    async fn bulk_move(
         &self,
+        ids: Vec<Uuid>,
         folder: &str,
-        limit: u64,
-        offset: u64,
-    ) -> Result<Vec<Conversation>, RepositoryError> {
+        dry_run: bool,
+    ) -> Result<Vec<Uuid>, RepositoryError> {
+        let txn = self.db.begin().await?;
+
         let models = conversations::Entity::find()
-            .filter(conversations::Column::Folder.eq(folder))
-            .order_by_desc(conversations::Column::UpdatedAt)
-            .limit(limit)
-            .offset(offset)
-            .all(&self.db)
+            .filter(conversations::Column::Id.is_in(ids))
+            .filter(conversations::Column::Folder.ne(folder))
+            .order_by_asc(conversations::Column::Id)
+            .all(&txn)
             .await?;
+        let affected: Vec<Uuid> = models.iter().map(|m| m.id).collect();
+
+        if !dry_run && !affected.is_empty() {
+            conversations::Entity::update_many()
+                .col_expr(conversations::Column::Folder, Expr::value(folder))
+                .col_expr(
+                    conversations::Column::UpdatedAt,
+                    Expr::value(chrono::Utc::now().naive_utc()),
+                )
+                .filter(conversations::Column::Id.is_in(affected.clone()))
+                .exec(&txn)
+                .await?;
 
-        Ok(models.into_iter().map(Conversation::from).collect())
+            for before in &models {
+                let after = conversations::Model {
+                    folder: folder.to_string(),
+                    ..before.clone()
+                };
+                Self::record_changes(&txn, before, &after).await?;
+            }
+        }
+
+        txn.commit().await?;
+        Ok(affected)
     }
 
-    async fn get_all_folders(&self) -> Result<Vec<String>, RepositoryError> {
-        let folders = conversations::Entity::find()
-            .select_only()
-            .column(conversations::Column::Folder)
-            .distinct()
-            .order_by_asc(conversations::Column::Folder)
-            .into_tuple::<String>()
-            .all(&self.db)
+    async fn bulk_update_status(
+        &self,
+        ids: Vec<Uuid>,
+        status: &str,
+        dry_run: bool,
+    ) -> Result<Vec<Uuid>, RepositoryError> {
+        let txn = self.db.begin().await?;
+
+        let models = conversations::Entity::find()
+            .filter(conversations::Column::Id.is_in(ids))
+            .filter(conversations::Column::Status.ne(status))
+            .order_by_asc(conversations::Column::Id)
+            .all(&txn)
             .await?;
+        let affected: Vec<Uuid> = models.iter().map(|m| m.id).collect();
+
+        if !dry_run && !affected.is_empty() {
+            conversations::Entity::update_many()
+                .col_expr(conversations::Column::Status, Expr::value(status))
+                .col_expr(
+                    conversations::Column::UpdatedAt,
+                    Expr::value(chrono::Utc::now().naive_utc()),
+                )
+                .filter(conversations::Column::Id.is_in(affected.clone()))
+                .exec(&txn)
+                .await?;
 
-        Ok(folders)
+            for before in &models {
+                let after = conversations::Model {
+                    status: status.to_string(),
+                    ..before.clone()
+                };
+                Self::record_changes(&txn, before, &after).await?;
+            }
+        }
+
+        txn.commit().await?;
+        Ok(affected)
     }
 
-    async fn update_status(&self, id: Uuid, status: &str) -> Result<(), RepositoryError> {
-        let model = conversations::Entity::find_by_id(id)
-            .one(&self.db)
-            .await?
-            .ok_or_else(|| RepositoryError::NotFound(format!("Conversation {} not found", id)))?;
+    async fn rename_folder(
+        &self,
+        from: &str,
+        to: &str,
+        dry_run: bool,
+    ) -> Result<Vec<Uuid>, RepositoryError> {
+        let from = from.trim_end_matches('/');
+        if from.is_empty() {
+            return Err(RepositoryError::InvalidInput(
+                "the root folder cannot be renamed".to_string(),
+            ));
+        }
 
-        let mut active_model: conversations::ActiveModel = model.into_active_model();
-        active_model.status = Set(status.to_string());
+        let txn = self.db.begin().await?;
 
-        active_model.update(&self.db).await?;
-        Ok(())
-    }
+        let prefix = format!("{}/", from);
+        let mut models = conversations::Entity::find()
+            .filter(
+                Condition::any()
+                    .add(conversations::Column::Folder.eq(from))
+                    .add(conversations::Column::Folder.like(like_prefix(&prefix))),
+            )
+            .order_by_asc(conversations::Column::Id)
+            .all(&txn)
+            .await?;
+        // SQLite's LIKE ignores ASCII case, so drop folders that only match
+        // the prefix case-insensitively
+        models.retain(|m| m.folder == from || m.folder.starts_with(&prefix));
+        let affected = models.iter().map(|m| m.id).collect();
 
-    async fn update_importance(&self, id: Uuid, score: i32) -> Result<(), RepositoryError> {
-        let model = conversations::Entity::find_by_id(id)
-            .one(&self.db)
-            .await?
-            .ok_or_else(|| RepositoryError::NotFound(format!("Conversation {} not found", id)))?;
+        if !dry_run {
+            let now = chrono::Utc::now().naive_utc();
+            for model in models {
+                // Keep the subfolder suffix when renaming a parent folder
+                let folder = match model.folder.strip_prefix(&prefix) {
+                    Some(rest) => format!("{}/{}", to.trim_end_matches('/'), rest),
+                    None => to.to_string(),
+                };
 
-        let mut active_model: conversations::ActiveModel = model.into_active_model();
-        active_model.importance_score = Set(score as i32);
+                let before = model.clone();
+                let mut active_model: conversations::ActiveModel = model.into_active_model();
+                active_model.folder = Set(folder);
+                active_model.updated_at = Set(now);
+                let updated = active_model.update(&txn).await?;
+                Self::record_changes(&txn, &before, &updated).await?;
+            }
+        }
 
-        active_model.update(&self.db).await?;
-        Ok(())
+        txn.commit().await?;
+        Ok(affected)
     }
 
     async fn count_messages_in_conversation(
@@ -590,16 +3488,58 @@ impl ConversationRepository for SeaOrmConversationRepository {
         conversation_id: Uuid,
     ) -> Result<u64, RepositoryError> {
         let count = messages::Entity::find()
-            .filter(messages::Column::ConversationId.eq(conversation_id.to_string()))
-            .count(&self.db)
+            .filter(messages::Column::ConversationId.eq(conversation_id))
+            .count(&self.read_db)
             .await?;
         Ok(count)
     }
 
+    async fn claim_summary_checkpoint(
+        &self,
+        conversation_id: Uuid,
+        every: u64,
+    ) -> Result<Option<u64>, RepositoryError> {
+        let count = self.count_messages_in_conversation(conversation_id).await?;
+        if every == 0 || count < every {
+            return Ok(None);
+        }
+
+        // The conditional upsert makes the claim atomic, so concurrent
+        // appends crossing the same threshold schedule a single summary
+        let checkpoint = summary_checkpoints::ActiveModel {
+            conversation_id: Set(conversation_id),
+            message_count: Set(count as i32),
+            summarized_at: Set(chrono::Utc::now().naive_utc()),
+        };
+        let claimed = summary_checkpoints::Entity::insert(checkpoint)
+            .on_conflict(
+                sea_orm::sea_query::OnConflict::column(summary_checkpoints::Column::ConversationId)
+                    .update_columns([
+                        summary_checkpoints::Column::MessageCount,
+                        summary_checkpoints::Column::SummarizedAt,
+                    ])
+                    .action_and_where(sea_orm::sea_query::ExprTrait::lte(
+                        Expr::col((
+                            summary_checkpoints::Entity,
+                            summary_checkpoints::Column::MessageCount,
+                        )),
+                        (count - every) as i64,
+                    ))
+                    .to_owned(),
+            )
+            .exec_without_returning(&self.db)
+            .await?;
+
+        Ok((claimed > 0).then_some(count))
+    }
+
     async fn full_text_search(
         &self,
         query: &str,
         limit: usize,
+        after: Option<chrono::NaiveDateTime>,
+        before: Option<chrono::NaiveDateTime>,
+        conversation_id: Option<Uuid>,
     ) -> Result<Vec<Message>, RepositoryError> {
         #[derive(sea_orm::FromQueryResult)]
         struct MessageResult {
@@ -614,27 +3554,33 @@ impl ConversationRepository for SeaOrmConversationRepository {
         let results: Vec<MessageResult> =
             MessageResult::find_by_statement(Statement::from_sql_and_values(
                 DatabaseBackend::Sqlite,
-                r#"
+                format!(
+                    r#"
             SELECT 
                 hex(m.id) as id,
                 hex(m.conversation_id) as conversation_id,
                 m.role, 
                 m.content, 
                 m.timestamp, 
-                COALESCE(m.metadata, '{}') as metadata
-            FROM messages m 
-            WHERE m.rowid IN (
-                SELECT rowid FROM messages_fts WHERE messages_fts MATCH ?1
-            )
-            LIMIT ?2
+                COALESCE(m.metadata, '{{}}') as metadata
+            FROM messages m {}
+            ORDER BY {}
+            LIMIT ?5
             "#,
+                    FTS_MATCH_FILTER,
+                    self.fts_ranking.order_by_clause()
+                ),
                 vec![
                     Value::String(Some(query.to_string())),
+                    fts_time_bound(after),
+                    fts_time_bound(before),
+                    Value::from(conversation_id),
                     Value::BigInt(Some(limit as i64)),
                 ],
             ))
-            .all(&self.db)
-            .await?;
+            .all(&self.read_db)
+            .await
+            .map_err(|e| fts_query_error(query, e))?;
 
         Ok(results
             .into_iter()
@@ -667,32 +3613,113 @@ impl ConversationRepository for SeaOrmConversationRepository {
                     content: m.content,
                     timestamp: chrono::NaiveDateTime::parse_from_str(
                         &m.timestamp,
-                        "%Y-%m-%d %H:%M:%S%.f",
+                        TIMESTAMP_FORMAT,
                     )
                     .ok()?,
                     embedding_id: None,
                     metadata: serde_json::from_str(&m.metadata).ok(),
+                    embedding_model: None,
+                    embedding_dim: None,
                 })
             })
             .collect())
     }
 
+    async fn full_text_search_count(
+        &self,
+        query: &str,
+        after: Option<chrono::NaiveDateTime>,
+        before: Option<chrono::NaiveDateTime>,
+        conversation_id: Option<Uuid>,
+    ) -> Result<u64, RepositoryError> {
+        let row = self
+            .read_db
+            .query_one_raw(Statement::from_sql_and_values(
+                DatabaseBackend::Sqlite,
+                format!(
+                    "SELECT COUNT(*) AS total FROM messages m {}",
+                    FTS_MATCH_FILTER
+                ),
+                vec![
+                    Value::String(Some(query.to_string())),
+                    fts_time_bound(after),
+                    fts_time_bound(before),
+                    Value::from(conversation_id),
+                ],
+            ))
+            .await
+            .map_err(|e| fts_query_error(query, e))?;
+
+        let total = match row {
+            Some(row) => row.try_get::<i64>("", "total")?,
+            None => 0,
+        };
+        Ok(total as u64)
+    }
+
     async fn semantic_search(
         &self,
         query: &str,
         limit: usize,
         filters: Option<JsonValue>,
     ) -> Result<Vec<SearchResult>, RepositoryError> {
+        let (results, _) = self
+            .semantic_search_with_model(query, limit, filters, None)
+            .await?;
+        Ok(results)
+    }
+
+    async fn semantic_search_with_model(
+        &self,
+        query: &str,
+        limit: usize,
+        filters: Option<JsonValue>,
+        model: Option<String>,
+    ) -> Result<(Vec<SearchResult>, SearchSource), RepositoryError> {
+        if !self.embeddings_enabled {
+            let results = self
+                .fts_search_results(query, limit, filters.as_ref())
+                .await?;
+            return Ok((results, SearchSource::FullText));
+        }
+
         // FIX: Graceful degradation when Chroma is unavailable (tests)
         let chroma_results = match self
             .embedding_service
-            .search_messages(query, limit, filters)
+            .search_messages_in(model.as_deref(), query, limit, filters.clone())
             .await
         {
             Ok(results) => results,
+            Err(e @ EmbeddingError::UnknownModel(_)) => {
+                return Err(RepositoryError::InvalidInput(e.to_string()))
+            }
+            // No fallback hides this: every query would fail the same way
+            // until the model or the collection changes
+            Err(EmbeddingError::ChromaError(ChromaError::DimensionMismatch {
+                expected,
+                actual,
+            })) => {
+                return Err(RepositoryError::InvalidInput(format!(
+                    "query embedding has {} dimensions but the collection expects {}; \
+                     the embedding model has likely changed, so reindex or switch back",
+                    actual, expected
+                )))
+            }
             Err(e) => {
-                tracing::warn!("Chroma search failed (ok in tests): {}", e);
-                return Ok(vec![]); // Return empty results instead of error
+                return match self.search_fallback {
+                    SearchFallback::Empty => {
+                        tracing::warn!("Chroma search failed (ok in tests): {}", e);
+                        Ok((vec![], SearchSource::Unavailable))
+                    }
+                    SearchFallback::Fts => {
+                        tracing::warn!("Chroma search failed, falling back to FTS: {}", e);
+                        let results = self
+                            .fts_search_results(query, limit, filters.as_ref())
+                            .await?;
+                        Ok((results, SearchSource::FullText))
+                    }
+                    SearchFallback::Error => Err(RepositoryError::ChromaError(e.to_string())),
+                };
             }
         };
 
@@ -700,16 +3727,20 @@ impl ConversationRepository for SeaOrmConversationRepository {
 
         for scored in chroma_results {
             if let Ok(msg_id) = Uuid::parse_str(&scored.id) {
-                if let Some(message) = messages::Entity::find_by_id(msg_id).one(&self.db).await? {
+                if let Some(message) = messages::Entity::find_by_id(msg_id)
+                    .one(&self.read_db)
+                    .await?
+                {
                     if let Some(conversation) =
                         conversations::Entity::find_by_id(message.conversation_id.clone())
-                            .one(&self.db)
+                            .one(&self.read_db)
                             .await?
                     {
                         results.push(SearchResult {
                             conversation_id: conversation.id,
                             message_id: msg_id,
                             score: scored.score,
+                            role: message.role,
                             content: message.content,
                             metadata: scored.metadata,
                             label: conversation.label,
@@ -721,7 +3752,7 @@ impl ConversationRepository for SeaOrmConversationRepository {
             }
         }
 
-        Ok(results)
+        Ok((results, SearchSource::Vectors))
     }
 
     async fn get_stats(&self, folder: Option<String>) -> Result<Stats, Box<dyn std::error::Error>> {
@@ -748,7 +3779,7 @@ impl ConversationRepository for SeaOrmConversationRepository {
             None => {
                 // Global stats across all folders
                 let folders = self.get_all_folders().await?;
-                let (convs, total_count) = self.find_with_filters(None, 10000, 0).await?;
+                let (convs, total_count) = self.find_with_filters(None, None, 10000, 0).await?;
 
                 let average_importance = if total_count > 0 {
                     convs.iter().map(|c| c.importance_score).sum::<i32>() as f32
@@ -791,7 +3822,7 @@ impl ConversationRepository for SeaOrmConversationRepository {
             }
             None => {
                 let folders = self.get_all_folders().await?;
-                let (convs, total_count) = self.find_with_filters(None, 10000, 0).await?;
+                let (convs, total_count) = self.find_with_filters(None, None, 10000, 0).await?;
 
                 let average_importance = if total_count > 0 {
                     convs.iter().map(|c| c.importance_score).sum::<i32>() as f32
@@ -837,7 +3868,7 @@ impl ConversationRepository for SeaOrmConversationRepository {
             None => {
                 // Global stats across all labels
                 let labels = self.get_all_labels().await?;
-                let (convs, total_count) = self.find_with_filters(None, 10000, 0).await?;
+                let (convs, total_count) = self.find_with_filters(None, None, 10000, 0).await?;
 
                 let average_importance = if total_count > 0 {
                     convs.iter().map(|c| c.importance_score).sum::<i32>() as f32
@@ -866,31 +3897,32 @@ impl SeaOrmConversationRepository {
         conversation_id: Uuid,
         new_msg: NewMessage,
     ) -> Result<Uuid, RepositoryError> {
-        let msg_id = Uuid::new_v4();
+        let mut new_msg = self.redact_message(new_msg);
+        let msg_id = new_id(self.id_scheme);
         let now = chrono::Utc::now().naive_utc();
 
-        let embedding_id = match self
-            .embedding_service
-            .process_message(
-                msg_id,
-                &new_msg.content,
-                conversation_id,
-                serde_json::json!({
-                    "role": new_msg.role.clone(),
-                    "conversation_id": conversation_id.to_string(),
-                    "timestamp": now,
-                }),
-            )
-            .await
-        {
-            Ok(id) => Some(id),
-            Err(e) => {
+        let embedding = if !self.embeddings_enabled || self.skip_short_message(&mut new_msg) {
+            None
+        } else {
+            let report = self
+                .embed_batch(vec![EmbeddingRequest {
+                    message_id: msg_id,
+                    conversation_id,
+                    content: new_msg.content.clone(),
+                    metadata: serde_json::json!({
+                        "role": new_msg.role.clone(),
+                        "conversation_id": conversation_id.to_string(),
+                        "timestamp": now,
+                    }),
+                }])
+                .await;
+            for (_, e) in &report.failed {
                 tracing::warn!("Embedding generation failed (ok in tests): {}", e);
-                None
             }
+            self.stored_embedding(&report, msg_id)
         };
 
-        let has_embedding = embedding_id.is_some();
+        let has_embedding = embedding.is_some();
 
         // FIX: Pass metadata directly as JsonValue
         let metadata_value = if new_msg.metadata.is_null() {
@@ -906,8 +3938,11 @@ impl SeaOrmConversationRepository {
             role: Set(new_msg.role),
             content: Set(new_msg.content),
             timestamp: Set(new_msg.timestamp),
-            embedding_id: Set(embedding_id.map(|id| ToString::to_string(&id))),
+            embedding_id: Set(embedding.as_ref().map(|e| e.id.clone())),
             metadata: Set(metadata_value),
+            seq: Set(Self::next_message_seq(&self.db, conversation_id).await?),
+            embedding_model: Set(embedding.as_ref().map(|e| e.model.clone())),
+            embedding_dim: Set(embedding.and_then(|e| e.dim)),
         };
 
         if let Err(e) = message.insert(&self.db).await {
@@ -929,6 +3964,92 @@ impl SeaOrmConversationRepository {
     }
 }
 
+/// Scale each result's score by its role's weight (unlisted roles keep 1.0)
+/// and re-sort best first. The sort is stable, so ties keep their order.
+pub fn apply_role_weights(results: &mut [SearchResult], weights: &HashMap<String, f32>) {
+    if weights.is_empty() {
+        return;
+    }
+
+    for result in results.iter_mut() {
+        if let Some(weight) = weights.get(&result.role) {
+            result.score *= weight;
+        }
+    }
+    results.sort_by(|a, b| b.score.total_cmp(&a.score));
+}
+
+/// Whether `fields` satisfy a Chroma `where` clause, so full-text results can
+/// honour the filters a semantic search would have applied. Supports plain
+/// equality, `$eq`/`$ne`/`$in`/`$nin`, numeric comparisons, `$and` and `$or`;
+/// anything else matches nothing rather than letting results through.
+fn matches_where(filter: &JsonValue, fields: &serde_json::Map<String, JsonValue>) -> bool {
+    let Some(clauses) = filter.as_object() else {
+        return false;
+    };
+
+    clauses.iter().all(|(key, condition)| match key.as_str() {
+        "$and" => condition
+            .as_array()
+            .is_some_and(|all| all.iter().all(|f| matches_where(f, fields))),
+        "$or" => condition
+            .as_array()
+            .is_some_and(|any| any.iter().any(|f| matches_where(f, fields))),
+        _ => {
+            let value = fields.get(key);
+            let Some(ops) = condition.as_object() else {
+                return value == Some(condition);
+            };
+            ops.iter().all(|(op, operand)| {
+                let compare = |ord: fn(f64, f64) -> bool| match (
+                    value.and_then(JsonValue::as_f64),
+                    operand.as_f64(),
+                ) {
+                    (Some(v), Some(o)) => ord(v, o),
+                    _ => false,
+                };
+                match op.as_str() {
+                    "$eq" => value == Some(operand),
+                    "$ne" => value != Some(operand),
+                    "$in" => operand
+                        .as_array()
+                        .is_some_and(|list| value.is_some_and(|v| list.contains(v))),
+                    "$nin" => operand
+                        .as_array()
+                        .is_some_and(|list| value.map_or(true, |v| !list.contains(v))),
+                    "$gt" => compare(|v, o| v > o),
+                    "$gte" => compare(|v, o| v >= o),
+                    "$lt" => compare(|v, o| v < o),
+                    "$lte" => compare(|v, o| v <= o),
+                    _ => false,
+                }
+            })
+        }
+    })
+}
+
+/// The metadata a message's vectors carry in Chroma, for [`matches_where`]
+fn vector_metadata(message: &Message) -> serde_json::Map<String, JsonValue> {
+    let mut fields = serde_json::Map::new();
+    if let Some(JsonValue::Object(metadata)) = &message.metadata {
+        for (key, value) in metadata {
+            if matches!(
+                value,
+                JsonValue::String(_) | JsonValue::Number(_) | JsonValue::Bool(_)
+            ) {
+                fields.insert(key.clone(), value.clone());
+            }
+        }
+    }
+    fields.insert("role".to_string(), json!(message.role));
+    fields.insert(
+        "conversation_id".to_string(),
+        json!(message.conversation_id.to_string()),
+    );
+    fields.insert("message_id".to_string(), json!(message.id.to_string()));
+    fields
+}
+
 // ============================================
 // Data structures
 // ============================================
@@ -938,6 +4059,7 @@ pub struct SearchResult {
     pub conversation_id: Uuid,
     pub message_id: Uuid,
     pub score: f32,
+    pub role: String,
     pub content: String,
     pub metadata: JsonValue,
     pub label: String,
@@ -961,6 +4083,21 @@ impl From<conversations::Model> for Conversation {
             session_count: model.session_count,
             created_at: model.created_at,
             updated_at: model.updated_at,
+            context_pinned: model.context_pinned,
+            last_accessed_at: model.last_accessed_at,
+        }
+    }
+}
+
+impl From<offloaded_conversations::Model> for OffloadRecord {
+    fn from(model: offloaded_conversations::Model) -> Self {
+        Self {
+            conversation_id: model.conversation_id,
+            label: model.label,
+            folder: model.folder,
+            file_path: model.file_path,
+            message_count: model.message_count,
+            offloaded_at: model.offloaded_at,
         }
     }
 }
@@ -975,10 +4112,85 @@ impl From<messages::Model> for Message {
             timestamp: model.timestamp,
             embedding_id: model.embedding_id,
             metadata: model.metadata,
+            embedding_model: model.embedding_model,
+            embedding_dim: model.embedding_dim,
+        }
+    }
+}
+
+impl From<hierarchical_summaries::Model> for StoredSummary {
+    fn from(model: hierarchical_summaries::Model) -> Self {
+        Self {
+            id: model.id,
+            conversation_id: model.conversation_id,
+            level: model.level,
+            summary_text: model.summary_text,
+            period: model.timestamp_range,
+            generated_at: model.generated_at,
+            model_used: model.model_used,
+            token_count: model.token_count,
+        }
+    }
+}
+
+impl From<conversation_history::Model> for ConversationChange {
+    fn from(model: conversation_history::Model) -> Self {
+        Self {
+            conversation_id: model.conversation_id,
+            field: model.field,
+            old_value: model.old_value,
+            new_value: model.new_value,
+            changed_at: model.changed_at,
+        }
+    }
+}
+
+impl From<semantic_tags::Model> for StoredTag {
+    fn from(model: semantic_tags::Model) -> Self {
+        Self {
+            id: model.id,
+            conversation_id: model.conversation_id,
+            tag: model.tag,
+            confidence: model.confidence,
+            extracted_at: model.extracted_at,
+        }
+    }
+}
+
+impl From<conversation_labels::Model> for ConversationLabel {
+    fn from(model: conversation_labels::Model) -> Self {
+        Self {
+            label: model.label,
+            weight: model.weight,
+            added_at: model.added_at,
+        }
+    }
+}
+
+impl From<conversation_templates::Model> for ConversationTemplate {
+    fn from(model: conversation_templates::Model) -> Self {
+        Self {
+            name: model.name,
+            label_pattern: model.label_pattern,
+            folder: model.folder,
+            system_message: model.system_message,
+            tags: serde_json::from_value(model.tags).unwrap_or_default(),
+            created_at: model.created_at,
+            updated_at: model.updated_at,
         }
     }
 }
 
+/// Sort key for summary levels, from finest to coarsest
+fn summary_level_rank(level: &str) -> u8 {
+    match level {
+        "daily" => 0,
+        "weekly" => 1,
+        "monthly" => 2,
+        _ => 3,
+    }
+}
+
 impl From<crate::storage::chroma_client::ChromaError> for RepositoryError {
     fn from(err: crate::storage::chroma_client::ChromaError) -> Self {
         RepositoryError::ChromaError(err.to_string())
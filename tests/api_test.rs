@@ -75,7 +75,13 @@ async fn create_test_app() -> Router {
         config: create_test_config().await,
         repo: repo.clone(),
         chroma_client,
+        events: sekha_controller::services::EventBus::default(),
+        search_cache: Default::default(),
+        tasks: Default::default(),
         embedding_service,
+        embedding_queue: Arc::new(
+            sekha_controller::services::embedding_queue::EmbeddingQueue::new(),
+        ),
         orchestrator: Arc::new(MemoryOrchestrator::new(repo, llm_bridge)),
     };
 
@@ -90,7 +96,13 @@ async fn create_test_mcp_app() -> Router {
         config: create_test_config().await,
         repo: repo.clone(),
         chroma_client,
+        events: sekha_controller::services::EventBus::default(),
+        search_cache: Default::default(),
+        tasks: Default::default(),
         embedding_service,
+        embedding_queue: Arc::new(
+            sekha_controller::services::embedding_queue::EmbeddingQueue::new(),
+        ),
         orchestrator: Arc::new(MemoryOrchestrator::new(repo, llm_bridge)),
     };
 
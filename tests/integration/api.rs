@@ -1,9 +1,10 @@
-use super::{create_test_app, Uuid};
+use super::{create_test_app, create_test_app_state, is_chroma_running, Uuid};
 // use crate::integration::create_test_app;
 use axum::{
     body::Body,
     http::{Request, StatusCode},
 };
+use sekha_controller::storage::repository::CreatedConversation;
 use tower::ServiceExt;
 
 // ============================================
@@ -39,6 +40,120 @@ async fn test_api_create_conversation() {
     assert!(body_str.contains("conversation_id"));
 }
 
+#[tokio::test]
+async fn test_api_create_response_reports_counts() {
+    let app = create_test_app().await;
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/conversations")
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    r#"{ "label": "Counts", "folder": "/api", "messages": [
+                        {"role": "user", "content": "Hello"},
+                        {"role": "assistant", "content": "Hi there"}
+                    ] }"#,
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = axum::body::to_bytes(response.into_body(), 4096)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["message_count"], 2);
+    assert_eq!(json["word_count"], "Hello".len() + "Hi there".len());
+}
+
+#[tokio::test]
+async fn test_api_append_response_reports_conversation_totals() {
+    let mut state = create_test_app_state().await;
+    state.repo = std::sync::Arc::new(
+        sekha_controller::storage::SeaOrmConversationRepository::new(
+            state.repo.get_db().clone(),
+            state.chroma_client.clone(),
+            state.embedding_service.clone(),
+        )
+        .with_redactor(
+            sekha_controller::models::redaction::Redactor::new(&[r"\d{4}-\d{4}".to_string()])
+                .unwrap(),
+        ),
+    );
+    let repo = state.repo.clone();
+    let app = sekha_controller::api::routes::create_router(state);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/conversations")
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    r#"{ "label": "Totals", "folder": "/api", "messages": [
+                        {"role": "user", "content": "Hello"},
+                        {"role": "assistant", "content": "Hi there, pin 1234-5678"}
+                    ] }"#,
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = axum::body::to_bytes(response.into_body(), 4096)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let conv_id = json["id"].as_str().unwrap().to_string();
+    // Create reports what was stored, counted after redaction
+    let created = repo
+        .find_by_id(Uuid::parse_str(&conv_id).unwrap())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(json["word_count"], created.word_count);
+    assert_eq!(
+        json["word_count"],
+        "Hello".len() + "Hi there, pin [REDACTED]".len()
+    );
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/v1/conversations/{}/messages", conv_id))
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    r#"{ "messages": [{"role": "user", "content": "Bye"}] }"#,
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = axum::body::to_bytes(response.into_body(), 4096)
+        .await
+        .unwrap();
+    let appended: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(appended["message_count"], 3);
+    assert_eq!(
+        appended["word_count"],
+        "Hello".len() + "Hi there, pin [REDACTED]".len() + "Bye".len()
+    );
+
+    let stored = repo
+        .find_by_id(Uuid::parse_str(&conv_id).unwrap())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(appended["word_count"], stored.word_count);
+}
+
 #[tokio::test]
 async fn test_api_get_conversation() {
     let app = create_test_app().await;
@@ -271,6 +386,99 @@ async fn test_api_query_semantic_search() {
     assert!(json["results"].is_array());
 }
 
+#[tokio::test]
+async fn test_api_semantic_query_clamps_oversized_limit() {
+    let app = create_test_app().await;
+
+    let json = post_json(
+        &app,
+        "/api/v1/query",
+        serde_json::json!({ "query": "capital France", "limit": 100000 }),
+    )
+    .await;
+    assert_eq!(json["effective_limit"], 100);
+    assert_eq!(json["page_size"], 100);
+
+    let json = post_json(
+        &app,
+        "/api/v1/query",
+        serde_json::json!({ "query": "capital France" }),
+    )
+    .await;
+    assert_eq!(json["effective_limit"], 10);
+}
+
+#[tokio::test]
+async fn test_api_semantic_query_exports_csv() {
+    let app = create_test_app().await;
+
+    let conversation = serde_json::json!({
+        "label": "Quarterly, planning",
+        "folder": "/work",
+        "messages": [{
+            "role": "user",
+            "content": "Budget review: cut travel, keep \"offsite\"\nthen hire"
+        }]
+    });
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/conversations")
+                .header("Content-Type", "application/json")
+                .body(Body::from(conversation.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/query?format=csv")
+                .header("Content-Type", "application/json")
+                .body(Body::from(r#"{ "query": "budget travel" }"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers()["content-type"]
+        .to_str()
+        .unwrap()
+        .starts_with("text/csv"));
+
+    let body = axum::body::to_bytes(response.into_body(), 8192)
+        .await
+        .unwrap();
+    let csv = String::from_utf8(body.to_vec()).unwrap();
+    let (header, rows) = csv.split_once("\r\n").unwrap();
+    assert_eq!(
+        header,
+        "conversation_id,label,folder,score,timestamp,content"
+    );
+    assert!(rows.contains(",\"Quarterly, planning\",/work,"));
+    assert!(rows.ends_with(",\"Budget review: cut travel, keep \"\"offsite\"\"\nthen hire\"\r\n"));
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/query?format=xml")
+                .header("Content-Type", "application/json")
+                .body(Body::from(r#"{ "query": "budget" }"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
 // ============================================
 // Error Handling Tests
 // ============================================
@@ -401,6 +609,62 @@ async fn test_count_conversations_by_folder() {
     assert_eq!(json["count"], 3);
 }
 
+#[tokio::test]
+async fn test_api_create_conversation_uses_default_folder() {
+    let state = create_test_app_state().await;
+    state.config.write().await.default_folder = "/triage/".to_string();
+    let app = sekha_controller::api::routes::create_router(state);
+
+    let create = |body: &'static str| {
+        app.clone().oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/conversations")
+                .header("Content-Type", "application/json")
+                .body(Body::from(body))
+                .unwrap(),
+        )
+    };
+    let folder_of = |response: axum::response::Response| async move {
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body = axum::body::to_bytes(response.into_body(), 4096)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        json["folder"].as_str().unwrap().to_string()
+    };
+
+    let response =
+        create(r#"{"label": "No folder", "messages": [{"role": "user", "content": "Hi"}]}"#)
+            .await
+            .unwrap();
+    assert_eq!(folder_of(response).await, "/triage");
+
+    // Given folders are tidied the same way
+    let response = create(
+        r#"{"label": "Given", "folder": "/work//notes/", "messages": [{"role": "user", "content": "Hi"}]}"#,
+    )
+    .await
+    .unwrap();
+    assert_eq!(folder_of(response).await, "/work/notes");
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/v1/conversations/count?folder=/triage")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = axum::body::to_bytes(response.into_body(), 1024)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["count"], 1);
+}
+
 #[tokio::test]
 async fn test_count_conversations_no_filters() {
     let app = create_test_app().await;
@@ -445,6 +709,93 @@ async fn test_count_conversations_no_filters() {
     assert!(json["count"].as_u64().unwrap() >= 5);
 }
 
+#[tokio::test]
+async fn test_api_stats_timeline_counts_per_bucket() {
+    let state = create_test_app_state().await;
+    let repo = state.repo.clone();
+    let app = sekha_controller::api::routes::create_router(state);
+
+    let at = |day: &str, time: &str| {
+        chrono::NaiveDateTime::parse_from_str(&format!("{day} {time}"), "%Y-%m-%d %H:%M:%S%.f")
+            .unwrap()
+    };
+    // 2024-03-04 and 2024-03-11 are Mondays
+    for (day, time, message_count) in [
+        ("2024-03-04", "09:00:00", 1),
+        ("2024-03-04", "23:59:59.250", 1),
+        ("2024-03-05", "12:30:00", 2),
+        ("2024-03-11", "00:00:00", 1),
+        ("2024-04-02", "08:15:00", 1),
+    ] {
+        let created = at(day, time);
+        let mut conv = super::create_test_conversation();
+        conv.created_at = created;
+        conv.updated_at = created;
+        conv.messages = (0..message_count)
+            .map(|i| sekha_controller::models::internal::NewMessage {
+                role: "user".to_string(),
+                content: format!("message {i}"),
+                timestamp: created,
+                metadata: serde_json::json!({}),
+            })
+            .collect();
+        repo.create_with_messages(conv).await.unwrap();
+    }
+
+    let timeline = |uri: &'static str| {
+        let app = app.clone();
+        async move {
+            let response = app
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            let status = response.status();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            (status, serde_json::from_slice(&body).unwrap_or_default())
+        }
+    };
+
+    let (status, json): (_, serde_json::Value) =
+        timeline("/api/v1/stats/timeline?bucket=day&from=2024-03-01&to=2024-03-31").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        json["buckets"],
+        serde_json::json!([
+            { "start": "2024-03-04", "conversations": 2, "messages": 2 },
+            { "start": "2024-03-05", "conversations": 1, "messages": 2 },
+            { "start": "2024-03-11", "conversations": 1, "messages": 1 }
+        ])
+    );
+
+    let (_, json) = timeline("/api/v1/stats/timeline?bucket=week").await;
+    assert_eq!(
+        json["buckets"],
+        serde_json::json!([
+            { "start": "2024-03-04", "conversations": 3, "messages": 4 },
+            { "start": "2024-03-11", "conversations": 1, "messages": 1 },
+            { "start": "2024-04-01", "conversations": 1, "messages": 1 }
+        ])
+    );
+
+    let (_, json) = timeline("/api/v1/stats/timeline?bucket=month").await;
+    assert_eq!(
+        json["buckets"],
+        serde_json::json!([
+            { "start": "2024-03-01", "conversations": 4, "messages": 5 },
+            { "start": "2024-04-01", "conversations": 1, "messages": 1 }
+        ])
+    );
+
+    let (status, json) = timeline("/api/v1/stats/timeline?bucket=hour").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(json["error"], "bucket must be one of: day, week, month");
+    let (status, json) = timeline("/api/v1/stats/timeline?from=2024-04-01&to=2024-03-01").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(json["error"], "from must not be after to");
+}
+
 #[tokio::test]
 async fn test_count_conversations_by_label() {
     let app = create_test_app().await;
@@ -552,6 +903,126 @@ async fn test_list_conversations_with_pinned_filter() {
     assert_eq!(response.status(), StatusCode::OK);
 }
 
+#[tokio::test]
+async fn test_list_conversations_fuzzy_label_tolerates_typos() {
+    let app = create_test_app().await;
+
+    for label in ["Rust Programming", "Cooking Notes"] {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/v1/conversations")
+                    .header("Content-Type", "application/json")
+                    .body(Body::from(
+                        serde_json::json!({
+                            "label": label,
+                            "folder": "/fuzzy",
+                            "messages": [{"role": "user", "content": "Hello"}]
+                        })
+                        .to_string(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    let list = |uri: &'static str| {
+        let app = app.clone();
+        async move {
+            let response = app
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+        }
+    };
+
+    let exact = list("/api/v1/conversations?label=rust%20programing").await;
+    assert_eq!(exact["total"], 0);
+
+    let fuzzy = list("/api/v1/conversations?label=rust%20programing&fuzzy=true").await;
+    assert_eq!(fuzzy["total"], 1);
+    assert_eq!(fuzzy["results"][0]["label"], "Rust Programming");
+}
+
+#[tokio::test]
+async fn test_list_conversations_filters_by_message_role() {
+    let app = create_test_app().await;
+
+    let conversations = [
+        (
+            "With System Prompt",
+            serde_json::json!([
+                {"role": "system", "content": "You are a terse assistant"},
+                {"role": "user", "content": "Hello"}
+            ]),
+        ),
+        (
+            "Plain Chat",
+            serde_json::json!([
+                {"role": "user", "content": "Hello"},
+                {"role": "assistant", "content": "Hi there"}
+            ]),
+        ),
+    ];
+    for (label, messages) in conversations {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/v1/conversations")
+                    .header("Content-Type", "application/json")
+                    .body(Body::from(
+                        serde_json::json!({
+                            "label": label,
+                            "folder": "/roles",
+                            "messages": messages
+                        })
+                        .to_string(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    let list = |uri: &'static str| {
+        let app = app.clone();
+        async move {
+            let response = app
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+        }
+    };
+
+    let system = list("/api/v1/conversations?has_role=system").await;
+    assert_eq!(system["total"], 1);
+    assert_eq!(system["results"][0]["label"], "With System Prompt");
+
+    let assistant = list("/api/v1/conversations?has_role=assistant").await;
+    assert_eq!(assistant["total"], 1);
+    assert_eq!(assistant["results"][0]["label"], "Plain Chat");
+
+    let user = list("/api/v1/conversations?has_role=user").await;
+    assert_eq!(user["total"], 2);
+}
+
 // ============================================
 // Error Path Coverage
 // ============================================
@@ -676,3 +1147,3847 @@ async fn test_list_conversations_pinned_filter() {
 
     assert_eq!(response.status(), StatusCode::OK);
 }
+
+#[tokio::test]
+async fn test_api_query_rejects_empty_query() {
+    let app = create_test_app().await;
+
+    for query in [r#"{ "query": "" }"#, r#"{ "query": "   \t " }"#] {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/v1/query")
+                    .header("Content-Type", "application/json")
+                    .body(Body::from(query))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = axum::body::to_bytes(response.into_body(), 1024)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["error"], "query must not be empty");
+    }
+}
+
+#[tokio::test]
+async fn test_api_query_rejects_unknown_embedding_model() {
+    let app = create_test_app().await;
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/query?model=nomic")
+                .header("Content-Type", "application/json")
+                .body(Body::from(r#"{ "query": "rust" }"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = axum::body::to_bytes(response.into_body(), 1024)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert!(json["error"]
+        .as_str()
+        .unwrap()
+        .contains("unknown embedding model 'nomic'"));
+}
+
+#[tokio::test]
+async fn test_api_fts_rejects_empty_query() {
+    let app = create_test_app().await;
+
+    for query in [r#"{ "query": "" }"#, r#"{ "query": "   " }"#] {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/v1/search/fts")
+                    .header("Content-Type", "application/json")
+                    .body(Body::from(query))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = axum::body::to_bytes(response.into_body(), 1024)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["error"], "query must not be empty");
+    }
+}
+
+#[tokio::test]
+async fn test_api_fts_rejects_malformed_query() {
+    let app = create_test_app().await;
+    create_api_test_conversation(&app).await;
+
+    for query in [r#"test ("#, r#""unterminated"#, "nosuchcolumn:test"] {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/v1/search/fts")
+                    .header("Content-Type", "application/json")
+                    .body(Body::from(
+                        serde_json::json!({ "query": query }).to_string(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{}", query);
+        let body = axum::body::to_bytes(response.into_body(), 1024)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert!(json["error"]
+            .as_str()
+            .unwrap()
+            .contains("invalid full-text query"));
+    }
+}
+
+#[tokio::test]
+async fn test_api_list_summaries_after_daily_summary() {
+    let app = create_test_app().await;
+
+    let create_response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/conversations")
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    r#"{ "label": "Summary List", "folder": "/summaries", "messages": [{"role": "user", "content": "Summarize me"}] }"#,
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let body = axum::body::to_bytes(create_response.into_body(), 1024)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let conv_id = json["id"].as_str().unwrap().to_string();
+
+    let summarize_response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/summarize")
+                .header("Content-Type", "application/json")
+                .body(Body::from(format!(
+                    r#"{{ "conversation_id": "{}", "level": "daily" }}"#,
+                    conv_id
+                )))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(summarize_response.status(), StatusCode::OK);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(format!("/api/v1/conversations/{}/summaries", conv_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), 4096)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["total"], 1);
+    assert_eq!(json["summaries"][0]["level"], "daily");
+    assert!(json["summaries"][0]["period"]
+        .as_str()
+        .unwrap()
+        .contains('/'));
+    assert!(json["summaries"][0]["generated_at"].is_string());
+}
+
+#[tokio::test]
+async fn test_api_list_summaries_nonexistent_conversation() {
+    let app = create_test_app().await;
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(format!(
+                    "/api/v1/conversations/{}/summaries",
+                    Uuid::new_v4()
+                ))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_api_version() {
+    let app = create_test_app().await;
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri("/api/v1/version")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), 1024)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["crate_version"], env!("CARGO_PKG_VERSION"));
+    assert!(!json["git_sha"].as_str().unwrap().is_empty());
+    assert!(json["schema_version"]
+        .as_str()
+        .unwrap()
+        .starts_with("m20241211_"));
+}
+
+async fn create_api_test_conversation(app: &axum::Router) -> String {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/conversations")
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    r#"{ "label": "Patch Test", "folder": "/patch", "messages": [{"role": "user", "content": "Test"}] }"#,
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let body = axum::body::to_bytes(response.into_body(), 1024)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    json["id"].as_str().unwrap().to_string()
+}
+
+async fn send_patch(app: &axum::Router, conv_id: &str, body: &str) -> axum::response::Response {
+    app.clone()
+        .oneshot(
+            Request::builder()
+                .method("PATCH")
+                .uri(format!("/api/v1/conversations/{}", conv_id))
+                .header("Content-Type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_api_patch_conversation_single_field() {
+    let app = create_test_app().await;
+    let conv_id = create_api_test_conversation(&app).await;
+
+    let response = send_patch(&app, &conv_id, r#"{ "label": "Patched" }"#).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), 1024)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["label"], "Patched");
+    assert_eq!(json["folder"], "/patch");
+    assert_eq!(json["status"], "active");
+}
+
+#[tokio::test]
+async fn test_api_patch_conversation_multiple_fields() {
+    let app = create_test_app().await;
+    let conv_id = create_api_test_conversation(&app).await;
+
+    let response = send_patch(
+        &app,
+        &conv_id,
+        r#"{ "label": "Multi", "folder": "/multi", "status": "archived", "pinned": true }"#,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), 1024)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["label"], "Multi");
+    assert_eq!(json["folder"], "/multi");
+    assert_eq!(json["status"], "archived");
+}
+
+#[tokio::test]
+async fn test_api_patch_conversation_empty_is_noop() {
+    let app = create_test_app().await;
+    let conv_id = create_api_test_conversation(&app).await;
+
+    let response = send_patch(&app, &conv_id, "{}").await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), 1024)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["label"], "Patch Test");
+    assert_eq!(json["folder"], "/patch");
+}
+
+#[tokio::test]
+async fn test_api_patch_conversation_rejects_invalid_values() {
+    let app = create_test_app().await;
+    let conv_id = create_api_test_conversation(&app).await;
+
+    let response = send_patch(&app, &conv_id, r#"{ "status": "deleted" }"#).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = send_patch(&app, &conv_id, r#"{ "importance_score": 11 }"#).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    for blank in [r#"{ "label": "  " }"#, r#"{ "folder": "" }"#] {
+        let response = send_patch(&app, &conv_id, blank).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{}", blank);
+    }
+
+    // Folders are stored the way every other endpoint stores them
+    let response = send_patch(&app, &conv_id, r#"{ "folder": " /work//notes/ " }"#).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), 1024)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["folder"], "/work/notes");
+
+    let response = send_patch(
+        &app,
+        &Uuid::new_v4().to_string(),
+        r#"{ "label": "Missing" }"#,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_api_patch_conversation_unpins_only_pinned_conversations() {
+    let state = create_test_app_state().await;
+    let repo = state.repo.clone();
+    let app = sekha_controller::api::routes::create_router(state);
+    let importance = |conv_id: String| {
+        let repo = repo.clone();
+        async move {
+            repo.find_by_id(Uuid::parse_str(&conv_id).unwrap())
+                .await
+                .unwrap()
+                .unwrap()
+                .importance_score
+        }
+    };
+
+    let pinned = create_api_test_conversation(&app).await;
+    let response = send_patch(&app, &pinned, r#"{ "pinned": true }"#).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(importance(pinned.clone()).await, 10);
+    let response = send_patch(&app, &pinned, r#"{ "pinned": false }"#).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(importance(pinned).await, 5);
+
+    let unpinned = create_api_test_conversation(&app).await;
+    let response = send_patch(&app, &unpinned, r#"{ "importance_score": 3 }"#).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = send_patch(&app, &unpinned, r#"{ "pinned": false }"#).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(importance(unpinned).await, 3);
+
+    let response = send_patch(&app, &Uuid::new_v4().to_string(), r#"{ "pinned": false }"#).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_api_query_reports_fts_fallback_when_chroma_down() {
+    if is_chroma_running().await {
+        eprintln!("⚠️  Skipping FTS fallback test - Chroma is running");
+        return;
+    }
+
+    let app = create_test_app().await;
+
+    app.clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/conversations")
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    r#"{ "label": "Fallback Test", "folder": "/fallback", "messages": [{"role": "user", "content": "Paris is the capital of France"}] }"#,
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/query")
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    r#"{ "query": "What is the capital of France?", "limit": 5 }"#,
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), 4096)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert!(json["took_ms"].is_u64());
+    assert_eq!(json["search_backend"], "fts");
+    assert_eq!(json["total"], 1);
+    assert_eq!(json["results"][0]["label"], "Fallback Test");
+}
+
+#[tokio::test]
+async fn test_api_query_fts_fallback_applies_filters() {
+    if is_chroma_running().await {
+        eprintln!("⚠️  Skipping FTS fallback test - Chroma is running");
+        return;
+    }
+
+    let state = create_test_app_state().await;
+    let repo = state.repo.clone();
+    let app = sekha_controller::api::routes::create_router(state);
+
+    let mut conversation_ids = Vec::new();
+    for label in ["Harbour one", "Harbour two"] {
+        let mut conv = super::create_test_conversation();
+        conv.label = label.to_string();
+        conv.messages[0].content = "the harbour pilot boarded at dawn".to_string();
+        conv.messages[1].content = "the harbour was calm".to_string();
+        conversation_ids.push(repo.create_with_messages(conv).await.unwrap());
+    }
+
+    let query = |filters: serde_json::Value| {
+        app.clone().oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/query")
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    serde_json::json!({ "query": "harbour", "filters": filters }).to_string(),
+                ))
+                .unwrap(),
+        )
+    };
+    let results = |response: axum::response::Response| async move {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["search_backend"], "fts");
+        json["results"].as_array().unwrap().clone()
+    };
+
+    let response = query(serde_json::json!({ "conversation_id": conversation_ids[1].to_string() }))
+        .await
+        .unwrap();
+    let hits = results(response).await;
+    assert_eq!(hits.len(), 2);
+    assert!(hits
+        .iter()
+        .all(|h| h["conversation_id"] == conversation_ids[1].to_string()));
+
+    let response = query(serde_json::json!({
+        "$and": [
+            { "role": "assistant" },
+            { "conversation_id": { "$in": [conversation_ids[0].to_string()] } }
+        ]
+    }))
+    .await
+    .unwrap();
+    let hits = results(response).await;
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0]["content"], "the harbour was calm");
+    assert_eq!(hits[0]["label"], "Harbour one");
+}
+
+#[tokio::test]
+async fn test_api_query_excludes_folders() {
+    let state = create_test_app_state().await;
+    {
+        let mut config = state.config.write().await;
+        config.embeddings_enabled = false;
+        config.search_exclude_folders = vec!["/archive".to_string()];
+    }
+    let app = sekha_controller::api::routes::create_router(state);
+
+    for (label, folder) in [
+        ("Raw import", "/imports/raw"),
+        ("Importsfile", "/importsfile"),
+        ("Archived", "/archive/2023"),
+        ("Notes", "/notes"),
+    ] {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/v1/conversations")
+                    .header("Content-Type", "application/json")
+                    .body(Body::from(
+                        serde_json::json!({
+                            "label": label,
+                            "folder": folder,
+                            "messages": [{"role": "user", "content": "zebra migration routes"}]
+                        })
+                        .to_string(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    let labels = |json: serde_json::Value| {
+        let mut labels: Vec<String> = json["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["label"].as_str().unwrap().to_string())
+            .collect();
+        labels.sort();
+        labels
+    };
+
+    // The request's list replaces the configured one, matching subfolders
+    // but not folders that merely share the name's prefix
+    let json = post_json(
+        &app,
+        "/api/v1/query",
+        serde_json::json!({ "query": "zebra migration", "exclude_folders": ["/imports/"] }),
+    )
+    .await;
+    assert_eq!(labels(json), vec!["Archived", "Importsfile", "Notes"]);
+
+    // Without one, the configured exclusions apply
+    let json = post_json(
+        &app,
+        "/api/v1/query",
+        serde_json::json!({ "query": "zebra migration" }),
+    )
+    .await;
+    assert_eq!(labels(json), vec!["Importsfile", "Notes", "Raw import"]);
+
+    let json = post_json(
+        &app,
+        "/api/v1/query",
+        serde_json::json!({ "query": "zebra migration", "exclude_folders": [] }),
+    )
+    .await;
+    assert_eq!(labels(json).len(), 4);
+}
+
+#[tokio::test]
+async fn test_api_query_max_age_excludes_stale_conversations() {
+    let state = create_test_app_state().await;
+    {
+        let mut config = state.config.write().await;
+        config.embeddings_enabled = false;
+        config.search_max_age_days = Some(30);
+    }
+    let repo = state.repo.clone();
+    let app = sekha_controller::api::routes::create_router(state);
+
+    let now = chrono::Utc::now().naive_utc();
+    for (label, last_active) in [
+        ("Old plan", now - chrono::Duration::days(90)),
+        ("Recent plan", now),
+    ] {
+        let mut conv = super::create_test_conversation();
+        conv.label = label.to_string();
+        conv.folder = "/plans".to_string();
+        conv.created_at = last_active;
+        conv.updated_at = last_active;
+        conv.messages = vec![sekha_controller::models::internal::NewMessage {
+            role: "user".to_string(),
+            content: "zebra migration routes".to_string(),
+            timestamp: last_active,
+            metadata: serde_json::json!({}),
+        }];
+        repo.create_with_messages(conv).await.unwrap();
+    }
+
+    let labels = |json: serde_json::Value| {
+        let mut labels: Vec<String> = json["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["label"].as_str().unwrap().to_string())
+            .collect();
+        labels.sort();
+        labels
+    };
+
+    // The configured default leaves out the conversation idle for 90 days
+    let json = post_json(
+        &app,
+        "/api/v1/query",
+        serde_json::json!({ "query": "zebra migration" }),
+    )
+    .await;
+    assert_eq!(labels(json), vec!["Recent plan"]);
+
+    // A wider window from the request brings it back, and 0 lifts the limit
+    let json = post_json(
+        &app,
+        "/api/v1/query",
+        serde_json::json!({ "query": "zebra migration", "max_age_days": 120 }),
+    )
+    .await;
+    assert_eq!(labels(json), vec!["Old plan", "Recent plan"]);
+
+    let json = post_json(
+        &app,
+        "/api/v1/query",
+        serde_json::json!({ "query": "zebra migration", "max_age_days": 0 }),
+    )
+    .await;
+    assert_eq!(labels(json).len(), 2);
+
+    // Combines with folder exclusions
+    let json = post_json(
+        &app,
+        "/api/v1/query",
+        serde_json::json!({
+            "query": "zebra migration",
+            "max_age_days": 120,
+            "exclude_folders": ["/plans"]
+        }),
+    )
+    .await;
+    assert!(labels(json).is_empty());
+}
+
+#[tokio::test]
+async fn test_api_reindex_fts_requires_admin_key() {
+    let app = create_test_app().await;
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/maintenance/reindex-fts")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/maintenance/reindex-fts")
+                .header(
+                    "Authorization",
+                    "Bearer not_the_admin_key_000000000000000000",
+                )
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    app.clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/conversations")
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    r#"{ "label": "Reindex", "folder": "/reindex", "messages": [{"role": "user", "content": "index me"}] }"#,
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    // Admin key falls back to mcp_api_key when not configured
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/maintenance/reindex-fts")
+                .header(
+                    "Authorization",
+                    "Bearer test_key_12345678901234567890123456789012",
+                )
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), 1024)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["indexed"], 1);
+}
+
+#[tokio::test]
+async fn test_api_touch_conversation() {
+    let app = create_test_app().await;
+    let conv_id = create_api_test_conversation(&app).await;
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/v1/conversations/{}/touch", conv_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/v1/conversations/{}/touch", Uuid::new_v4()))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_api_create_and_append_return_message_ids() {
+    let app = create_test_app().await;
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/conversations")
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    r#"{ "label": "Ids", "folder": "/ids", "messages": [{"role": "user", "content": "One"}, {"role": "assistant", "content": "Two"}] }"#,
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let body = axum::body::to_bytes(response.into_body(), 2048)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["message_ids"].as_array().unwrap().len(), 2);
+    let conv_id = json["id"].as_str().unwrap().to_string();
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/v1/conversations/{}/messages", conv_id))
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    r#"{ "messages": [{"role": "user", "content": "Three"}] }"#,
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let body = axum::body::to_bytes(response.into_body(), 2048)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["conversation_id"], conv_id);
+    let appended = json["message_ids"].as_array().unwrap();
+    assert_eq!(appended.len(), 1);
+    assert!(Uuid::parse_str(appended[0].as_str().unwrap()).is_ok());
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/v1/conversations/{}/messages", Uuid::new_v4()))
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    r#"{ "messages": [{"role": "user", "content": "Orphan"}] }"#,
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+async fn post_json(app: &axum::Router, uri: &str, body: serde_json::Value) -> serde_json::Value {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(uri)
+                .header("Content-Type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), 8192)
+        .await
+        .unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
+
+async fn get_conversation_json(app: &axum::Router, conv_id: &str) -> serde_json::Value {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/conversations/{}", conv_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let body = axum::body::to_bytes(response.into_body(), 4096)
+        .await
+        .unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[tokio::test]
+async fn test_api_bulk_move_and_status_dry_run() {
+    let app = create_test_app().await;
+    let first = create_api_test_conversation(&app).await;
+    let second = create_api_test_conversation(&app).await;
+    let ids = serde_json::json!([first, second]);
+
+    let preview = post_json(
+        &app,
+        "/api/v1/conversations/bulk/move",
+        serde_json::json!({ "ids": ids, "folder": "/moved", "dry_run": true }),
+    )
+    .await;
+    assert_eq!(preview["count"], 2);
+    assert_eq!(preview["dry_run"], true);
+    assert_eq!(
+        get_conversation_json(&app, &first).await["folder"],
+        "/patch"
+    );
+
+    let preview = post_json(
+        &app,
+        "/api/v1/conversations/bulk/status",
+        serde_json::json!({ "ids": ids, "status": "archived", "dry_run": true }),
+    )
+    .await;
+    assert_eq!(preview["count"], 2);
+    assert_eq!(
+        get_conversation_json(&app, &second).await["status"],
+        "active"
+    );
+
+    // The real run touches exactly what the preview reported
+    let moved = post_json(
+        &app,
+        "/api/v1/conversations/bulk/move",
+        serde_json::json!({ "ids": ids, "folder": "/moved" }),
+    )
+    .await;
+    assert_eq!(moved["affected_ids"], preview["affected_ids"]);
+    assert_eq!(moved["dry_run"], false);
+    assert_eq!(
+        get_conversation_json(&app, &first).await["folder"],
+        "/moved"
+    );
+}
+
+#[tokio::test]
+async fn test_api_bulk_tag_add_and_remove() {
+    let state = create_test_app_state().await;
+    let repo = state.repo.clone();
+    let app = sekha_controller::api::routes::create_router(state);
+
+    let mut ids = Vec::new();
+    for _ in 0..3 {
+        ids.push(create_api_test_conversation(&app).await);
+    }
+    let uuids: Vec<Uuid> = ids.iter().map(|id| id.parse().unwrap()).collect();
+    repo.add_tags(uuids[0], vec!["q3-review".to_string()])
+        .await
+        .unwrap();
+    let missing = Uuid::new_v4();
+
+    let added = post_json(
+        &app,
+        "/api/v1/tags/bulk-add",
+        serde_json::json!({
+            "conversation_ids": [ids[0], ids[1], ids[2], missing],
+            "tags": ["q3-review", "finance"]
+        }),
+    )
+    .await;
+    assert_eq!(added["updated"], 3);
+    let results = added["results"].as_array().unwrap();
+    assert_eq!(results.len(), 4);
+    // The first already carried one of the tags
+    assert_eq!(results[0]["changed"], 1);
+    assert_eq!(results[1]["changed"], 2);
+    assert_eq!(results[3]["conversation_id"], missing.to_string());
+    assert_eq!(results[3]["found"], false);
+
+    let tag_names = |tags: Vec<sekha_controller::models::internal::StoredTag>| {
+        let mut names: Vec<String> = tags.into_iter().map(|t| t.tag).collect();
+        names.sort();
+        names
+    };
+    for id in &uuids {
+        assert_eq!(
+            tag_names(repo.find_tags(*id).await.unwrap()),
+            vec!["finance", "q3-review"]
+        );
+    }
+
+    let removed = post_json(
+        &app,
+        "/api/v1/tags/bulk-remove",
+        serde_json::json!({ "conversation_ids": [ids[0], ids[1]], "tag": "finance" }),
+    )
+    .await;
+    assert_eq!(removed["updated"], 2);
+    assert_eq!(
+        tag_names(repo.find_tags(uuids[0]).await.unwrap()),
+        vec!["q3-review"]
+    );
+    assert_eq!(
+        tag_names(repo.find_tags(uuids[2]).await.unwrap()),
+        vec!["finance", "q3-review"]
+    );
+}
+
+#[tokio::test]
+async fn test_api_bulk_delete_reports_deleted_conversations() {
+    let app = create_test_app().await;
+    let first = create_api_test_conversation(&app).await;
+    let second = create_api_test_conversation(&app).await;
+
+    let deleted = post_json(
+        &app,
+        "/api/v1/conversations/bulk-delete",
+        serde_json::json!({ "ids": [first, second, Uuid::new_v4()] }),
+    )
+    .await;
+    assert_eq!(deleted["deleted"], 2);
+    assert!(deleted["vectors_removed"].is_u64());
+
+    for conv_id in [&first, &second] {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/api/v1/conversations/{}", conv_id))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
+
+#[tokio::test]
+async fn test_api_rename_folder_dry_run() {
+    let app = create_test_app().await;
+    let parent = format!("/rename-{}", Uuid::new_v4());
+
+    for folder in [parent.clone(), format!("{}/child", parent)] {
+        let conv_id = create_api_test_conversation(&app).await;
+        let response = send_patch(
+            &app,
+            &conv_id,
+            &serde_json::json!({ "folder": folder }).to_string(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let preview = post_json(
+        &app,
+        "/api/v1/folders/rename",
+        serde_json::json!({ "from": parent, "to": "/renamed", "dry_run": true }),
+    )
+    .await;
+    assert_eq!(preview["count"], 2);
+
+    let affected: Vec<String> = preview["affected_ids"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|id| id.as_str().unwrap().to_string())
+        .collect();
+    for conv_id in &affected {
+        let folder = get_conversation_json(&app, conv_id).await["folder"].clone();
+        assert!(folder.as_str().unwrap().starts_with(&parent));
+    }
+
+    let renamed = post_json(
+        &app,
+        "/api/v1/folders/rename",
+        serde_json::json!({ "from": parent, "to": "/renamed" }),
+    )
+    .await;
+    assert_eq!(renamed["affected_ids"], preview["affected_ids"]);
+
+    let mut folders = Vec::new();
+    for conv_id in &affected {
+        folders.push(get_conversation_json(&app, conv_id).await["folder"].clone());
+    }
+    folders.sort_by_key(|f| f.to_string());
+    assert_eq!(folders, vec!["/renamed", "/renamed/child"]);
+}
+
+#[tokio::test]
+async fn test_api_optimize_db_is_admin_only_and_accepted() {
+    let app = create_test_app().await;
+
+    let optimize = |auth: &str| {
+        Request::builder()
+            .method("POST")
+            .uri("/api/v1/maintenance/optimize")
+            .header("Authorization", format!("Bearer {}", auth))
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let response = app
+        .clone()
+        .oneshot(optimize("not_the_admin_key_000000000000000000"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = app
+        .clone()
+        .oneshot(optimize("test_key_12345678901234567890123456789012"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+    let body = axum::body::to_bytes(response.into_body(), 1024)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["status"], "accepted");
+    // The test app runs on an in-memory database, so there is no file size
+    assert!(json["size_before_bytes"].is_null());
+}
+
+#[tokio::test]
+async fn test_api_optimize_db_task_reports_size_after() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let url = format!("sqlite://{}", dir.path().join("optimize.db").display());
+    let db = sekha_controller::storage::init_db(&url).await.unwrap();
+
+    let mut state = create_test_app_state().await;
+    state.config.write().await.database_url = url;
+    state.repo = std::sync::Arc::new(
+        sekha_controller::storage::SeaOrmConversationRepository::new(
+            db,
+            state.chroma_client.clone(),
+            state.embedding_service.clone(),
+        ),
+    );
+    let app = sekha_controller::api::routes::create_router(state);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/maintenance/optimize")
+                .header(
+                    "Authorization",
+                    "Bearer test_key_12345678901234567890123456789012",
+                )
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    let body = axum::body::to_bytes(response.into_body(), 1024)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert!(json["size_before_bytes"].as_u64().unwrap() > 0);
+    let task_id = json["task_id"].as_str().unwrap().to_string();
+
+    // Task status needs the same key as starting the task
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/tasks/{}", task_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let mut task = serde_json::Value::Null;
+    for _ in 0..50 {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/api/v1/tasks/{}", task_id))
+                    .header(
+                        "Authorization",
+                        "Bearer test_key_12345678901234567890123456789012",
+                    )
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), 4096)
+            .await
+            .unwrap();
+        task = serde_json::from_slice(&body).unwrap();
+        if task["state"] != "running" {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    assert_eq!(task["kind"], "optimize_db");
+    assert_eq!(task["state"], "completed");
+    assert_eq!(task["result"]["vacuumed"], true);
+    assert!(task["result"]["size_before_bytes"].as_u64().unwrap() > 0);
+    assert!(task["result"]["size_after_bytes"].as_u64().unwrap() > 0);
+}
+
+#[tokio::test]
+async fn test_api_create_conversation_invalid_body_is_structured_400() {
+    let app = create_test_app().await;
+
+    let cases = [
+        (
+            r#"{ "folder": "/api", "messages": [] }"#,
+            "missing field `label`",
+        ),
+        (
+            r#"{ "label": "L", "folder": "/api", "messages": [{"content": "hi"}] }"#,
+            "messages[0]: missing field `role`",
+        ),
+    ];
+
+    for (body, expected) in cases {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/v1/conversations")
+                    .header("Content-Type", "application/json")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = axum::body::to_bytes(response.into_body(), 1024)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["code"], 400);
+        assert!(
+            json["error"].as_str().unwrap().contains(expected),
+            "unexpected error: {}",
+            json["error"]
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_api_offload_and_reload_conversation() {
+    let app = create_test_app().await;
+    let conv_id = create_api_test_conversation(&app).await;
+
+    let offloaded = post_json(
+        &app,
+        &format!("/api/v1/conversations/{}/offload", conv_id),
+        serde_json::json!({}),
+    )
+    .await;
+    assert_eq!(offloaded["conversation_id"], conv_id.as_str());
+    assert_eq!(offloaded["message_count"], 1);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/conversations/{}", conv_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let reloaded = post_json(
+        &app,
+        &format!("/api/v1/conversations/{}/reload", conv_id),
+        serde_json::json!({}),
+    )
+    .await;
+    assert_eq!(reloaded["id"], conv_id.as_str());
+    assert_eq!(reloaded["label"], "Patch Test");
+    assert_eq!(reloaded["message_count"], 1);
+
+    let conv = get_conversation_json(&app, &conv_id).await;
+    assert_eq!(conv["folder"], "/patch");
+
+    // Nothing left to reload
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/v1/conversations/{}/reload", conv_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_api_create_surfaces_pending_embeddings_and_queues_retry() {
+    if is_chroma_running().await {
+        println!("Skipping: test needs the vector store to be unreachable");
+        return;
+    }
+
+    let state = create_test_app_state().await;
+    let queue = state.embedding_queue.clone();
+    let app = sekha_controller::api::routes::create_router(state);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/conversations")
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    r#"{ "label": "Offline", "folder": "/offline", "messages": [{"role": "user", "content": "Hello"}, {"role": "assistant", "content": "Hi"}] }"#,
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let body = axum::body::to_bytes(response.into_body(), 4096)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["embeddings_pending"], true);
+    let warnings = json["warnings"].as_array().unwrap();
+    assert!(
+        warnings
+            .iter()
+            .any(|w| w.as_str().unwrap().starts_with("2 message(s)")),
+        "unexpected warnings: {:?}",
+        warnings
+    );
+    assert_eq!(queue.enqueued_jobs(), 1);
+}
+
+#[tokio::test]
+async fn test_api_embedding_timeout_queues_retry() {
+    use sekha_controller::services::embedding_provider::MockProvider;
+    use sekha_controller::services::embedding_service::EmbeddingService;
+    use std::time::{Duration, Instant};
+
+    let provider = MockProvider::new_success(vec![0.1; 8]).with_delay(Duration::from_secs(5));
+    let embedding_service = std::sync::Arc::new(
+        EmbeddingService::with_provider(
+            std::sync::Arc::new(provider),
+            "http://localhost:1".to_string(),
+        )
+        .with_timeout(Duration::from_millis(100)),
+    );
+
+    let mut state = create_test_app_state().await;
+    state.repo = std::sync::Arc::new(
+        sekha_controller::storage::SeaOrmConversationRepository::new(
+            state.repo.get_db().clone(),
+            state.chroma_client.clone(),
+            embedding_service.clone(),
+        ),
+    );
+    state.embedding_service = embedding_service;
+    let queue = state.embedding_queue.clone();
+    let app = sekha_controller::api::routes::create_router(state);
+
+    let started = Instant::now();
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/conversations")
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    r#"{ "label": "Slow", "folder": "/slow", "messages": [{"role": "user", "content": "A very long message"}] }"#,
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert!(started.elapsed() < Duration::from_secs(2));
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let body = axum::body::to_bytes(response.into_body(), 4096)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["embeddings_pending"], true);
+    assert_eq!(queue.enqueued_jobs(), 1);
+}
+
+#[tokio::test]
+async fn test_api_suggest_matches_label_folder_and_tag_prefixes() {
+    let state = create_test_app_state().await;
+    let repo = state.repo.clone();
+    let app = sekha_controller::api::routes::create_router(state);
+
+    let mut ids = Vec::new();
+    for (label, folder) in [
+        ("Research notes", "/work"),
+        ("Research notes", "/research/papers"),
+        ("Resume draft", "/personal"),
+        ("Groceries", "/research_archive"),
+    ] {
+        let mut conv = super::create_test_conversation();
+        conv.label = label.to_string();
+        conv.folder = folder.to_string();
+        ids.push(repo.create_with_messages(conv).await.unwrap());
+    }
+    repo.add_tags(ids[3], vec!["reading-list".to_string()])
+        .await
+        .unwrap();
+    repo.add_label(ids[2], "Reference", 0.5).await.unwrap();
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/v1/suggest?q=re")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    // Most used first, then alphabetical; "Groceries" doesn't start with "re"
+    assert_eq!(
+        json["labels"],
+        serde_json::json!([
+            {"value": "Research notes", "count": 2},
+            {"value": "Reference", "count": 1},
+            {"value": "Resume draft", "count": 1}
+        ])
+    );
+    // The leading slash is optional, and "_" is matched literally
+    assert_eq!(
+        json["folders"],
+        serde_json::json!([
+            {"value": "/research/papers", "count": 1},
+            {"value": "/research_archive", "count": 1}
+        ])
+    );
+    assert_eq!(
+        json["tags"],
+        serde_json::json!([{"value": "reading-list", "count": 1}])
+    );
+
+    let suggestions = repo.suggest("research_", 5).await.unwrap();
+    assert_eq!(suggestions.folders.len(), 1);
+    assert!(suggestions.labels.is_empty());
+    assert_eq!(repo.suggest("re", 1).await.unwrap().labels.len(), 1);
+}
+
+#[tokio::test]
+async fn test_api_folder_tree_rolls_up_counts() {
+    let app = create_test_app().await;
+
+    for folder in ["/a", "/a/b", "/a/b/c", "/a/b/c"] {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/v1/conversations")
+                    .header("Content-Type", "application/json")
+                    .body(Body::from(
+                        serde_json::json!({ "label": "Tree", "folder": folder, "messages": [] })
+                            .to_string(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/v1/folders/tree")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), 8192)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let a = &json["folders"][0];
+    assert_eq!(a["path"], "/a");
+    assert_eq!(a["count"], 4);
+    assert_eq!(a["direct_count"], 1);
+    assert_eq!(a["children"][0]["path"], "/a/b");
+    assert_eq!(a["children"][0]["count"], 3);
+    assert_eq!(a["children"][0]["children"][0]["path"], "/a/b/c");
+    assert_eq!(a["children"][0]["children"][0]["count"], 2);
+}
+
+#[tokio::test]
+async fn test_api_request_timeout_returns_gateway_timeout() {
+    let chroma = wiremock::MockServer::start().await;
+    wiremock::Mock::given(wiremock::matchers::any())
+        .respond_with(
+            wiremock::ResponseTemplate::new(200).set_delay(std::time::Duration::from_secs(5)),
+        )
+        .mount(&chroma)
+        .await;
+
+    let mut state = create_test_app_state().await;
+    state.chroma_client = std::sync::Arc::new(
+        sekha_controller::storage::chroma_client::ChromaClient::new(chroma.uri()),
+    );
+    let limit = std::time::Duration::from_millis(200);
+    let app = sekha_controller::api::routes::create_router(state).layer(
+        axum::middleware::from_fn_with_state(
+            limit,
+            sekha_controller::api::timeout::timeout_middleware,
+        ),
+    );
+
+    let started = std::time::Instant::now();
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/health")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert!(started.elapsed() < std::time::Duration::from_secs(2));
+    assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+
+    let body = axum::body::to_bytes(response.into_body(), 4096)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["code"], 504);
+}
+
+/// Requests the LLM bridge has received for `/summarize`
+async fn summarize_calls(bridge: &wiremock::MockServer) -> usize {
+    bridge
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .filter(|r| r.url.path() == "/summarize")
+        .count()
+}
+
+#[tokio::test]
+async fn test_api_append_past_threshold_schedules_summary() {
+    let bridge = wiremock::MockServer::start().await;
+    wiremock::Mock::given(wiremock::matchers::path("/summarize"))
+        .respond_with(
+            wiremock::ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "summary": "Short recap",
+                "level": "daily",
+                "model": "llama3.1:8b",
+                "tokens_used": 10
+            })),
+        )
+        .mount(&bridge)
+        .await;
+
+    let mut state = create_test_app_state().await;
+    state.config.write().await.summarize_after_messages = Some(3);
+    state.orchestrator =
+        std::sync::Arc::new(sekha_controller::orchestrator::MemoryOrchestrator::new(
+            state.repo.clone(),
+            std::sync::Arc::new(
+                sekha_controller::services::llm_bridge_client::LlmBridgeClient::new(bridge.uri()),
+            ),
+        ));
+    let app = sekha_controller::api::routes::create_router(state);
+    let conv_id = create_api_test_conversation(&app).await;
+
+    let append = |content: &'static str| {
+        let app = app.clone();
+        let uri = format!("/api/v1/conversations/{}/messages", conv_id);
+        async move {
+            let response = app
+                .oneshot(
+                    Request::builder()
+                        .method("POST")
+                        .uri(uri)
+                        .header("Content-Type", "application/json")
+                        .body(Body::from(
+                            serde_json::json!({
+                                "messages": [{"role": "user", "content": content}]
+                            })
+                            .to_string(),
+                        ))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::CREATED);
+        }
+    };
+
+    // Two messages: still below the threshold
+    append("second").await;
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    assert_eq!(summarize_calls(&bridge).await, 0);
+
+    // The third message crosses it and schedules a summary in the background
+    append("third").await;
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+    while summarize_calls(&bridge).await == 0 {
+        assert!(
+            std::time::Instant::now() < deadline,
+            "summary was not scheduled"
+        );
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+
+    // The checkpoint stops the next append from triggering again
+    append("fourth").await;
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    assert_eq!(summarize_calls(&bridge).await, 1);
+}
+
+#[tokio::test]
+async fn test_api_append_rescores_importance_after_debounce() {
+    // The bridge fails scoring, so the heuristics decide on their own
+    let bridge = wiremock::MockServer::start().await;
+    wiremock::Mock::given(wiremock::matchers::path("/score_importance"))
+        .respond_with(wiremock::ResponseTemplate::new(503))
+        .mount(&bridge)
+        .await;
+
+    let mut state = create_test_app_state().await;
+    {
+        let mut config = state.config.write().await;
+        config.rescore_importance_on_append = true;
+        config.importance_rescore_debounce_secs = 1;
+    }
+    state.orchestrator =
+        std::sync::Arc::new(sekha_controller::orchestrator::MemoryOrchestrator::new(
+            state.repo.clone(),
+            std::sync::Arc::new(
+                sekha_controller::services::llm_bridge_client::LlmBridgeClient::new(bridge.uri()),
+            ),
+        ));
+    let repo = state.repo.clone();
+    let app = sekha_controller::api::routes::create_router(state);
+    let conv_id = create_api_test_conversation(&app).await;
+    let id = Uuid::parse_str(&conv_id).unwrap();
+    let initial = repo.find_by_id(id).await.unwrap().unwrap();
+
+    let substantive = [
+        "Critical decision: we migrate the billing service to the new queue before the \
+         quarter closes, otherwise reconciliation breaks.\n```sql\nALTER TABLE invoices ADD COLUMN queue_id TEXT;\n```",
+        "Important and urgent: the rollback plan must be reviewed before the decision is final.\n\
+         ```bash\n./rollback.sh --dry-run\n```",
+    ];
+    for content in substantive {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(format!("/api/v1/conversations/{}/messages", conv_id))
+                    .header("Content-Type", "application/json")
+                    .body(Body::from(
+                        serde_json::json!({
+                            "messages": [{"role": "user", "content": content}]
+                        })
+                        .to_string(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    // Nothing is scored inside the debounce window
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    let during = repo.find_by_id(id).await.unwrap().unwrap();
+    assert_eq!(during.importance_score, initial.importance_score);
+
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+    loop {
+        let conv = repo.find_by_id(id).await.unwrap().unwrap();
+        if conv.importance_score > initial.importance_score {
+            break;
+        }
+        assert!(
+            std::time::Instant::now() < deadline,
+            "importance was not re-scored"
+        );
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+
+    // Both appends were coalesced into a single scoring pass
+    let score_calls = bridge
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .filter(|r| r.url.path() == "/score_importance")
+        .count();
+    assert_eq!(score_calls, 1);
+}
+
+#[tokio::test]
+async fn test_api_archive_with_compaction_keeps_only_summary() {
+    let bridge = wiremock::MockServer::start().await;
+    wiremock::Mock::given(wiremock::matchers::path("/summarize"))
+        .respond_with(
+            wiremock::ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "summary": "Recap of the zebra migration plan",
+                "level": "daily",
+                "model": "llama3.1:8b",
+                "tokens_used": 10
+            })),
+        )
+        .mount(&bridge)
+        .await;
+
+    let mut state = create_test_app_state().await;
+    {
+        let mut config = state.config.write().await;
+        config.compact_on_archive = true;
+        config.embeddings_enabled = false;
+    }
+    state.orchestrator =
+        std::sync::Arc::new(sekha_controller::orchestrator::MemoryOrchestrator::new(
+            state.repo.clone(),
+            std::sync::Arc::new(
+                sekha_controller::services::llm_bridge_client::LlmBridgeClient::new(bridge.uri()),
+            ),
+        ));
+    let repo = state.repo.clone();
+    let app = sekha_controller::api::routes::create_router(state);
+    let CreatedConversation {
+        id, message_ids, ..
+    } = repo
+        .create_with_message_ids(super::create_test_conversation())
+        .await
+        .unwrap();
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/prune/execute")
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    serde_json::json!({ "conversation_ids": [id] }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    // Archived right away; compaction runs as a task
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    assert_eq!(
+        repo.find_by_id(id).await.unwrap().unwrap().status,
+        "archived"
+    );
+    let body = axum::body::to_bytes(response.into_body(), 4096)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let task_id = json["task_id"].as_str().unwrap().to_string();
+
+    let mut task = serde_json::Value::Null;
+    for _ in 0..50 {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/api/v1/tasks/{}", task_id))
+                    .header(
+                        "Authorization",
+                        "Bearer test_key_12345678901234567890123456789012",
+                    )
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), 4096)
+            .await
+            .unwrap();
+        task = serde_json::from_slice(&body).unwrap();
+        if task["state"] != "running" {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    assert_eq!(task["kind"], "compact");
+    assert_eq!(task["state"], "completed");
+    assert_eq!(task["result"]["messages_compacted"], message_ids.len());
+    assert_eq!(task["result"]["failed"], serde_json::json!([]));
+
+    let messages = repo.get_conversation_messages(id).await.unwrap();
+    assert_eq!(messages.len(), message_ids.len() + 1);
+    for message in messages.iter().filter(|m| message_ids.contains(&m.id)) {
+        assert!(message.content.is_empty());
+        // Roles and metadata outlive the bodies
+        let metadata = message.metadata.as_ref().unwrap();
+        assert_eq!(metadata["source"], "test");
+        assert_eq!(metadata["compacted"], true);
+    }
+    let summaries = repo.find_summaries(id).await.unwrap();
+    assert_eq!(
+        summaries[0].summary_text,
+        "Recap of the zebra migration plan"
+    );
+
+    let json = post_json(
+        &app,
+        "/api/v1/query",
+        serde_json::json!({ "query": "zebra migration" }),
+    )
+    .await;
+    assert_eq!(json["total"], 1);
+    assert_eq!(json["results"][0]["conversation_id"], id.to_string());
+    assert_eq!(
+        json["results"][0]["content"],
+        "Recap of the zebra migration plan"
+    );
+    let json = post_json(
+        &app,
+        "/api/v1/query",
+        serde_json::json!({ "query": "response test" }),
+    )
+    .await;
+    assert_eq!(json["total"], 0);
+}
+
+#[tokio::test]
+async fn test_api_append_past_message_cap_rolls_up_oldest() {
+    let bridge = wiremock::MockServer::start().await;
+    wiremock::Mock::given(wiremock::matchers::path("/summarize"))
+        .respond_with(
+            wiremock::ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "summary": "Recap of early messages",
+                "level": "daily",
+                "model": "llama3.1:8b",
+                "tokens_used": 10
+            })),
+        )
+        .mount(&bridge)
+        .await;
+
+    let mut state = create_test_app_state().await;
+    state.config.write().await.max_messages_per_conversation = Some(3);
+    state.orchestrator =
+        std::sync::Arc::new(sekha_controller::orchestrator::MemoryOrchestrator::new(
+            state.repo.clone(),
+            std::sync::Arc::new(
+                sekha_controller::services::llm_bridge_client::LlmBridgeClient::new(bridge.uri()),
+            ),
+        ));
+    let repo = state.repo.clone();
+    let app = sekha_controller::api::routes::create_router(state);
+
+    let mut conv = super::create_test_conversation();
+    let start = chrono::Utc::now().naive_utc() - chrono::Duration::days(3);
+    conv.messages = (0..3)
+        .map(|i| sekha_controller::models::internal::NewMessage {
+            role: "user".to_string(),
+            content: format!("old {}", i),
+            timestamp: start + chrono::Duration::hours(i),
+            metadata: serde_json::json!({}),
+        })
+        .collect();
+    let conv_id = repo.create_with_messages(conv).await.unwrap();
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/v1/conversations/{}/messages", conv_id))
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    serde_json::json!({
+                        "messages": [
+                            {"role": "user", "content": "new 1"},
+                            {"role": "assistant", "content": "new 2"}
+                        ]
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    // The two oldest messages made way for the new ones
+    let mut contents: Vec<String> = repo
+        .get_conversation_messages(conv_id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|m| m.content)
+        .collect();
+    contents.sort();
+    assert_eq!(contents, ["new 1", "new 2", "old 2"]);
+    let stored = repo.find_by_id(conv_id).await.unwrap().unwrap();
+    assert_eq!(
+        stored.word_count,
+        contents.iter().map(|c| c.len() as i32).sum::<i32>()
+    );
+
+    let summaries = repo.find_summaries(conv_id).await.unwrap();
+    assert_eq!(summaries.len(), 1);
+    assert_eq!(summaries[0].level, "daily");
+    assert_eq!(summaries[0].summary_text, "Recap of early messages");
+    assert_eq!(summarize_calls(&bridge).await, 1);
+}
+
+#[tokio::test]
+async fn test_api_message_cap_keeps_messages_when_llm_is_down() {
+    let bridge = wiremock::MockServer::start().await;
+    wiremock::Mock::given(wiremock::matchers::path("/summarize"))
+        .respond_with(wiremock::ResponseTemplate::new(500))
+        .mount(&bridge)
+        .await;
+
+    let mut state = create_test_app_state().await;
+    state.config.write().await.max_messages_per_conversation = Some(3);
+    state.orchestrator =
+        std::sync::Arc::new(sekha_controller::orchestrator::MemoryOrchestrator::new(
+            state.repo.clone(),
+            std::sync::Arc::new(
+                sekha_controller::services::llm_bridge_client::LlmBridgeClient::new(bridge.uri()),
+            ),
+        ));
+    let repo = state.repo.clone();
+    let app = sekha_controller::api::routes::create_router(state);
+
+    let mut conv = super::create_test_conversation();
+    conv.messages.push(conv.messages[0].clone());
+    let conv_id = repo.create_with_messages(conv).await.unwrap();
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/v1/conversations/{}/messages", conv_id))
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    serde_json::json!({
+                        "messages": [{"role": "user", "content": "one too many"}]
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    // Nothing is deleted without a real summary to replace it
+    assert_eq!(
+        repo.get_conversation_messages(conv_id).await.unwrap().len(),
+        4
+    );
+    assert!(repo.find_summaries(conv_id).await.unwrap().is_empty());
+    assert_eq!(summarize_calls(&bridge).await, 1);
+}
+
+async fn get_embedding_response(
+    app: &axum::Router,
+    message_id: &str,
+    auth: Option<&str>,
+) -> axum::response::Response {
+    let mut request = Request::builder().uri(format!("/api/v1/messages/{}/embedding", message_id));
+    if let Some(auth) = auth {
+        request = request.header("Authorization", format!("Bearer {}", auth));
+    }
+    app.clone()
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_api_message_embedding_requires_admin_and_embedding() {
+    if is_chroma_running().await {
+        println!("Skipping: test needs messages stored without embeddings");
+        return;
+    }
+
+    let state = create_test_app_state().await;
+    let repo = state.repo.clone();
+    let app = sekha_controller::api::routes::create_router(state);
+    let CreatedConversation { message_ids, .. } = repo
+        .create_with_message_ids(super::create_test_conversation())
+        .await
+        .unwrap();
+    let message_id = message_ids[0].to_string();
+
+    let response = get_embedding_response(&app, &message_id, None).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let admin_key = "test_key_12345678901234567890123456789012";
+    let response = get_embedding_response(&app, &message_id, Some(admin_key)).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = get_embedding_response(&app, &Uuid::new_v4().to_string(), Some(admin_key)).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_api_message_embedding_round_trip() {
+    if !is_chroma_running().await {
+        println!("Skipping: Chroma not running");
+        return;
+    }
+
+    let vector: Vec<f32> = (0..768).map(|i| i as f32 / 768.0).collect();
+    let mut state = create_test_app_state().await;
+    let embedding_service =
+        std::sync::Arc::new(sekha_controller::services::EmbeddingService::with_provider(
+            std::sync::Arc::new(sekha_controller::services::MockProvider::new_success(
+                vector.clone(),
+            )),
+            "http://localhost:8000".to_string(),
+        ));
+    state.repo = std::sync::Arc::new(
+        sekha_controller::storage::SeaOrmConversationRepository::new(
+            state.repo.get_db().clone(),
+            state.chroma_client.clone(),
+            embedding_service.clone(),
+        ),
+    );
+    let repo = state.repo.clone();
+    state.embedding_service = embedding_service;
+    let app = sekha_controller::api::routes::create_router(state);
+
+    let CreatedConversation { message_ids, .. } = repo
+        .create_with_message_ids(super::create_test_conversation())
+        .await
+        .unwrap();
+    let response = get_embedding_response(
+        &app,
+        &message_ids[0].to_string(),
+        Some("test_key_12345678901234567890123456789012"),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), 1 << 20)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["dimension"], 768);
+    assert_eq!(json["model"], "nomic-embed-text:latest");
+    let returned: Vec<f32> = serde_json::from_value(json["embedding"].clone()).unwrap();
+    assert_eq!(returned, vector);
+}
+
+#[tokio::test]
+async fn test_api_query_reports_embedding_dimension_mismatch() {
+    use sekha_controller::services::embedding_provider::MockProvider;
+    use sekha_controller::services::embedding_service::EmbeddingService;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const COLLECTIONS: &str =
+        "/api/v2/tenants/default_tenant/databases/default_database/collections";
+    let chroma = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v2/heartbeat"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&chroma)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(format!("{}/conversations", COLLECTIONS)))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({"id": "col-1", "name": "conversations"})),
+        )
+        .mount(&chroma)
+        .await;
+    Mock::given(method("POST"))
+        .and(path(format!("{}/col-1/query", COLLECTIONS)))
+        .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+            "error": "InvalidArgumentError",
+            "message": "Collection expecting embedding with dimension of 768, got 8"
+        })))
+        .mount(&chroma)
+        .await;
+
+    let mut state = create_test_app_state().await;
+    let embedding_service = std::sync::Arc::new(EmbeddingService::with_provider(
+        std::sync::Arc::new(MockProvider::new_success(vec![0.25; 8])),
+        chroma.uri(),
+    ));
+    let chroma_client = std::sync::Arc::new(
+        sekha_controller::storage::chroma_client::ChromaClient::new(chroma.uri()),
+    );
+    state.repo = std::sync::Arc::new(
+        sekha_controller::storage::SeaOrmConversationRepository::new(
+            state.repo.get_db().clone(),
+            chroma_client.clone(),
+            embedding_service.clone(),
+        ),
+    );
+    state.chroma_client = chroma_client;
+    state.embedding_service = embedding_service;
+    let app = sekha_controller::api::routes::create_router(state);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/query")
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    serde_json::json!({ "query": "zebra migration" }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    // A clear client error rather than an empty result
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let error = json["error"].as_str().unwrap();
+    assert!(error.contains("8 dimensions"), "{}", error);
+    assert!(error.contains("expects 768"), "{}", error);
+}
+
+#[tokio::test]
+async fn test_api_message_embedding_records_model_and_dimension() {
+    use sekha_controller::services::embedding_provider::MockProvider;
+    use sekha_controller::services::embedding_service::EmbeddingService;
+    use sekha_controller::storage::ConversationRepository;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const COLLECTIONS: &str =
+        "/api/v2/tenants/default_tenant/databases/default_database/collections";
+    let chroma = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(COLLECTIONS))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!([{"id": "col-1", "name": "conversations"}])),
+        )
+        .mount(&chroma)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(format!("{}/conversations", COLLECTIONS)))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({"id": "col-1", "name": "conversations"})),
+        )
+        .mount(&chroma)
+        .await;
+    Mock::given(method("POST"))
+        .and(path(format!("{}/col-1/upsert", COLLECTIONS)))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&chroma)
+        .await;
+
+    let mut state = create_test_app_state().await;
+    let embedding_service = std::sync::Arc::new(EmbeddingService::with_provider(
+        std::sync::Arc::new(MockProvider::new_success(vec![0.25; 8])),
+        chroma.uri(),
+    ));
+    let chroma_client = std::sync::Arc::new(
+        sekha_controller::storage::chroma_client::ChromaClient::new(chroma.uri()),
+    );
+    let repo = std::sync::Arc::new(
+        sekha_controller::storage::SeaOrmConversationRepository::new(
+            state.repo.get_db().clone(),
+            chroma_client.clone(),
+            embedding_service.clone(),
+        ),
+    );
+    state.repo = repo.clone();
+    state.chroma_client = chroma_client;
+    state.embedding_service = embedding_service.clone();
+    let app = sekha_controller::api::routes::create_router(state);
+
+    let CreatedConversation { message_ids, .. } = repo
+        .create_with_message_ids(super::create_test_conversation())
+        .await
+        .unwrap();
+    let message = repo
+        .find_message_by_id(message_ids[0])
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(message.embedding_model.as_deref(), Some("mock"));
+    assert_eq!(
+        message.embedding_model.as_deref(),
+        Some(embedding_service.model())
+    );
+    assert_eq!(message.embedding_dim, Some(8));
+
+    Mock::given(method("POST"))
+        .and(path(format!("{}/col-1/get", COLLECTIONS)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "ids": [message_ids[0].to_string()],
+            "embeddings": [[0.25, 0.25, 0.25, 0.25, 0.25, 0.25, 0.25, 0.25]]
+        })))
+        .mount(&chroma)
+        .await;
+    let response = get_embedding_response(
+        &app,
+        &message_ids[0].to_string(),
+        Some("test_key_12345678901234567890123456789012"),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), 1 << 20)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["embedding_model"], "mock");
+    assert_eq!(json["embedding_dim"], 8);
+}
+
+#[tokio::test]
+async fn test_api_message_embedding_returns_chunk_vectors() {
+    use sekha_controller::services::embedding_provider::MockProvider;
+    use sekha_controller::services::embedding_service::EmbeddingService;
+    use sekha_controller::storage::ConversationRepository;
+    use wiremock::matchers::{body_partial_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const COLLECTIONS: &str =
+        "/api/v2/tenants/default_tenant/databases/default_database/collections";
+    let chroma = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(COLLECTIONS))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!([{"id": "col-1", "name": "conversations"}])),
+        )
+        .mount(&chroma)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(format!("{}/conversations", COLLECTIONS)))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({"id": "col-1", "name": "conversations"})),
+        )
+        .mount(&chroma)
+        .await;
+    Mock::given(method("POST"))
+        .and(path(format!("{}/col-1/upsert", COLLECTIONS)))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&chroma)
+        .await;
+
+    let mut state = create_test_app_state().await;
+    let embedding_service = std::sync::Arc::new(
+        EmbeddingService::with_provider(
+            std::sync::Arc::new(MockProvider::new_success(vec![0.5; 4])),
+            chroma.uri(),
+        )
+        .with_chunking(10, 0),
+    );
+    let chroma_client = std::sync::Arc::new(
+        sekha_controller::storage::chroma_client::ChromaClient::new(chroma.uri()),
+    );
+    let repo = std::sync::Arc::new(
+        sekha_controller::storage::SeaOrmConversationRepository::new(
+            state.repo.get_db().clone(),
+            chroma_client.clone(),
+            embedding_service.clone(),
+        ),
+    );
+    state.repo = repo.clone();
+    state.chroma_client = chroma_client;
+    state.embedding_service = embedding_service;
+    let app = sekha_controller::api::routes::create_router(state);
+
+    let mut conv = super::create_test_conversation();
+    conv.messages.truncate(1);
+    conv.messages[0].content = "a message long enough to be chunked".to_string();
+    let CreatedConversation { message_ids, .. } = repo.create_with_message_ids(conv).await.unwrap();
+    let message_id = message_ids[0].to_string();
+
+    // No vector is stored under the bare message ID, only its chunks
+    Mock::given(method("POST"))
+        .and(path(format!("{}/col-1/get", COLLECTIONS)))
+        .and(body_partial_json(
+            serde_json::json!({ "where": { "message_id": message_id } }),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "ids": [format!("{}:1", message_id), format!("{}:0", message_id)],
+            "embeddings": [[1.0, 1.0, 1.0, 1.0], [0.0, 0.0, 0.0, 0.0]]
+        })))
+        .with_priority(1)
+        .mount(&chroma)
+        .await;
+    Mock::given(method("POST"))
+        .and(path(format!("{}/col-1/get", COLLECTIONS)))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({ "ids": [], "embeddings": [] })),
+        )
+        .mount(&chroma)
+        .await;
+
+    let response = get_embedding_response(
+        &app,
+        &message_id,
+        Some("test_key_12345678901234567890123456789012"),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), 1 << 20)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["dimension"], 4);
+    assert_eq!(json["embedding"], serde_json::json!([]));
+    let chunks = json["chunks"].as_array().unwrap();
+    assert_eq!(chunks.len(), 2);
+    assert_eq!(chunks[0]["embedding_id"], format!("{}:0", message_id));
+    assert_eq!(
+        chunks[0]["embedding"],
+        serde_json::json!([0.0, 0.0, 0.0, 0.0])
+    );
+    assert_eq!(chunks[1]["embedding_id"], format!("{}:1", message_id));
+}
+
+#[tokio::test]
+async fn test_api_count_conversations_by_status() {
+    let state = create_test_app_state().await;
+    let repo = state.repo.clone();
+    let app = sekha_controller::api::routes::create_router(state);
+
+    let mut ids = Vec::new();
+    for _ in 0..3 {
+        let mut conv = super::create_test_conversation();
+        conv.folder = "/count-status".to_string();
+        ids.push(repo.create_with_messages(conv).await.unwrap());
+    }
+    repo.update_status(ids[0], "archived").await.unwrap();
+
+    let count = |query: &'static str| {
+        let app = app.clone();
+        async move {
+            let response = app
+                .oneshot(
+                    Request::builder()
+                        .uri(format!(
+                            "/api/v1/conversations/count?folder=/count-status{}",
+                            query
+                        ))
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            let status = response.status();
+            let body = axum::body::to_bytes(response.into_body(), 1024)
+                .await
+                .unwrap();
+            let json: serde_json::Value = serde_json::from_slice(&body).unwrap_or_default();
+            (status, json["count"].as_u64())
+        }
+    };
+
+    // Archived conversations are left out unless asked for
+    assert_eq!(count("").await, (StatusCode::OK, Some(2)));
+    assert_eq!(count("&status=active").await, (StatusCode::OK, Some(2)));
+    assert_eq!(count("&status=archived").await, (StatusCode::OK, Some(1)));
+    assert_eq!(
+        count("&exclude_archived=false").await,
+        (StatusCode::OK, Some(3))
+    );
+    assert_eq!(
+        count("&status=deleted").await,
+        (StatusCode::BAD_REQUEST, None)
+    );
+}
+
+/// GET `uri` on the ops router, returning the status and JSON body
+async fn probe(
+    state: sekha_controller::api::routes::AppState,
+    uri: &str,
+) -> (StatusCode, serde_json::Value) {
+    let app = sekha_controller::api::routes::create_ops_router(state);
+    let response = app
+        .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), 4096)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[tokio::test]
+async fn test_api_readyz_ready_when_dependencies_up() {
+    let chroma = wiremock::MockServer::start().await;
+    wiremock::Mock::given(wiremock::matchers::path("/api/v2/heartbeat"))
+        .respond_with(wiremock::ResponseTemplate::new(200))
+        .mount(&chroma)
+        .await;
+
+    let mut state = create_test_app_state().await;
+    state.chroma_client = std::sync::Arc::new(
+        sekha_controller::storage::chroma_client::ChromaClient::new(chroma.uri()),
+    );
+
+    let (status, body) = probe(state, "/readyz").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "ready");
+    assert_eq!(body["checks"]["database"]["status"], "ok");
+    assert_eq!(body["checks"]["migrations"]["status"], "ok");
+    assert_eq!(body["checks"]["chroma"]["status"], "ok");
+    assert_eq!(body["failed"], serde_json::json!([]));
+}
+
+#[tokio::test]
+async fn test_api_readyz_reports_failed_dependency() {
+    let chroma = wiremock::MockServer::start().await;
+    wiremock::Mock::given(wiremock::matchers::path("/api/v2/heartbeat"))
+        .respond_with(wiremock::ResponseTemplate::new(503))
+        .mount(&chroma)
+        .await;
+
+    let mut state = create_test_app_state().await;
+    state.chroma_client = std::sync::Arc::new(
+        sekha_controller::storage::chroma_client::ChromaClient::new(chroma.uri()),
+    );
+
+    let (status, body) = probe(state.clone(), "/readyz").await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["status"], "not_ready");
+    assert_eq!(body["failed"], serde_json::json!(["chroma"]));
+    assert_eq!(body["checks"]["chroma"]["status"], "error");
+    assert_eq!(body["checks"]["database"]["status"], "ok");
+
+    // Liveness ignores dependencies
+    let (status, body) = probe(state.clone(), "/healthz").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "alive");
+
+    // Without embeddings Chroma is not a dependency
+    state.config.write().await.embeddings_enabled = false;
+    let (status, body) = probe(state, "/readyz").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["checks"]["chroma"]["status"], "disabled");
+}
+
+#[tokio::test]
+async fn test_api_readyz_fails_when_schema_is_behind() {
+    use sea_orm::ConnectionTrait;
+
+    let state = create_test_app_state().await;
+    state.config.write().await.embeddings_enabled = false;
+    let (status, _) = probe(state.clone(), "/readyz").await;
+    assert_eq!(status, StatusCode::OK);
+
+    state
+        .repo
+        .get_db()
+        .execute_unprepared(
+            "DELETE FROM seaql_migrations \
+             WHERE version = (SELECT MAX(version) FROM seaql_migrations)",
+        )
+        .await
+        .unwrap();
+
+    let (status, body) = probe(state, "/readyz").await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["failed"], serde_json::json!(["migrations"]));
+    assert!(body["checks"]["migrations"]["error"]
+        .as_str()
+        .unwrap()
+        .contains(&sekha_controller::storage::db::migration_version(
+            sekha_controller::storage::db::LATEST_MIGRATION
+        )));
+}
+
+#[tokio::test]
+async fn test_api_health_and_metrics_bypass_rate_limit() {
+    use sekha_controller::api::{rate_limiter::RateLimiter, routes::create_app_router};
+
+    let app = create_app_router(create_test_app_state().await, RateLimiter::new(2));
+    let get = |uri: &str| {
+        app.clone()
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+    };
+
+    for uri in ["/health", "/healthz", "/readyz", "/metrics"] {
+        for _ in 0..10 {
+            let response = get(uri).await.unwrap();
+            assert_ne!(
+                response.status(),
+                StatusCode::TOO_MANY_REQUESTS,
+                "{} was throttled",
+                uri
+            );
+        }
+    }
+
+    // API routes still share the limiter
+    assert_ne!(
+        get("/api/v1/version").await.unwrap().status(),
+        StatusCode::TOO_MANY_REQUESTS
+    );
+    assert_ne!(
+        get("/api/v1/version").await.unwrap().status(),
+        StatusCode::TOO_MANY_REQUESTS
+    );
+    assert_eq!(
+        get("/api/v1/version").await.unwrap().status(),
+        StatusCode::TOO_MANY_REQUESTS
+    );
+}
+
+#[tokio::test]
+async fn test_api_rate_limit_warns_before_rejecting() {
+    use sekha_controller::api::{rate_limiter::RateLimiter, routes::create_app_router};
+
+    let limiter = RateLimiter::new(5).with_soft_limit_percent(60);
+    let app = create_app_router(create_test_app_state().await, limiter);
+
+    let mut warned_at = Vec::new();
+    for request in 1..=5 {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/v1/version")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        if let Some(warning) = response.headers().get("X-RateLimit-Warning") {
+            assert_eq!(
+                warning.to_str().unwrap(),
+                format!("{} of 5 requests per minute used", request)
+            );
+            warned_at.push(request);
+        }
+    }
+    assert_eq!(warned_at, [4, 5]);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/v1/version")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[tokio::test]
+async fn test_api_context_debug_reports_every_candidate() {
+    let state = create_test_app_state().await;
+    let repo = state.repo.clone();
+    let app = sekha_controller::api::routes::create_router(state);
+    let CreatedConversation {
+        id: conv_id,
+        message_ids,
+        ..
+    } = repo
+        .create_with_message_ids(super::create_test_conversation())
+        .await
+        .unwrap();
+
+    let debug = |auth: Option<&str>, budget: usize| {
+        let mut request = Request::builder().uri(format!(
+            "/api/v1/conversations/{}/context-debug?query=test&context_budget={}",
+            conv_id, budget
+        ));
+        if let Some(key) = auth {
+            request = request.header("Authorization", format!("Bearer {}", key));
+        }
+        app.clone().oneshot(request.body(Body::empty()).unwrap())
+    };
+
+    let response = debug(None, 4000).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let admin_key = "test_key_12345678901234567890123456789012";
+    for (budget, expect_selected) in [(4000, true), (1, false)] {
+        let response = debug(Some(admin_key), budget).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let candidates = json["candidates"].as_array().unwrap();
+
+        // The budget only decides what is selected, never what is listed
+        for id in &message_ids {
+            let id = id.to_string();
+            assert!(
+                candidates.iter().any(|c| c["message_id"] == id.as_str()),
+                "message {} missing from dump",
+                id
+            );
+        }
+        for candidate in candidates {
+            assert!(candidate["score"].is_number());
+            assert!(candidate["importance"].is_number());
+            assert!(candidate["recency"].is_number());
+            assert_eq!(candidate["label_match"], true);
+        }
+        assert_eq!(
+            candidates.iter().any(|c| c["selected"] == true),
+            expect_selected
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_api_patch_message_metadata_merges_keys() {
+    let state = create_test_app_state().await;
+    let repo = state.repo.clone();
+    let app = sekha_controller::api::routes::create_router(state);
+    let CreatedConversation { message_ids, .. } = repo
+        .create_with_message_ids(super::create_test_conversation())
+        .await
+        .unwrap();
+    let message_id = message_ids[0];
+
+    let patch = |body: &str| {
+        app.clone().oneshot(
+            Request::builder()
+                .method("PATCH")
+                .uri(format!("/api/v1/messages/{}/metadata", message_id))
+                .header("Content-Type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+    };
+
+    let response = patch(r#"{"metadata": {"verified": true}}"#).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["metadata"]["verified"], true);
+    assert_eq!(json["metadata"]["source"], "test");
+
+    let stored = repo.find_message_by_id(message_id).await.unwrap().unwrap();
+    assert_eq!(
+        stored.metadata,
+        Some(serde_json::json!({ "source": "test", "verified": true }))
+    );
+
+    let response = patch(r#"{"metadata": {"reviewed": "yes"}, "merge": false}"#)
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let stored = repo.find_message_by_id(message_id).await.unwrap().unwrap();
+    assert_eq!(
+        stored.metadata,
+        Some(serde_json::json!({ "reviewed": "yes" }))
+    );
+
+    let response = patch(r#"{"metadata": ["not", "an", "object"]}"#)
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // Keys the controller records can't be set, and a replace keeps them
+    let response = patch(r#"{"metadata": {"compacted": false}}"#)
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    use sea_orm::ConnectionTrait;
+    repo.get_db()
+        .execute_unprepared(&format!(
+            r#"UPDATE messages SET metadata = '{{"compacted": true, "reviewed": "yes"}}' WHERE id = x'{}'"#,
+            message_id.simple()
+        ))
+        .await
+        .unwrap();
+    let response = patch(r#"{"metadata": {"reviewed": "no"}, "merge": false}"#)
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let stored = repo.find_message_by_id(message_id).await.unwrap().unwrap();
+    assert_eq!(
+        stored.metadata,
+        Some(serde_json::json!({ "compacted": true, "reviewed": "no" }))
+    );
+}
+
+#[tokio::test]
+async fn test_api_secondary_label_matches_preferred_labels() {
+    let state = create_test_app_state().await;
+    let repo = state.repo.clone();
+    let app = sekha_controller::api::routes::create_router(state);
+    let mut conv = super::create_test_conversation();
+    conv.label = "Budget".to_string();
+    let CreatedConversation {
+        id, message_ids, ..
+    } = repo.create_with_message_ids(conv).await.unwrap();
+
+    let json = post_json(
+        &app,
+        &format!("/api/v1/conversations/{}/labels", id),
+        serde_json::json!({ "label": "Finance", "weight": 0.6 }),
+    )
+    .await;
+    assert_eq!(json["label"], "Budget");
+    assert_eq!(json["labels"][0]["label"], "Finance");
+    assert_eq!(json["labels"][0]["weight"], 0.6);
+
+    // Context assembly recalls the conversation through its secondary label
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/context/assemble")
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    serde_json::json!({
+                        "query": "unrelated words",
+                        "preferred_labels": ["Finance"],
+                        "context_budget": 4000
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let messages: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    for message_id in &message_ids {
+        assert!(messages
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|m| m["id"] == message_id.to_string()));
+    }
+
+    // The primary label always weighs 1.0
+    let matches = repo
+        .find_by_any_label(&["Finance".to_string(), "Budget".to_string()])
+        .await
+        .unwrap();
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].1, 1.0);
+    let matches = repo
+        .find_by_any_label(&["Finance".to_string()])
+        .await
+        .unwrap();
+    assert_eq!(matches[0].1, 0.6);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("DELETE")
+                .uri(format!("/api/v1/conversations/{}/labels/Finance", id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(repo
+        .find_by_any_label(&["Finance".to_string()])
+        .await
+        .unwrap()
+        .is_empty());
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/v1/conversations/{}/labels", id))
+                .header("Content-Type", "application/json")
+                .body(Body::from(r#"{"label": "Finance", "weight": 3.0}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_api_move_message_between_conversations() {
+    let state = create_test_app_state().await;
+    let repo = state.repo.clone();
+    let app = sekha_controller::api::routes::create_router(state);
+    let CreatedConversation {
+        id: source_id,
+        message_ids,
+        ..
+    } = repo
+        .create_with_message_ids(super::create_test_conversation())
+        .await
+        .unwrap();
+    let CreatedConversation { id: target_id, .. } = repo
+        .create_with_message_ids(super::create_test_conversation())
+        .await
+        .unwrap();
+    let message_id = message_ids[0];
+    let source_words = repo
+        .find_by_id(source_id)
+        .await
+        .unwrap()
+        .unwrap()
+        .word_count;
+    let target_words = repo
+        .find_by_id(target_id)
+        .await
+        .unwrap()
+        .unwrap()
+        .word_count;
+
+    let move_to = |message_id: Uuid, target: Uuid| {
+        app.clone().oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/v1/messages/{}/move", message_id))
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    serde_json::json!({ "target_conversation_id": target }).to_string(),
+                ))
+                .unwrap(),
+        )
+    };
+
+    let response = move_to(message_id, target_id).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let source_messages = repo.get_conversation_messages(source_id).await.unwrap();
+    let target_messages = repo.get_conversation_messages(target_id).await.unwrap();
+    assert!(source_messages.iter().all(|m| m.id != message_id));
+    assert!(target_messages.iter().any(|m| m.id == message_id));
+
+    let moved = repo.find_message_by_id(message_id).await.unwrap().unwrap();
+    let words = moved.content.len() as i32;
+    assert_eq!(moved.conversation_id, target_id);
+    assert_eq!(
+        repo.find_by_id(source_id)
+            .await
+            .unwrap()
+            .unwrap()
+            .word_count,
+        source_words - words
+    );
+    assert_eq!(
+        repo.find_by_id(target_id)
+            .await
+            .unwrap()
+            .unwrap()
+            .word_count,
+        target_words + words
+    );
+
+    let response = move_to(message_id, Uuid::new_v4()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = move_to(Uuid::new_v4(), target_id).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_api_embeddings_disabled_skips_embedding_and_searches_fts() {
+    let provider = std::sync::Arc::new(sekha_controller::services::MockProvider::new_success(
+        vec![0.1; 768],
+    ));
+    let call_count = provider.call_count.clone();
+    let embedding_service =
+        std::sync::Arc::new(sekha_controller::services::EmbeddingService::with_provider(
+            provider,
+            "http://localhost:8000".to_string(),
+        ));
+
+    let mut state = create_test_app_state().await;
+    state.config.write().await.embeddings_enabled = false;
+    state.repo = std::sync::Arc::new(
+        sekha_controller::storage::SeaOrmConversationRepository::new(
+            state.repo.get_db().clone(),
+            state.chroma_client.clone(),
+            embedding_service.clone(),
+        )
+        .with_embeddings_enabled(false),
+    );
+    let repo = state.repo.clone();
+    let queue = state.embedding_queue.clone();
+    state.embedding_service = embedding_service;
+    let app = sekha_controller::api::routes::create_router(state);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/conversations")
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    r#"{ "label": "FTS only", "folder": "/fts", "messages": [{"role": "user", "content": "the lighthouse keeper's ledger"}] }"#,
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = axum::body::to_bytes(response.into_body(), 4096)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["embeddings_pending"], false);
+    let conv_id: Uuid = json["id"].as_str().unwrap().parse().unwrap();
+
+    assert_eq!(*call_count.lock().unwrap(), 0);
+    assert_eq!(queue.enqueued_jobs(), 0);
+    let messages = repo.get_conversation_messages(conv_id).await.unwrap();
+    assert!(messages.iter().all(|m| m.embedding_id.is_none()));
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/query")
+                .header("Content-Type", "application/json")
+                .body(Body::from(r#"{ "query": "lighthouse" }"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), 1 << 20)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["search_backend"], "fts");
+    assert_eq!(json["results"].as_array().unwrap().len(), 1);
+    assert_eq!(json["results"][0]["conversation_id"], conv_id.to_string());
+    assert_eq!(*call_count.lock().unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_api_query_does_not_cache_an_unavailable_vector_store() {
+    let chroma = wiremock::MockServer::start().await;
+    wiremock::Mock::given(wiremock::matchers::any())
+        .respond_with(wiremock::ResponseTemplate::new(500))
+        .mount(&chroma)
+        .await;
+
+    let mut state = create_test_app_state().await;
+    let embedding_service =
+        std::sync::Arc::new(sekha_controller::services::EmbeddingService::with_provider(
+            std::sync::Arc::new(sekha_controller::services::MockProvider::new_success(
+                vec![0.1; 768],
+            )),
+            chroma.uri(),
+        ));
+    state.repo = std::sync::Arc::new(
+        sekha_controller::storage::SeaOrmConversationRepository::new(
+            state.repo.get_db().clone(),
+            std::sync::Arc::new(sekha_controller::storage::chroma_client::ChromaClient::new(
+                chroma.uri(),
+            )),
+            embedding_service,
+        )
+        .with_search_fallback(sekha_controller::config::SearchFallback::Empty),
+    );
+    let cache = state.search_cache.clone();
+    let app = sekha_controller::api::routes::create_router(state);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/query")
+                .header("Content-Type", "application/json")
+                .body(Body::from(r#"{ "query": "lighthouse" }"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), 1 << 20)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["search_backend"], "unavailable");
+    assert_eq!(json["total"], 0);
+    assert_eq!(cache.len(), 0);
+}
+
+#[tokio::test]
+async fn test_api_query_conversations_groups_messages() {
+    let app = create_test_app().await;
+
+    for body in [
+        r#"{ "label": "Airships", "folder": "/grouped", "messages": [{"role": "user", "content": "the zeppelin docked at noon"}, {"role": "assistant", "content": "a zeppelin needs a mooring mast"}] }"#,
+        r#"{ "label": "Ports", "folder": "/grouped", "messages": [{"role": "user", "content": "one zeppelin over the harbour"}] }"#,
+    ] {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/v1/conversations")
+                    .header("Content-Type", "application/json")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/query/conversations")
+                .header("Content-Type", "application/json")
+                .body(Body::from(r#"{ "query": "zeppelin" }"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), 1 << 20)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(json["total"], 2);
+    let results = json["results"].as_array().unwrap();
+    let airships = results
+        .iter()
+        .find(|r| r["label"] == "Airships")
+        .expect("grouped conversation missing");
+    assert_eq!(airships["match_count"], 2);
+    assert!(airships["snippet"].as_str().unwrap().contains("zeppelin"));
+    let ports = results.iter().find(|r| r["label"] == "Ports").unwrap();
+    assert_eq!(ports["match_count"], 1);
+
+    // A mistyped body gets the usual JSON error
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/query/conversations")
+                .header("Content-Type", "application/json")
+                .body(Body::from(r#"{ "query": 7 }"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = axum::body::to_bytes(response.into_body(), 1 << 20)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["code"], 400);
+    assert!(json["error"].as_str().unwrap().contains("query"));
+}
+
+#[tokio::test]
+async fn test_api_conversation_events_reach_subscribers_and_sse() {
+    use futures::StreamExt;
+    use sekha_controller::services::MemoryEvent;
+    use std::time::Duration;
+
+    let state = create_test_app_state().await;
+    let mut receiver = state.subscribe();
+    let app = sekha_controller::api::routes::create_router(state);
+
+    let sse_response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/v1/events")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(sse_response.status(), StatusCode::OK);
+    assert_eq!(sse_response.headers()["content-type"], "text/event-stream");
+    let mut sse_body = sse_response.into_body().into_data_stream();
+
+    let create_response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/conversations")
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    r#"{"label":"Events","folder":"/events","messages":[{"role":"user","content":"Hi"}]}"#,
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = axum::body::to_bytes(create_response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let created: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let id: Uuid = created["id"].as_str().unwrap().parse().unwrap();
+
+    let event = tokio::time::timeout(Duration::from_secs(5), receiver.recv())
+        .await
+        .expect("no event published")
+        .unwrap();
+    assert_eq!(
+        event,
+        MemoryEvent::Created {
+            conversation_id: id
+        }
+    );
+
+    let chunk = tokio::time::timeout(Duration::from_secs(5), sse_body.next())
+        .await
+        .expect("no event streamed")
+        .unwrap()
+        .unwrap();
+    let frame = String::from_utf8(chunk.to_vec()).unwrap();
+    assert!(
+        frame.contains("event: created"),
+        "unexpected frame: {}",
+        frame
+    );
+    assert!(
+        frame.contains(&id.to_string()),
+        "unexpected frame: {}",
+        frame
+    );
+}
+
+#[tokio::test]
+async fn test_api_context_pin_toggles_flag() {
+    let app = create_test_app().await;
+
+    let create_response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/conversations")
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    r#"{"label":"Guidelines","folder":"/work","messages":[{"role":"user","content":"Use tabs"}]}"#,
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = axum::body::to_bytes(create_response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let created: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let id = created["id"].as_str().unwrap().to_string();
+
+    let send = |method: &'static str, uri: String| {
+        let app = app.clone();
+        async move {
+            app.oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+        }
+    };
+    let context_pinned = |id: String| async move {
+        let response = send("GET", format!("/api/v1/conversations/{}", id)).await;
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice::<serde_json::Value>(&body).unwrap()["context_pinned"].clone()
+    };
+
+    assert_eq!(context_pinned(id.clone()).await, false);
+
+    let pin = send("PUT", format!("/api/v1/conversations/{}/context-pin", id)).await;
+    assert_eq!(pin.status(), StatusCode::OK);
+    assert_eq!(context_pinned(id.clone()).await, true);
+
+    let unpin = send(
+        "DELETE",
+        format!("/api/v1/conversations/{}/context-pin", id),
+    )
+    .await;
+    assert_eq!(unpin.status(), StatusCode::OK);
+    assert_eq!(context_pinned(id.clone()).await, false);
+
+    let missing = send(
+        "PUT",
+        format!("/api/v1/conversations/{}/context-pin", Uuid::new_v4()),
+    )
+    .await;
+    assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_api_reconcile_embeddings_removes_orphans_and_requeues_missing() {
+    use sea_orm::ConnectionTrait;
+    use wiremock::matchers::{body_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const COLLECTIONS: &str =
+        "/api/v2/tenants/default_tenant/databases/default_database/collections";
+    let chroma = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(format!("{}/conversations", COLLECTIONS)))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({"id": "col-1", "name": "conversations"})),
+        )
+        .mount(&chroma)
+        .await;
+    Mock::given(method("POST"))
+        .and(path(format!("{}/col-1/get", COLLECTIONS)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "ids": ["kept-vector", "orphan-vector"]
+        })))
+        .mount(&chroma)
+        .await;
+    Mock::given(method("POST"))
+        .and(path(format!("{}/col-1/delete", COLLECTIONS)))
+        .and(body_json(serde_json::json!({"ids": ["orphan-vector"]})))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&chroma)
+        .await;
+
+    let mut state = create_test_app_state().await;
+    let chroma_client = std::sync::Arc::new(
+        sekha_controller::storage::chroma_client::ChromaClient::new(chroma.uri()),
+    );
+    state.repo = std::sync::Arc::new(
+        sekha_controller::storage::SeaOrmConversationRepository::new(
+            state.repo.get_db().clone(),
+            chroma_client.clone(),
+            state.embedding_service.clone(),
+        ),
+    );
+    state.chroma_client = chroma_client;
+    let repo = state.repo.clone();
+    let queue = state.embedding_queue.clone();
+    let app = sekha_controller::api::routes::create_router(state);
+
+    // One message keeps its vector, the other's vector has gone from Chroma
+    let CreatedConversation { message_ids, .. } = repo
+        .create_with_message_ids(super::create_test_conversation())
+        .await
+        .unwrap();
+    for (id, embedding_id) in [
+        (message_ids[0], "kept-vector"),
+        (message_ids[1], "gone-vector"),
+    ] {
+        repo.get_db()
+            .execute_raw(sea_orm::Statement::from_sql_and_values(
+                sea_orm::DatabaseBackend::Sqlite,
+                "UPDATE messages SET embedding_id = ? WHERE id = ?",
+                [embedding_id.into(), id.into()],
+            ))
+            .await
+            .unwrap();
+    }
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/maintenance/reconcile-embeddings")
+                .header(
+                    "Authorization",
+                    "Bearer test_key_12345678901234567890123456789012",
+                )
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["orphaned_vectors_removed"], 1);
+    assert_eq!(json["missing_vectors_requeued"], 1);
+    assert_eq!(queue.enqueued_jobs(), 1);
+
+    let kept = repo
+        .find_message_by_id(message_ids[0])
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(kept.embedding_id.as_deref(), Some("kept-vector"));
+    let requeued = repo
+        .find_message_by_id(message_ids[1])
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(requeued.embedding_id, None);
+}
+
+#[tokio::test]
+async fn test_api_recompute_aggregates_fixes_drifted_word_count() {
+    use sea_orm::ConnectionTrait;
+
+    let state = create_test_app_state().await;
+    let repo = state.repo.clone();
+    let app = sekha_controller::api::routes::create_router(state);
+
+    let CreatedConversation { id: drifted, .. } = repo
+        .create_with_message_ids(super::create_test_conversation())
+        .await
+        .unwrap();
+    repo.create_with_message_ids(super::create_test_conversation())
+        .await
+        .unwrap();
+    let expected = repo.find_by_id(drifted).await.unwrap().unwrap().word_count;
+    repo.get_db()
+        .execute_raw(sea_orm::Statement::from_sql_and_values(
+            sea_orm::DatabaseBackend::Sqlite,
+            "UPDATE conversations SET word_count = ? WHERE id = ?",
+            [9999.into(), drifted.into()],
+        ))
+        .await
+        .unwrap();
+
+    let recompute = || {
+        app.clone().oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/maintenance/recompute-aggregates")
+                .header(
+                    "Authorization",
+                    "Bearer test_key_12345678901234567890123456789012",
+                )
+                .body(Body::empty())
+                .unwrap(),
+        )
+    };
+
+    let response = recompute().await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["conversations_corrected"], 1);
+    assert_eq!(json["messages_counted"], 4);
+
+    let fixed = repo.find_by_id(drifted).await.unwrap().unwrap();
+    assert_eq!(fixed.word_count, expected);
+    assert_eq!(fixed.session_count, 1);
+
+    // Nothing left to correct
+    let response = recompute().await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["conversations_corrected"], 0);
+}
+
+#[tokio::test]
+async fn test_api_scoped_reembed_only_touches_matching_conversations() {
+    use sekha_controller::services::embedding_provider::MockProvider;
+    use sekha_controller::services::embedding_service::EmbeddingService;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const COLLECTIONS: &str =
+        "/api/v2/tenants/default_tenant/databases/default_database/collections";
+    let chroma = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(COLLECTIONS))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!([{"id": "col-1", "name": "conversations"}])),
+        )
+        .mount(&chroma)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(format!("{}/conversations", COLLECTIONS)))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({"id": "col-1", "name": "conversations"})),
+        )
+        .mount(&chroma)
+        .await;
+    let upsert_path = format!("{}/col-1/upsert", COLLECTIONS);
+    Mock::given(method("POST"))
+        .and(path(upsert_path.as_str()))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&chroma)
+        .await;
+
+    // Seeded through the default state, whose embedder is unreachable, so
+    // every vector written below comes from the re-embed
+    let mut state = create_test_app_state().await;
+    let mut matching = std::collections::HashSet::new();
+    for folder in ["/work", "/work/notes", "/personal"] {
+        let mut conv = super::create_test_conversation();
+        conv.folder = folder.to_string();
+        let CreatedConversation { message_ids, .. } =
+            state.repo.create_with_message_ids(conv).await.unwrap();
+        if folder.starts_with("/work") {
+            matching.extend(message_ids.iter().map(Uuid::to_string));
+        }
+    }
+
+    let provider = MockProvider::new_success(vec![0.1; 8]);
+    let calls = provider.call_count.clone();
+    let embedding_service = std::sync::Arc::new(EmbeddingService::with_provider(
+        std::sync::Arc::new(provider),
+        chroma.uri(),
+    ));
+    let repo = std::sync::Arc::new(
+        sekha_controller::storage::SeaOrmConversationRepository::new(
+            state.repo.get_db().clone(),
+            state.chroma_client.clone(),
+            embedding_service.clone(),
+        ),
+    );
+    state.repo = repo.clone();
+    state.embedding_service = embedding_service;
+    let app = sekha_controller::api::routes::create_router(state);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/rebuild-embeddings/scoped")
+                .header("Content-Type", "application/json")
+                .header(
+                    "Authorization",
+                    "Bearer test_key_12345678901234567890123456789012",
+                )
+                .body(Body::from(r#"{"folder": "/work"}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    let body = axum::body::to_bytes(response.into_body(), 4096)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let task_id = json["task_id"].as_str().unwrap().to_string();
+
+    let mut task = serde_json::Value::Null;
+    for _ in 0..50 {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/api/v1/tasks/{}", task_id))
+                    .header(
+                        "Authorization",
+                        "Bearer test_key_12345678901234567890123456789012",
+                    )
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), 4096)
+            .await
+            .unwrap();
+        task = serde_json::from_slice(&body).unwrap();
+        if task["state"] != "running" {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    assert_eq!(task["state"], "completed");
+    assert_eq!(task["result"]["conversations"], 2);
+    assert_eq!(task["result"]["messages_reembedded"], 4);
+    assert_eq!(*calls.lock().unwrap(), 4);
+
+    let mut upserted = std::collections::HashSet::new();
+    for request in chroma.received_requests().await.unwrap() {
+        if request.url.path() == upsert_path {
+            let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+            for id in body["ids"].as_array().unwrap() {
+                upserted.insert(id.as_str().unwrap().to_string());
+            }
+        }
+    }
+    assert_eq!(upserted, matching);
+}
+
+#[tokio::test]
+async fn test_api_conversation_history_records_label_changes() {
+    let app = create_test_app().await;
+
+    let create_response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/conversations")
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    r#"{"label":"Draft","folder":"/notes","messages":[{"role":"user","content":"Hello"}]}"#,
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = axum::body::to_bytes(create_response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let created: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let id = created["id"].as_str().unwrap().to_string();
+
+    for label in ["Review", "Final"] {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("PUT")
+                    .uri(format!("/api/v1/conversations/{}/label", id))
+                    .header("Content-Type", "application/json")
+                    .body(Body::from(
+                        serde_json::json!({"label": label, "folder": "/notes"}).to_string(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/conversations/{}/history", id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let history: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    // The folder didn't change, so only the label is logged
+    assert_eq!(history["total"], 2);
+    let changes = history["changes"].as_array().unwrap();
+    assert_eq!(changes[0]["field"], "label");
+    assert_eq!(changes[0]["old_value"], "Draft");
+    assert_eq!(changes[0]["new_value"], "Review");
+    assert_eq!(changes[1]["field"], "label");
+    assert_eq!(changes[1]["old_value"], "Review");
+    assert_eq!(changes[1]["new_value"], "Final");
+
+    let missing = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/conversations/{}/history", Uuid::new_v4()))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_api_create_conversation_from_template() {
+    let state = create_test_app_state().await;
+    let repo = state.repo.clone();
+    let app = sekha_controller::api::routes::create_router(state);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri("/api/v1/templates/standup")
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    serde_json::json!({
+                        "label_pattern": "Standup {date}",
+                        "folder": " /team//standups/{year}/ ",
+                        "system_message": "Daily standup for {weekday}",
+                        "tags": ["standup", "team"]
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let today = chrono::Utc::now().naive_utc();
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/conversations/from-template/standup")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let body = axum::body::to_bytes(response.into_body(), 8192)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(
+        json["label"],
+        format!("Standup {}", today.format("%Y-%m-%d"))
+    );
+    assert_eq!(
+        json["folder"],
+        format!("/team/standups/{}", today.format("%Y"))
+    );
+
+    let id: Uuid = json["id"].as_str().unwrap().parse().unwrap();
+    let messages = repo.get_conversation_messages(id).await.unwrap();
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].role, "system");
+    assert_eq!(
+        messages[0].content,
+        format!("Daily standup for {}", today.format("%A"))
+    );
+
+    let mut tags: Vec<String> = repo
+        .find_tags(id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|t| t.tag)
+        .collect();
+    tags.sort();
+    assert_eq!(tags, vec!["standup", "team"]);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/conversations/from-template/missing")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_api_get_conversation_records_access_without_updating() {
+    let state = create_test_app_state().await;
+    let repo = state.repo.clone();
+    let app = sekha_controller::api::routes::create_router(state);
+
+    let mut conv = super::create_test_conversation();
+    let updated_at = chrono::NaiveDate::from_ymd_opt(2024, 1, 1)
+        .unwrap()
+        .and_hms_opt(12, 0, 0)
+        .unwrap();
+    conv.updated_at = updated_at;
+    let id = repo.create_with_messages(conv).await.unwrap();
+    assert_eq!(
+        repo.find_by_id(id).await.unwrap().unwrap().last_accessed_at,
+        None
+    );
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/conversations/{}", id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // The access is written in the background
+    let mut conv = repo.find_by_id(id).await.unwrap().unwrap();
+    for _ in 0..50 {
+        if conv.last_accessed_at.is_some() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        conv = repo.find_by_id(id).await.unwrap().unwrap();
+    }
+    let first_access = conv.last_accessed_at.expect("access was not recorded");
+    assert!(first_access > updated_at);
+    assert_eq!(conv.updated_at, updated_at);
+
+    // Reads in quick succession are coalesced
+    repo.record_access(id, first_access + chrono::Duration::seconds(10))
+        .await
+        .unwrap();
+    let conv = repo.find_by_id(id).await.unwrap().unwrap();
+    assert_eq!(conv.last_accessed_at, Some(first_access));
+
+    let later = first_access + chrono::Duration::minutes(5);
+    repo.record_access(id, later).await.unwrap();
+    let conv = repo.find_by_id(id).await.unwrap().unwrap();
+    assert_eq!(conv.last_accessed_at, Some(later));
+    assert_eq!(conv.updated_at, updated_at);
+}
+
+#[tokio::test]
+async fn test_api_writes_outside_crud_publish_events() {
+    let state = create_test_app_state().await;
+    let mut events = state.events.subscribe();
+    let repo = state.repo.clone();
+    let app = sekha_controller::api::routes::create_router(state.clone())
+        .merge(sekha_controller::api::mcp::create_mcp_router(state));
+
+    let CreatedConversation {
+        id: conv_id,
+        message_ids,
+        ..
+    } = repo
+        .create_with_message_ids(super::create_test_conversation())
+        .await
+        .unwrap();
+
+    let send = |method: &str, uri: String, body: serde_json::Value| {
+        app.clone().oneshot(
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("Content-Type", "application/json")
+                .header(
+                    "Authorization",
+                    "Bearer test_key_12345678901234567890123456789012",
+                )
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+    };
+    let mut next_event = || events.try_recv().map(|e| (e.kind(), e.conversation_id()));
+
+    let calls = [
+        (
+            "PUT",
+            format!("/api/v1/conversations/{}/folder", conv_id),
+            serde_json::json!({ "folder": "/moved" }),
+        ),
+        (
+            "PATCH",
+            format!("/api/v1/messages/{}/metadata", message_ids[0]),
+            serde_json::json!({ "metadata": { "reviewed": true } }),
+        ),
+        (
+            "PUT",
+            format!("/api/v1/conversations/{}/archive", conv_id),
+            serde_json::json!({}),
+        ),
+        (
+            "POST",
+            format!("/api/v1/conversations/{}/offload", conv_id),
+            serde_json::json!({}),
+        ),
+        (
+            "POST",
+            format!("/api/v1/conversations/{}/reload", conv_id),
+            serde_json::json!({}),
+        ),
+        (
+            "POST",
+            "/mcp/tools/memory_update".to_string(),
+            serde_json::json!({ "conversation_id": conv_id, "label": "Renamed" }),
+        ),
+    ];
+    for (method, uri, body) in calls {
+        let response = send(method, uri.clone(), body).await.unwrap();
+        assert!(response.status().is_success(), "{} {}", method, uri);
+    }
+
+    assert_eq!(next_event(), Ok(("updated", conv_id)));
+    assert_eq!(next_event(), Ok(("updated", conv_id)));
+    assert_eq!(next_event(), Ok(("updated", conv_id)));
+    assert_eq!(next_event(), Ok(("deleted", conv_id)));
+    assert_eq!(next_event(), Ok(("created", conv_id)));
+    assert_eq!(next_event(), Ok(("updated", conv_id)));
+
+    let response = send(
+        "POST",
+        "/mcp/tools/memory_store".to_string(),
+        serde_json::json!({
+            "label": "From MCP",
+            "folder": "/mcp",
+            "messages": [{ "role": "user", "content": "hello" }]
+        }),
+    )
+    .await
+    .unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let stored: Uuid = json["data"]["conversation_id"]
+        .as_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert_eq!(next_event(), Ok(("created", stored)));
+}
@@ -0,0 +1,93 @@
+use super::create_test_app_state;
+use sekha_controller::api::dto::{CreateConversationRequest, MessageDto, QueryRequest};
+use sekha_controller::client::{ClientError, SekhaClient};
+
+/// Serve the in-memory app on a local port, returning a client for it
+async fn spawn_client() -> SekhaClient {
+    let state = create_test_app_state().await;
+    state.config.write().await.embeddings_enabled = false;
+    let app = sekha_controller::api::routes::create_router(state);
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    SekhaClient::new(format!("http://{}/", addr))
+}
+
+fn message(content: &str) -> MessageDto {
+    MessageDto {
+        role: "user".to_string(),
+        content: content.to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_client_round_trip() {
+    let client = spawn_client().await;
+
+    let created = client
+        .create_conversation(&CreateConversationRequest {
+            label: "Client".to_string(),
+            folder: Some("/clients".to_string()),
+            messages: vec![message("Typed clients beat hand-rolled requests")],
+        })
+        .await
+        .unwrap();
+    assert_eq!(created.folder, "/clients");
+    assert_eq!(created.message_ids.len(), 1);
+
+    let appended = client
+        .append_messages(created.id, vec![message("Another typed message")])
+        .await
+        .unwrap();
+    assert_eq!(appended.message_ids.len(), 1);
+
+    let conversation = client.get_conversation(created.id).await.unwrap();
+    assert_eq!(conversation.label, "Client");
+    assert_eq!(conversation.message_count, 2);
+
+    let results = client
+        .search(&QueryRequest {
+            query: "typed".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(results.total, 2);
+    assert!(results
+        .results
+        .iter()
+        .all(|r| r.conversation_id == created.id));
+
+    let context = client
+        .get_context("typed clients", vec!["Client".to_string()], 4000)
+        .await
+        .unwrap();
+    assert!(context.iter().all(|m| m.conversation_id == created.id));
+
+    client.delete_conversation(created.id).await.unwrap();
+    let err = client.get_conversation(created.id).await.unwrap_err();
+    assert_eq!(err.status(), Some(404));
+    assert!(matches!(err, ClientError::ApiError(ref body) if !body.error.is_empty()));
+}
+
+#[tokio::test]
+async fn test_client_surfaces_validation_errors() {
+    let client = spawn_client().await;
+
+    let err = client
+        .search(&QueryRequest {
+            query: "  ".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(err.status(), Some(400));
+    let ClientError::ApiError(body) = err else {
+        panic!("expected an API error");
+    };
+    assert_eq!(body.error, "query must not be empty");
+}
@@ -2,9 +2,12 @@
 //! Minimal integration tests for file watcher
 //! Tests end-to-end flow, skips if external dependencies unavailable
 
-use super::{create_test_services, Arc, ConversationRepository};
+use super::{create_test_services, is_llm_bridge_running, Arc, ConversationRepository};
 use sekha_controller::{
+    config::ImportMode,
+    services::embedding_provider::{EmbeddingProvider, ProviderError},
     services::file_watcher::{ImportProcessor, ImportWatcher},
+    services::llm_bridge_client::LlmBridgeClient,
     storage::{init_db, SeaOrmConversationRepository},
 };
 use std::fs;
@@ -389,6 +392,73 @@ async fn test_processor_concurrent_processing() {
         .unwrap();
 }
 
+/// Embedding provider that records the peak number of calls in flight,
+/// which tracks how many imports are running at once
+struct ConcurrencyProbe {
+    in_flight: std::sync::atomic::AtomicUsize,
+    peak: std::sync::atomic::AtomicUsize,
+}
+
+#[async_trait::async_trait]
+impl EmbeddingProvider for ConcurrencyProbe {
+    async fn generate_embedding(&self, _content: &str) -> Result<Vec<f32>, ProviderError> {
+        use std::sync::atomic::Ordering;
+
+        let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.peak.fetch_max(now, Ordering::SeqCst);
+        sleep(Duration::from_millis(50)).await;
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+        Ok(vec![0.1; 768])
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_processor_caps_concurrent_imports() {
+    let temp_dir = TempDir::new().unwrap();
+    let watch_path = temp_dir.path().join("import");
+    fs::create_dir_all(&watch_path).unwrap();
+
+    let probe = Arc::new(ConcurrencyProbe {
+        in_flight: Default::default(),
+        peak: Default::default(),
+    });
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let chroma = Arc::new(ChromaClient::new("http://localhost:1".to_string()));
+    let embedding = Arc::new(EmbeddingService::with_provider(
+        probe.clone(),
+        "http://localhost:1".to_string(),
+    ));
+    let repo = Arc::new(SeaOrmConversationRepository::new(db, chroma, embedding));
+
+    let processor = ImportProcessor::new(repo).with_max_concurrency(2);
+
+    let mut handles = vec![];
+    for i in 0..8 {
+        let file_path = watch_path.join(format!("burst_{}.json", i));
+        fs::write(&file_path, create_chatgpt_single_export()).unwrap();
+
+        let proc_clone = processor.clone();
+        handles.push(tokio::spawn(async move {
+            proc_clone.process_file(&file_path).await
+        }));
+    }
+
+    for handle in handles {
+        assert!(handle.await.unwrap().is_ok());
+    }
+
+    let peak = probe.peak.load(std::sync::atomic::Ordering::SeqCst);
+    assert!(peak <= 2, "{} imports ran at once with a limit of 2", peak);
+    assert!(peak >= 1);
+
+    let imported = processor
+        .repo()
+        .find_by_label("ChatGPT Single Test", 100, 0)
+        .await
+        .unwrap();
+    assert_eq!(imported.len(), 8);
+}
+
 // ============================================
 // Test: Error handling and logging
 // ============================================
@@ -420,12 +490,425 @@ async fn test_processor_graceful_error_handling() {
     // Should not create any conversations
     let conversations: (Vec<_>, u64) = processor
         .repo()
-        .find_with_filters(None, 100, 0)
+        .find_with_filters(None, None, 100, 0)
         .await
         .unwrap();
     assert_eq!(conversations.0.len(), 0);
 }
 
+// ============================================
+// Test: ZIP export archives
+// ============================================
+
+#[tokio::test]
+async fn test_processor_imports_chatgpt_zip_export() {
+    use std::io::Write;
+
+    let temp_dir = TempDir::new().unwrap();
+    let watch_path = temp_dir.path().join("import");
+    fs::create_dir_all(&watch_path).unwrap();
+
+    // ChatGPT's export.zip: conversations.json plus account files and media
+    let zip_file = watch_path.join("export.zip");
+    let mut writer = zip::ZipWriter::new(fs::File::create(&zip_file).unwrap());
+    let options = zip::write::SimpleFileOptions::default();
+    let conversations_json = format!("[{}]", create_chatgpt_single_export());
+    let entries: [(&str, &[u8]); 3] = [
+        ("conversations.json", conversations_json.as_bytes()),
+        ("user.json", br#"{"id": "user-123", "email": "a@b.c"}"#),
+        ("file-abc123-photo.png", &[0x89, 0x50, 0x4e, 0x47]),
+    ];
+    for (name, bytes) in entries {
+        writer.start_file(name, options).unwrap();
+        writer.write_all(bytes).unwrap();
+    }
+    writer.finish().unwrap();
+
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let chroma = Arc::new(ChromaClient::new("http://localhost:1".to_string()));
+    let embedding = Arc::new(EmbeddingService::new(
+        "http://localhost:1".to_string(),
+        "http://localhost:1".to_string(),
+    ));
+    let repo = Arc::new(SeaOrmConversationRepository::new(db, chroma, embedding));
+    let processor = ImportProcessor::new(repo);
+
+    processor.process_file(&zip_file).await.unwrap();
+
+    let (conversations, total) = processor
+        .repo()
+        .find_with_filters(None, None, 100, 0)
+        .await
+        .unwrap();
+    assert_eq!(total, 1);
+    assert_eq!(conversations[0].label, "ChatGPT Single Test");
+    assert_eq!(conversations[0].folder, "/imports/chatgpt");
+    assert!(!zip_file.exists(), "Archive should be moved once imported");
+}
+
+#[tokio::test]
+async fn test_import_keeps_source_order_for_identical_timestamps() {
+    let temp_dir = TempDir::new().unwrap();
+    let export_file = temp_dir.path().join("export.json");
+
+    // Both turns share a create_time, as ChatGPT exports often do
+    let chatgpt_json = r#"{
+        "title": "Same Second",
+        "create_time": 1703073600.0,
+        "mapping": {
+            "root": {"id": "root", "message": null, "parent": null, "children": ["q"]},
+            "q": {
+                "id": "q",
+                "message": {
+                    "id": "q",
+                    "author": {"role": "user"},
+                    "create_time": 1703073600.0,
+                    "content": {"content_type": "text", "parts": ["Question first"]}
+                },
+                "parent": "root",
+                "children": ["a"]
+            },
+            "a": {
+                "id": "a",
+                "message": {
+                    "id": "a",
+                    "author": {"role": "assistant"},
+                    "create_time": 1703073600.0,
+                    "content": {"content_type": "text", "parts": ["Answer second"]}
+                },
+                "parent": "q",
+                "children": []
+            }
+        }
+    }"#;
+    fs::write(&export_file, chatgpt_json).unwrap();
+
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let chroma = Arc::new(ChromaClient::new("http://localhost:1".to_string()));
+    let embedding = Arc::new(EmbeddingService::new(
+        "http://localhost:1".to_string(),
+        "http://localhost:1".to_string(),
+    ));
+    let repo = Arc::new(SeaOrmConversationRepository::new(db, chroma, embedding));
+    let processor = ImportProcessor::new(repo.clone());
+    processor.process_file(&export_file).await.unwrap();
+
+    let (conversations, _) = repo.find_with_filters(None, None, 100, 0).await.unwrap();
+    let messages = repo
+        .get_conversation_messages(conversations[0].id)
+        .await
+        .unwrap();
+    assert_eq!(messages[0].timestamp, messages[1].timestamp);
+    let contents: Vec<&str> = messages.iter().map(|m| m.content.as_str()).collect();
+    assert_eq!(contents, ["Question first", "Answer second"]);
+
+    let recent = repo
+        .find_recent_messages(conversations[0].id, 1)
+        .await
+        .unwrap();
+    assert_eq!(recent[0].content, "Answer second");
+}
+
+#[tokio::test]
+async fn test_memory_export_round_trips_through_import() {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    let state = super::create_test_app_state().await;
+    let repo = state.repo.clone();
+    let app = sekha_controller::api::mcp::create_mcp_router(state);
+
+    let mut conv = super::create_test_conversation();
+    conv.label = "Round Trip".to_string();
+    let original_id = repo.create_with_messages(conv).await.unwrap();
+    let original = repo.get_conversation_messages(original_id).await.unwrap();
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/mcp/tools/memory_export")
+                .header("Content-Type", "application/json")
+                .header(
+                    "Authorization",
+                    "Bearer test_key_12345678901234567890123456789012",
+                )
+                .body(Body::from(format!(
+                    r#"{{"conversation_id": "{}", "format": "json"}}"#,
+                    original_id
+                )))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+
+    // The saved tool response goes straight into the import directory
+    let temp_dir = TempDir::new().unwrap();
+    let export_file = temp_dir.path().join("memory_export.json");
+    fs::write(&export_file, &body).unwrap();
+    ImportProcessor::new(repo.clone())
+        .process_file(&export_file)
+        .await
+        .unwrap();
+
+    let imported = repo.find_by_folder("/imports/sekha", 10, 0).await.unwrap();
+    assert_eq!(imported.len(), 1);
+    assert_ne!(imported[0].id, original_id);
+    assert_eq!(imported[0].label, "Round Trip");
+    let messages = repo
+        .get_conversation_messages(imported[0].id)
+        .await
+        .unwrap();
+    assert_eq!(messages.len(), original.len());
+    for (restored, stored) in messages.iter().zip(&original) {
+        assert_eq!(restored.role, stored.role);
+        assert_eq!(restored.content, stored.content);
+        assert_eq!(restored.timestamp, stored.timestamp);
+    }
+}
+
+#[tokio::test]
+async fn test_deterministic_import_mode_reimports_to_same_id() {
+    let temp_dir = TempDir::new().unwrap();
+    let watch_path = temp_dir.path().join("import");
+    fs::create_dir_all(&watch_path).unwrap();
+
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let chroma = Arc::new(ChromaClient::new("http://localhost:1".to_string()));
+    let embedding = Arc::new(EmbeddingService::new(
+        "http://localhost:1".to_string(),
+        "http://localhost:1".to_string(),
+    ));
+    let repo = Arc::new(SeaOrmConversationRepository::new(db, chroma, embedding));
+    let processor = ImportProcessor::new(repo).with_import_mode(ImportMode::Deterministic);
+
+    let mut ids = Vec::new();
+    for _ in 0..2 {
+        // Processing moves the file away, so drop a fresh copy each run
+        let export_file = watch_path.join("export.json");
+        fs::write(&export_file, create_chatgpt_single_export()).unwrap();
+        processor.process_file(&export_file).await.unwrap();
+
+        let (conversations, total) = processor
+            .repo()
+            .find_with_filters(None, None, 100, 0)
+            .await
+            .unwrap();
+        assert_eq!(total, 1, "Re-import should replace, not duplicate");
+        assert_eq!(
+            processor
+                .repo()
+                .count_messages_in_conversation(conversations[0].id)
+                .await
+                .unwrap(),
+            1
+        );
+        ids.push(conversations[0].id);
+    }
+    assert_eq!(ids[0], ids[1]);
+}
+
+#[tokio::test]
+async fn test_deterministic_reimport_keeps_user_changes() {
+    let temp_dir = TempDir::new().unwrap();
+    let first_dir = temp_dir.path().join("first");
+    let second_dir = temp_dir.path().join("second");
+    fs::create_dir_all(&first_dir).unwrap();
+    fs::create_dir_all(&second_dir).unwrap();
+
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let chroma = Arc::new(ChromaClient::new("http://localhost:1".to_string()));
+    let embedding = Arc::new(EmbeddingService::new(
+        "http://localhost:1".to_string(),
+        "http://localhost:1".to_string(),
+    ));
+    let repo = Arc::new(SeaOrmConversationRepository::new(db, chroma, embedding));
+    let processor = ImportProcessor::new(repo.clone()).with_import_mode(ImportMode::Deterministic);
+
+    let notes = first_dir.join("notes.txt");
+    fs::write(&notes, "User: first draft\nAssistant: noted\n").unwrap();
+    processor.process_file(&notes).await.unwrap();
+    let (conversations, _) = repo.find_with_filters(None, None, 10, 0).await.unwrap();
+    let id = conversations[0].id;
+
+    repo.update_label(id, "Renamed", "/work").await.unwrap();
+    repo.add_tags(id, vec!["keep".to_string()]).await.unwrap();
+
+    fs::write(
+        &notes,
+        "User: second draft\nAssistant: noted again\nUser: thanks\n",
+    )
+    .unwrap();
+    processor.process_file(&notes).await.unwrap();
+    let conv = repo.find_by_id(id).await.unwrap().unwrap();
+    assert_eq!(conv.label, "Renamed");
+    assert_eq!(conv.folder, "/work");
+    let tags: Vec<String> = repo
+        .find_tags(id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|t| t.tag)
+        .collect();
+    assert_eq!(tags, vec!["keep"]);
+    let messages = repo.get_conversation_messages(id).await.unwrap();
+    assert_eq!(messages.len(), 3);
+    assert_eq!(messages[0].content, "second draft");
+
+    // Another file with the same title is a different conversation
+    let other = second_dir.join("notes.txt");
+    fs::write(&other, "User: unrelated\n").unwrap();
+    processor.process_file(&other).await.unwrap();
+    let (_, total) = repo.find_with_filters(None, None, 10, 0).await.unwrap();
+    assert_eq!(total, 2);
+    assert_eq!(repo.get_conversation_messages(id).await.unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn test_deferred_fts_import_is_searchable_after_rebuild() {
+    let temp_dir = TempDir::new().unwrap();
+    let export_file = temp_dir.path().join("bulk.txt");
+    let transcript: String = (0..200)
+        .map(|i| {
+            format!(
+                "User: bulk question {} about quokkas\nAssistant: answer {}\n",
+                i, i
+            )
+        })
+        .collect();
+    fs::write(&export_file, transcript).unwrap();
+
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let chroma = Arc::new(ChromaClient::new("http://localhost:1".to_string()));
+    let embedding = Arc::new(EmbeddingService::new(
+        "http://localhost:1".to_string(),
+        "http://localhost:1".to_string(),
+    ));
+    let repo = Arc::new(SeaOrmConversationRepository::new(db, chroma, embedding));
+    let processor = ImportProcessor::new(repo.clone()).with_deferred_fts(true);
+    processor.process_file(&export_file).await.unwrap();
+
+    assert_eq!(
+        repo.full_text_search_count("quokkas", None, None, None)
+            .await
+            .unwrap(),
+        200
+    );
+
+    // Per-message indexing is back on for later writes
+    let (conversations, _) = repo.find_with_filters(None, None, 10, 0).await.unwrap();
+    repo.append_messages(
+        conversations[0].id,
+        vec![sekha_controller::models::internal::NewMessage {
+            role: "user".to_string(),
+            content: "one more about wombats".to_string(),
+            metadata: serde_json::json!({}),
+            timestamp: chrono::Utc::now().naive_utc(),
+        }],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        repo.full_text_search("wombats", 10, None, None, None)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn test_processor_imports_extensionless_chatgpt_json() {
+    let temp_dir = TempDir::new().unwrap();
+    let export_file = temp_dir.path().join("import").join("conversations");
+    fs::create_dir_all(export_file.parent().unwrap()).unwrap();
+    fs::write(&export_file, create_chatgpt_single_export()).unwrap();
+
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let chroma = Arc::new(ChromaClient::new("http://localhost:1".to_string()));
+    let embedding = Arc::new(EmbeddingService::new(
+        "http://localhost:1".to_string(),
+        "http://localhost:1".to_string(),
+    ));
+    let repo = Arc::new(SeaOrmConversationRepository::new(db, chroma, embedding));
+    ImportProcessor::new(repo.clone())
+        .process_file(&export_file)
+        .await
+        .unwrap();
+
+    let (conversations, total) = repo.find_with_filters(None, None, 10, 0).await.unwrap();
+    assert_eq!(total, 1);
+    assert_eq!(conversations[0].label, "ChatGPT Single Test");
+    assert!(!export_file.exists(), "File should be moved once imported");
+}
+
+/// Processor over an in-memory repo whose vector services are unreachable,
+/// deriving labels through the bridge at `llm_bridge_url`
+async fn labeling_processor(llm_bridge_url: &str) -> ImportProcessor {
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let chroma = Arc::new(ChromaClient::new("http://localhost:1".to_string()));
+    let embedding = Arc::new(EmbeddingService::new(
+        "http://localhost:1".to_string(),
+        "http://localhost:1".to_string(),
+    ));
+    let repo = Arc::new(SeaOrmConversationRepository::new(db, chroma, embedding));
+    ImportProcessor::new(repo)
+        .with_label_derivation(Arc::new(LlmBridgeClient::new(llm_bridge_url.to_string())))
+}
+
+const TITLELESS_TXT: &str = "User: How do I rotate the TLS certificates on the staging cluster?\n\
+Assistant: Renew them with cert-manager and restart the ingress pods.\n";
+
+#[tokio::test]
+async fn test_import_derives_label_for_titleless_file() {
+    if !is_llm_bridge_running().await {
+        eprintln!(
+            "⚠️  Skipping test_import_derives_label_for_titleless_file - LLM bridge not running"
+        );
+        return;
+    }
+
+    let temp_dir = TempDir::new().unwrap();
+    let import_file = temp_dir.path().join("test.txt");
+    fs::write(&import_file, TITLELESS_TXT).unwrap();
+
+    let processor = labeling_processor("http://localhost:11434").await;
+    processor.process_file(&import_file).await.unwrap();
+
+    let (conversations, total) = processor
+        .repo()
+        .find_with_filters(None, None, 10, 0)
+        .await
+        .unwrap();
+    assert_eq!(total, 1);
+    let label = &conversations[0].label;
+    assert!(!label.is_empty());
+    assert_ne!(label, "test", "label should be derived, not the filename");
+}
+
+#[tokio::test]
+async fn test_import_label_derivation_falls_back_to_filename() {
+    let temp_dir = TempDir::new().unwrap();
+    let import_file = temp_dir.path().join("test.txt");
+    fs::write(&import_file, TITLELESS_TXT).unwrap();
+
+    // Nothing listens here, so derivation fails and the filename is kept
+    let processor = labeling_processor("http://localhost:1").await;
+    processor.process_file(&import_file).await.unwrap();
+
+    let (conversations, _) = processor
+        .repo()
+        .find_with_filters(None, None, 10, 0)
+        .await
+        .unwrap();
+    assert_eq!(conversations[0].label, "test");
+}
+
 // Test data helper
 fn create_chatgpt_single_export() -> String {
     r#"{
@@ -530,6 +1013,9 @@ async fn test_watch_ignores_non_supported_files() {
     let docx_file = watch_path.join("test.docx");
     fs::write(&pdf_file, "fake pdf").unwrap();
     fs::write(&docx_file, "fake docx").unwrap();
+    // Directories have no extension but aren't import candidates either
+    let subdir = watch_path.join("archive");
+    fs::create_dir(&subdir).unwrap();
 
     tokio::time::sleep(tokio::time::Duration::from_millis(600)).await;
 
@@ -538,6 +1024,7 @@ async fn test_watch_ignores_non_supported_files() {
     // Files should still exist (ignored by watcher)
     assert!(pdf_file.exists(), "PDF should be ignored");
     assert!(docx_file.exists(), "DOCX should be ignored");
+    assert!(subdir.is_dir(), "Directory should be ignored");
 }
 
 #[tokio::test]
@@ -607,7 +1094,7 @@ async fn test_ensure_directories_creates_nested_paths() {
     // Verify directories were created
     assert!(watch_path.exists(), "Watch path should be created");
     assert!(
-        watch_path.parent().unwrap().join("imported").exists(),
+        watch_path.join("imported").exists(),
         "Imported directory should be created"
     );
 
@@ -622,7 +1109,7 @@ async fn test_ensure_directories_handles_existing_paths() {
 
     // Pre-create directories
     fs::create_dir_all(&watch_path).unwrap();
-    fs::create_dir_all(watch_path.parent().unwrap().join("imported")).unwrap();
+    fs::create_dir_all(watch_path.join("imported")).unwrap();
 
     let db = init_db("sqlite::memory:").await.unwrap();
     let (chroma_client, embedding_service) = create_test_services();
@@ -702,3 +1189,159 @@ async fn test_process_existing_files_handles_read_errors() {
     #[cfg(unix)]
     assert!(bad_file.exists(), "Unreadable file should remain");
 }
+
+#[tokio::test]
+async fn test_imported_image_reference_is_retrievable_as_attachment() {
+    use axum::{body::Body, http::Request};
+    use tower::ServiceExt;
+
+    let temp_dir = TempDir::new().unwrap();
+    let export_file = temp_dir.path().join("export.json");
+    let chatgpt_json = r#"{
+        "title": "Chart Question",
+        "create_time": 1703073600.0,
+        "mapping": {
+            "root": {"id": "root", "message": null, "parent": null, "children": ["q"]},
+            "q": {
+                "id": "q",
+                "message": {
+                    "id": "q",
+                    "author": {"role": "user"},
+                    "create_time": 1703073600.0,
+                    "content": {
+                        "content_type": "multimodal_text",
+                        "parts": [
+                            {"content_type": "image_asset_pointer", "asset_pointer": "file-service://file-chart", "size_bytes": 48213},
+                            "What does this chart show?"
+                        ]
+                    },
+                    "metadata": {"attachments": [
+                        {"id": "file-chart", "name": "chart.png", "mime_type": "image/png"}
+                    ]}
+                },
+                "parent": "root",
+                "children": []
+            }
+        }
+    }"#;
+    fs::write(&export_file, chatgpt_json).unwrap();
+
+    let state = super::create_test_app_state().await;
+    let repo = state.repo.clone();
+    ImportProcessor::new(repo.clone())
+        .process_file(&export_file)
+        .await
+        .unwrap();
+
+    let (conversations, _) = repo.find_with_filters(None, None, 100, 0).await.unwrap();
+    let messages = repo
+        .get_conversation_messages(conversations[0].id)
+        .await
+        .unwrap();
+    assert_eq!(messages[0].content, "What does this chart show?");
+
+    let app = sekha_controller::api::routes::create_router(state);
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/messages/{}/attachments", messages[0].id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let body = axum::body::to_bytes(response.into_body(), 65536)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(
+        json["attachments"],
+        serde_json::json!([{
+            "filename": "chart.png",
+            "mime": "image/png",
+            "url": "file-service://file-chart"
+        }])
+    );
+}
+
+// ============================================
+// Test: Multiple watched directories
+// ============================================
+
+#[tokio::test]
+async fn test_watcher_imports_each_directory_into_its_folder() {
+    let temp_dir = TempDir::new().unwrap();
+    let work_path = temp_dir.path().join("work").join("import");
+    let personal_path = temp_dir.path().join("personal").join("import");
+    fs::create_dir_all(&work_path).unwrap();
+    fs::create_dir_all(&personal_path).unwrap();
+
+    let work_file = work_path.join("work.json");
+    let personal_file = personal_path.join("personal.json");
+    fs::write(&work_file, create_chatgpt_single_export()).unwrap();
+    fs::write(&personal_file, create_chatgpt_single_export()).unwrap();
+
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let chroma = Arc::new(ChromaClient::new("http://localhost:1".to_string()));
+    let embedding = Arc::new(EmbeddingService::new(
+        "http://localhost:1".to_string(),
+        "http://localhost:1".to_string(),
+    ));
+    let repo = Arc::new(SeaOrmConversationRepository::new(db, chroma, embedding));
+
+    // The work directory maps to a folder, normalized like API input; the
+    // personal one keeps the per-source default
+    let watcher = ImportWatcher::with_directories(
+        repo.clone(),
+        vec![(work_path.clone(), Some(" /work//chats/ ".to_string()))],
+    )
+    .with_directory(personal_path.clone(), None);
+    assert_eq!(watcher.directories().len(), 2);
+
+    watcher.import_existing_files().await.unwrap();
+
+    let work = repo.find_by_folder("/work/chats", 10, 0).await.unwrap();
+    assert_eq!(work.len(), 1);
+    assert_eq!(work[0].label, "ChatGPT Single Test");
+
+    let personal = repo
+        .find_by_folder("/imports/chatgpt", 10, 0)
+        .await
+        .unwrap();
+    assert_eq!(personal.len(), 1);
+    assert_ne!(work[0].id, personal[0].id);
+
+    // Both files moved out of their directories once imported
+    assert!(!work_file.exists());
+    assert!(!personal_file.exists());
+    assert!(work_path.join("imported").exists());
+    assert!(personal_path.join("imported").exists());
+}
+
+#[tokio::test]
+async fn test_processor_publishes_created_event_per_import() {
+    let temp_dir = TempDir::new().unwrap();
+    let watch_path = temp_dir.path().join("import");
+    fs::create_dir_all(&watch_path).unwrap();
+    let file = watch_path.join("export.json");
+    fs::write(&file, create_chatgpt_single_export()).unwrap();
+
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let (chroma, embedding) = create_test_services();
+    let repo = Arc::new(SeaOrmConversationRepository::new(db, chroma, embedding));
+    let events = sekha_controller::services::EventBus::default();
+    let mut received = events.subscribe();
+
+    let processor = ImportProcessor::new(repo.clone()).with_events(events);
+    processor.process_file(&file).await.unwrap();
+
+    // Search caches listening on the bus drop results that predate the import
+    let event = received.try_recv().unwrap();
+    assert_eq!(event.kind(), "created");
+    let imported = repo
+        .find_by_label("ChatGPT Single Test", 10, 0)
+        .await
+        .unwrap();
+    assert_eq!(event.conversation_id(), imported[0].id);
+}
@@ -12,7 +12,7 @@ use axum::Router;
 use sekha_controller::ConversationRepository;
 use sekha_controller::{
     api::routes::{create_router, AppState},
-    config::Config,
+    config::{Config, SearchFallback},
     models::internal::{NewConversation, NewMessage},
     services::{embedding_service::EmbeddingService, llm_bridge_client::LlmBridgeClient},
     storage::{chroma_client::ChromaClient, init_db, SeaOrmConversationRepository},
@@ -23,6 +23,8 @@ use tokio::sync::RwLock;
 // Public modules (test files)
 // ============================================
 pub mod api;
+#[cfg(feature = "client")]
+pub mod client;
 pub mod concurrency;
 pub mod file_watcher;
 pub mod mcp;
@@ -83,12 +85,62 @@ pub async fn create_test_config() -> Arc<RwLock<Config>> {
         log_level: "info".to_string(),
         summarization_enabled: true,
         pruning_enabled: true,
+        import_directories: vec![],
+        search_max_age_days: None,
+        rescore_importance_on_append: false,
+        importance_rescore_debounce_secs: 60,
+        rate_limit_warn_percent: 80,
+        import_defer_fts: false,
+        id_scheme: Default::default(),
+        embedding_timeout_secs: 60,
+        log_bodies: false,
+        log_body_max_bytes: 4096,
+        log_redact_fields: vec![],
+        compact_on_archive: false,
+        search_exclude_folders: vec![],
+        search_cache_ttl_secs: 30,
+        search_cache_capacity: 256,
+        default_folder: "/inbox".to_string(),
+        prune_by_last_access: false,
+        fts_ranking: Default::default(),
+        llm_max_concurrency: 8,
+        min_embed_length: 0,
+        import_max_concurrency: 4,
+        redaction_patterns: Vec::new(),
+        embedding_models: Default::default(),
+        fts_stop_words: Vec::new(),
+        read_database_url: None,
+        import_derive_labels: false,
+        embeddings_enabled: true,
+        max_messages_per_conversation: None,
+        retention_policies: Default::default(),
+        import_mode: Default::default(),
+        default_search_limit: 10,
+        max_search_limit: 100,
+        summarize_after_messages: None,
+        request_timeout_secs: 30,
+        offload_dir: std::env::temp_dir()
+            .join("sekha-test-offload")
+            .to_string_lossy()
+            .into_owned(),
+        role_weights: Default::default(),
+        search_fallback: SearchFallback::Fts,
+        session_gap_minutes: 30,
+        chroma_upsert_batch_size: 32,
+        admin_api_key: None,
+        embedding_strip_code_blocks: false,
+        embedding_collapse_whitespace: false,
+        embedding_max_tokens: None,
         embedding_model: "nomic-embed-text:latest".to_string(),
         summarization_model: "llama3.1:8b".to_string(),
     }))
 }
 
 pub async fn create_test_app() -> Router {
+    create_router(create_test_app_state().await)
+}
+
+pub async fn create_test_app_state() -> AppState {
     let db = init_db("sqlite::memory:").await.unwrap();
     let (chroma_client, embedding_service) = create_test_services();
     let repo = Arc::new(SeaOrmConversationRepository::new(
@@ -99,17 +151,21 @@ pub async fn create_test_app() -> Router {
 
     let llm_bridge = Arc::new(LlmBridgeClient::new("http://localhost:11434".to_string()));
 
-    let state = AppState {
+    AppState {
         config: create_test_config().await,
         repo: repo.clone(),
         chroma_client,
+        events: sekha_controller::services::EventBus::default(),
+        search_cache: Default::default(),
+        tasks: Default::default(),
         embedding_service,
+        embedding_queue: Arc::new(
+            sekha_controller::services::embedding_queue::EmbeddingQueue::new(),
+        ),
         orchestrator: Arc::new(sekha_controller::orchestrator::MemoryOrchestrator::new(
             repo, llm_bridge,
         )),
-    };
-
-    create_router(state)
+    }
 }
 
 pub async fn create_test_mcp_app() -> Router {
@@ -127,7 +183,13 @@ pub async fn create_test_mcp_app() -> Router {
         config: create_test_config().await,
         repo: repo.clone(),
         chroma_client,
+        events: sekha_controller::services::EventBus::default(),
+        search_cache: Default::default(),
+        tasks: Default::default(),
         embedding_service,
+        embedding_queue: Arc::new(
+            sekha_controller::services::embedding_queue::EmbeddingQueue::new(),
+        ),
         orchestrator: Arc::new(sekha_controller::orchestrator::MemoryOrchestrator::new(
             repo, llm_bridge,
         )),
@@ -1,9 +1,22 @@
-use super::{create_test_app, is_llm_bridge_running, Uuid};
+use super::{
+    create_test_app, create_test_config, create_test_conversation, is_llm_bridge_running, Arc,
+    ConversationRepository, Uuid,
+};
 use axum::{
     body::Body,
     http::{Request, StatusCode},
 };
+use sekha_controller::api::routes::{create_router, AppState};
+use sekha_controller::orchestrator::MemoryOrchestrator;
+use sekha_controller::services::{EmbeddingService, LlmBridgeClient, MockProvider, SearchCache};
+use sekha_controller::storage::{
+    chroma_client::ChromaClient, init_db, repository::CreatedConversation,
+    SeaOrmConversationRepository,
+};
+use serde_json::json;
 use tower::ServiceExt;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
 
 // ============================================
 // Memory Orchestration Tests
@@ -523,6 +536,92 @@ async fn test_orchestrator_context_assembly_large_budget() {
     assert!(json.is_array());
 }
 
+#[tokio::test]
+async fn test_context_assembly_verbose_envelope_explains_empty_store() {
+    let app = create_test_app().await;
+
+    let assemble = |verbose: bool| {
+        Request::builder()
+            .method("POST")
+            .uri("/api/v1/context/assemble")
+            .header("Content-Type", "application/json")
+            .body(Body::from(
+                json!({
+                    "query": "anything at all",
+                    "preferred_labels": [],
+                    "context_budget": 4000,
+                    "verbose": verbose
+                })
+                .to_string(),
+            ))
+            .unwrap()
+    };
+
+    let response = app.clone().oneshot(assemble(true)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), 65536)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["messages"], json!([]));
+    assert_eq!(json["budget_used"], 0);
+    assert_eq!(json["candidates_considered"], 0);
+    assert!(json["note"]
+        .as_str()
+        .unwrap()
+        .contains("no stored messages"));
+
+    // Without the flag the bare array is kept
+    let response = app.oneshot(assemble(false)).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), 65536)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json, json!([]));
+}
+
+#[tokio::test]
+async fn test_context_assembly_prompt_format_renders_role_prefixed_messages() {
+    let state = super::create_test_app_state().await;
+    state
+        .repo
+        .create_with_message_ids(create_test_conversation())
+        .await
+        .unwrap();
+    let app = create_router(state);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/context/assemble")
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    json!({
+                        "query": "unrelated words",
+                        "preferred_labels": ["Test Conversation"],
+                        "context_budget": 4000,
+                        "format": "prompt"
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), 65536)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    let prompt = json["prompt"].as_str().unwrap();
+    assert!(prompt.contains("User: Hello, this is a test message"));
+    assert!(prompt.contains("Assistant: This is a response to the test"));
+    assert_eq!(json["message_count"], 2);
+    assert_eq!(json["estimated_tokens"], prompt.len() / 4);
+}
+
 #[tokio::test]
 async fn test_orchestrator_pruning_with_different_thresholds() {
     let app = create_test_app().await;
@@ -573,11 +672,12 @@ async fn test_orchestrator_error_handling_nonexistent_conversation() {
         .await
         .unwrap();
 
-    // Should return error (500 or 404 depending on implementation)
-    assert!(
-        summary_response.status() == StatusCode::INTERNAL_SERVER_ERROR
-            || summary_response.status() == StatusCode::NOT_FOUND
-    );
+    assert_eq!(summary_response.status(), StatusCode::NOT_FOUND);
+    let body = axum::body::to_bytes(summary_response.into_body(), 1024)
+        .await
+        .unwrap();
+    let error: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(error["code"], 404);
 
     // Test label suggest with nonexistent conversation
     let label_response = app
@@ -596,8 +696,356 @@ async fn test_orchestrator_error_handling_nonexistent_conversation() {
         .await
         .unwrap();
 
-    assert!(
-        label_response.status() == StatusCode::INTERNAL_SERVER_ERROR
-            || label_response.status() == StatusCode::NOT_FOUND
-    );
+    assert_eq!(label_response.status(), StatusCode::NOT_FOUND);
+    let body = axum::body::to_bytes(label_response.into_body(), 1024)
+        .await
+        .unwrap();
+    let error: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(error["code"], 404);
+}
+
+const CHROMA_COLLECTIONS: &str =
+    "/api/v2/tenants/default_tenant/databases/default_database/collections";
+
+#[tokio::test]
+async fn test_query_rerank_reorders_results() {
+    let chroma = MockServer::start().await;
+    let bridge = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/v2/heartbeat"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&chroma)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(format!("{}/conversations", CHROMA_COLLECTIONS)))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({"id": "col-1", "name": "conversations"})),
+        )
+        .mount(&chroma)
+        .await;
+    Mock::given(method("POST"))
+        .and(path(format!("{}/col-1/upsert", CHROMA_COLLECTIONS)))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&chroma)
+        .await;
+
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let chroma_client = Arc::new(ChromaClient::new(chroma.uri()));
+    let embedding_service = Arc::new(EmbeddingService::with_provider(
+        Arc::new(MockProvider::new_success(vec![0.1; 768])),
+        chroma.uri(),
+    ));
+    let repo = Arc::new(SeaOrmConversationRepository::new(
+        db,
+        chroma_client.clone(),
+        embedding_service.clone(),
+    ));
+
+    let CreatedConversation { message_ids, .. } = repo
+        .create_with_message_ids(create_test_conversation())
+        .await
+        .unwrap();
+
+    // Chroma ranks the first message highest, the LLM prefers the second
+    Mock::given(method("POST"))
+        .and(path(format!("{}/col-1/query", CHROMA_COLLECTIONS)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "ids": [[message_ids[0].to_string(), message_ids[1].to_string()]],
+            "distances": [[0.1, 0.2]],
+            "metadatas": [[
+                {"message_id": message_ids[0].to_string()},
+                {"message_id": message_ids[1].to_string()},
+            ]],
+        })))
+        .mount(&chroma)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/rerank"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "ranking": [1, 0],
+            "model": "llama3.1:8b"
+        })))
+        .mount(&bridge)
+        .await;
+
+    let llm_bridge = Arc::new(LlmBridgeClient::new(bridge.uri()));
+    let app = create_router(AppState {
+        config: create_test_config().await,
+        repo: repo.clone(),
+        chroma_client,
+        events: sekha_controller::services::EventBus::default(),
+        search_cache: Default::default(),
+        tasks: Default::default(),
+        embedding_service,
+        embedding_queue: Arc::new(
+            sekha_controller::services::embedding_queue::EmbeddingQueue::new(),
+        ),
+        orchestrator: Arc::new(MemoryOrchestrator::new(repo, llm_bridge)),
+    });
+
+    let mut orders = Vec::new();
+    for rerank in [false, true] {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/v1/query")
+                    .header("Content-Type", "application/json")
+                    .body(Body::from(
+                        json!({ "query": "test message", "limit": 2, "rerank": rerank })
+                            .to_string(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), 65536)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        orders.push(json["results"].as_array().unwrap().clone());
+    }
+
+    let ids = |results: &[serde_json::Value]| -> Vec<Uuid> {
+        results
+            .iter()
+            .map(|r| r["message_id"].as_str().unwrap().parse().unwrap())
+            .collect()
+    };
+    assert_eq!(ids(&orders[0]), message_ids);
+    assert!(orders[0].iter().all(|r| r.get("llm_rank").is_none()));
+
+    assert_eq!(ids(&orders[1]), vec![message_ids[1], message_ids[0]]);
+    assert_eq!(orders[1][0]["llm_rank"], 1);
+    assert_eq!(orders[1][1]["llm_rank"], 2);
+}
+
+#[tokio::test]
+async fn test_repeated_query_is_served_from_search_cache() {
+    let chroma = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v2/heartbeat"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&chroma)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(format!("{}/conversations", CHROMA_COLLECTIONS)))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({"id": "col-1", "name": "conversations"})),
+        )
+        .mount(&chroma)
+        .await;
+    Mock::given(method("POST"))
+        .and(path(format!("{}/col-1/upsert", CHROMA_COLLECTIONS)))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&chroma)
+        .await;
+
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let chroma_client = Arc::new(ChromaClient::new(chroma.uri()));
+    let embedder = MockProvider::new_success(vec![0.1; 768]);
+    let embed_calls = embedder.call_count.clone();
+    let embedding_service = Arc::new(EmbeddingService::with_provider(
+        Arc::new(embedder),
+        chroma.uri(),
+    ));
+    let repo = Arc::new(SeaOrmConversationRepository::new(
+        db,
+        chroma_client.clone(),
+        embedding_service.clone(),
+    ));
+
+    let CreatedConversation { message_ids, .. } = repo
+        .create_with_message_ids(create_test_conversation())
+        .await
+        .unwrap();
+    Mock::given(method("POST"))
+        .and(path(format!("{}/col-1/query", CHROMA_COLLECTIONS)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "ids": [[message_ids[0].to_string()]],
+            "distances": [[0.1]],
+            "metadatas": [[{"message_id": message_ids[0].to_string()}]],
+        })))
+        .mount(&chroma)
+        .await;
+
+    let events = sekha_controller::services::EventBus::default();
+    let state = AppState {
+        config: create_test_config().await,
+        repo: repo.clone(),
+        chroma_client,
+        events: events.clone(),
+        search_cache: Arc::new(SearchCache::new(
+            std::time::Duration::from_secs(60),
+            16,
+            &events,
+        )),
+        tasks: Default::default(),
+        embedding_service,
+        embedding_queue: Arc::new(
+            sekha_controller::services::embedding_queue::EmbeddingQueue::new(),
+        ),
+        orchestrator: Arc::new(MemoryOrchestrator::new(
+            repo,
+            Arc::new(LlmBridgeClient::new("http://localhost:1".to_string())),
+        )),
+    };
+    let app = create_router(state.clone());
+
+    let query = || async {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/v1/query")
+                    .header("Content-Type", "application/json")
+                    .body(Body::from(
+                        json!({ "query": "test message", "limit": 1 }).to_string(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), 65536)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        json["results"][0]["message_id"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    };
+    let embeds = || *embed_calls.lock().unwrap();
+
+    let before = embeds();
+    let first = query().await;
+    assert_eq!(embeds(), before + 1);
+    let second = query().await;
+    assert_eq!(second, first);
+    assert_eq!(embeds(), before + 1, "second query should not re-embed");
+
+    let metrics = sekha_controller::api::routes::create_ops_router(state)
+        .oneshot(
+            Request::builder()
+                .uri("/metrics")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = axum::body::to_bytes(metrics.into_body(), 65536)
+        .await
+        .unwrap();
+    let metrics = String::from_utf8(body.to_vec()).unwrap();
+    assert!(metrics.contains("sekha_search_cache_hits_total 1\n"));
+    assert!(metrics.contains("sekha_search_cache_misses_total 1\n"));
+
+    // Any conversation change drops the cached results
+    events.publish(sekha_controller::services::MemoryEvent::Updated {
+        conversation_id: message_ids[0],
+    });
+    query().await;
+    assert_eq!(embeds(), before + 2);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_summarize_burst_queues_llm_calls_and_health_stays_responsive() {
+    let bridge = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/summarize"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({
+                    "summary": "Daily recap",
+                    "level": "daily",
+                    "model": "llama3.1:8b",
+                    "tokens_used": 12
+                }))
+                .set_delay(std::time::Duration::from_millis(200)),
+        )
+        .mount(&bridge)
+        .await;
+
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let chroma_client = Arc::new(ChromaClient::new("http://localhost:1".to_string()));
+    let embedding_service = Arc::new(EmbeddingService::with_provider(
+        Arc::new(MockProvider::new_success(vec![0.1; 768])),
+        "http://localhost:1".to_string(),
+    ));
+    let repo = Arc::new(SeaOrmConversationRepository::new(
+        db,
+        chroma_client.clone(),
+        embedding_service.clone(),
+    ));
+    let conv_id = repo
+        .create_with_messages(create_test_conversation())
+        .await
+        .unwrap();
+
+    let llm_bridge = Arc::new(LlmBridgeClient::new(bridge.uri()).with_max_concurrency(2));
+    let app = create_router(AppState {
+        config: create_test_config().await,
+        repo: repo.clone(),
+        chroma_client,
+        events: sekha_controller::services::EventBus::default(),
+        search_cache: Default::default(),
+        tasks: Default::default(),
+        embedding_service,
+        embedding_queue: Arc::new(
+            sekha_controller::services::embedding_queue::EmbeddingQueue::new(),
+        ),
+        orchestrator: Arc::new(MemoryOrchestrator::new(repo, llm_bridge)),
+    });
+
+    let started = std::time::Instant::now();
+    let mut handles = Vec::new();
+    for _ in 0..8 {
+        let app = app.clone();
+        handles.push(tokio::spawn(async move {
+            app.oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/v1/summarize")
+                    .header("Content-Type", "application/json")
+                    .body(Body::from(
+                        json!({ "conversation_id": conv_id, "level": "daily" }).to_string(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+            .status()
+        }));
+    }
+
+    // While the burst is queued on the bridge, health still answers promptly
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    let health_started = std::time::Instant::now();
+    app.clone()
+        .oneshot(
+            Request::builder()
+                .uri("/health")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert!(health_started.elapsed() < std::time::Duration::from_millis(500));
+    assert!(handles.iter().any(|h| !h.is_finished()));
+
+    for handle in handles {
+        assert_eq!(handle.await.unwrap(), StatusCode::OK);
+    }
+
+    // 8 calls of 200ms, two at a time, take at least four rounds
+    assert!(started.elapsed() >= std::time::Duration::from_millis(750));
+    assert_eq!(bridge.received_requests().await.unwrap().len(), 8);
 }
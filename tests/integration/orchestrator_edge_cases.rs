@@ -254,3 +254,59 @@ async fn test_metadata_enhancement() {
     // If we get here, no citation was found
     // This might be OK if no messages were returned
 }
+
+/// Context-pinned conversations are included whatever the query
+#[tokio::test]
+async fn test_context_pinned_conversation_always_assembled() {
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let (chroma_client, embedding_service) = create_test_services();
+    let repo = Arc::new(SeaOrmConversationRepository::new(
+        db,
+        chroma_client,
+        embedding_service,
+    ));
+
+    let mut guidelines = create_test_conversation();
+    guidelines.label = "Project Guidelines".to_string();
+    guidelines.messages[0].content = "Always run clippy before committing".to_string();
+    let guidelines_id = repo.create_with_messages(guidelines).await.unwrap();
+
+    let mut other = create_test_conversation();
+    other.label = "Unrelated".to_string();
+    repo.create_with_messages(other).await.unwrap();
+
+    let assembler = ContextAssembler::new(repo.clone());
+    let query = "favourite pasta recipes";
+
+    let before = assembler
+        .assemble(query, vec![], 4000, vec![])
+        .await
+        .unwrap();
+    assert!(before.iter().all(|m| m.conversation_id != guidelines_id));
+
+    repo.set_context_pinned(guidelines_id, true).await.unwrap();
+    assert!(
+        repo.find_by_id(guidelines_id)
+            .await
+            .unwrap()
+            .unwrap()
+            .context_pinned
+    );
+
+    let after = assembler
+        .assemble(query, vec![], 4000, vec![])
+        .await
+        .unwrap();
+    assert!(!after.is_empty());
+    assert_eq!(after[0].conversation_id, guidelines_id);
+    assert!(after
+        .iter()
+        .any(|m| m.content == "Always run clippy before committing"));
+
+    // Excluded folders still win over the pin
+    let excluded = assembler
+        .assemble(query, vec![], 4000, vec!["/".to_string()])
+        .await
+        .unwrap();
+    assert!(excluded.iter().all(|m| m.conversation_id != guidelines_id));
+}
@@ -7,9 +7,21 @@ use super::{
     ConversationRepository, // ✅ Import trait
 };
 use sekha_controller::{
-    models::internal::NewMessage, // ✅ Import NewMessage
-    storage::{init_db, SeaOrmConversationRepository},
+    config::{FtsRanking, IdScheme, SearchFallback},
+    models::{internal::NewMessage, redaction::Redactor}, // ✅ Import NewMessage
+    services::{EmbeddingService, MockProvider},
+    storage::{
+        chroma_client::ChromaClient,
+        db::init_read_db,
+        init_db,
+        repository::{
+            apply_role_weights, fts_match_query, fts_match_query_filtered, CreatedConversation,
+            RepositoryError, SearchResult, SearchSource,
+        },
+        SeaOrmConversationRepository,
+    },
 };
+use std::collections::HashMap;
 use uuid::Uuid;
 
 // ============================================
@@ -32,6 +44,317 @@ async fn test_repository_create_with_messages() {
     );
 }
 
+#[tokio::test]
+async fn test_repository_stores_quotes_and_sql_verbatim() {
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let (chroma_client, embedding_service) = create_test_services();
+    let repo = SeaOrmConversationRepository::new(db, chroma_client, embedding_service);
+
+    let hostile = r#"O'Brien said "hi"; DROP TABLE messages;--"#;
+    let mut conv = create_test_conversation();
+    conv.label = hostile.to_string();
+    conv.folder = "/it's/mine".to_string();
+    conv.messages = vec![NewMessage {
+        role: "assistant".to_string(),
+        content: hostile.to_string(),
+        metadata: json!({ "note": hostile }),
+        timestamp: chrono::Utc::now().naive_utc(),
+    }];
+    let conv_id = repo.create_with_messages(conv).await.unwrap();
+
+    let appended = repo
+        .create_message(
+            conv_id,
+            NewMessage {
+                role: "user".to_string(),
+                content: "it's fine".to_string(),
+                metadata: json!({}),
+                timestamp: chrono::Utc::now().naive_utc(),
+            },
+        )
+        .await
+        .unwrap();
+
+    let stored = repo.find_by_id(conv_id).await.unwrap().unwrap();
+    assert_eq!(stored.label, hostile);
+    assert_eq!(stored.folder, "/it's/mine");
+
+    let messages = repo.get_conversation_messages(conv_id).await.unwrap();
+    assert_eq!(messages.len(), 2);
+    let first = messages.iter().find(|m| m.id != appended).unwrap();
+    assert_eq!(first.content, hostile);
+    assert_eq!(first.metadata, Some(json!({ "note": hostile })));
+    assert_eq!(
+        messages.iter().find(|m| m.id == appended).unwrap().content,
+        "it's fine"
+    );
+
+    // The text reached the full-text index intact too
+    let hits = repo
+        .full_text_search("Brien", 10, None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].content, hostile);
+}
+
+#[tokio::test]
+async fn test_create_with_messages_rolls_back_when_a_message_fails() {
+    use sea_orm::ConnectionTrait;
+
+    let db = init_db("sqlite::memory:").await.unwrap();
+    // Reject one message mid-batch, as a constraint violation would
+    db.execute_unprepared(
+        "CREATE TRIGGER reject_poison_message BEFORE INSERT ON messages \
+         WHEN NEW.content = 'poison' BEGIN SELECT RAISE(ABORT, 'poison message'); END",
+    )
+    .await
+    .unwrap();
+    let (chroma_client, embedding_service) = create_test_services();
+    let repo = SeaOrmConversationRepository::new(db, chroma_client, embedding_service);
+
+    let conv_id = Uuid::new_v4();
+    let mut conv = create_test_conversation();
+    conv.id = Some(conv_id);
+    conv.messages = ["first", "second", "poison", "fourth"]
+        .iter()
+        .map(|content| NewMessage {
+            role: "user".to_string(),
+            content: content.to_string(),
+            metadata: json!({}),
+            timestamp: chrono::Utc::now().naive_utc(),
+        })
+        .collect();
+
+    assert!(repo.create_with_messages(conv).await.is_err());
+
+    // Neither the conversation nor the messages before the failure remain
+    assert!(repo.find_by_id(conv_id).await.unwrap().is_none());
+    assert!(repo
+        .get_conversation_messages(conv_id)
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(repo
+        .full_text_search("first", 10, None, None, None)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_create_with_tags_rolls_back_when_a_tag_fails() {
+    use sea_orm::ConnectionTrait;
+
+    let db = init_db("sqlite::memory:").await.unwrap();
+    db.execute_unprepared(
+        "CREATE TRIGGER reject_poison_tag BEFORE INSERT ON semantic_tags \
+         WHEN NEW.tag = 'poison' BEGIN SELECT RAISE(ABORT, 'poison tag'); END",
+    )
+    .await
+    .unwrap();
+    let (chroma_client, embedding_service) = create_test_services();
+    let repo = SeaOrmConversationRepository::new(db, chroma_client, embedding_service);
+
+    let conv_id = Uuid::new_v4();
+    let mut conv = create_test_conversation();
+    conv.id = Some(conv_id);
+    assert!(repo
+        .create_with_tags(conv, vec!["standup".to_string(), "poison".to_string()])
+        .await
+        .is_err());
+    assert!(repo.find_by_id(conv_id).await.unwrap().is_none());
+    assert!(repo
+        .get_conversation_messages(conv_id)
+        .await
+        .unwrap()
+        .is_empty());
+
+    let mut conv = create_test_conversation();
+    conv.id = Some(conv_id);
+    let CreatedConversation {
+        id, message_ids, ..
+    } = repo
+        .create_with_tags(conv, vec!["standup".to_string()])
+        .await
+        .unwrap();
+    assert_eq!(id, conv_id);
+    assert_eq!(message_ids.len(), 2);
+    let tags: Vec<String> = repo
+        .find_tags(id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|t| t.tag)
+        .collect();
+    assert_eq!(tags, vec!["standup"]);
+}
+
+#[tokio::test]
+async fn test_move_message_reports_the_missing_conversation() {
+    use sea_orm::ConnectionTrait;
+
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let (chroma_client, embedding_service) = create_test_services();
+    let repo = SeaOrmConversationRepository::new(db.clone(), chroma_client, embedding_service);
+
+    let CreatedConversation {
+        id: source_id,
+        message_ids,
+        ..
+    } = repo
+        .create_with_message_ids(create_test_conversation())
+        .await
+        .unwrap();
+    let missing_target = Uuid::new_v4();
+    match repo.move_message(message_ids[0], missing_target).await {
+        Err(RepositoryError::NotFound(message)) => {
+            assert!(message.contains(&missing_target.to_string()))
+        }
+        other => panic!("expected NotFound, got {:?}", other),
+    }
+
+    // A message orphaned by a delete without cascade names its own
+    // conversation, not the target
+    let CreatedConversation { id: target_id, .. } = repo
+        .create_with_message_ids(create_test_conversation())
+        .await
+        .unwrap();
+    db.execute_unprepared("PRAGMA foreign_keys = OFF")
+        .await
+        .unwrap();
+    db.execute_unprepared(&format!(
+        "DELETE FROM conversations WHERE id = x'{}'",
+        source_id.simple()
+    ))
+    .await
+    .unwrap();
+    match repo.move_message(message_ids[1], target_id).await {
+        Err(RepositoryError::NotFound(message)) => {
+            assert!(message.contains(&source_id.to_string()))
+        }
+        other => panic!("expected NotFound, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_find_updated_before_returns_only_stale_ids() {
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let (chroma_client, embedding_service) = create_test_services();
+    let repo = SeaOrmConversationRepository::new(db, chroma_client, embedding_service);
+
+    let now = chrono::Utc::now().naive_utc();
+    let mut ids = Vec::new();
+    for updated_at in [now - chrono::Duration::days(90), now] {
+        let mut conv = create_test_conversation();
+        conv.created_at = updated_at;
+        conv.updated_at = updated_at;
+        ids.push(repo.create_with_messages(conv).await.unwrap());
+    }
+
+    let mut requested = ids.clone();
+    requested.push(ids[0]);
+    requested.push(Uuid::new_v4());
+    let stale = repo
+        .find_updated_before(requested, now - chrono::Duration::days(30))
+        .await
+        .unwrap();
+    assert_eq!(stale, vec![ids[0]]);
+    assert!(repo
+        .find_updated_before(Vec::new(), now)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_move_message_clears_the_embedding_when_reembedding_fails() {
+    use sea_orm::ConnectionTrait;
+
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let (chroma_client, embedding_service) = create_test_services();
+    let repo = SeaOrmConversationRepository::new(db.clone(), chroma_client, embedding_service);
+
+    let CreatedConversation { message_ids, .. } = repo
+        .create_with_message_ids(create_test_conversation())
+        .await
+        .unwrap();
+    let CreatedConversation { id: target_id, .. } = repo
+        .create_with_message_ids(create_test_conversation())
+        .await
+        .unwrap();
+    let id = message_ids[0];
+    db.execute_unprepared(&format!(
+        "UPDATE messages SET embedding_id = '{}', embedding_model = 'old' WHERE id = x'{}'",
+        id,
+        id.simple()
+    ))
+    .await
+    .unwrap();
+
+    // No embedding service is reachable, so the vector keeps its old metadata
+    let moved = repo.move_message(id, target_id).await.unwrap();
+    assert_eq!(moved.conversation_id, target_id);
+    assert!(moved.embedding_id.is_none());
+    let stored = repo.find_message_by_id(id).await.unwrap().unwrap();
+    assert!(stored.embedding_id.is_none());
+    assert!(stored.embedding_model.is_none());
+}
+
+#[tokio::test]
+async fn test_updating_a_message_keeps_its_timestamp() {
+    use sea_orm::ConnectionTrait;
+
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let (chroma_client, embedding_service) = create_test_services();
+    let repo = SeaOrmConversationRepository::new(db.clone(), chroma_client, embedding_service);
+
+    let CreatedConversation { message_ids, .. } = repo
+        .create_with_message_ids(create_test_conversation())
+        .await
+        .unwrap();
+    let id = message_ids[0];
+    db.execute_unprepared(&format!(
+        "UPDATE messages SET timestamp = '2020-01-01 00:00:00' WHERE id = x'{}'",
+        id.simple()
+    ))
+    .await
+    .unwrap();
+    let written = repo
+        .find_message_by_id(id)
+        .await
+        .unwrap()
+        .unwrap()
+        .timestamp;
+
+    repo.update_message_metadata(id, json!({"reviewed": true}), true)
+        .await
+        .unwrap();
+    assert_eq!(
+        repo.find_message_by_id(id)
+            .await
+            .unwrap()
+            .unwrap()
+            .timestamp,
+        written
+    );
+
+    db.execute_unprepared(&format!(
+        "UPDATE messages SET embedding_id = 'vec-1' WHERE id = x'{}'",
+        id.simple()
+    ))
+    .await
+    .unwrap();
+    assert_eq!(
+        repo.find_message_by_id(id)
+            .await
+            .unwrap()
+            .unwrap()
+            .timestamp,
+        written
+    );
+}
+
 #[tokio::test]
 #[ignore] // Because Github CI fails this test for some reason even though it passes otherwise
 async fn test_repository_semantic_search() {
@@ -57,6 +380,59 @@ async fn test_repository_semantic_search() {
     assert_eq!(results[0].conversation_id, conv_id);
 }
 
+#[tokio::test]
+async fn test_repository_embeds_into_each_configured_model_collection() {
+    if !is_chroma_running().await {
+        eprintln!("⚠️  Skipping secondary embedding model test - Chroma not running");
+        return;
+    }
+
+    let chroma_url = "http://localhost:8000".to_string();
+    let run = Uuid::new_v4().simple().to_string();
+    let primary_collection = format!("test_primary_{}", run);
+    let secondary_collection = format!("test_secondary_{}", run);
+
+    // Different dimensions, as two real models would have
+    let secondary = EmbeddingService::with_provider(
+        Arc::new(MockProvider::new_success(vec![0.2; 4])),
+        chroma_url.clone(),
+    )
+    .with_collection(secondary_collection.clone());
+    let embedding_service = EmbeddingService::with_provider(
+        Arc::new(MockProvider::new_success(vec![0.1; 8])),
+        chroma_url.clone(),
+    )
+    .with_collection(primary_collection.clone())
+    .with_secondary_model("small", secondary);
+
+    let chroma = Arc::new(ChromaClient::new(chroma_url));
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let repo = SeaOrmConversationRepository::new(db, chroma.clone(), Arc::new(embedding_service));
+
+    let CreatedConversation { message_ids, .. } = repo
+        .create_with_message_ids(create_test_conversation())
+        .await
+        .unwrap();
+
+    for collection in [&primary_collection, &secondary_collection] {
+        let ids = chroma.list_ids(collection).await.unwrap();
+        for message_id in &message_ids {
+            assert!(
+                ids.contains(&message_id.to_string()),
+                "{} should hold a vector for message {}",
+                collection,
+                message_id
+            );
+        }
+    }
+
+    let (results, _) = repo
+        .semantic_search_with_model("test message", 10, None, Some("small".to_string()))
+        .await
+        .unwrap();
+    assert!(!results.is_empty());
+}
+
 #[tokio::test]
 async fn test_repository_delete_cascades() {
     let db = init_db("sqlite::memory:").await.unwrap();
@@ -95,6 +471,77 @@ async fn test_repository_count_by_label() {
     assert_eq!(count, 3);
 }
 
+#[tokio::test]
+async fn test_rename_folder_matches_the_prefix_literally() {
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let (chroma_client, embedding_service) = create_test_services();
+    let repo = SeaOrmConversationRepository::new(db, chroma_client, embedding_service);
+
+    // `_` must match itself and case must match exactly
+    let mut ids = HashMap::new();
+    for folder in ["/work_", "/work_/notes", "/workX/notes", "/Work_/notes"] {
+        let mut conv = create_test_conversation();
+        conv.folder = folder.to_string();
+        ids.insert(folder, repo.create_with_messages(conv).await.unwrap());
+    }
+
+    let mut renamed = repo.rename_folder("/work_/", "/done", false).await.unwrap();
+    renamed.sort();
+    let mut expected = vec![ids["/work_"], ids["/work_/notes"]];
+    expected.sort();
+    assert_eq!(renamed, expected);
+
+    for (folder, now) in [
+        ("/work_", "/done"),
+        ("/work_/notes", "/done/notes"),
+        ("/workX/notes", "/workX/notes"),
+        ("/Work_/notes", "/Work_/notes"),
+    ] {
+        let conv = repo.find_by_id(ids[folder]).await.unwrap().unwrap();
+        assert_eq!(conv.folder, now);
+    }
+
+    assert!(matches!(
+        repo.rename_folder("/", "/everything", false).await,
+        Err(RepositoryError::InvalidInput(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_repository_count_with_filters() {
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let (chroma_client, embedding_service) = create_test_services();
+    let repo = SeaOrmConversationRepository::new(db, chroma_client, embedding_service);
+
+    let mut ids = Vec::new();
+    for folder in ["/a", "/a", "/b"] {
+        let mut conv = create_test_conversation();
+        conv.label = "filtered".to_string();
+        conv.folder = folder.to_string();
+        conv.id = Some(Uuid::new_v4());
+        ids.push(repo.create_with_messages(conv).await.unwrap());
+    }
+    repo.update_status(ids[0], "archived").await.unwrap();
+
+    let count = |label: Option<&str>, folder: Option<&str>, status: Option<&str>| {
+        repo.count_with_filters(
+            label.map(str::to_string),
+            folder.map(str::to_string),
+            status.map(str::to_string),
+        )
+    };
+    assert_eq!(count(Some("filtered"), None, None).await.unwrap(), 3);
+    assert_eq!(count(None, Some("/a"), None).await.unwrap(), 2);
+    assert_eq!(count(None, Some("/a"), Some("active")).await.unwrap(), 1);
+    assert_eq!(
+        count(Some("filtered"), None, Some("archived"))
+            .await
+            .unwrap(),
+        1
+    );
+    assert_eq!(count(None, Some("/b"), Some("archived")).await.unwrap(), 0);
+}
+
 // ============================================
 // Storage Edge Cases
 // ============================================
@@ -219,6 +666,116 @@ async fn test_updated_at_trigger() {
     assert_eq!(updated_conv.label, "Updated Label");
 }
 
+#[tokio::test]
+async fn test_touch_advances_updated_at() {
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let (chroma_client, embedding_service) = create_test_services();
+    let repo = SeaOrmConversationRepository::new(db, chroma_client, embedding_service);
+
+    let conv_id = repo
+        .create_with_messages(create_test_conversation())
+        .await
+        .unwrap();
+    let before = repo.find_by_id(conv_id).await.unwrap().unwrap();
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    repo.touch(conv_id).await.unwrap();
+
+    let after = repo.find_by_id(conv_id).await.unwrap().unwrap();
+    assert!(after.updated_at > before.updated_at);
+    assert_eq!(after.label, before.label);
+    assert_eq!(after.folder, before.folder);
+
+    assert!(repo.touch(Uuid::new_v4()).await.is_err());
+}
+
+#[tokio::test]
+async fn test_created_and_appended_message_ids_are_fetchable() {
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let (chroma_client, embedding_service) = create_test_services();
+    let repo = SeaOrmConversationRepository::new(db, chroma_client, embedding_service);
+
+    let CreatedConversation {
+        id: conv_id,
+        message_ids: created_ids,
+        ..
+    } = repo
+        .create_with_message_ids(create_test_conversation())
+        .await
+        .unwrap();
+    assert_eq!(created_ids.len(), 2);
+
+    let appended_ids = repo
+        .append_messages(
+            conv_id,
+            vec![NewMessage {
+                role: "user".to_string(),
+                content: "A follow-up question".to_string(),
+                timestamp: chrono::Utc::now().naive_utc(),
+                metadata: json!({}),
+            }],
+        )
+        .await
+        .unwrap()
+        .message_ids;
+    assert_eq!(appended_ids.len(), 1);
+
+    for id in created_ids.iter().chain(appended_ids.iter()) {
+        let message = repo.find_message_by_id(*id).await.unwrap();
+        assert_eq!(message.unwrap().conversation_id, conv_id);
+    }
+
+    let appended = repo
+        .find_message_by_id(appended_ids[0])
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(appended.content, "A follow-up question");
+
+    assert!(repo.append_messages(Uuid::new_v4(), vec![]).await.is_err());
+}
+
+#[tokio::test]
+async fn test_append_after_idle_gap_starts_new_session() {
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let (chroma_client, embedding_service) = create_test_services();
+    let repo = SeaOrmConversationRepository::new(db, chroma_client, embedding_service)
+        .with_session_gap_minutes(30);
+
+    let conv_id = repo
+        .create_with_messages(create_test_conversation())
+        .await
+        .unwrap();
+
+    let follow_up = |minutes: i64| NewMessage {
+        role: "user".to_string(),
+        content: "Picking this back up".to_string(),
+        timestamp: chrono::Utc::now().naive_utc() + chrono::Duration::minutes(minutes),
+        metadata: json!({}),
+    };
+
+    // Within the gap: same session
+    repo.append_messages(conv_id, vec![follow_up(5)])
+        .await
+        .unwrap();
+    let conv = repo.find_by_id(conv_id).await.unwrap().unwrap();
+    assert_eq!(conv.session_count, 1);
+
+    // Hours later: a new session
+    repo.append_messages(conv_id, vec![follow_up(180)])
+        .await
+        .unwrap();
+    let conv = repo.find_by_id(conv_id).await.unwrap().unwrap();
+    assert_eq!(conv.session_count, 2);
+
+    repo.increment_session(conv_id).await.unwrap();
+    let conv = repo.find_by_id(conv_id).await.unwrap().unwrap();
+    assert_eq!(conv.session_count, 3);
+
+    assert!(repo.increment_session(Uuid::new_v4()).await.is_err());
+}
+
 #[tokio::test]
 async fn test_fts_auto_indexing() {
     let db = init_db("sqlite::memory:").await.unwrap();
@@ -237,7 +794,10 @@ async fn test_fts_auto_indexing() {
     let conv_id = repo.create_with_messages(conv).await.unwrap();
 
     // Search using FTS - should find the message immediately
-    let results = repo.full_text_search("quick brown fox", 10).await.unwrap();
+    let results = repo
+        .full_text_search("quick brown fox", 10, None, None, None)
+        .await
+        .unwrap();
 
     assert!(!results.is_empty(), "FTS should find the indexed message");
     assert_eq!(results[0].conversation_id, conv_id);
@@ -268,34 +828,1346 @@ async fn test_fts_update_trigger() {
     ).await.unwrap();
 
     // Search for updated content - trigger should have updated FTS index
-    let results = repo.full_text_search("searchable", 10).await.unwrap();
+    let results = repo
+        .full_text_search("searchable", 10, None, None, None)
+        .await
+        .unwrap();
 
     assert!(!results.is_empty(), "FTS should find updated content");
     assert!(results[0].content.contains("searchable"));
 }
 
 #[tokio::test]
-async fn test_fts_performance() {
+async fn test_reindex_fts_restores_search_after_drift() {
     let db = init_db("sqlite::memory:").await.unwrap();
     let (chroma_client, embedding_service) = create_test_services();
-    let repo = SeaOrmConversationRepository::new(db, chroma_client, embedding_service);
+    let repo = SeaOrmConversationRepository::new(db.clone(), chroma_client, embedding_service);
 
-    // Create 100 conversations with unique words
-    for i in 0..100 {
-        let mut conv = create_test_conversation();
-        conv.id = Some(Uuid::new_v4());
-        conv.messages = vec![NewMessage {
+    let mut conv = create_test_conversation();
+    conv.messages = vec![
+        NewMessage {
             role: "user".to_string(),
-            content: format!("Message with unique word number{}", i),
+            content: "Reindex target alpha".to_string(),
             timestamp: chrono::Utc::now().naive_utc(),
             metadata: json!({}),
-        }];
-        repo.create_with_messages(conv).await.unwrap();
-    }
-
-    // FTS should find ONLY the matching message
-    let results = repo.full_text_search("number42", 10).await.unwrap();
+        },
+        NewMessage {
+            role: "assistant".to_string(),
+            content: "Reindex target beta".to_string(),
+            timestamp: chrono::Utc::now().naive_utc(),
+            metadata: json!({}),
+        },
+    ];
+    repo.create_with_messages(conv).await.unwrap();
+    assert_eq!(
+        repo.full_text_search("reindex", 10, None, None, None)
+            .await
+            .unwrap()
+            .len(),
+        2
+    );
 
-    assert_eq!(results.len(), 1, "Should find exactly one message");
-    assert!(results[0].content.contains("number42"));
+    // Simulate drift: drop one row from the FTS index behind the triggers' back
+    use sea_orm::ConnectionTrait;
+    db.execute_unprepared(
+        "DELETE FROM messages_fts WHERE rowid = (SELECT MIN(rowid) FROM messages_fts)",
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        repo.full_text_search("reindex", 10, None, None, None)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+
+    let indexed = repo.reindex_fts().await.unwrap();
+
+    assert_eq!(indexed, 2);
+    assert_eq!(
+        repo.full_text_search("reindex", 10, None, None, None)
+            .await
+            .unwrap()
+            .len(),
+        2
+    );
+}
+
+#[tokio::test]
+async fn test_deferred_fts_indexing_catches_up_on_new_messages_only() {
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let (chroma_client, embedding_service) = create_test_services();
+    let repo = SeaOrmConversationRepository::new(db, chroma_client, embedding_service);
+    let count = |query: &'static str| {
+        let repo = &repo;
+        async move {
+            repo.full_text_search(query, 10, None, None, None)
+                .await
+                .unwrap()
+                .len()
+        }
+    };
+
+    repo.create_with_messages(create_test_conversation())
+        .await
+        .unwrap();
+    let after_rowid = repo.defer_fts_indexing().await.unwrap();
+
+    let mut conv = create_test_conversation();
+    conv.messages[0].content = "deferred lantern".to_string();
+    conv.messages[1].content = "another deferred lantern".to_string();
+    repo.create_with_messages(conv).await.unwrap();
+    assert_eq!(count("lantern").await, 0);
+
+    // Only the two deferred messages are added; earlier rows are untouched
+    let indexed = repo.index_fts_since(after_rowid).await.unwrap();
+    assert_eq!(indexed, 2);
+    assert_eq!(count("lantern").await, 2);
+    assert_eq!(count("test").await, 2);
+
+    // Per-message indexing is back
+    let mut conv = create_test_conversation();
+    conv.messages[0].content = "live lantern".to_string();
+    repo.create_with_messages(conv).await.unwrap();
+    assert_eq!(count("lantern").await, 3);
+    assert_eq!(repo.index_fts_since(after_rowid).await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_fts_performance() {
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let (chroma_client, embedding_service) = create_test_services();
+    let repo = SeaOrmConversationRepository::new(db, chroma_client, embedding_service);
+
+    // Create 100 conversations with unique words
+    for i in 0..100 {
+        let mut conv = create_test_conversation();
+        conv.id = Some(Uuid::new_v4());
+        conv.messages = vec![NewMessage {
+            role: "user".to_string(),
+            content: format!("Message with unique word number{}", i),
+            timestamp: chrono::Utc::now().naive_utc(),
+            metadata: json!({}),
+        }];
+        repo.create_with_messages(conv).await.unwrap();
+    }
+
+    // FTS should find ONLY the matching message
+    let results = repo
+        .full_text_search("number42", 10, None, None, None)
+        .await
+        .unwrap();
+
+    assert_eq!(results.len(), 1, "Should find exactly one message");
+    assert!(results[0].content.contains("number42"));
+}
+
+#[tokio::test]
+async fn test_fts_date_range_narrows_hits() {
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let (chroma_client, embedding_service) = create_test_services();
+    let repo = SeaOrmConversationRepository::new(db, chroma_client, embedding_service);
+
+    let at = |date: &str| {
+        chrono::NaiveDateTime::parse_from_str(&format!("{} 12:00:00", date), "%Y-%m-%d %H:%M:%S")
+            .unwrap()
+    };
+
+    let mut conv = create_test_conversation();
+    conv.messages = vec![
+        NewMessage {
+            role: "user".to_string(),
+            content: "deploy the old monolith".to_string(),
+            timestamp: at("2023-03-01"),
+            metadata: json!({}),
+        },
+        NewMessage {
+            role: "user".to_string(),
+            content: "deploy the new services".to_string(),
+            timestamp: at("2024-06-01"),
+            metadata: json!({}),
+        },
+    ];
+    repo.create_with_messages(conv).await.unwrap();
+
+    let all = repo
+        .full_text_search("deploy", 10, None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(all.len(), 2);
+
+    let recent = repo
+        .full_text_search("deploy", 10, Some(at("2024-01-01")), None, None)
+        .await
+        .unwrap();
+    assert_eq!(recent.len(), 1);
+    assert_eq!(recent[0].content, "deploy the new services");
+
+    let old = repo
+        .full_text_search("deploy", 10, None, Some(at("2024-01-01")), None)
+        .await
+        .unwrap();
+    assert_eq!(old.len(), 1);
+    assert_eq!(old[0].content, "deploy the old monolith");
+
+    // Bounds are inclusive of `after` and exclusive of `before`
+    let exact = repo
+        .full_text_search(
+            "deploy",
+            10,
+            Some(at("2024-06-01")),
+            Some(at("2024-06-02")),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(exact.len(), 1);
+}
+
+#[tokio::test]
+async fn test_fts_count_reports_total_beyond_limit() {
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let (chroma_client, embedding_service) = create_test_services();
+    let repo = SeaOrmConversationRepository::new(db, chroma_client, embedding_service);
+
+    let mut conv = create_test_conversation();
+    conv.messages = (0..5)
+        .map(|i| NewMessage {
+            role: "user".to_string(),
+            content: format!("kubernetes rollout step {}", i),
+            timestamp: chrono::Utc::now().naive_utc(),
+            metadata: json!({}),
+        })
+        .collect();
+    repo.create_with_messages(conv).await.unwrap();
+
+    let page = repo
+        .full_text_search("kubernetes", 2, None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(page.len(), 2);
+
+    let total = repo
+        .full_text_search_count("kubernetes", None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(total, 5);
+
+    let none = repo
+        .full_text_search_count("nonexistentterm", None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(none, 0);
+}
+
+#[tokio::test]
+async fn test_fts_scoped_to_conversation() {
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let (chroma_client, embedding_service) = create_test_services();
+    let repo = SeaOrmConversationRepository::new(db, chroma_client, embedding_service);
+
+    let mut conv_ids = Vec::new();
+    for _ in 0..2 {
+        let mut conv = create_test_conversation();
+        conv.messages[0].content = "Rotate the terraform state bucket".to_string();
+        conv_ids.push(repo.create_with_messages(conv).await.unwrap());
+    }
+
+    let everywhere = repo
+        .full_text_search("terraform", 10, None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(everywhere.len(), 2);
+
+    let scoped = repo
+        .full_text_search("terraform", 10, None, None, Some(conv_ids[1]))
+        .await
+        .unwrap();
+    assert_eq!(scoped.len(), 1);
+    assert_eq!(scoped[0].conversation_id, conv_ids[1]);
+
+    let total = repo
+        .full_text_search_count("terraform", None, None, Some(conv_ids[1]))
+        .await
+        .unwrap();
+    assert_eq!(total, 1);
+
+    let unknown = repo
+        .full_text_search("terraform", 10, None, None, Some(Uuid::new_v4()))
+        .await
+        .unwrap();
+    assert!(unknown.is_empty());
+}
+
+#[test]
+fn test_fts_ranking_order_by_clause() {
+    assert_eq!(
+        FtsRanking::default().order_by_clause(),
+        "bm25(messages_fts), m.rowid"
+    );
+    assert_eq!(
+        FtsRanking::default()
+            .with_column_weights(vec![2.5])
+            .order_by_clause(),
+        "bm25(messages_fts, 2.5), m.rowid"
+    );
+
+    let recency = FtsRanking::default()
+        .with_recency_weight(0.1)
+        .order_by_clause();
+    assert!(recency.starts_with("bm25(messages_fts) + 0.1 * (julianday('now')"));
+
+    // Non-finite weights must not leak into the SQL
+    let guarded = FtsRanking::default()
+        .with_column_weights(vec![f64::NAN])
+        .with_recency_weight(f64::INFINITY)
+        .order_by_clause();
+    assert_eq!(guarded, "bm25(messages_fts, 1.0), m.rowid");
+}
+
+#[tokio::test]
+async fn test_fts_recency_weight_prefers_newer_matches() {
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let (chroma_client, embedding_service) = create_test_services();
+    let repo = SeaOrmConversationRepository::new(db.clone(), chroma_client, embedding_service);
+
+    let mut conv_ids = Vec::new();
+    for date in ["2024-01-01", "2024-06-01"] {
+        let mut conv = create_test_conversation();
+        conv.messages.truncate(1);
+        conv.messages[0].content = "Rotate the terraform state bucket".to_string();
+        conv.messages[0].timestamp = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap();
+        conv_ids.push(repo.create_with_messages(conv).await.unwrap());
+    }
+
+    // Equal relevance: ties break on insertion order, oldest first
+    let by_relevance = repo
+        .full_text_search("terraform", 10, None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(by_relevance[0].conversation_id, conv_ids[0]);
+
+    let (chroma_client, embedding_service) = create_test_services();
+    let recent_first = SeaOrmConversationRepository::new(db, chroma_client, embedding_service)
+        .with_fts_ranking(FtsRanking::default().with_recency_weight(1.0));
+    let results = recent_first
+        .full_text_search("terraform", 10, None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].conversation_id, conv_ids[1]);
+}
+
+#[test]
+fn test_fts_match_query_strips_stop_words() {
+    let stop_words = vec!["the".to_string(), "How".to_string(), "do".to_string()];
+
+    assert_eq!(
+        fts_match_query_filtered("How do I reset the Rust toolchain?", &stop_words),
+        r#""I" OR "reset" OR "Rust" OR "toolchain""#
+    );
+    // Without a list nothing is dropped
+    assert_eq!(
+        fts_match_query("How do I reset the toolchain"),
+        r#""How" OR "do" OR "I" OR "reset" OR "the" OR "toolchain""#
+    );
+    // A query of nothing but stop words is kept as-is
+    assert_eq!(
+        fts_match_query_filtered("how do the", &stop_words),
+        r#""how" OR "do" OR "the""#
+    );
+}
+
+#[test]
+fn test_role_weights_reorder_equal_similarity_results() {
+    let hit = |role: &str| SearchResult {
+        conversation_id: Uuid::new_v4(),
+        message_id: Uuid::new_v4(),
+        score: 0.8,
+        role: role.to_string(),
+        content: format!("{} message", role),
+        metadata: json!({ "role": role }),
+        label: "Test".to_string(),
+        folder: "/test".to_string(),
+        timestamp: chrono::Utc::now().naive_utc(),
+    };
+
+    // Without weights, equal scores keep the vector store's order
+    let mut results = vec![hit("user"), hit("assistant")];
+    apply_role_weights(&mut results, &HashMap::new());
+    assert_eq!(results[0].role, "user");
+
+    let weights = HashMap::from([("assistant".to_string(), 1.5)]);
+    apply_role_weights(&mut results, &weights);
+    assert_eq!(results[0].role, "assistant");
+    assert!((results[0].score - 1.2).abs() < 1e-6);
+    assert_eq!(results[1].role, "user");
+    assert!((results[1].score - 0.8).abs() < 1e-6);
+}
+
+// ============================================
+// Cold storage offload
+// ============================================
+
+#[tokio::test]
+async fn test_offload_and_restore_round_trip() {
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let (chroma_client, embedding_service) = create_test_services();
+    let repo = SeaOrmConversationRepository::new(db, chroma_client, embedding_service);
+    let dir = tempfile::TempDir::new().unwrap();
+
+    let mut conv = create_test_conversation();
+    conv.messages = vec![
+        NewMessage {
+            role: "user".to_string(),
+            content: "where did we leave the migration plan?".to_string(),
+            timestamp: chrono::Utc::now().naive_utc(),
+            metadata: json!({ "source": "cli" }),
+        },
+        NewMessage {
+            role: "assistant".to_string(),
+            content: "in the ops/ runbook".to_string(),
+            timestamp: chrono::Utc::now().naive_utc() + chrono::Duration::seconds(1),
+            metadata: json!({}),
+        },
+    ];
+    let conv_id = repo.create_with_messages(conv).await.unwrap();
+    {
+        use sea_orm::{ActiveModelTrait, Set};
+        use sekha_controller::storage::entities::semantic_tags;
+        semantic_tags::ActiveModel {
+            id: Set(uuid::Uuid::new_v4()),
+            conversation_id: Set(conv_id),
+            tag: Set("migrations".to_string()),
+            confidence: Set(0.9),
+            extracted_at: Set(chrono::Utc::now().naive_utc()),
+        }
+        .insert(repo.get_db())
+        .await
+        .unwrap();
+    }
+
+    repo.update_status(conv_id, "archived").await.unwrap();
+
+    let original = repo.find_by_id(conv_id).await.unwrap().unwrap();
+    let original_messages = repo.get_conversation_messages(conv_id).await.unwrap();
+    let original_tags = repo.find_tags(conv_id).await.unwrap();
+    let original_history = repo.find_history(conv_id).await.unwrap();
+    assert_eq!(original_history.len(), 1);
+
+    let record = repo.archive_to_disk(conv_id, dir.path()).await.unwrap();
+    assert_eq!(record.conversation_id, conv_id);
+    assert_eq!(record.message_count, 2);
+    assert!(std::path::Path::new(&record.file_path).exists());
+
+    // Gone from the hot DB, including the full-text index
+    assert!(repo.find_by_id(conv_id).await.unwrap().is_none());
+    assert!(repo.find_history(conv_id).await.unwrap().is_empty());
+    assert!(repo
+        .get_conversation_messages(conv_id)
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(repo
+        .full_text_search("runbook", 10, None, None, None)
+        .await
+        .unwrap()
+        .is_empty());
+
+    let restored = repo.restore_from_disk(conv_id).await.unwrap();
+    assert_eq!(
+        serde_json::to_value(&restored).unwrap(),
+        serde_json::to_value(&original).unwrap()
+    );
+    let stored = repo.find_by_id(conv_id).await.unwrap().unwrap();
+    assert_eq!(
+        serde_json::to_value(&stored).unwrap(),
+        serde_json::to_value(&original).unwrap()
+    );
+
+    let restored_messages = repo.get_conversation_messages(conv_id).await.unwrap();
+    assert_eq!(
+        serde_json::to_value(&restored_messages).unwrap(),
+        serde_json::to_value(&original_messages).unwrap()
+    );
+    assert_eq!(
+        repo.full_text_search("runbook", 10, None, None, None)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+
+    assert_eq!(
+        serde_json::to_value(repo.find_tags(conv_id).await.unwrap()).unwrap(),
+        serde_json::to_value(&original_tags).unwrap()
+    );
+    assert_eq!(
+        serde_json::to_value(repo.find_history(conv_id).await.unwrap()).unwrap(),
+        serde_json::to_value(&original_history).unwrap()
+    );
+
+    // The pointer and file are consumed by the restore
+    assert!(!std::path::Path::new(&record.file_path).exists());
+    assert!(matches!(
+        repo.restore_from_disk(conv_id).await,
+        Err(RepositoryError::NotFound(_))
+    ));
+}
+
+// ============================================
+// Semantic search fallback policy
+// ============================================
+
+/// Repository whose vector store answers every request with a 500
+async fn repo_with_failing_vector_store(
+    policy: SearchFallback,
+) -> (SeaOrmConversationRepository, wiremock::MockServer) {
+    let chroma = wiremock::MockServer::start().await;
+    wiremock::Mock::given(wiremock::matchers::any())
+        .respond_with(wiremock::ResponseTemplate::new(500))
+        .mount(&chroma)
+        .await;
+
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let embedding_service = Arc::new(EmbeddingService::with_provider(
+        Arc::new(MockProvider::new_success(vec![0.1; 768])),
+        chroma.uri(),
+    ));
+    let repo = SeaOrmConversationRepository::new(
+        db,
+        Arc::new(ChromaClient::new(chroma.uri())),
+        embedding_service,
+    )
+    .with_search_fallback(policy);
+
+    let mut conv = create_test_conversation();
+    conv.messages[0].content = "Deploy the staging cluster tonight".to_string();
+    repo.create_with_messages(conv).await.unwrap();
+
+    (repo, chroma)
+}
+
+#[tokio::test]
+async fn test_semantic_search_fallback_policies() {
+    let (repo, _chroma) = repo_with_failing_vector_store(SearchFallback::Fts).await;
+    let results = repo
+        .semantic_search("staging deploy", 10, None)
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 1);
+    assert!(results[0].content.contains("staging cluster"));
+
+    let (repo, _chroma) = repo_with_failing_vector_store(SearchFallback::Empty).await;
+    let results = repo
+        .semantic_search("staging deploy", 10, None)
+        .await
+        .unwrap();
+    assert!(results.is_empty());
+    let (_, source) = repo
+        .semantic_search_with_model("staging deploy", 10, None, None)
+        .await
+        .unwrap();
+    assert_eq!(source, SearchSource::Unavailable);
+
+    let (repo, _chroma) = repo_with_failing_vector_store(SearchFallback::Error).await;
+    assert!(repo
+        .semantic_search("staging deploy", 10, None)
+        .await
+        .is_err());
+}
+
+// ============================================
+// Bulk delete
+// ============================================
+
+const CHROMA_COLLECTIONS: &str =
+    "/api/v2/tenants/default_tenant/databases/default_database/collections";
+
+/// Mock vector store that accepts upserts and deletes into one collection
+async fn mock_vector_store() -> wiremock::MockServer {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let chroma = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(CHROMA_COLLECTIONS))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!([{"id": "col-1", "name": "conversations"}])),
+        )
+        .mount(&chroma)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(format!("{}/conversations", CHROMA_COLLECTIONS)))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({"id": "col-1", "name": "conversations"})),
+        )
+        .mount(&chroma)
+        .await;
+    let store = VectorStore::default();
+    for operation in ["upsert", "delete", "get"] {
+        Mock::given(method("POST"))
+            .and(path(format!("{}/col-1/{}", CHROMA_COLLECTIONS, operation)))
+            .respond_with(store.clone())
+            .mount(&chroma)
+            .await;
+    }
+    chroma
+}
+
+/// In-memory stand-in for the `conversations` collection: upserts keep each
+/// vector's metadata, deletes drop them and `get` answers `ids` lookups,
+/// `message_id` filters and plain listings
+#[derive(Clone, Default)]
+struct VectorStore(Arc<std::sync::Mutex<HashMap<String, serde_json::Value>>>);
+
+impl wiremock::Respond for VectorStore {
+    fn respond(&self, request: &wiremock::Request) -> wiremock::ResponseTemplate {
+        let body: serde_json::Value = request.body_json().unwrap();
+        let ids: Vec<String> = serde_json::from_value(body["ids"].clone()).unwrap_or_default();
+        let mut vectors = self.0.lock().unwrap();
+
+        match request.url.path().rsplit('/').next() {
+            Some("upsert") => {
+                for (idx, id) in ids.into_iter().enumerate() {
+                    vectors.insert(id, body["metadatas"][idx].clone());
+                }
+            }
+            Some("delete") => {
+                for id in &ids {
+                    vectors.remove(id);
+                }
+            }
+            _ => {
+                let message_ids = body["where"]["message_id"]["$in"].as_array();
+                let mut found: Vec<&String> = vectors
+                    .iter()
+                    .filter(|(id, metadata)| {
+                        (ids.is_empty() || ids.contains(id))
+                            && message_ids
+                                .map_or(true, |wanted| wanted.contains(&metadata["message_id"]))
+                    })
+                    .map(|(id, _)| id)
+                    .collect();
+                found.sort();
+                return wiremock::ResponseTemplate::new(200).set_body_json(json!({ "ids": found }));
+            }
+        }
+        wiremock::ResponseTemplate::new(200)
+    }
+}
+
+/// Vector IDs sent in every request to a collection operation
+async fn vector_ids_sent(chroma: &wiremock::MockServer, operation: &str) -> Vec<Vec<String>> {
+    let suffix = format!("/col-1/{}", operation);
+    chroma
+        .received_requests()
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|r| r.url.path().ends_with(&suffix))
+        .map(|r| {
+            let body: serde_json::Value = r.body_json().unwrap();
+            serde_json::from_value(body["ids"].clone()).unwrap()
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_delete_many_removes_rows_and_vectors() {
+    let chroma = mock_vector_store().await;
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let embedding_service = Arc::new(EmbeddingService::with_provider(
+        Arc::new(MockProvider::new_success(vec![0.1; 768])),
+        chroma.uri(),
+    ));
+    let repo = SeaOrmConversationRepository::new(
+        db,
+        Arc::new(ChromaClient::new(chroma.uri())),
+        embedding_service,
+    );
+
+    let mut doomed = Vec::new();
+    for _ in 0..3 {
+        doomed.push(
+            repo.create_with_messages(create_test_conversation())
+                .await
+                .unwrap(),
+        );
+    }
+    let kept = repo
+        .create_with_messages(create_test_conversation())
+        .await
+        .unwrap();
+    let kept_vectors: Vec<String> = repo
+        .get_conversation_messages(kept)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter_map(|m| m.embedding_id)
+        .collect();
+    assert_eq!(kept_vectors.len(), 2);
+
+    let mut ids = doomed.clone();
+    ids.push(Uuid::new_v4()); // unknown IDs are ignored
+    let (mut deleted, vectors_removed) = repo.delete_many(ids).await.unwrap();
+    deleted.sort();
+    doomed.sort();
+    assert_eq!(deleted, doomed);
+    assert_eq!(vectors_removed, 6);
+
+    // One delete call covering exactly the doomed conversations' vectors
+    let deletes = vector_ids_sent(&chroma, "delete").await;
+    assert_eq!(deletes.len(), 1);
+    let mut removed = deletes[0].clone();
+    removed.sort();
+    let mut stored: Vec<String> = vector_ids_sent(&chroma, "upsert")
+        .await
+        .into_iter()
+        .flatten()
+        .filter(|id| !kept_vectors.contains(id))
+        .collect();
+    stored.sort();
+    assert_eq!(removed, stored);
+
+    for id in &doomed {
+        assert!(repo.find_by_id(*id).await.unwrap().is_none());
+        assert!(repo
+            .get_conversation_messages(*id)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+    assert!(repo.find_by_id(kept).await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn test_delete_many_counts_only_vectors_chroma_held() {
+    let chroma = mock_vector_store().await;
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let chroma_client = Arc::new(ChromaClient::new(chroma.uri()));
+    let embedding_service = Arc::new(EmbeddingService::with_provider(
+        Arc::new(MockProvider::new_success(vec![0.1; 768])),
+        chroma.uri(),
+    ));
+    let repo = SeaOrmConversationRepository::new(db, chroma_client.clone(), embedding_service);
+
+    let id = repo
+        .create_with_messages(create_test_conversation())
+        .await
+        .unwrap();
+    let embedding_ids: Vec<String> = repo
+        .get_conversation_messages(id)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter_map(|m| m.embedding_id)
+        .collect();
+    assert_eq!(embedding_ids.len(), 2);
+
+    // One vector is already gone, so only the other is removed
+    chroma_client
+        .delete("conversations", vec![embedding_ids[0].clone()])
+        .await
+        .unwrap();
+    let (deleted, vectors_removed) = repo.delete_many(vec![id]).await.unwrap();
+    assert_eq!(deleted, vec![id]);
+    assert_eq!(vectors_removed, 1);
+}
+
+#[tokio::test]
+async fn test_delete_paths_remove_chunk_vectors() {
+    let chroma = mock_vector_store().await;
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let chroma_client = Arc::new(ChromaClient::new(chroma.uri()));
+    // Small chunks split every test message into three vectors
+    let embedding_service = Arc::new(
+        EmbeddingService::with_provider(
+            Arc::new(MockProvider::new_success(vec![0.1; 768])),
+            chroma.uri(),
+        )
+        .with_chunking(10, 0),
+    );
+    let repo = SeaOrmConversationRepository::new(db, chroma_client.clone(), embedding_service);
+
+    let mut conversations = Vec::new();
+    for _ in 0..3 {
+        conversations.push(
+            repo.create_with_message_ids(create_test_conversation())
+                .await
+                .unwrap(),
+        );
+    }
+    let stored = chroma_client.list_ids("conversations").await.unwrap();
+    assert_eq!(stored.len(), 18);
+    assert!(stored.iter().all(|id| id.contains(':')));
+    let remaining = || async { chroma_client.list_ids("conversations").await.unwrap() };
+
+    let first_messages = &conversations[0].message_ids;
+    repo.delete_messages(vec![first_messages[0]]).await.unwrap();
+    let left = remaining().await;
+    assert_eq!(left.len(), 15);
+    assert!(!left
+        .iter()
+        .any(|id| id.starts_with(&first_messages[0].to_string())));
+
+    repo.delete(conversations[0].id).await.unwrap();
+    assert_eq!(remaining().await.len(), 12);
+
+    let (_, vectors_removed) = repo.delete_many(vec![conversations[1].id]).await.unwrap();
+    assert_eq!(vectors_removed, 6);
+
+    let dir = tempfile::TempDir::new().unwrap();
+    repo.archive_to_disk(conversations[2].id, dir.path())
+        .await
+        .unwrap();
+    assert!(remaining().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_failed_compaction_keeps_vectors() {
+    use sea_orm::ConnectionTrait;
+
+    let chroma = mock_vector_store().await;
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let chroma_client = Arc::new(ChromaClient::new(chroma.uri()));
+    let embedding_service = Arc::new(EmbeddingService::with_provider(
+        Arc::new(MockProvider::new_success(vec![0.1; 768])),
+        chroma.uri(),
+    ));
+    let repo =
+        SeaOrmConversationRepository::new(db.clone(), chroma_client.clone(), embedding_service);
+
+    let CreatedConversation {
+        id, message_ids, ..
+    } = repo
+        .create_with_message_ids(create_test_conversation())
+        .await
+        .unwrap();
+    let embedding_ids: Vec<String> = repo
+        .get_conversation_messages(id)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter_map(|m| m.embedding_id)
+        .collect();
+    assert_eq!(embedding_ids.len(), 2);
+
+    db.execute_unprepared(
+        "CREATE TRIGGER reject_compaction BEFORE UPDATE ON messages \
+         WHEN NEW.content = '' BEGIN SELECT RAISE(ABORT, 'no compaction'); END",
+    )
+    .await
+    .unwrap();
+    let summary = NewMessage {
+        role: "system".to_string(),
+        content: "Recap".to_string(),
+        metadata: json!({}),
+        timestamp: chrono::Utc::now().naive_utc(),
+    };
+    assert!(repo
+        .compact_messages(id, message_ids.clone(), summary.clone())
+        .await
+        .is_err());
+    // The bodies are still there, so their vectors must be too
+    let stored = chroma_client.list_ids("conversations").await.unwrap();
+    assert!(embedding_ids.iter().all(|id| stored.contains(id)));
+
+    db.execute_unprepared("DROP TRIGGER reject_compaction")
+        .await
+        .unwrap();
+    assert_eq!(
+        repo.compact_messages(id, message_ids.clone(), summary)
+            .await
+            .unwrap(),
+        2
+    );
+    let stored = chroma_client.list_ids("conversations").await.unwrap();
+    assert!(!embedding_ids.iter().any(|id| stored.contains(id)));
+}
+
+#[tokio::test]
+async fn test_reembed_scope_replaces_old_chunk_vectors() {
+    use sekha_controller::models::internal::ConversationFilter;
+
+    let chroma = mock_vector_store().await;
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let chroma_client = Arc::new(ChromaClient::new(chroma.uri()));
+    let embedder = |chunk_chars| {
+        Arc::new(
+            EmbeddingService::with_provider(
+                Arc::new(MockProvider::new_success(vec![0.1; 768])),
+                chroma.uri(),
+            )
+            .with_chunking(chunk_chars, 0),
+        )
+    };
+    let chunked =
+        SeaOrmConversationRepository::new(db.clone(), chroma_client.clone(), embedder(10));
+
+    // `_` must match itself, not any character
+    let mut in_scope = Vec::new();
+    for folder in ["/work_/notes", "/workX/notes"] {
+        let mut conv = create_test_conversation();
+        conv.folder = folder.to_string();
+        let CreatedConversation { message_ids, .. } =
+            chunked.create_with_message_ids(conv).await.unwrap();
+        if folder.starts_with("/work_") {
+            in_scope = message_ids;
+        }
+    }
+    assert_eq!(
+        chroma_client.list_ids("conversations").await.unwrap().len(),
+        12
+    );
+
+    let whole = SeaOrmConversationRepository::new(db, chroma_client.clone(), embedder(1000));
+    let report = whole
+        .reembed_scope(ConversationFilter {
+            folder: Some("/work_".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(report.conversations, 1);
+    assert_eq!(report.messages_reembedded, 2);
+
+    let stored = chroma_client.list_ids("conversations").await.unwrap();
+    assert_eq!(stored.len(), 8);
+    for id in &in_scope {
+        let id = id.to_string();
+        assert!(stored.contains(&id));
+        assert!(!stored.iter().any(|v| v.starts_with(&format!("{}:", id))));
+    }
+}
+
+#[tokio::test]
+async fn test_reconcile_keeps_chunk_vectors_of_stored_messages() {
+    use sea_orm::ConnectionTrait;
+    use wiremock::matchers::{body_json, method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    let chroma = mock_vector_store().await;
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let (_, embedding_service) = create_test_services();
+    let repo = SeaOrmConversationRepository::new(
+        db,
+        Arc::new(ChromaClient::new(chroma.uri())),
+        embedding_service,
+    );
+
+    let CreatedConversation { message_ids, .. } = repo
+        .create_with_message_ids(create_test_conversation())
+        .await
+        .unwrap();
+    // The first message is long enough to be stored as two chunk vectors
+    let chunked = message_ids[0].to_string();
+    repo.get_db()
+        .execute_raw(sea_orm::Statement::from_sql_and_values(
+            sea_orm::DatabaseBackend::Sqlite,
+            "UPDATE messages SET embedding_id = ? WHERE id = ?",
+            [chunked.clone().into(), message_ids[0].into()],
+        ))
+        .await
+        .unwrap();
+
+    let orphan = format!("{}:0", Uuid::new_v4());
+    Mock::given(method("POST"))
+        .and(path(format!("{}/col-1/get", CHROMA_COLLECTIONS)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "ids": [format!("{chunked}:0"), format!("{chunked}:1"), orphan]
+        })))
+        .with_priority(1)
+        .mount(&chroma)
+        .await;
+    Mock::given(method("POST"))
+        .and(path(format!("{}/col-1/delete", CHROMA_COLLECTIONS)))
+        .and(body_json(json!({ "ids": [orphan] })))
+        .respond_with(ResponseTemplate::new(200))
+        .with_priority(1)
+        .expect(1)
+        .mount(&chroma)
+        .await;
+
+    let report = repo.reconcile_embeddings().await.unwrap();
+
+    // Only the chunk of a message that doesn't exist goes; the chunked
+    // message isn't reported missing
+    assert_eq!(report.orphaned_vectors_removed, 1);
+    assert!(report
+        .missing_vectors
+        .values()
+        .flatten()
+        .all(|id| *id != message_ids[0]));
+    let kept = repo
+        .find_message_by_id(message_ids[0])
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(kept.embedding_id, Some(chunked));
+}
+
+#[tokio::test]
+async fn test_reconcile_keeps_vectors_embedded_during_the_scan() {
+    use wiremock::matchers::{body_json, method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    let chroma = mock_vector_store().await;
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let (_, embedding_service) = create_test_services();
+    let repo = SeaOrmConversationRepository::new(
+        db,
+        Arc::new(ChromaClient::new(chroma.uri())),
+        embedding_service,
+    );
+
+    // Neither vector has a message yet, but the fresh one's may still be
+    // being written
+    let fresh = Uuid::new_v4().to_string();
+    let old = Uuid::new_v4().to_string();
+    let now = chrono::Utc::now().timestamp();
+    Mock::given(method("POST"))
+        .and(path(format!("{}/col-1/get", CHROMA_COLLECTIONS)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "ids": [fresh, old],
+            "metadatas": [{"embedded_at": now}, {"embedded_at": now - 3600}]
+        })))
+        .with_priority(1)
+        .mount(&chroma)
+        .await;
+    Mock::given(method("POST"))
+        .and(path(format!("{}/col-1/delete", CHROMA_COLLECTIONS)))
+        .and(body_json(json!({ "ids": [old] })))
+        .respond_with(ResponseTemplate::new(200))
+        .with_priority(1)
+        .expect(1)
+        .mount(&chroma)
+        .await;
+
+    let report = repo.reconcile_embeddings().await.unwrap();
+
+    assert_eq!(report.orphaned_vectors_removed, 1);
+}
+
+#[tokio::test]
+async fn test_reconcile_does_not_report_skipped_messages_missing() {
+    use sea_orm::ConnectionTrait;
+
+    let chroma = mock_vector_store().await;
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let (_, embedding_service) = create_test_services();
+    let repo = SeaOrmConversationRepository::new(
+        db,
+        Arc::new(ChromaClient::new(chroma.uri())),
+        embedding_service,
+    );
+
+    let CreatedConversation { message_ids, .. } = repo
+        .create_with_message_ids(create_test_conversation())
+        .await
+        .unwrap();
+    repo.get_db()
+        .execute_raw(sea_orm::Statement::from_sql_and_values(
+            sea_orm::DatabaseBackend::Sqlite,
+            "UPDATE messages SET embedding_id = NULL, metadata = ? WHERE id = ?",
+            [
+                json!({"embedding_skipped": "below_min_length"}).into(),
+                message_ids[0].into(),
+            ],
+        ))
+        .await
+        .unwrap();
+
+    let report = repo.reconcile_embeddings().await.unwrap();
+
+    assert!(report
+        .missing_vectors
+        .values()
+        .flatten()
+        .all(|id| *id != message_ids[0]));
+}
+
+#[tokio::test]
+async fn test_redaction_scrubs_stored_and_embedded_content() {
+    let chroma = mock_vector_store().await;
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let embedding_service = Arc::new(EmbeddingService::with_provider(
+        Arc::new(MockProvider::new_success(vec![0.1; 768])),
+        chroma.uri(),
+    ));
+    let redactor = Redactor::new(&[r"[\w.+-]+@[\w-]+\.[\w.]+".to_string()]).unwrap();
+    let repo = SeaOrmConversationRepository::new(
+        db,
+        Arc::new(ChromaClient::new(chroma.uri())),
+        embedding_service,
+    )
+    .with_redactor(redactor);
+
+    let mut conv = create_test_conversation();
+    conv.messages[0].content = "Reach me at jane.doe@example.com tomorrow".to_string();
+    let CreatedConversation {
+        id: conv_id,
+        message_ids,
+        ..
+    } = repo.create_with_message_ids(conv).await.unwrap();
+
+    let stored = repo
+        .find_message_by_id(message_ids[0])
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored.content, "Reach me at [REDACTED] tomorrow");
+    assert_eq!(stored.metadata.unwrap()["redactions"], 1);
+
+    // The word count matches what was stored, so a recompute finds no drift
+    let stored_len: usize = repo
+        .get_conversation_messages(conv_id)
+        .await
+        .unwrap()
+        .iter()
+        .map(|m| m.content.len())
+        .sum();
+    let conversation = repo.find_by_id(conv_id).await.unwrap().unwrap();
+    assert_eq!(conversation.word_count as usize, stored_len);
+    let recompute = repo.recompute_aggregates().await.unwrap();
+    assert_eq!(recompute.conversations_corrected, 0);
+
+    // Untouched messages carry no redaction count
+    let clean = repo
+        .find_message_by_id(message_ids[1])
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(clean
+        .metadata
+        .map_or(true, |m| m.get("redactions").is_none()));
+
+    let documents: Vec<String> = chroma
+        .received_requests()
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|r| r.url.path().ends_with("/col-1/upsert"))
+        .flat_map(|r| {
+            let body: serde_json::Value = r.body_json().unwrap();
+            serde_json::from_value::<Vec<String>>(body["documents"].clone()).unwrap()
+        })
+        .collect();
+    assert!(documents.iter().any(|d| d.contains("[REDACTED]")));
+    assert!(documents
+        .iter()
+        .all(|d| !d.contains("jane.doe@example.com")));
+}
+
+#[tokio::test]
+async fn test_short_messages_are_stored_without_embedding() {
+    let chroma = mock_vector_store().await;
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let provider = Arc::new(MockProvider::new_success(vec![0.1; 768]));
+    let embedding_service = Arc::new(EmbeddingService::with_provider(
+        provider.clone(),
+        chroma.uri(),
+    ));
+    let repo = SeaOrmConversationRepository::new(
+        db,
+        Arc::new(ChromaClient::new(chroma.uri())),
+        embedding_service,
+    )
+    .with_min_embed_length(10);
+
+    let mut conv = create_test_conversation();
+    conv.messages[0].content = " thanks ".to_string();
+    conv.messages[1].content = "Glad the migration plan worked out".to_string();
+    let CreatedConversation { message_ids, .. } = repo.create_with_message_ids(conv).await.unwrap();
+
+    // Only the long message reached the embedder
+    assert_eq!(*provider.call_count.lock().unwrap(), 1);
+
+    let short = repo
+        .find_message_by_id(message_ids[0])
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(short.embedding_id.is_none());
+    assert!(short.embedding_skipped());
+    assert_eq!(
+        short.metadata.unwrap()["embedding_skipped"],
+        "below_min_length"
+    );
+
+    let long = repo
+        .find_message_by_id(message_ids[1])
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(long.embedding_id.is_some());
+    assert!(!long.embedding_skipped());
+
+    // Retries leave skipped messages alone
+    let retried = repo.retry_embeddings(vec![message_ids[0]]).await.unwrap();
+    assert!(retried.is_empty());
+    assert_eq!(*provider.call_count.lock().unwrap(), 1);
+
+    let hits = repo
+        .full_text_search("thanks", 10, None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].id, message_ids[0]);
+}
+
+#[tokio::test]
+async fn test_claim_summary_checkpoint_fires_once_per_interval() {
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let (chroma_client, embedding_service) = create_test_services();
+    let repo = SeaOrmConversationRepository::new(db, chroma_client, embedding_service);
+    let conv_id = repo
+        .create_with_messages(create_test_conversation())
+        .await
+        .unwrap();
+    let new_message = || NewMessage {
+        role: "user".to_string(),
+        content: "More context".to_string(),
+        timestamp: chrono::Utc::now().naive_utc(),
+        metadata: json!({}),
+    };
+
+    assert_eq!(
+        repo.claim_summary_checkpoint(conv_id, 3).await.unwrap(),
+        None
+    );
+
+    repo.append_messages(conv_id, vec![new_message()])
+        .await
+        .unwrap();
+    assert_eq!(
+        repo.claim_summary_checkpoint(conv_id, 3).await.unwrap(),
+        Some(3)
+    );
+    assert_eq!(
+        repo.claim_summary_checkpoint(conv_id, 3).await.unwrap(),
+        None
+    );
+
+    repo.append_messages(conv_id, vec![new_message(), new_message()])
+        .await
+        .unwrap();
+    assert_eq!(
+        repo.claim_summary_checkpoint(conv_id, 3).await.unwrap(),
+        None
+    );
+    repo.append_messages(conv_id, vec![new_message()])
+        .await
+        .unwrap();
+    assert_eq!(
+        repo.claim_summary_checkpoint(conv_id, 3).await.unwrap(),
+        Some(6)
+    );
+}
+
+#[tokio::test]
+async fn test_repository_reads_go_through_read_connection() {
+    use sea_orm::ConnectionTrait;
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let url = format!("sqlite://{}", dir.path().join("primary.db").display());
+    let db = init_db(&url).await.unwrap();
+    let read_db = init_read_db(&url).await.unwrap();
+    let (chroma_client, embedding_service) = create_test_services();
+    let repo = SeaOrmConversationRepository::new(db.clone(), chroma_client, embedding_service)
+        .with_read_connection(read_db.clone());
+
+    let mut conv = create_test_conversation();
+    conv.messages = vec![NewMessage {
+        role: "user".to_string(),
+        content: "Replica reads see the quick brown fox".to_string(),
+        timestamp: chrono::Utc::now().naive_utc(),
+        metadata: json!({}),
+    }];
+    let conv_id = repo.create_with_messages(conv).await.unwrap();
+
+    // Writes on the primary are visible through the read-only pool
+    assert!(repo.find_by_id(conv_id).await.unwrap().is_some());
+    let results = repo
+        .full_text_search("quick brown fox", 10, None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].conversation_id, conv_id);
+
+    // The read pool refuses writes
+    let insert = read_db
+        .execute_unprepared("INSERT INTO conversations (id) VALUES (x'00')")
+        .await;
+    assert!(insert.is_err(), "read connection accepted a write");
+
+    // Point the read pool at another database to show lookups use it
+    let other_url = format!("sqlite://{}", dir.path().join("other.db").display());
+    let other_db = init_db(&other_url).await.unwrap();
+    let (chroma_client, embedding_service) = create_test_services();
+    let other_id = SeaOrmConversationRepository::new(other_db, chroma_client, embedding_service)
+        .create_with_messages(create_test_conversation())
+        .await
+        .unwrap();
+    let (chroma_client, embedding_service) = create_test_services();
+    let routed = SeaOrmConversationRepository::new(db, chroma_client, embedding_service)
+        .with_read_connection(init_read_db(&other_url).await.unwrap());
+    assert!(routed.find_by_id(other_id).await.unwrap().is_some());
+    assert!(routed.find_by_id(conv_id).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_ulid_scheme_ids_sort_in_creation_order() {
+    let db = init_db("sqlite::memory:").await.unwrap();
+    let (chroma_client, embedding_service) = create_test_services();
+    let repo = SeaOrmConversationRepository::new(db, chroma_client, embedding_service)
+        .with_embeddings_enabled(false)
+        .with_id_scheme(IdScheme::Ulid);
+
+    // Same timestamps throughout, so only the IDs tell the rows apart
+    let updated_at = chrono::Utc::now().naive_utc();
+    let mut conversation_ids = Vec::new();
+    let mut message_ids = Vec::new();
+    for i in 0..5 {
+        let mut conv = create_test_conversation();
+        conv.id = None;
+        conv.label = format!("Conversation {}", i);
+        conv.updated_at = updated_at;
+        let CreatedConversation {
+            id,
+            message_ids: ids,
+            ..
+        } = repo.create_with_message_ids(conv).await.unwrap();
+        conversation_ids.push(id);
+        message_ids.extend(ids);
+    }
+
+    for ids in [&conversation_ids, &message_ids] {
+        let mut sorted = ids.clone();
+        sorted.sort();
+        assert_eq!(&sorted, ids);
+        let text: Vec<String> = ids.iter().map(Uuid::to_string).collect();
+        let mut sorted_text = text.clone();
+        sorted_text.sort();
+        assert_eq!(sorted_text, text);
+    }
+
+    // Ties on updated_at list the most recently created first
+    let (listed, _) = repo.find_with_filters(None, None, 10, 0).await.unwrap();
+    let listed: Vec<Uuid> = listed.iter().map(|c| c.id).collect();
+    let newest_first: Vec<Uuid> = conversation_ids.iter().rev().copied().collect();
+    assert_eq!(listed, newest_first);
+
+    // UUIDs from before the switch are still accepted
+    let mut conv = create_test_conversation();
+    let legacy = Uuid::new_v4();
+    conv.id = Some(legacy);
+    repo.create_with_messages(conv).await.unwrap();
+    assert!(repo.find_by_id(legacy).await.unwrap().is_some());
 }
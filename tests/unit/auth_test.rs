@@ -2,7 +2,7 @@ use axum::extract::FromRequestParts;
 use axum::http::{Request, StatusCode};
 use sekha_controller::api::routes::AppState;
 use sekha_controller::auth::McpAuth;
-use sekha_controller::config::Config;
+use sekha_controller::config::{Config, SearchFallback};
 use sekha_controller::orchestrator::MemoryOrchestrator;
 use sekha_controller::services::embedding_service::EmbeddingService;
 use sekha_controller::services::llm_bridge_client::LlmBridgeClient;
@@ -28,6 +28,49 @@ async fn create_test_state(api_key: String) -> AppState {
         log_level: "info".to_string(),
         summarization_enabled: true,
         pruning_enabled: true,
+        import_directories: vec![],
+        search_max_age_days: None,
+        rescore_importance_on_append: false,
+        importance_rescore_debounce_secs: 60,
+        rate_limit_warn_percent: 80,
+        import_defer_fts: false,
+        id_scheme: Default::default(),
+        embedding_timeout_secs: 60,
+        log_bodies: false,
+        log_body_max_bytes: 4096,
+        log_redact_fields: vec![],
+        compact_on_archive: false,
+        search_exclude_folders: vec![],
+        search_cache_ttl_secs: 30,
+        search_cache_capacity: 256,
+        default_folder: "/inbox".to_string(),
+        prune_by_last_access: false,
+        fts_ranking: Default::default(),
+        llm_max_concurrency: 8,
+        min_embed_length: 0,
+        import_max_concurrency: 4,
+        redaction_patterns: Vec::new(),
+        embedding_models: Default::default(),
+        fts_stop_words: Vec::new(),
+        read_database_url: None,
+        import_derive_labels: false,
+        embeddings_enabled: true,
+        max_messages_per_conversation: None,
+        retention_policies: Default::default(),
+        import_mode: Default::default(),
+        default_search_limit: 10,
+        max_search_limit: 100,
+        summarize_after_messages: None,
+        request_timeout_secs: 30,
+        offload_dir: "sekha_offload".to_string(),
+        role_weights: Default::default(),
+        search_fallback: SearchFallback::Fts,
+        session_gap_minutes: 30,
+        chroma_upsert_batch_size: 32,
+        admin_api_key: None,
+        embedding_strip_code_blocks: false,
+        embedding_collapse_whitespace: false,
+        embedding_max_tokens: None,
         embedding_model: "nomic-embed-text:latest".to_string(),
         summarization_model: "llama3.1:8b".to_string(),
     }));
@@ -51,7 +94,13 @@ async fn create_test_state(api_key: String) -> AppState {
         config,
         repo: repo.clone(),
         chroma_client: chroma,
+        events: sekha_controller::services::EventBus::default(),
+        search_cache: Default::default(),
+        tasks: Default::default(),
         embedding_service,
+        embedding_queue: Arc::new(
+            sekha_controller::services::embedding_queue::EmbeddingQueue::new(),
+        ),
         orchestrator: Arc::new(MemoryOrchestrator::new(repo.clone(), llm_bridge)),
     }
 }
@@ -239,3 +239,74 @@ async fn test_ping_success() {
     let result = client.ping().await;
     assert!(result.is_ok());
 }
+
+#[tokio::test]
+async fn test_get_embedding_returns_stored_vector() {
+    let mock_server = MockServer::start().await;
+    let client = ChromaClient::new(mock_server.uri());
+
+    Mock::given(method("GET"))
+        .and(path(
+            "/api/v2/tenants/default_tenant/databases/default_database/collections/test_collection",
+        ))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({"id": "col-123", "name": "test_collection"})),
+        )
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path(
+            "/api/v2/tenants/default_tenant/databases/default_database/collections/col-123/get",
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "ids": ["vec-1"],
+            "embeddings": [[0.1, 0.2, 0.3]]
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let embedding = client
+        .get_embedding("test_collection", "vec-1")
+        .await
+        .unwrap();
+    assert_eq!(embedding, Some(vec![0.1, 0.2, 0.3]));
+
+    let missing = client
+        .get_embedding("test_collection", "vec-2")
+        .await
+        .unwrap();
+    assert_eq!(missing, None);
+}
+
+#[tokio::test]
+async fn test_collection_info_reports_dimension() {
+    let mock_server = MockServer::start().await;
+    let client = ChromaClient::new(mock_server.uri());
+
+    Mock::given(method("GET"))
+        .and(path(
+            "/api/v2/tenants/default_tenant/databases/default_database/collections/conversations",
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "id": "col-123",
+            "name": "conversations",
+            "dimension": null,
+            "metadata": {"hnsw:space": "cosine", "dimension": 768}
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let info = client
+        .collection_info("conversations")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(info.id, "col-123");
+    assert_eq!(info.dimension, Some(768));
+
+    // Unknown collections are reported as absent rather than as an error
+    let missing = client.collection_info("other").await.unwrap();
+    assert_eq!(missing, None);
+}
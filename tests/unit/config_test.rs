@@ -1,4 +1,4 @@
-use sekha_controller::config::Config;
+use sekha_controller::config::{Config, SearchFallback};
 
 #[test]
 fn test_config_default_exists() {
@@ -133,6 +133,49 @@ fn test_get_all_api_keys_deduplication() {
         summarization_enabled: true,
         summarization_model: "test-model".to_string(),
         pruning_enabled: true,
+        import_directories: vec![],
+        search_max_age_days: None,
+        rescore_importance_on_append: false,
+        importance_rescore_debounce_secs: 60,
+        rate_limit_warn_percent: 80,
+        import_defer_fts: false,
+        id_scheme: Default::default(),
+        embedding_timeout_secs: 60,
+        log_bodies: false,
+        log_body_max_bytes: 4096,
+        log_redact_fields: vec![],
+        compact_on_archive: false,
+        search_exclude_folders: vec![],
+        search_cache_ttl_secs: 30,
+        search_cache_capacity: 256,
+        default_folder: "/inbox".to_string(),
+        prune_by_last_access: false,
+        fts_ranking: Default::default(),
+        llm_max_concurrency: 8,
+        min_embed_length: 0,
+        import_max_concurrency: 4,
+        redaction_patterns: Vec::new(),
+        embedding_models: Default::default(),
+        fts_stop_words: Vec::new(),
+        read_database_url: None,
+        import_derive_labels: false,
+        embeddings_enabled: true,
+        max_messages_per_conversation: None,
+        retention_policies: Default::default(),
+        import_mode: Default::default(),
+        default_search_limit: 10,
+        max_search_limit: 100,
+        summarize_after_messages: None,
+        request_timeout_secs: 30,
+        offload_dir: "sekha_offload".to_string(),
+        role_weights: Default::default(),
+        search_fallback: SearchFallback::Fts,
+        session_gap_minutes: 30,
+        chroma_upsert_batch_size: 32,
+        admin_api_key: None,
+        embedding_strip_code_blocks: false,
+        embedding_collapse_whitespace: false,
+        embedding_max_tokens: None,
         rest_api_key: Some("key1".to_string()), // Duplicate!
         additional_api_keys: vec!["key1".to_string(), "key2".to_string()], // More duplicates
         rate_limit_per_minute: 1000,
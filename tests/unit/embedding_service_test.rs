@@ -3,9 +3,16 @@
 //! Tests that require Chroma moved to integration tests
 
 use sekha_controller::services::embedding_provider::ProviderError;
-use sekha_controller::services::embedding_service::EmbeddingError;
+use sekha_controller::services::embedding_service::{
+    chunk_text, EmbeddingError, EmbeddingPreprocessing, EmbeddingRequest, DEFAULT_CHUNK_CHARS,
+    DEFAULT_CHUNK_OVERLAP,
+};
 use sekha_controller::services::{EmbeddingProvider, EmbeddingService, MockProvider};
+use serde_json::json;
 use std::sync::Arc;
+use uuid::Uuid;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
 
 // ============================================
 // Test: Successful embedding generation
@@ -172,3 +179,296 @@ async fn test_generate_embedding_with_retry_no_embeddings() {
     // Should immediately fail without retries on NoEmbeddings
     assert!(matches!(result, Err(EmbeddingError::NoEmbeddings)));
 }
+
+// ============================================
+// Test: Embedding text preprocessing
+// ============================================
+
+#[test]
+fn test_preprocessing_strips_code_block() {
+    let preprocessing = EmbeddingPreprocessing {
+        strip_code_blocks: true,
+        collapse_whitespace: true,
+        max_tokens: None,
+    };
+    let content =
+        "How do I fix this?\n```rust\nfn main() {\n    panic!();\n}\n```\n\nIt   crashes on start.";
+
+    assert_eq!(
+        preprocessing.apply(content),
+        "How do I fix this? It crashes on start."
+    );
+}
+
+#[test]
+fn test_preprocessing_truncates_and_keeps_code_only_messages() {
+    let preprocessing = EmbeddingPreprocessing {
+        strip_code_blocks: true,
+        collapse_whitespace: false,
+        max_tokens: Some(3),
+    };
+
+    assert_eq!(
+        preprocessing.apply("one two  three four five"),
+        "one two  three"
+    );
+
+    // Nothing left after stripping: embed the original instead
+    let code_only = "```\nlet x = 1;\n```";
+    assert_eq!(preprocessing.apply(code_only), code_only);
+}
+
+#[test]
+fn test_preprocessing_default_is_verbatim() {
+    let content = "  keep\n\nthis   as is ```x``` ";
+    assert_eq!(EmbeddingPreprocessing::default().apply(content), content);
+}
+
+// ============================================
+// Test: Long messages are embedded in chunks
+// ============================================
+
+const COLLECTIONS_PATH: &str =
+    "/api/v2/tenants/default_tenant/databases/default_database/collections";
+
+async fn mount_chroma_collection(mock_server: &MockServer) {
+    Mock::given(method("GET"))
+        .and(path(COLLECTIONS_PATH))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!([{"id": "col-123", "name": "conversations"}])),
+        )
+        .mount(mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path(format!("{}/conversations", COLLECTIONS_PATH)))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({"id": "col-123", "name": "conversations"})),
+        )
+        .mount(mock_server)
+        .await;
+}
+
+#[tokio::test]
+async fn test_process_long_message_stores_chunk_vectors() {
+    let mock_server = MockServer::start().await;
+    mount_chroma_collection(&mock_server).await;
+
+    Mock::given(method("POST"))
+        .and(path(format!("{}/col-123/upsert", COLLECTIONS_PATH)))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    let provider = Arc::new(MockProvider::new_success(vec![0.1; 768]));
+    let provider_clone = provider.clone();
+    let service = EmbeddingService::with_provider(provider, mock_server.uri());
+
+    let message_id = Uuid::new_v4();
+    let content = "lorem ipsum ".repeat(50_000 / 12);
+
+    let embedding_id = service
+        .process_message(message_id, &content, Uuid::new_v4(), json!({}))
+        .await
+        .unwrap();
+    assert_eq!(embedding_id, message_id.to_string());
+
+    let upserts: Vec<serde_json::Value> = mock_server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .filter(|r| r.url.path().ends_with("/upsert"))
+        .map(|r| serde_json::from_slice(&r.body).unwrap())
+        .collect();
+
+    let expected_chunks = chunk_text(&content, DEFAULT_CHUNK_CHARS, DEFAULT_CHUNK_OVERLAP).len();
+    assert!(expected_chunks > 1);
+    assert_eq!(*provider_clone.call_count.lock().unwrap(), expected_chunks);
+
+    // Chunk vectors are flushed together, so flatten across upsert calls
+    let ids: Vec<&serde_json::Value> = upserts
+        .iter()
+        .flat_map(|u| u["ids"].as_array().unwrap())
+        .collect();
+    let metadatas: Vec<&serde_json::Value> = upserts
+        .iter()
+        .flat_map(|u| u["metadatas"].as_array().unwrap())
+        .collect();
+    assert_eq!(ids.len(), expected_chunks);
+
+    for (idx, (id, metadata)) in ids.iter().zip(&metadatas).enumerate() {
+        assert_eq!(**id, format!("{}:{}", message_id, idx));
+        assert_eq!(metadata["chunk_index"], idx);
+        assert_eq!(metadata["message_id"], message_id.to_string());
+    }
+}
+
+#[tokio::test]
+async fn test_search_merges_chunk_hits_into_one_result() {
+    let mock_server = MockServer::start().await;
+    mount_chroma_collection(&mock_server).await;
+
+    let long_message = Uuid::new_v4().to_string();
+    let short_message = Uuid::new_v4().to_string();
+
+    Mock::given(method("POST"))
+        .and(path(format!("{}/col-123/query", COLLECTIONS_PATH)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "ids": [[
+                format!("{}:3", long_message),
+                format!("{}:0", long_message),
+                short_message.clone(),
+            ]],
+            "distances": [[0.1, 0.2, 0.3]],
+            "metadatas": [[
+                {"message_id": long_message, "chunk_index": 3},
+                {"message_id": long_message, "chunk_index": 0},
+                {"message_id": short_message},
+            ]],
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let provider = Arc::new(MockProvider::new_success(vec![0.1; 768]));
+    let service = EmbeddingService::with_provider(provider, mock_server.uri());
+
+    let results = service.search_messages("query", 10, None).await.unwrap();
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].id, long_message);
+    assert_eq!(results[0].score, 0.1);
+    assert_eq!(results[1].id, short_message);
+}
+
+fn embedding_requests(count: usize) -> Vec<EmbeddingRequest> {
+    let conversation_id = Uuid::new_v4();
+    (0..count)
+        .map(|i| EmbeddingRequest {
+            message_id: Uuid::new_v4(),
+            conversation_id,
+            content: format!("Message number {}", i),
+            metadata: json!({"role": "user"}),
+        })
+        .collect()
+}
+
+async fn upsert_bodies(mock_server: &MockServer) -> Vec<serde_json::Value> {
+    mock_server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .filter(|r| r.url.path().ends_with("/upsert"))
+        .map(|r| serde_json::from_slice(&r.body).unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_batch_embedding_groups_upserts() {
+    let mock_server = MockServer::start().await;
+    mount_chroma_collection(&mock_server).await;
+
+    Mock::given(method("POST"))
+        .and(path(format!("{}/col-123/upsert", COLLECTIONS_PATH)))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    let provider = Arc::new(MockProvider::new_success(vec![0.1; 768]));
+    let service =
+        EmbeddingService::with_provider(provider, mock_server.uri()).with_upsert_batch_size(20);
+
+    let requests = embedding_requests(50);
+    let expected: Vec<Uuid> = requests.iter().map(|r| r.message_id).collect();
+
+    let report = service.process_messages_batch(requests).await;
+    assert!(report.failed.is_empty());
+    assert_eq!(report.succeeded, expected);
+
+    let upserts = upsert_bodies(&mock_server).await;
+    let sizes: Vec<usize> = upserts
+        .iter()
+        .map(|u| u["ids"].as_array().unwrap().len())
+        .collect();
+    assert!(upserts.len() < 50);
+    assert_eq!(sizes, vec![20, 20, 10]);
+}
+
+#[tokio::test]
+async fn test_batch_embedding_reports_failed_ids() {
+    let mock_server = MockServer::start().await;
+    mount_chroma_collection(&mock_server).await;
+
+    // First batch is rejected, the rest succeed
+    Mock::given(method("POST"))
+        .and(path(format!("{}/col-123/upsert", COLLECTIONS_PATH)))
+        .respond_with(ResponseTemplate::new(500).set_body_string("disk full"))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path(format!("{}/col-123/upsert", COLLECTIONS_PATH)))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    let provider = Arc::new(MockProvider::new_success(vec![0.1; 768]));
+    let service =
+        EmbeddingService::with_provider(provider, mock_server.uri()).with_upsert_batch_size(4);
+
+    let requests = embedding_requests(10);
+    let ids: Vec<Uuid> = requests.iter().map(|r| r.message_id).collect();
+
+    let report = service.process_messages_batch(requests).await;
+
+    let mut failed: Vec<Uuid> = report.failed.iter().map(|(id, _)| *id).collect();
+    failed.sort();
+    let mut expected_failed = ids[..4].to_vec();
+    expected_failed.sort();
+    assert_eq!(failed, expected_failed);
+    assert!(report.failed.iter().all(|(_, e)| e.contains("disk full")));
+    assert_eq!(report.succeeded, ids[4..].to_vec());
+}
+
+// ============================================
+// Test: Collection dimension guard
+// ============================================
+
+#[tokio::test]
+async fn test_verify_collection_dimension_detects_model_switch() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(
+            "/api/v2/tenants/default_tenant/databases/default_database/collections/conversations",
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "id": "col-123",
+            "name": "conversations",
+            "dimension": 768
+        })))
+        .mount(&mock_server)
+        .await;
+
+    // Same model as the stored vectors
+    let provider = Arc::new(MockProvider::new_success(vec![0.1; 768]));
+    let service = EmbeddingService::with_provider(provider, mock_server.uri());
+    assert_eq!(service.verify_collection_dimension().await.unwrap(), 768);
+
+    // A smaller model can't share the collection
+    let provider = Arc::new(MockProvider::new_success(vec![0.1; 384]));
+    let service = EmbeddingService::with_provider(provider, mock_server.uri());
+    let err = service.verify_collection_dimension().await.unwrap_err();
+    assert!(matches!(
+        err,
+        EmbeddingError::CollectionDimensionMismatch {
+            stored: 768,
+            model: 384,
+            ..
+        }
+    ));
+    assert!(err.to_string().contains("reindex"));
+}
@@ -250,7 +250,13 @@ async fn test_process_unknown_extension() {
     let (processor, _repo) = create_test_processor().await;
     let result = processor.process_file(&file_path).await;
 
-    assert!(result.is_err(), "Should fail on unknown extension");
+    assert!(result.is_ok(), "Transcript should be sniffed as plain text");
+
+    let unparseable = temp_dir.path().join("notes.xyz");
+    fs::write(&unparseable, "no roles in here").unwrap();
+    let result = processor.process_file(&unparseable).await;
+
+    assert!(result.is_err(), "Should fail when nothing parses");
 }
 
 // ============================================
@@ -1,6 +1,7 @@
 use async_trait::async_trait;
 use mockall::mock;
 use mockall::predicate::*;
+use sekha_controller::models::importance;
 use sekha_controller::models::internal::Message;
 use sekha_controller::orchestrator::importance_engine::ImportanceEngine;
 use sekha_controller::services::llm_bridge_client::LlmBridgeClient;
@@ -19,29 +20,79 @@ mock! {
     impl ConversationRepository for ConversationRepo {
         async fn create(&self, conv: sekha_controller::models::internal::Conversation) -> Result<Uuid, RepositoryError>;
         async fn create_with_messages(&self, conv: sekha_controller::models::internal::NewConversation) -> Result<Uuid, RepositoryError>;
+        async fn create_with_message_ids(&self, conv: sekha_controller::models::internal::NewConversation) -> Result<sekha_controller::storage::repository::CreatedConversation, RepositoryError>;
+        async fn create_with_tags(&self, conv: sekha_controller::models::internal::NewConversation, tags: Vec<String>) -> Result<sekha_controller::storage::repository::CreatedConversation, RepositoryError>;
+        async fn upsert_import(&self, conv: sekha_controller::models::internal::NewConversation) -> Result<Uuid, RepositoryError>;
+        async fn append_messages(&self, conversation_id: Uuid, messages: Vec<sekha_controller::models::internal::NewMessage>) -> Result<sekha_controller::storage::repository::AppendedMessages, RepositoryError>;
         async fn delete(&self, id: Uuid) -> Result<(), RepositoryError>;
+        async fn delete_many(&self, ids: Vec<Uuid>) -> Result<(Vec<Uuid>, usize), RepositoryError>;
+        async fn delete_messages(&self, message_ids: Vec<Uuid>) -> Result<u64, RepositoryError>;
         async fn count_by_label(&self, label: &str) -> Result<u64, RepositoryError>;
         async fn count_by_folder(&self, folder: &str) -> Result<u64, RepositoryError>;
         async fn count_all(&self) -> Result<u64, RepositoryError>;
+        async fn count_with_filters(&self, label: Option<String>, folder: Option<String>, status: Option<String>) -> Result<u64, RepositoryError>;
         async fn find_by_id(&self, id: Uuid) -> Result<Option<sekha_controller::models::internal::Conversation>, RepositoryError>;
         async fn find_by_label(&self, label: &str, limit: u64, offset: u64) -> Result<Vec<sekha_controller::models::internal::Conversation>, RepositoryError>;
+        async fn find_by_label_fuzzy(&self, label: &str, limit: u64) -> Result<Vec<sekha_controller::models::internal::Conversation>, RepositoryError>;
         async fn get_conversation_messages(&self, conversation_id: Uuid) -> Result<Vec<Message>, RepositoryError>;
         async fn find_message_by_id(&self, id: Uuid) -> Result<Option<Message>, RepositoryError>;
+        async fn update_message_metadata(&self, message_id: Uuid, metadata: serde_json::Value, merge: bool) -> Result<Message, RepositoryError>;
+        async fn compact_messages(&self, conversation_id: Uuid, message_ids: Vec<Uuid>, summary: sekha_controller::models::internal::NewMessage) -> Result<u64, RepositoryError>;
+        async fn move_message(&self, message_id: Uuid, target_conversation_id: Uuid) -> Result<Message, RepositoryError>;
         async fn find_recent_messages(&self, conversation_id: Uuid, limit: usize) -> Result<Vec<Message>, RepositoryError>;
-        async fn find_with_filters(&self, filter: Option<String>, limit: usize, offset: u32) -> Result<(Vec<sekha_controller::models::internal::Conversation>, u64), RepositoryError>;
+        async fn find_with_filters(&self, filter: Option<String>, has_role: Option<String>, limit: usize, offset: u32) -> Result<(Vec<sekha_controller::models::internal::Conversation>, u64), RepositoryError>;
         async fn update_label(&self, id: Uuid, new_label: &str, new_folder: &str) -> Result<(), RepositoryError>;
         async fn get_message_list(&self, conversation_id: Uuid) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>>;
         async fn get_stats(&self, folder: Option<String>) -> Result<sekha_controller::storage::repository::Stats, Box<dyn std::error::Error>>;
         async fn get_stats_by_folder(&self, folder: Option<String>) -> Result<sekha_controller::storage::repository::Stats, Box<dyn std::error::Error>>;
         async fn get_stats_by_label(&self, label: Option<String>) -> Result<sekha_controller::storage::repository::Stats, Box<dyn std::error::Error>>;
         async fn get_all_folders(&self) -> Result<Vec<String>, RepositoryError>;
+        async fn folder_counts(&self) -> Result<Vec<(String, u64)>, RepositoryError>;
+        async fn conversation_timeline(&self, bucket: sekha_controller::storage::repository::TimeBucket, from: Option<chrono::NaiveDate>, to: Option<chrono::NaiveDate>) -> Result<Vec<sekha_controller::storage::repository::TimelineBucket>, RepositoryError>;
         async fn find_by_folder(&self, folder: &str, limit: u64, offset: u64) -> Result<Vec<sekha_controller::models::internal::Conversation>, RepositoryError>;
+        async fn find_by_ids(&self, ids: Vec<Uuid>) -> Result<Vec<sekha_controller::models::internal::Conversation>, RepositoryError>;
+        async fn find_updated_before(&self, ids: Vec<Uuid>, cutoff: chrono::NaiveDateTime) -> Result<Vec<Uuid>, RepositoryError>;
         async fn update_status(&self, id: Uuid, status: &str) -> Result<(), RepositoryError>;
         async fn update_importance(&self, id: Uuid, score: i32) -> Result<(), RepositoryError>;
+        async fn set_context_pinned(&self, id: Uuid, pinned: bool) -> Result<(), RepositoryError>;
+        async fn touch(&self, id: Uuid) -> Result<(), RepositoryError>;
+        async fn record_access(&self, id: Uuid, at: chrono::NaiveDateTime) -> Result<(), RepositoryError>;
+        async fn increment_session(&self, id: Uuid) -> Result<(), RepositoryError>;
+        async fn apply_patch(&self, id: Uuid, patch: sekha_controller::models::internal::ConversationPatch) -> Result<sekha_controller::models::internal::Conversation, RepositoryError>;
+        async fn find_history(&self, id: Uuid) -> Result<Vec<sekha_controller::models::internal::ConversationChange>, RepositoryError>;
+        async fn bulk_move(&self, ids: Vec<Uuid>, folder: &str, dry_run: bool) -> Result<Vec<Uuid>, RepositoryError>;
+        async fn bulk_update_status(&self, ids: Vec<Uuid>, status: &str, dry_run: bool) -> Result<Vec<Uuid>, RepositoryError>;
+        async fn rename_folder(&self, from: &str, to: &str, dry_run: bool) -> Result<Vec<Uuid>, RepositoryError>;
         async fn count_messages_in_conversation(&self, conversation_id: Uuid) -> Result<u64, RepositoryError>;
-        async fn full_text_search(&self, query: &str, limit: usize) -> Result<Vec<Message>, RepositoryError>;
+        async fn claim_summary_checkpoint(&self, conversation_id: Uuid, every: u64) -> Result<Option<u64>, RepositoryError>;
+        async fn full_text_search(&self, query: &str, limit: usize, after: Option<chrono::NaiveDateTime>, before: Option<chrono::NaiveDateTime>, conversation_id: Option<Uuid>) -> Result<Vec<Message>, RepositoryError>;
+        async fn full_text_search_count(&self, query: &str, after: Option<chrono::NaiveDateTime>, before: Option<chrono::NaiveDateTime>, conversation_id: Option<Uuid>) -> Result<u64, RepositoryError>;
         async fn semantic_search(&self, query: &str, limit: usize, filters: Option<serde_json::Value>) -> Result<Vec<sekha_controller::storage::repository::SearchResult>, RepositoryError>;
+        async fn semantic_search_with_model(&self, query: &str, limit: usize, filters: Option<serde_json::Value>, model: Option<String>) -> Result<(Vec<sekha_controller::storage::repository::SearchResult>, sekha_controller::storage::repository::SearchSource), RepositoryError>;
         async fn get_all_labels(&self) -> Result<Vec<String>, RepositoryError>;
+        async fn suggest(&self, prefix: &str, limit: usize) -> Result<sekha_controller::models::internal::SearchSuggestions, RepositoryError>;
+        async fn reindex_fts(&self) -> Result<u64, RepositoryError>;
+        async fn defer_fts_indexing(&self) -> Result<i64, RepositoryError>;
+        async fn index_fts_since(&self, after_rowid: i64) -> Result<u64, RepositoryError>;
+        async fn find_summaries(&self, conversation_id: Uuid) -> Result<Vec<sekha_controller::models::internal::StoredSummary>, RepositoryError>;
+        async fn find_tags(&self, conversation_id: Uuid) -> Result<Vec<sekha_controller::models::internal::StoredTag>, RepositoryError>;
+        async fn add_tags(&self, conversation_id: Uuid, tags: Vec<String>) -> Result<(), RepositoryError>;
+        async fn add_tags_many(&self, conversation_ids: Vec<Uuid>, tags: Vec<String>) -> Result<Vec<sekha_controller::models::internal::TagUpdate>, RepositoryError>;
+        async fn remove_tag_from_many(&self, conversation_ids: Vec<Uuid>, tag: &str) -> Result<Vec<sekha_controller::models::internal::TagUpdate>, RepositoryError>;
+        async fn find_labels(&self, conversation_id: Uuid) -> Result<Vec<sekha_controller::models::internal::ConversationLabel>, RepositoryError>;
+        async fn add_label(&self, conversation_id: Uuid, label: &str, weight: f64) -> Result<(), RepositoryError>;
+        async fn remove_label(&self, conversation_id: Uuid, label: &str) -> Result<bool, RepositoryError>;
+        async fn find_by_any_label(&self, labels: &[String]) -> Result<Vec<(sekha_controller::models::internal::Conversation, f64)>, RepositoryError>;
+        async fn save_template(&self, template: sekha_controller::models::internal::ConversationTemplate) -> Result<sekha_controller::models::internal::ConversationTemplate, RepositoryError>;
+        async fn find_template(&self, name: &str) -> Result<Option<sekha_controller::models::internal::ConversationTemplate>, RepositoryError>;
+        async fn list_templates(&self) -> Result<Vec<sekha_controller::models::internal::ConversationTemplate>, RepositoryError>;
+        async fn archive_to_disk(&self, id: Uuid, dir: &std::path::Path) -> Result<sekha_controller::models::internal::OffloadRecord, RepositoryError>;
+        async fn restore_from_disk(&self, id: Uuid) -> Result<sekha_controller::models::internal::Conversation, RepositoryError>;
+        async fn retry_embeddings(&self, message_ids: Vec<Uuid>) -> Result<Vec<Uuid>, RepositoryError>;
+        async fn reconcile_embeddings(&self) -> Result<sekha_controller::storage::repository::EmbeddingReconciliation, RepositoryError>;
+        async fn reembed_scope(&self, filter: sekha_controller::models::internal::ConversationFilter) -> Result<sekha_controller::storage::repository::ReembedReport, RepositoryError>;
+        async fn recompute_aggregates(&self) -> Result<sekha_controller::storage::repository::AggregateRecompute, RepositoryError>;
+        async fn schema_version(&self) -> Result<Option<String>, RepositoryError>;
         fn get_db(&self) -> &sea_orm::DatabaseConnection;
     }
 }
@@ -62,6 +113,8 @@ async fn test_calculate_score_basic_message() {
         content: "This is a short message".to_string(),
         timestamp: chrono::Utc::now().naive_utc(),
         embedding_id: None,
+        embedding_model: None,
+        embedding_dim: None,
         metadata: None,
     };
 
@@ -85,10 +138,11 @@ async fn test_calculate_score_basic_message() {
     let engine = ImportanceEngine::new(Arc::new(mock_repo), llm_bridge);
     let score = engine.calculate_score(message_id).await.unwrap();
 
-    // Score should be weighted: (heuristic * 0.3) + (llm_score * 0.7)
-    // heuristic ~5.0, llm 0.6 -> (5.0 * 0.3) + (0.6 * 0.7) = 1.5 + 0.42 = 1.92
-    assert!(score > 1.0);
-    assert!(score < 3.0);
+    // Score should be weighted: (heuristic * 0.3) + (llm_score * 0.7), with the
+    // bridge's 0.0-1.0 score mapped onto 1.0-10.0 first
+    // heuristic 5.0, llm 0.6 -> 6.4: (5.0 * 0.3) + (6.4 * 0.7) = 1.5 + 4.48 = 5.98
+    assert!(score > 5.5, "Score: {}", score);
+    assert!(score < 6.5, "Score: {}", score);
 }
 
 #[tokio::test]
@@ -107,6 +161,8 @@ async fn test_calculate_score_important_message_with_code() {
         content: "This is a critical decision with code: ```rust\nfn main() {}\n``` - over 100 characters long to trigger length bonus".to_string(),
         timestamp: chrono::Utc::now().naive_utc(),
         embedding_id: None,
+        embedding_model: None,
+        embedding_dim: None,
         metadata: Some(json!({"type": "important"})),
     };
 
@@ -135,9 +191,9 @@ async fn test_calculate_score_important_message_with_code() {
     // - Length > 100 (+1.0)
     // - Code block ``` (+2.0)
     // Base 5.0 + 4.0 = 9.0 heuristic
-    // (9.0 * 0.3) + (0.9 * 0.7) = 2.7 + 0.63 = 3.33
+    // llm 0.9 -> 9.1: (9.0 * 0.3) + (9.1 * 0.7) = 2.7 + 6.37 = 9.07
     assert!(
-        score > 3.0,
+        score > 8.5,
         "Score should be high for important message, got {}",
         score
     );
@@ -158,6 +214,8 @@ async fn test_calculate_score_question_with_urgent_keyword() {
         content: "What is the urgent issue here?".to_string(),
         timestamp: chrono::Utc::now().naive_utc(),
         embedding_id: None,
+        embedding_model: None,
+        embedding_dim: None,
         metadata: None,
     };
 
@@ -181,8 +239,9 @@ async fn test_calculate_score_question_with_urgent_keyword() {
     let score = engine.calculate_score(message_id).await.unwrap();
 
     // Heuristic: 5.0 + 1.0 (urgent) + 0.5 (question mark) = 6.5
-    // (6.5 * 0.3) + (0.7 * 0.7) = 1.95 + 0.49 = 2.44
-    assert!(score > 2.0, "Score: {}", score);
+    // llm 0.7 -> 7.3: (6.5 * 0.3) + (7.3 * 0.7) = 1.95 + 5.11 = 7.06
+    assert!(score > 6.5, "Score: {}", score);
+    assert!(score < 7.5, "Score: {}", score);
 }
 
 #[tokio::test]
@@ -224,6 +283,8 @@ async fn test_calculate_score_llm_error() {
         content: "Test message".to_string(),
         timestamp: chrono::Utc::now().naive_utc(),
         embedding_id: None,
+        embedding_model: None,
+        embedding_dim: None,
         metadata: None,
     };
 
@@ -263,6 +324,8 @@ async fn test_heuristic_score_all_keywords() {
         content: "This is a critical important urgent decision with code ```js\nconsole.log('test')\n``` that requires immediate attention?".to_string(),
         timestamp: chrono::Utc::now().naive_utc(),
         embedding_id: None,
+        embedding_model: None,
+        embedding_dim: None,
         metadata: None,
     };
 
@@ -287,6 +350,72 @@ async fn test_heuristic_score_all_keywords() {
 
     // Heuristic: 5.0 + 1.0 (length) + 2.0 (code) + 0.5 (question) + 3.0 (keywords) = 11.5
     // But clamped to 10.0
-    // (10.0 * 0.3) + (0.95 * 0.7) = 3.0 + 0.665 = 3.665
-    assert!(score > 3.5, "Score should be maximum, got {}", score);
+    // llm 0.95 -> 9.55: (10.0 * 0.3) + (9.55 * 0.7) = 3.0 + 6.685 = 9.685
+    assert!(score > 9.5, "Score should be maximum, got {}", score);
+    assert!(score <= 10.0, "Score must stay on the scale, got {}", score);
+}
+
+#[tokio::test]
+async fn test_engine_score_round_trips_through_stored_importance() {
+    let mock_server = MockServer::start().await;
+    let llm_bridge = Arc::new(LlmBridgeClient::new(mock_server.uri()));
+
+    let mut mock_repo = MockConversationRepo::new();
+    let message_id = Uuid::new_v4();
+
+    let test_message = Message {
+        id: message_id,
+        conversation_id: Uuid::new_v4(),
+        role: "user".to_string(),
+        content: "This is a short message".to_string(),
+        timestamp: chrono::Utc::now().naive_utc(),
+        embedding_id: None,
+        embedding_model: None,
+        embedding_dim: None,
+        metadata: None,
+    };
+
+    mock_repo
+        .expect_find_message_by_id()
+        .with(eq(message_id))
+        .times(1)
+        .returning(move |_| Ok(Some(test_message.clone())));
+
+    Mock::given(method("POST"))
+        .and(path("/score_importance"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "score": 0.6,
+            "reasoning": "Normal importance",
+            "model": "llama3.1:8b"
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let engine = ImportanceEngine::new(Arc::new(mock_repo), llm_bridge);
+    let score = engine.calculate_score(message_id).await.unwrap();
+
+    // 5.98 is stored as 6, which reads back as 6.0 and stores as 6 again
+    let stored = importance::to_stored(score);
+    assert_eq!(stored, 6);
+    assert!(importance::is_valid(stored));
+    assert_eq!(importance::from_stored(stored), 6.0);
+    assert_eq!(
+        importance::to_stored(importance::from_stored(stored)),
+        stored
+    );
+
+    // The ends of the scale map onto the ends of the stored range
+    assert_eq!(
+        importance::to_stored(importance::from_unit(0.0)),
+        importance::MIN_IMPORTANCE
+    );
+    assert_eq!(
+        importance::to_stored(importance::from_unit(1.0)),
+        importance::MAX_IMPORTANCE
+    );
+    assert_eq!(importance::to_stored(42.0), importance::MAX_IMPORTANCE);
+    assert_eq!(
+        importance::to_stored(f32::NAN),
+        importance::DEFAULT_IMPORTANCE
+    );
 }
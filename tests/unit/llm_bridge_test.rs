@@ -1,5 +1,7 @@
+use sekha_controller::orchestrator::search_reranker::SearchReranker;
 use sekha_controller::services::llm_bridge_client::LlmBridgeClient;
 use serde_json::json;
+use std::sync::Arc;
 use wiremock::matchers::{method, path};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
@@ -193,3 +195,57 @@ async fn test_health_check_failure() {
     let result = client.health_check().await.unwrap();
     assert!(!result);
 }
+
+#[tokio::test]
+async fn test_rerank_success() {
+    let mock_server = MockServer::start().await;
+    let client = LlmBridgeClient::new(mock_server.uri());
+
+    Mock::given(method("POST"))
+        .and(path("/rerank"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "ranking": [2, 0],
+            "model": "llama3.1:8b"
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let documents = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+    let result = client.rerank("query", documents, 2, None).await.unwrap();
+    assert_eq!(result, vec![2, 0]);
+}
+
+#[tokio::test]
+async fn test_search_reranker_sanitizes_ranking() {
+    let mock_server = MockServer::start().await;
+    let reranker = SearchReranker::new(Arc::new(LlmBridgeClient::new(mock_server.uri())));
+
+    // Out-of-range and repeated indices are dropped, then capped at top_k
+    Mock::given(method("POST"))
+        .and(path("/rerank"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "ranking": [7, 1, 1, 3, 0, 2],
+            "model": "llama3.1:8b"
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let documents: Vec<String> = (0..4).map(|i| format!("doc {}", i)).collect();
+    let ranking = reranker.rerank("query", &documents, 3).await;
+    assert_eq!(ranking, Some(vec![1, 3, 0]));
+}
+
+#[tokio::test]
+async fn test_search_reranker_falls_back_when_bridge_down() {
+    let mock_server = MockServer::start().await;
+    let reranker = SearchReranker::new(Arc::new(LlmBridgeClient::new(mock_server.uri())));
+
+    Mock::given(method("POST"))
+        .and(path("/rerank"))
+        .respond_with(ResponseTemplate::new(503))
+        .mount(&mock_server)
+        .await;
+
+    let documents = vec!["a".to_string(), "b".to_string()];
+    assert_eq!(reranker.rerank("query", &documents, 2).await, None);
+}
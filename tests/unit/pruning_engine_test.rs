@@ -1,4 +1,5 @@
 use chrono::Utc;
+use sekha_controller::config::{RetentionAction, RetentionPolicy};
 use sekha_controller::models::internal::{NewConversation, NewMessage};
 use sekha_controller::orchestrator::pruning_engine::PruningEngine;
 use sekha_controller::services::embedding_service::EmbeddingService;
@@ -7,6 +8,7 @@ use sekha_controller::storage::chroma_client::ChromaClient;
 use sekha_controller::storage::repository::ConversationRepository;
 use sekha_controller::storage::SeaOrmConversationRepository;
 use serde_json::json;
+use std::collections::HashMap;
 use std::sync::Arc;
 use wiremock::matchers::{method, path};
 use wiremock::{Mock, MockServer, ResponseTemplate};
@@ -141,3 +143,248 @@ async fn test_generate_suggestions_empty_database() {
 
     assert_eq!(suggestions.len(), 0);
 }
+
+#[tokio::test]
+async fn test_generate_suggestions_applies_per_folder_retention_policies() {
+    let mock_server = MockServer::start().await;
+    let llm_bridge = Arc::new(LlmBridgeClient::new(mock_server.uri()));
+
+    let db = sekha_controller::storage::init_db("sqlite::memory:")
+        .await
+        .unwrap();
+    let chroma = Arc::new(ChromaClient::new("http://localhost:8000".to_string()));
+    let embedding_service = Arc::new(EmbeddingService::new(
+        mock_server.uri(),
+        "http://localhost:8000".to_string(),
+    ));
+    let repo = Arc::new(SeaOrmConversationRepository::new(
+        db,
+        chroma,
+        embedding_service,
+    ));
+
+    // 30 messages, enough for the token estimate to allow archiving
+    let mut ids = HashMap::new();
+    for folder in ["/legal/contracts", "/scratch", "/scratch/keepers", "/notes"] {
+        let now = Utc::now().naive_utc();
+        let conv = NewConversation {
+            id: None,
+            label: folder.to_string(),
+            folder: folder.to_string(),
+            status: "active".to_string(),
+            importance_score: Some(7),
+            word_count: 1000,
+            session_count: Some(1),
+            created_at: now,
+            updated_at: now,
+            messages: (0..30)
+                .map(|i| NewMessage {
+                    role: "user".to_string(),
+                    content: format!("Message {}", i),
+                    metadata: json!({}),
+                    timestamp: now,
+                })
+                .collect(),
+        };
+        ids.insert(folder, repo.create_with_messages(conv).await.unwrap());
+    }
+
+    Mock::given(method("POST"))
+        .and(path("/summarize"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "summary": "Preview",
+            "level": "daily",
+            "model": "llama3.1:8b",
+            "tokens_used": 5
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let policies = HashMap::from([
+        (
+            "/legal".to_string(),
+            RetentionPolicy {
+                action: RetentionAction::Keep,
+                ..Default::default()
+            },
+        ),
+        (
+            "/scratch".to_string(),
+            RetentionPolicy {
+                threshold_days: Some(0),
+                importance_threshold: Some(10.0),
+                action: RetentionAction::Archive,
+            },
+        ),
+        (
+            "/scratch/keepers".to_string(),
+            RetentionPolicy {
+                action: RetentionAction::Keep,
+                ..Default::default()
+            },
+        ),
+    ]);
+
+    let engine = PruningEngine::new(repo.clone(), llm_bridge).with_retention_policies(policies);
+    let suggestions = engine.generate_suggestions(0, 5.0).await.unwrap();
+
+    let recommendation = |folder: &str| {
+        suggestions
+            .iter()
+            .find(|s| s.conversation_id == ids[folder])
+            .map(|s| s.recommendation.as_str())
+    };
+    assert_eq!(suggestions.len(), 2);
+    assert_eq!(recommendation("/legal/contracts"), None);
+    assert_eq!(recommendation("/scratch/keepers"), None);
+    // Importance 7 is under the /scratch threshold but not the global one
+    assert_eq!(recommendation("/scratch"), Some("archive"));
+    assert_eq!(recommendation("/notes"), Some("keep"));
+
+    // A longer window for /scratch leaves it out entirely
+    let engine = engine.with_retention_policies(HashMap::from([(
+        "/scratch".to_string(),
+        RetentionPolicy {
+            threshold_days: Some(30),
+            ..Default::default()
+        },
+    )]));
+    let suggestions = engine.generate_suggestions(0, 5.0).await.unwrap();
+    assert_eq!(suggestions.len(), 2);
+    assert!(suggestions
+        .iter()
+        .all(|s| s.conversation_id != ids["/scratch"]));
+}
+
+#[tokio::test]
+async fn test_generate_suggestions_scoped_to_folder() {
+    let mock_server = MockServer::start().await;
+    let llm_bridge = Arc::new(LlmBridgeClient::new(mock_server.uri()));
+
+    let db = sekha_controller::storage::init_db("sqlite::memory:")
+        .await
+        .unwrap();
+    let chroma = Arc::new(ChromaClient::new("http://localhost:8000".to_string()));
+    let embedding_service = Arc::new(EmbeddingService::new(
+        mock_server.uri(),
+        "http://localhost:8000".to_string(),
+    ));
+    let repo = Arc::new(SeaOrmConversationRepository::new(
+        db,
+        chroma,
+        embedding_service,
+    ));
+
+    let mut ids = HashMap::new();
+    for folder in ["/work", "/work/old", "/personal", "/workshop"] {
+        let now = Utc::now().naive_utc();
+        let conv = NewConversation {
+            id: None,
+            label: folder.to_string(),
+            folder: folder.to_string(),
+            status: "active".to_string(),
+            importance_score: Some(5),
+            word_count: 10,
+            session_count: Some(1),
+            created_at: now,
+            updated_at: now,
+            messages: vec![NewMessage {
+                role: "user".to_string(),
+                content: "Message".to_string(),
+                metadata: json!({}),
+                timestamp: now,
+            }],
+        };
+        ids.insert(folder, repo.create_with_messages(conv).await.unwrap());
+    }
+
+    Mock::given(method("POST"))
+        .and(path("/summarize"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "summary": "Preview",
+            "level": "daily",
+            "model": "llama3.1:8b",
+            "tokens_used": 5
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let engine = PruningEngine::new(repo.clone(), llm_bridge);
+    let suggestions = engine
+        .generate_suggestions_in_folder(0, 5.0, Some("/work"))
+        .await
+        .unwrap();
+
+    let mut suggested: Vec<_> = suggestions.iter().map(|s| s.conversation_id).collect();
+    suggested.sort();
+    let mut expected = vec![ids["/work"], ids["/work/old"]];
+    expected.sort();
+    assert_eq!(suggested, expected);
+
+    // Unscoped, every folder is considered
+    let suggestions = engine.generate_suggestions(0, 5.0).await.unwrap();
+    assert_eq!(suggestions.len(), 4);
+}
+
+#[tokio::test]
+async fn test_generate_suggestions_last_access_keeps_read_conversations() {
+    let mock_server = MockServer::start().await;
+    let llm_bridge = Arc::new(LlmBridgeClient::new(mock_server.uri()));
+
+    let db = sekha_controller::storage::init_db("sqlite::memory:")
+        .await
+        .unwrap();
+    let chroma = Arc::new(ChromaClient::new("http://localhost:8000".to_string()));
+    let embedding_service = Arc::new(EmbeddingService::new(
+        mock_server.uri(),
+        "http://localhost:8000".to_string(),
+    ));
+    let repo = Arc::new(SeaOrmConversationRepository::new(
+        db,
+        chroma,
+        embedding_service,
+    ));
+
+    let now = Utc::now().naive_utc();
+    let written = now - chrono::Duration::days(10);
+    let conv = NewConversation {
+        id: None,
+        label: "Reference notes".to_string(),
+        folder: "test".to_string(),
+        status: "active".to_string(),
+        importance_score: Some(5),
+        word_count: 100,
+        session_count: Some(1),
+        created_at: written,
+        updated_at: written,
+        messages: vec![NewMessage {
+            role: "user".to_string(),
+            content: "Still useful".to_string(),
+            metadata: json!({}),
+            timestamp: written,
+        }],
+    };
+    let conv_id = repo.create_with_messages(conv).await.unwrap();
+    repo.record_access(conv_id, now).await.unwrap();
+
+    Mock::given(method("POST"))
+        .and(path("/summarize"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "summary": "Preview",
+            "level": "daily",
+            "model": "llama3.1:8b",
+            "tokens_used": 5
+        })))
+        .mount(&mock_server)
+        .await;
+
+    // By default only writes count, so the conversation looks stale
+    let engine = PruningEngine::new(repo.clone(), llm_bridge.clone());
+    let suggestions = engine.generate_suggestions(7, 5.0).await.unwrap();
+    assert_eq!(suggestions.len(), 1);
+    assert_eq!(suggestions[0].last_accessed, written);
+
+    let engine = PruningEngine::new(repo.clone(), llm_bridge).with_last_access_signal(true);
+    let suggestions = engine.generate_suggestions(7, 5.0).await.unwrap();
+    assert!(suggestions.is_empty());
+}